@@ -5,11 +5,10 @@
 use anyhow::Result;
 use axum::extract::State;
 use axum::{http::StatusCode, routing::get, Json, Router};
+use postgresql_embedded::helpers::{connect_pool, enable_extension};
 use postgresql_embedded::{PostgreSQL, Settings, VersionReq};
-use sqlx::postgres::PgPoolOptions;
 use sqlx::PgPool;
 use std::env;
-use std::time::Duration;
 use tokio::net::TcpListener;
 use tracing::info;
 
@@ -41,25 +40,11 @@ async fn main() -> Result<()> {
     info!("Creating database {database_name}");
     postgresql.create_database(database_name).await?;
 
-    info!("Configuring extension");
-    let settings = postgresql.settings().clone();
-    let database_url = settings.url(database_name);
-    let pool = PgPool::connect(database_url.as_str()).await?;
-    pool.close().await;
-
-    info!("Restarting database");
-    postgresql.stop().await?;
-    postgresql.start().await?;
+    info!("Enabling extension");
+    enable_extension(&postgresql, database_name, "vector").await?;
 
     info!("Setup connection pool");
-    let pool = PgPoolOptions::new()
-        .max_connections(5)
-        .acquire_timeout(Duration::from_secs(3))
-        .connect(&database_url)
-        .await?;
-
-    info!("Enabling extension");
-    enable_extension(&pool).await?;
+    let pool = connect_pool(&postgresql, database_name).await?;
 
     info!("Start application");
     let app = Router::new().route("/", get(extensions)).with_state(pool);
@@ -71,13 +56,6 @@ async fn main() -> Result<()> {
     Ok(())
 }
 
-async fn enable_extension(pool: &PgPool) -> Result<()> {
-    sqlx::query("CREATE EXTENSION IF NOT EXISTS vector")
-        .execute(pool)
-        .await?;
-    Ok(())
-}
-
 async fn extensions(State(pool): State<PgPool>) -> Result<Json<Vec<String>>, (StatusCode, String)> {
     sqlx::query_scalar("SELECT name FROM pg_available_extensions ORDER BY name")
         .fetch_all(&pool)
@@ -4,13 +4,15 @@
 
 use anyhow::Result;
 use indicatif::ProgressStyle;
-use postgresql_embedded::{PostgreSQL, Settings, VersionReq};
+use postgresql_embedded::{Event, PostgreSQL, Settings, VersionReq};
+use tracing::info;
 use tracing_indicatif::IndicatifLayer;
 use tracing_subscriber::filter::LevelFilter;
 use tracing_subscriber::prelude::*;
 use tracing_subscriber::{fmt, Registry};
 
-/// Example of how to display a progress bar for the postgresql embedded archive download
+/// Example of how to display a progress bar for the postgresql embedded archive download and
+/// extraction
 #[tokio::main]
 async fn main() -> Result<()> {
     let progress_style = ProgressStyle::with_template("{span_child_prefix}{spinner} {span_name} [{elapsed_precise}] [{wide_bar:.green.bold}] {bytes}/{total_bytes} ({bytes_per_sec}, {eta})")?
@@ -25,7 +27,16 @@ async fn main() -> Result<()> {
         version: VersionReq::parse("=16.4.0")?,
         ..Default::default()
     };
-    let mut postgresql = PostgreSQL::new(settings);
+    let mut postgresql = PostgreSQL::new(settings).with_event_listener(|event: &Event| {
+        if let Event::ExtractionProgress { progress, .. } = event {
+            if progress.files > 0 && progress.files % 250 == 0 {
+                info!(
+                    "Extracted {} files totalling {} bytes",
+                    progress.files, progress.bytes
+                );
+            }
+        }
+    });
     postgresql.setup().await?;
     postgresql.start().await?;
 
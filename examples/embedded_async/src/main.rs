@@ -6,10 +6,9 @@ use postgresql_embedded::{PostgreSQL, Result, Settings, VersionReq};
 
 #[tokio::main]
 async fn main() -> Result<()> {
-    let settings = Settings {
-        version: VersionReq::parse("=16.4.0")?,
-        ..Default::default()
-    };
+    let settings = Settings::builder()
+        .version(VersionReq::parse("=16.4.0")?)
+        .try_build()?;
     let mut postgresql = PostgreSQL::new(settings);
     postgresql.setup().await?;
     postgresql.start().await?;
@@ -0,0 +1,57 @@
+#![forbid(unsafe_code)]
+#![forbid(clippy::allow_attributes)]
+#![deny(clippy::pedantic)]
+
+use anyhow::Result;
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_sdk::trace::TracerProvider;
+use postgresql_embedded::{PostgreSQL, Settings, VersionReq};
+use tracing::{info_span, Instrument};
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::util::SubscriberInitExt;
+
+/// Example of exporting the spans of the embedded server, including the subprocesses it runs, as
+/// part of the traces of the application. With the `opentelemetry` feature the trace context is
+/// also propagated to the server as the `application_name` and as SQL comments.
+#[tokio::main]
+async fn main() -> Result<()> {
+    let provider = TracerProvider::builder()
+        .with_simple_exporter(opentelemetry_stdout::SpanExporter::default())
+        .build();
+    let tracer = provider.tracer("opentelemetry_embedded");
+    tracing_subscriber::registry()
+        .with(tracing_opentelemetry::layer().with_tracer(tracer))
+        .init();
+
+    async {
+        let settings = Settings {
+            version: VersionReq::parse("=16.4.0")?,
+            ..Default::default()
+        };
+        let mut postgresql = PostgreSQL::new(settings);
+        postgresql.setup().await?;
+        postgresql.start().await?;
+
+        let database_name = "test";
+        postgresql.create_database(database_name).await?;
+        postgresql.drop_database(database_name).await?;
+
+        postgresql.stop().await?;
+        Ok::<(), anyhow::Error>(())
+    }
+    .instrument(info_span!("application"))
+    .await?;
+
+    provider.shutdown()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_main() -> Result<()> {
+        main()
+    }
+}
@@ -7,6 +7,7 @@ use indoc::indoc;
 use sqlx::{PgPool, Row};
 use tracing::info;
 
+use postgresql_embedded::helpers::{connect_pool, enable_extension, preload_libraries};
 use postgresql_embedded::{PostgreSQL, Settings, VersionReq};
 
 /// Example of how to install and configure the TensorChord vector extension.
@@ -41,19 +42,14 @@ async fn main() -> Result<()> {
     postgresql.create_database(database_name).await?;
 
     info!("Configuring extension");
-    let settings = postgresql.settings();
-    let database_url = settings.url(database_name);
-    let pool = PgPool::connect(database_url.as_str()).await?;
+    let pool = connect_pool(&postgresql, database_name).await?;
     configure_extension(&pool).await?;
     pool.close().await;
-
-    info!("Restarting database");
-    postgresql.stop().await?;
-    postgresql.start().await?;
+    preload_libraries(&mut postgresql, &["vectors.so"]).await?;
 
     info!("Enabling extension");
-    let pool = PgPool::connect(database_url.as_str()).await?;
-    enable_extension(&pool).await?;
+    enable_extension(&postgresql, database_name, "vectors").await?;
+    let pool = connect_pool(&postgresql, database_name).await?;
 
     info!("Creating table");
     create_table(&pool).await?;
@@ -88,25 +84,12 @@ async fn main() -> Result<()> {
 }
 
 async fn configure_extension(pool: &PgPool) -> Result<()> {
-    sqlx::query("ALTER SYSTEM SET shared_preload_libraries = \"vectors.so\"")
-        .execute(pool)
-        .await?;
     sqlx::query("ALTER SYSTEM SET search_path = \"$user\", public, vectors")
         .execute(pool)
         .await?;
     Ok(())
 }
 
-async fn enable_extension(pool: &PgPool) -> Result<()> {
-    sqlx::query("DROP EXTENSION IF EXISTS vectors")
-        .execute(pool)
-        .await?;
-    sqlx::query("CREATE EXTENSION IF NOT EXISTS vectors")
-        .execute(pool)
-        .await?;
-    Ok(())
-}
-
 async fn create_table(pool: &PgPool) -> Result<()> {
     sqlx::query(indoc! {"
         CREATE TABLE IF NOT EXISTS items (
@@ -1,25 +1,155 @@
 //! Manage PostgreSQL archives
 #![allow(dead_code)]
 
+use crate::cache;
+use crate::download::temporary_path;
 use crate::error::Result;
-use crate::{extractor, repository};
+use crate::extractor::progress::{self, ProgressCallback};
+use crate::extractor::ExtractProgress;
+#[cfg(feature = "minisign")]
+use crate::hasher::minisign;
+#[cfg(feature = "minisign")]
+use crate::repository::Repository;
+use crate::repository::{Archive, ReleaseInfo};
+use crate::scoped::{self, with_scoped_options, ScopedOptions};
+#[cfg(feature = "minisign")]
+use crate::Error::SignatureNotFound;
+use crate::Error::{OfflineCacheMiss, UnsupportedExtractor};
+use crate::{extractor, reporter, repository, Channel};
 use regex_lite::Regex;
 use semver::{Version, VersionReq};
 use std::path::{Path, PathBuf};
-use tracing::instrument;
+use std::sync::Arc;
+use tracing::field::Empty;
+use tracing::{debug, instrument, warn, Span};
+
+/// Gets the latest cached archive of the repository `url` that matches the
+/// [version requirement](VersionReq) in the [channel](Channel), for
+/// [offline](crate::DownloadOptions::offline) retrieval, from the [scoped cache](ScopedOptions::cache)
+/// or the cache directory of the options if they [cache archives](crate::DownloadOptions::cache_archives).
+/// Only archives that match the hash stored when they were cached are returned.
+///
+/// # Errors
+/// * If no cached archive satisfies the version requirement.
+fn get_cached(url: &str, version_req: &VersionReq, channel: Channel) -> Result<(Version, PathBuf)> {
+    let Some(cache) = scoped::cache() else {
+        return Err(OfflineCacheMiss {
+            version_req: version_req.to_string(),
+            cached_versions: Vec::new(),
+        });
+    };
+    match cache.get(url, version_req, channel) {
+        Some((version, path)) => {
+            debug!("Using cached archive {}", path.to_string_lossy());
+            Ok((version, path))
+        }
+        None => Err(OfflineCacheMiss {
+            version_req: version_req.to_string(),
+            cached_versions: cache.versions(url),
+        }),
+    }
+}
+
+/// Caches the downloaded `archive` of the repository `url` in the
+/// [scoped cache](ScopedOptions::cache), or the cache directory of the options if they
+/// [cache archives](crate::DownloadOptions::cache_archives); failures are logged rather than returned,
+/// as the archive was downloaded successfully.
+fn cache_archive(url: &str, archive: &Archive) {
+    if let Some(cache) = scoped::cache() {
+        if let Err(error) = cache.store(url, archive) {
+            warn!("Failed to cache archive {}: {error}", archive.name());
+        }
+    }
+}
+
+/// Verifies the downloaded `archive` of the `repository` at the `url` against its signature, if
+/// a [public key is registered](minisign::register) for the repository; archives of any
+/// repository that are not signed with the key are rejected.
+///
+/// # Errors
+/// * If a public key is registered and the repository has no signature for the archive.
+/// * If the signature is invalid.
+#[cfg(feature = "minisign")]
+async fn verify_signature(
+    repository: &dyn Repository,
+    url: &str,
+    version: &Version,
+    archive: Signed<'_>,
+) -> Result<()> {
+    let Some(public_key) = minisign::public_key(url)? else {
+        return Ok(());
+    };
+    let Some(signature) = repository.get_signature(version).await? else {
+        let name = match archive {
+            Signed::Archive(archive) => archive.name().to_string(),
+            Signed::File(path) => path.to_string_lossy().to_string(),
+        };
+        return Err(SignatureNotFound(name));
+    };
+    match archive {
+        Signed::Archive(archive) => minisign::verify(&public_key, archive.bytes(), &signature)?,
+        Signed::File(path) => minisign::verify_file(&public_key, path, &signature)?,
+    }
+    debug!("Archive {version} signature verified");
+    Ok(())
+}
+
+/// Downloaded archive to [verify the signature](verify_signature) of.
+#[cfg(feature = "minisign")]
+#[derive(Clone, Copy)]
+enum Signed<'a> {
+    Archive(&'a Archive),
+    File(&'a Path),
+}
 
 /// Gets the version for the specified [version requirement](VersionReq). If a version for the
 /// [version requirement](VersionReq) is not found, then an error is returned.
 ///
 /// # Errors
 /// * If the version is not found.
-#[instrument(level = "debug")]
+#[instrument(level = "debug", fields(operation = "get_version", version = Empty))]
 pub async fn get_version(url: &str, version_req: &VersionReq) -> Result<Version> {
+    let options = scoped::download_options();
+    if options.is_offline_for(url) {
+        let (version, _) = get_cached(url, version_req, Channel::Stable)?;
+        Span::current().record("version", version.to_string());
+        return Ok(version);
+    }
     let repository = repository::registry::get(url)?;
     let version = repository.get_version(version_req).await?;
+    Span::current().record("version", version.to_string());
     Ok(version)
 }
 
+/// Gets the latest stable version of the `major` version; e.g. `16.6.0` for `16`. If no version of
+/// the major version is found, then an error is returned.
+///
+/// # Errors
+/// * If the version is not found.
+#[instrument(level = "debug")]
+pub async fn latest_of(url: &str, major: u64) -> Result<Version> {
+    let version_req = VersionReq::parse(&format!("={major}"))?;
+    get_version(url, &version_req).await
+}
+
+/// Gets all the versions published by the repository at the `url`, including pre-releases, in
+/// descending order; e.g. to present the available versions, or to validate a
+/// [version requirement](VersionReq) before downloading. When
+/// [offline](crate::DownloadOptions::offline), the versions of the cached archives are returned.
+///
+/// # Errors
+/// * If the versions cannot be retrieved.
+#[instrument(level = "debug")]
+pub async fn list_versions(url: &str) -> Result<Vec<Version>> {
+    let options = scoped::download_options();
+    if options.is_offline_for(url) {
+        let versions = scoped::cache().map(|cache| cache.versions(url));
+        return Ok(versions.unwrap_or_default());
+    }
+    let repository = repository::registry::get(url)?;
+    repository.list_versions().await
+}
+
 /// Gets the archive for a given [version requirement](VersionReq) that passes the default
 /// matcher. If no archive is found for the [version requirement](VersionReq) and matcher then
 /// an [error](crate::error::Error) is returned.
@@ -27,31 +157,366 @@ pub async fn get_version(url: &str, version_req: &VersionReq) -> Result<Version>
 /// # Errors
 /// * If the archive is not found.
 /// * If the archive cannot be downloaded.
-#[instrument]
+#[instrument(fields(operation = "download", version = Empty, bytes_total = Empty))]
 pub async fn get_archive(url: &str, version_req: &VersionReq) -> Result<(Version, Vec<u8>)> {
+    let options = scoped::download_options();
+    let (version, bytes) = if options.is_offline_for(url) {
+        let (version, path) = get_cached(url, version_req, Channel::Stable)?;
+        (version, cache::read_archive(&path)?)
+    } else {
+        let repository = repository::registry::get(url)?;
+        let archive = repository.get_archive(version_req).await?;
+        #[cfg(feature = "minisign")]
+        verify_signature(
+            &*repository,
+            url,
+            archive.version(),
+            Signed::Archive(&archive),
+        )
+        .await?;
+        cache_archive(url, &archive);
+        (archive.version().clone(), archive.bytes().to_vec())
+    };
+    let span = Span::current();
+    span.record("version", version.to_string());
+    span.record("bytes_total", bytes.len());
+    Ok((version, bytes))
+}
+
+/// Downloads the archive for a given [version requirement](VersionReq) that passes the default
+/// matcher to the file at `path` without buffering the archive in memory, and returns the version
+/// of the archive.
+///
+/// # Errors
+/// * If the archive is not found.
+/// * If the archive cannot be downloaded.
+#[instrument(fields(operation = "download", version = Empty, bytes_total = Empty))]
+pub async fn get_archive_to_file(
+    url: &str,
+    version_req: &VersionReq,
+    path: &Path,
+) -> Result<Version> {
+    let options = scoped::download_options();
+    let version = if options.is_offline_for(url) {
+        let (version, cached_path) = get_cached(url, version_req, Channel::Stable)?;
+        cache::copy_archive(&cached_path, path)?;
+        version
+    } else {
+        let repository = repository::registry::get(url)?;
+        // The archive is only moved to the path once its signature is verified
+        let temp_path = temporary_path(path);
+        let version = match repository
+            .get_archive_to_file(version_req, &temp_path)
+            .await
+        {
+            Ok(version) => version,
+            Err(error) => {
+                let _ = std::fs::remove_file(&temp_path);
+                return Err(error);
+            }
+        };
+        #[cfg(feature = "minisign")]
+        if let Err(error) =
+            verify_signature(&*repository, url, &version, Signed::File(&temp_path)).await
+        {
+            let _ = std::fs::remove_file(&temp_path);
+            return Err(error);
+        }
+        std::fs::rename(&temp_path, path)?;
+        if let Some(cache) = scoped::cache() {
+            let name = path.file_name().map_or_else(
+                || "archive".to_string(),
+                |name| name.to_string_lossy().to_string(),
+            );
+            if let Err(error) = cache.store_file(url, &version, &name, path) {
+                warn!("Failed to cache archive {name}: {error}");
+            }
+        }
+        version
+    };
+    let span = Span::current();
+    span.record("version", version.to_string());
+    span.record("bytes_total", path.metadata()?.len());
+    Ok(version)
+}
+
+/// Gets the version for the specified [version requirement](VersionReq) in the release
+/// [channel](Channel); e.g. the latest beta of a major version. If a version for the
+/// [version requirement](VersionReq) is not found, then an error is returned.
+///
+/// # Errors
+/// * If the version is not found.
+#[instrument(level = "debug", fields(operation = "get_version", version = Empty))]
+pub async fn get_version_in_channel(
+    url: &str,
+    version_req: &VersionReq,
+    channel: Channel,
+) -> Result<Version> {
+    let options = scoped::download_options();
+    if options.is_offline_for(url) {
+        let (version, _) = get_cached(url, version_req, channel)?;
+        Span::current().record("version", version.to_string());
+        return Ok(version);
+    }
     let repository = repository::registry::get(url)?;
-    let archive = repository.get_archive(version_req).await?;
-    let version = archive.version().clone();
-    let bytes = archive.bytes().to_vec();
+    let version = repository
+        .get_version_in_channel(version_req, channel)
+        .await?;
+    Span::current().record("version", version.to_string());
+    Ok(version)
+}
+
+/// Gets the [release information](ReleaseInfo) of the latest version for the specified
+/// [version requirement](VersionReq) in the release [channel](Channel); e.g. to check whether a
+/// newer version is available. When [offline](crate::DownloadOptions::offline), the information of
+/// the latest cached archive is returned.
+///
+/// # Errors
+/// * If the version is not found.
+#[instrument(level = "debug", fields(operation = "get_release_info", version = Empty))]
+pub async fn get_release_info(
+    url: &str,
+    version_req: &VersionReq,
+    channel: Channel,
+) -> Result<ReleaseInfo> {
+    let options = scoped::download_options();
+    let release_info = if options.is_offline_for(url) {
+        let (version, path) = get_cached(url, version_req, channel)?;
+        ReleaseInfo {
+            version,
+            published_at: None,
+            size: std::fs::metadata(path).ok().map(|metadata| metadata.len()),
+        }
+    } else {
+        let repository = repository::registry::get(url)?;
+        repository.get_release_info(version_req, channel).await?
+    };
+    Span::current().record("version", release_info.version.to_string());
+    Ok(release_info)
+}
+
+/// Gets the archive for a given [version requirement](VersionReq) in the release
+/// [channel](Channel) that passes the default matcher. If no archive is found for the
+/// [version requirement](VersionReq) and matcher then an [error](crate::error::Error) is
+/// returned.
+///
+/// # Errors
+/// * If the archive is not found.
+/// * If the archive cannot be downloaded.
+#[instrument(fields(operation = "download", version = Empty, bytes_total = Empty))]
+pub async fn get_archive_in_channel(
+    url: &str,
+    version_req: &VersionReq,
+    channel: Channel,
+) -> Result<(Version, Vec<u8>)> {
+    let options = scoped::download_options();
+    let (version, bytes) = if options.is_offline_for(url) {
+        let (version, path) = get_cached(url, version_req, channel)?;
+        (version, cache::read_archive(&path)?)
+    } else {
+        let repository = repository::registry::get(url)?;
+        let archive = repository
+            .get_archive_in_channel(version_req, channel)
+            .await?;
+        #[cfg(feature = "minisign")]
+        verify_signature(
+            &*repository,
+            url,
+            archive.version(),
+            Signed::Archive(&archive),
+        )
+        .await?;
+        cache_archive(url, &archive);
+        (archive.version().clone(), archive.bytes().to_vec())
+    };
+    let span = Span::current();
+    span.record("version", version.to_string());
+    span.record("bytes_total", bytes.len());
     Ok((version, bytes))
 }
 
-/// Extracts the compressed tar `bytes` to the [out_dir](Path).
+/// Gets the archive for a given [version requirement](VersionReq) built for the specified
+/// `target` triple (e.g. `aarch64-apple-darwin`) rather than the target this crate was built for;
+/// e.g. to bundle an archive when cross compiling. The target is the scoped
+/// [target](crate::ScopedOptions::target) that the matcher matches assets for.
+///
+/// # Errors
+/// * If the archive is not found.
+/// * If the archive cannot be downloaded.
+#[instrument(fields(operation = "download", version = Empty, bytes_total = Empty))]
+pub async fn get_archive_for_target(
+    url: &str,
+    version_req: &VersionReq,
+    target: &str,
+) -> Result<(Version, Vec<u8>)> {
+    let options = ScopedOptions {
+        target: Some(target.to_string()),
+        ..ScopedOptions::default()
+    };
+    with_scoped_options(options, get_archive(url, version_req)).await
+}
+
+/// Extracts the compressed tar `bytes` to the [out_dir](Path). Only the files matching the
+/// [extract filter](ScopedOptions::extract_filter) of the scope, or else the filter of the
+/// [default extract options](extractor::ExtractOptions), are extracted.
 ///
 /// # Errors
 /// Returns an error if the extraction fails.
-#[instrument(skip(bytes))]
+#[instrument(skip(bytes), fields(operation = "extract", path = %out_dir.display(), bytes_total = bytes.len()))]
 pub async fn extract(url: &str, bytes: &Vec<u8>, out_dir: &Path) -> Result<Vec<PathBuf>> {
+    extract_tracked(url, bytes, out_dir, None)
+}
+
+/// Extracts the compressed tar `bytes` to the [out_dir](Path), passing the
+/// [progress](ExtractProgress) of the extraction to `on_progress` as each file is extracted.
+/// The progress is reported on the calling thread and `on_progress` should return quickly.
+///
+/// # Errors
+/// Returns an error if the extraction fails.
+#[instrument(skip(bytes, on_progress), fields(operation = "extract", path = %out_dir.display(), bytes_total = bytes.len()))]
+pub async fn extract_with_progress<F>(
+    url: &str,
+    bytes: &Vec<u8>,
+    out_dir: &Path,
+    on_progress: F,
+) -> Result<Vec<PathBuf>>
+where
+    F: Fn(&ExtractProgress) + Send + Sync + 'static,
+{
+    extract_tracked(url, bytes, out_dir, Some(Arc::new(on_progress)))
+}
+
+/// Extracts the `bytes` to the `out_dir`, tracking the progress of the extraction in the
+/// current span and passing it to the progress reporter of the scope and the `callback`, if any.
+///
+/// # Errors
+/// Returns an error if the extraction fails.
+fn extract_tracked(
+    url: &str,
+    bytes: &Vec<u8>,
+    out_dir: &Path,
+    callback: Option<ProgressCallback>,
+) -> Result<Vec<PathBuf>> {
     let extractor_fn = extractor::registry::get(url)?;
     let mut extract_directories = extractor::ExtractDirectories::default();
     extract_directories.add_mapping(Regex::new(".*")?, out_dir.to_path_buf());
-    extractor_fn(bytes, extract_directories)
+    extract_directories.set_filter(scoped::extract_filter());
+    let archive_bytes_total = u64::try_from(bytes.len()).ok();
+    let callback = reporting_callback(out_dir, callback);
+    progress::track(archive_bytes_total, Some(callback), || {
+        extractor_fn(bytes, extract_directories)
+    })
+}
+
+/// Creates a callback that passes the progress of an extraction to the `out_dir` to the
+/// [progress reporter](crate::ScopedOptions::progress_reporter) of the scope and the `callback`,
+/// if any.
+fn reporting_callback(out_dir: &Path, callback: Option<ProgressCallback>) -> ProgressCallback {
+    let out_dir = out_dir.to_path_buf();
+    Arc::new(move |extract_progress: &ExtractProgress| {
+        reporter::report_extract(&out_dir, extract_progress);
+        if let Some(callback) = &callback {
+            callback(extract_progress);
+        }
+    })
+}
+
+/// Extracts the archive file at [path](Path) to the [out_dir](Path), streaming the archive from
+/// disk. Archives for URLs without a registered file extractor are read into memory and
+/// extracted with the registered extractor.
+///
+/// # Errors
+/// Returns an error if the extraction fails.
+#[instrument(fields(operation = "extract", path = %out_dir.display()))]
+pub async fn extract_from_path(url: &str, path: &Path, out_dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut extract_directories = extractor::ExtractDirectories::default();
+    extract_directories.add_mapping(Regex::new(".*")?, out_dir.to_path_buf());
+    extract_directories.set_filter(scoped::extract_filter());
+    let archive_bytes_total = std::fs::metadata(path)?.len();
+    let callback = reporting_callback(out_dir, None);
+    progress::track(Some(archive_bytes_total), Some(callback), || {
+        match extractor::registry::get_file(url) {
+            Ok(extract_file_fn) => extract_file_fn(path, extract_directories),
+            Err(UnsupportedExtractor(_)) => {
+                let extractor_fn = extractor::registry::get(url)?;
+                extractor_fn(&std::fs::read(path)?, extract_directories)
+            }
+            Err(error) => Err(error),
+        }
+    })
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::cache::{ArchiveCache, FileSystemCache};
     use crate::configuration::theseus::URL;
+    use crate::DownloadOptions;
+
+    #[test]
+    fn test_get_cached() -> Result<()> {
+        let cache_dir = tempfile::tempdir()?;
+        let download_options = DownloadOptions {
+            cache_dir: Some(cache_dir.path().to_path_buf()),
+            cache_archives: true,
+            offline: true,
+            ..DownloadOptions::default()
+        };
+        let options = ScopedOptions {
+            download_options: Some(download_options.clone()),
+            ..ScopedOptions::default()
+        };
+        scoped::with_scoped_options_blocking(options, || -> Result<()> {
+            let archive = Archive::new(
+                "postgresql-16.4.0.tar.gz".to_string(),
+                Version::new(16, 4, 0),
+                vec![1, 2, 3],
+            );
+            cache_archive(URL, &archive);
+
+            let (version, path) = get_cached(URL, &VersionReq::parse("=16")?, Channel::Stable)?;
+            assert_eq!(Version::new(16, 4, 0), version);
+            assert_eq!(vec![1, 2, 3], cache::read_archive(&path)?);
+
+            let error = get_cached(URL, &VersionReq::parse("=17")?, Channel::Stable)
+                .expect_err("cache miss");
+            assert!(matches!(
+                &error,
+                OfflineCacheMiss { cached_versions, .. }
+                    if cached_versions == &vec![Version::new(16, 4, 0)]
+            ));
+            assert!(error.to_string().contains("16.4.0"));
+            Ok(())
+        })?;
+
+        let options = ScopedOptions {
+            download_options: Some(DownloadOptions {
+                cache_archives: false,
+                ..download_options
+            }),
+            ..ScopedOptions::default()
+        };
+        let error = scoped::with_scoped_options_blocking(options, || {
+            get_cached(URL, &VersionReq::parse("=16")?, Channel::Stable)
+        })
+        .expect_err("cache disabled");
+        assert!(matches!(
+            &error,
+            OfflineCacheMiss { cached_versions, .. } if cached_versions.is_empty()
+        ));
+
+        // An archive cache of the scope is used regardless of the download options
+        let cache: Arc<dyn ArchiveCache> = Arc::new(FileSystemCache::new(cache_dir.path()));
+        let options = ScopedOptions {
+            cache: Some(cache),
+            ..ScopedOptions::default()
+        };
+        let (version, _) = scoped::with_scoped_options_blocking(options, || {
+            get_cached(URL, &VersionReq::parse("=16")?, Channel::Stable)
+        })?;
+        assert_eq!(Version::new(16, 4, 0), version);
+        Ok(())
+    }
 
     #[tokio::test]
     async fn test_get_version() -> Result<()> {
@@ -61,6 +526,31 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_latest_of() -> Result<()> {
+        let version = latest_of(URL, 16).await?;
+        assert_eq!(16, version.major);
+        assert!(version >= Version::new(16, 4, 0));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_list_versions() -> Result<()> {
+        let versions = list_versions(URL).await?;
+        assert!(versions.contains(&Version::new(16, 4, 0)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_release_info() -> Result<()> {
+        let version_req = VersionReq::parse("=16.4.0")?;
+        let release_info = get_release_info(URL, &version_req, Channel::Stable).await?;
+        assert_eq!(Version::new(16, 4, 0), release_info.version);
+        assert!(release_info.published_at.is_some());
+        assert!(release_info.size.is_some_and(|size| size > 0));
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_get_archive() -> Result<()> {
         let version_req = VersionReq::parse("=16.4.0")?;
@@ -69,4 +559,60 @@ mod tests {
         assert!(!bytes.is_empty());
         Ok(())
     }
+
+    #[cfg(all(feature = "file", feature = "minisign"))]
+    #[tokio::test]
+    async fn test_verify_signature() -> Result<()> {
+        // Test vectors of the minisign-verify crate
+        const PUBLIC_KEY: &str = "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3";
+        const SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RWQf6LRCGA9i59SLOFxz6NxvASXDJeRtuZykwQepbDEGt87ig1BNpWaVWuNrm73YiIiJbq71Wi+dP9eKL8OC351vwIasSSbXxwA=
+trusted comment: timestamp:1555779966\tfile:test
+QtKMXWyYcwdpZAlPF7tE2ENJkRd1ujvKjlj1m9RtHTBnZPa5WKU5uWRs5GoP5M/VqE81QFuMKI5k/SfNQUaOAA==";
+
+        let temp_dir = tempfile::tempdir()?;
+        let archive_path = temp_dir.path().join("postgresql-16.4.0.tar.gz");
+        std::fs::write(&archive_path, "test")?;
+        let url = url::Url::from_file_path(&archive_path)
+            .map_err(|()| crate::Error::RepositoryFailure("invalid path".to_string()))?
+            .to_string();
+        minisign::register(&url, PUBLIC_KEY)?;
+
+        let error = get_archive(&url, &VersionReq::STAR)
+            .await
+            .expect_err("signature not found");
+        assert!(matches!(error, SignatureNotFound(_)));
+        let path = temp_dir.path().join("archive.tar.gz");
+        assert!(get_archive_to_file(&url, &VersionReq::STAR, &path)
+            .await
+            .is_err());
+        assert!(!path.exists());
+
+        let signature_path = temp_dir.path().join("postgresql-16.4.0.tar.gz.minisig");
+        std::fs::write(&signature_path, SIGNATURE)?;
+        let (version, bytes) = get_archive(&url, &VersionReq::STAR).await?;
+        assert_eq!(Version::new(16, 4, 0), version);
+        assert_eq!(b"test", bytes.as_slice());
+        get_archive_to_file(&url, &VersionReq::STAR, &path).await?;
+
+        std::fs::write(&archive_path, "tampered")?;
+        assert!(get_archive(&url, &VersionReq::STAR).await.is_err());
+        minisign::unregister(&url)?;
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_archive_to_file_and_extract_from_path() -> Result<()> {
+        let version_req = VersionReq::parse("=16.4.0")?;
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("postgresql.tar.gz");
+        let version = get_archive_to_file(URL, &version_req, &path).await?;
+        assert_eq!(Version::new(16, 4, 0), version);
+
+        let out_dir = temp_dir.path().join("postgresql");
+        let files = extract_from_path(URL, &path, &out_dir).await?;
+        assert!(!files.is_empty());
+        assert!(out_dir.join("bin").exists());
+        Ok(())
+    }
 }
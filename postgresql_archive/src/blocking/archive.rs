@@ -1,4 +1,4 @@
-use crate::{Version, VersionReq};
+use crate::{Channel, ReleaseInfo, Version, VersionReq};
 use std::path::{Path, PathBuf};
 use std::sync::LazyLock;
 use tokio::runtime::Runtime;
@@ -31,6 +31,106 @@ pub fn get_archive(url: &str, version_req: &VersionReq) -> crate::Result<(Versio
         .block_on(async move { crate::get_archive(url, version_req).await })
 }
 
+/// Downloads the archive for a given [version requirement](VersionReq) that passes the default
+/// matcher to the file at `path`, and returns the version of the archive.
+///
+/// # Errors
+/// * If the archive is not found.
+/// * If the archive cannot be downloaded.
+pub fn get_archive_to_file(
+    url: &str,
+    version_req: &VersionReq,
+    path: &Path,
+) -> crate::Result<Version> {
+    RUNTIME
+        .handle()
+        .block_on(async move { crate::get_archive_to_file(url, version_req, path).await })
+}
+
+/// Gets the archive for a given [version requirement](VersionReq) built for the specified
+/// `target` triple rather than the target this crate was built for.
+///
+/// # Errors
+/// * If the archive is not found.
+/// * If the archive cannot be downloaded.
+pub fn get_archive_for_target(
+    url: &str,
+    version_req: &VersionReq,
+    target: &str,
+) -> crate::Result<(Version, Vec<u8>)> {
+    RUNTIME
+        .handle()
+        .block_on(async move { crate::get_archive_for_target(url, version_req, target).await })
+}
+
+/// Gets all the versions published by the repository at the `url`, including pre-releases, in
+/// descending order.
+///
+/// # Errors
+/// * If the versions cannot be retrieved.
+pub fn list_versions(url: &str) -> crate::Result<Vec<Version>> {
+    RUNTIME
+        .handle()
+        .block_on(async move { crate::list_versions(url).await })
+}
+
+/// Gets the latest stable version of the `major` version; e.g. `16.6.0` for `16`.
+///
+/// # Errors
+/// * If the version is not found.
+pub fn latest_of(url: &str, major: u64) -> crate::Result<Version> {
+    RUNTIME
+        .handle()
+        .block_on(async move { crate::latest_of(url, major).await })
+}
+
+/// Gets the version for the specified [version requirement](VersionReq) in the release
+/// [channel](Channel). If a version for the [version requirement](VersionReq) is not found, then
+/// an error is returned.
+///
+/// # Errors
+/// * If the version is not found.
+pub fn get_version_in_channel(
+    url: &str,
+    version_req: &VersionReq,
+    channel: Channel,
+) -> crate::Result<Version> {
+    RUNTIME
+        .handle()
+        .block_on(async move { crate::get_version_in_channel(url, version_req, channel).await })
+}
+
+/// Gets the [release information](ReleaseInfo) of the latest version for the specified
+/// [version requirement](VersionReq) in the release [channel](Channel).
+///
+/// # Errors
+/// * If the version is not found.
+pub fn get_release_info(
+    url: &str,
+    version_req: &VersionReq,
+    channel: Channel,
+) -> crate::Result<ReleaseInfo> {
+    RUNTIME
+        .handle()
+        .block_on(async move { crate::get_release_info(url, version_req, channel).await })
+}
+
+/// Gets the archive for a given [version requirement](VersionReq) in the release
+/// [channel](Channel) that passes the default matcher.
+///
+/// # Errors
+/// * If the archive is not found.
+/// * If the archive cannot be downloaded.
+pub fn get_archive_in_channel(
+    url: &str,
+    version_req: &VersionReq,
+    channel: Channel,
+) -> crate::Result<(Version, Vec<u8>)> {
+    RUNTIME
+        .handle()
+        .block_on(async move { crate::get_archive_in_channel(url, version_req, channel).await })
+}
+
 /// Extracts the compressed tar `bytes` to the [out_dir](Path).
 ///
 /// # Errors
@@ -40,3 +140,33 @@ pub fn extract(url: &str, bytes: &Vec<u8>, out_dir: &Path) -> crate::Result<Vec<
         .handle()
         .block_on(async move { crate::extract(url, bytes, out_dir).await })
 }
+
+/// Extracts the compressed tar `bytes` to the [out_dir](Path), passing the
+/// [progress](crate::ExtractProgress) of the extraction to `on_progress` as each file is
+/// extracted.
+///
+/// # Errors
+/// Returns an error if the extraction fails.
+pub fn extract_with_progress<F>(
+    url: &str,
+    bytes: &Vec<u8>,
+    out_dir: &Path,
+    on_progress: F,
+) -> crate::Result<Vec<PathBuf>>
+where
+    F: Fn(&crate::ExtractProgress) + Send + Sync + 'static,
+{
+    RUNTIME.handle().block_on(async move {
+        crate::extract_with_progress(url, bytes, out_dir, on_progress).await
+    })
+}
+
+/// Extracts the archive file at [path](Path) to the [out_dir](Path).
+///
+/// # Errors
+/// Returns an error if the extraction fails.
+pub fn extract_from_path(url: &str, path: &Path, out_dir: &Path) -> crate::Result<Vec<PathBuf>> {
+    RUNTIME
+        .handle()
+        .block_on(async move { crate::extract_from_path(url, path, out_dir).await })
+}
@@ -1,3 +1,7 @@
 mod archive;
 
-pub use archive::{extract, get_archive, get_version};
+pub use archive::{
+    extract, extract_from_path, extract_with_progress, get_archive, get_archive_for_target,
+    get_archive_in_channel, get_archive_to_file, get_release_info, get_version,
+    get_version_in_channel, latest_of, list_versions,
+};
@@ -0,0 +1,1003 @@
+use crate::download::create_cache_dir;
+use crate::extractor::ExtractFilter;
+use crate::repository::Archive;
+#[cfg(not(feature = "sha2"))]
+use crate::Error::UnsupportedHasher;
+use crate::Error::{ArchiveHashMismatch, AssetHashNotFound, IoError, VersionNotFound};
+use crate::{Channel, Result};
+use semver::Version;
+use semver::VersionReq;
+#[cfg(feature = "sha2")]
+use sha2::{Digest, Sha256};
+use std::cmp::Reverse;
+use std::fmt::Debug;
+use std::fs::{self, create_dir_all};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+use tracing::{debug, warn};
+
+/// Directory of the cache directory that contains the cached archives
+const ARCHIVES_DIR: &str = "archives";
+/// Directory of the cache directory that contains the cached release metadata
+#[cfg(feature = "github")]
+const METADATA_DIR: &str = "metadata";
+/// File of a cached metadata directory that contains the response body
+#[cfg(feature = "github")]
+const METADATA_BODY_FILE: &str = "body";
+/// File of a cached metadata directory that contains the entity tag of the response
+#[cfg(feature = "github")]
+const METADATA_ETAG_FILE: &str = "etag";
+/// File of a cached repository directory that contains the URL of the repository
+const URL_FILE: &str = ".url";
+/// Extension of the file next to a cached archive that contains the SHA2-256 hash of the archive
+const HASH_EXTENSION: &str = "sha256";
+
+/// File in an installation directory that lists the SHA2-256 hash of each installed file, in the
+/// format of `sha256sum`
+pub const MANIFEST_FILE: &str = ".manifest.sha256";
+/// File in an installation directory that contains the [filter](ExtractFilter) the installation
+/// was extracted with; installations without the file contain all files of the archive
+pub const FILTER_FILE: &str = ".extract_filter";
+
+/// A version installed in the cache directory (e.g. `~/.theseus/postgresql/16.4.0`)
+#[derive(Clone, Debug, PartialEq)]
+pub struct CacheEntry {
+    /// Installed version
+    pub version: Version,
+    /// Installation directory of the version
+    pub path: PathBuf,
+    /// Total size of the files of the installation, in bytes
+    pub size: u64,
+    /// Time the installation directory was last modified
+    pub modified: SystemTime,
+}
+
+/// Policy for the installed versions that are [pruned](prune) from the cache directory, and the
+/// archives that are [pruned](ArchiveCache::prune) from an archive cache
+#[derive(Clone, Debug, PartialEq)]
+pub enum PrunePolicy {
+    /// Keep the versions that match the version requirement
+    Keep(VersionReq),
+    /// Keep the versions that were modified within the duration
+    MaxAge(Duration),
+    /// Keep the latest versions whose total size does not exceed the number of bytes
+    MaxSize(u64),
+}
+
+impl PrunePolicy {
+    /// Whether the policy keeps the `version` modified at `modified`, given the `total_size` of
+    /// the entries up to and including the version.
+    fn keeps(
+        &self,
+        version: &Version,
+        modified: SystemTime,
+        total_size: u64,
+        now: SystemTime,
+    ) -> bool {
+        match self {
+            PrunePolicy::Keep(version_req) => version_req.matches(version),
+            PrunePolicy::MaxAge(max_age) => {
+                !matches!(now.duration_since(modified), Ok(age) if age > *max_age)
+            }
+            PrunePolicy::MaxSize(max_size) => total_size <= *max_size,
+        }
+    }
+}
+
+/// Lists the versions installed in the `cache_dir`, in descending order; each in a directory
+/// named after its version.
+///
+/// # Errors
+/// * If the cache directory, or an installation directory, cannot be read.
+pub fn list_installed(cache_dir: &Path) -> Result<Vec<CacheEntry>> {
+    if !cache_dir.is_dir() {
+        return Ok(Vec::new());
+    }
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(cache_dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        let Ok(version) = Version::parse(&entry.file_name().to_string_lossy()) else {
+            continue;
+        };
+        if !path.is_dir() {
+            continue;
+        }
+        let metadata = entry.metadata()?;
+        entries.push(CacheEntry {
+            version,
+            size: dir_size(&path)?,
+            modified: metadata.modified()?,
+            path,
+        });
+    }
+    entries.sort_by(|a, b| b.version.cmp(&a.version));
+    Ok(entries)
+}
+
+/// Removes the versions installed in the `cache_dir` that are not kept by the `policy`; the
+/// versions `in_use`, such as the versions of running servers, are never removed, but count
+/// towards the [maximum size](PrunePolicy::MaxSize). Returns the removed versions.
+///
+/// # Errors
+/// * If the cache directory cannot be read, or an installation directory cannot be removed.
+pub fn prune(
+    cache_dir: &Path,
+    policy: &PrunePolicy,
+    in_use: &[Version],
+) -> Result<Vec<CacheEntry>> {
+    let now = SystemTime::now();
+    let mut total_size = 0u64;
+    let mut removed = Vec::new();
+    for entry in list_installed(cache_dir)? {
+        total_size = total_size.saturating_add(entry.size);
+        let keep = policy.keeps(&entry.version, entry.modified, total_size, now);
+        if keep || in_use.contains(&entry.version) {
+            continue;
+        }
+        debug!(
+            "Pruning PostgreSQL {} from {}",
+            entry.version,
+            entry.path.to_string_lossy()
+        );
+        fs::remove_dir_all(&entry.path)?;
+        if matches!(policy, PrunePolicy::MaxSize(_)) {
+            total_size = total_size.saturating_sub(entry.size);
+        }
+        removed.push(entry);
+    }
+    Ok(removed)
+}
+
+/// Verifies the files of the `version` installed in the `cache_dir` by re-hashing them and
+/// comparing the hashes with the [manifest](MANIFEST_FILE) written when the version was
+/// installed. Returns the paths, relative to the installation directory, of the files that are
+/// missing or modified; an empty list if the installation is intact.
+///
+/// # Errors
+/// * If the version is not installed, or the installation has no manifest.
+/// * If the manifest cannot be read.
+pub fn verify(cache_dir: &Path, version: &Version) -> Result<Vec<PathBuf>> {
+    let installation_dir = cache_dir.join(version.to_string());
+    if !installation_dir.is_dir() {
+        return Err(VersionNotFound(version.to_string()));
+    }
+    verify_dir(&installation_dir)
+}
+
+/// Verifies the files of the installation in the `installation_dir`, in any layout, by
+/// re-hashing them and comparing the hashes with the [manifest](MANIFEST_FILE) written when the
+/// installation was extracted. Returns the paths, relative to the installation directory, of the
+/// files that are missing or modified; an empty list if the installation is intact.
+///
+/// # Errors
+/// * If the installation has no manifest, or the manifest cannot be read.
+pub fn verify_dir(installation_dir: &Path) -> Result<Vec<PathBuf>> {
+    let manifest_file = installation_dir.join(MANIFEST_FILE);
+    let manifest = fs::read_to_string(&manifest_file).map_err(|error| {
+        IoError(format!(
+            "cannot read manifest {}: {error}",
+            manifest_file.to_string_lossy()
+        ))
+    })?;
+
+    let mut invalid_files = Vec::new();
+    for line in manifest.lines().filter(|line| !line.is_empty()) {
+        let Some((hash, relative_path)) = line.split_once("  ") else {
+            return Err(IoError(format!("invalid manifest line: {line}")));
+        };
+        let relative_path = PathBuf::from(relative_path);
+        let path = installation_dir.join(&relative_path);
+        let valid = path.is_file() && hash_file(&path)? == hash;
+        if !valid {
+            debug!("Invalid file {}", path.to_string_lossy());
+            invalid_files.push(relative_path);
+        }
+    }
+    Ok(invalid_files)
+}
+
+/// Removes all versions installed in the `cache_dir`, and the archives cached in it.
+///
+/// # Errors
+/// * If the cache directory cannot be read, or a directory cannot be removed.
+pub fn clear(cache_dir: &Path) -> Result<()> {
+    for entry in list_installed(cache_dir)? {
+        fs::remove_dir_all(&entry.path)?;
+    }
+    let archives_dir = cache_dir.join(ARCHIVES_DIR);
+    if archives_dir.is_dir() {
+        fs::remove_dir_all(archives_dir)?;
+    }
+    Ok(())
+}
+
+/// Writes the [manifest](MANIFEST_FILE) of the files in the `installation_dir`, so that the
+/// installation can be [verified](verify); nothing is written without the `sha2` feature.
+/// Symbolic links are not followed.
+///
+/// # Errors
+/// * If a file cannot be read, or the manifest cannot be written.
+pub fn write_manifest(installation_dir: &Path) -> Result<()> {
+    if cfg!(not(feature = "sha2")) {
+        debug!(
+            "Not writing manifest of {}; the sha2 feature is disabled",
+            installation_dir.to_string_lossy()
+        );
+        return Ok(());
+    }
+    let mut files = Vec::new();
+    collect_files(installation_dir, installation_dir, &mut files)?;
+    files.sort();
+    let mut manifest = String::new();
+    for relative_path in files {
+        let hash = hash_file(&installation_dir.join(&relative_path))?;
+        let relative_path = relative_path.to_string_lossy().replace('\\', "/");
+        manifest.push_str(&format!("{hash}  {relative_path}\n"));
+    }
+    fs::write(installation_dir.join(MANIFEST_FILE), manifest)?;
+    Ok(())
+}
+
+/// Writes the [filter](FILTER_FILE) that the `installation_dir` was extracted with, so that an
+/// installation that lacks files is not used where they are needed; nothing is written for an
+/// empty filter.
+///
+/// # Errors
+/// * If the filter cannot be written.
+pub fn write_filter(installation_dir: &Path, filter: &ExtractFilter) -> Result<()> {
+    if filter.is_empty() {
+        return Ok(());
+    }
+    fs::write(installation_dir.join(FILTER_FILE), filter.to_string())?;
+    Ok(())
+}
+
+/// Gets the [filter](FILTER_FILE) that the `installation_dir` was extracted with; an empty
+/// filter if the installation contains all files of the archive.
+///
+/// # Errors
+/// * If the filter cannot be read or parsed.
+pub fn installed_filter(installation_dir: &Path) -> Result<ExtractFilter> {
+    let filter_file = installation_dir.join(FILTER_FILE);
+    if !filter_file.is_file() {
+        return Ok(ExtractFilter::new());
+    }
+    fs::read_to_string(filter_file)?.parse()
+}
+
+/// Collects the paths, relative to the `root`, of the regular files in the `dir` and its
+/// subdirectories, excluding the manifest and the filter.
+fn collect_files(root: &Path, dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        let path = entry.path();
+        if file_type.is_dir() {
+            collect_files(root, &path, files)?;
+        } else if file_type.is_file()
+            && entry.file_name() != MANIFEST_FILE
+            && entry.file_name() != FILTER_FILE
+        {
+            if let Ok(relative_path) = path.strip_prefix(root) {
+                files.push(relative_path.to_path_buf());
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Gets the SHA2-256 hash of the file at `path`, without reading it into memory.
+#[cfg(feature = "sha2")]
+fn hash_file(path: &Path) -> Result<String> {
+    let mut file = fs::File::open(path)?;
+    let mut hasher = Sha256::new();
+    std::io::copy(&mut file, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Gets the SHA2-256 hash of the file at `path`; files cannot be hashed without the `sha2`
+/// feature, so installations cannot be verified and archives are not cached.
+#[cfg(not(feature = "sha2"))]
+fn hash_file(path: &Path) -> Result<String> {
+    Err(UnsupportedHasher(format!(
+        "{HASH_EXTENSION} for {}",
+        path.to_string_lossy()
+    )))
+}
+
+/// Gets the SHA2-256 hash of the `bytes`.
+#[cfg(feature = "sha2")]
+#[expect(clippy::unnecessary_wraps)]
+fn hash_bytes(bytes: &[u8]) -> Result<String> {
+    Ok(hex::encode(Sha256::digest(bytes)))
+}
+
+/// Gets the SHA2-256 hash of the `bytes`; bytes cannot be hashed without the `sha2` feature.
+#[cfg(not(feature = "sha2"))]
+fn hash_bytes(_bytes: &[u8]) -> Result<String> {
+    Err(UnsupportedHasher(HASH_EXTENSION.to_string()))
+}
+
+/// Gets the total size of the regular files in the `dir` and its subdirectories, in bytes.
+fn dir_size(dir: &Path) -> Result<u64> {
+    let mut size = 0u64;
+    for entry in fs::read_dir(dir)? {
+        let entry = entry?;
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            size = size.saturating_add(dir_size(&entry.path())?);
+        } else if file_type.is_file() {
+            size = size.saturating_add(entry.metadata()?.len());
+        }
+    }
+    Ok(size)
+}
+
+/// Gets the directory of the archives cached for the repository `url` in the `cache_dir`; one
+/// directory per version, each containing the archive with its original name.
+pub(crate) fn archive_dir(cache_dir: &Path, url: &str) -> PathBuf {
+    cache_dir.join(ARCHIVES_DIR).join(cache_name(url))
+}
+
+/// Gets the name of the cache directory for the `url`.
+fn cache_name(url: &str) -> String {
+    url.chars()
+        .map(|c| if c.is_ascii_alphanumeric() { c } else { '_' })
+        .collect()
+}
+
+/// Release metadata cached from a repository response
+#[cfg(feature = "github")]
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct CachedMetadata {
+    /// Entity tag of the response, used to revalidate the metadata
+    pub etag: Option<String>,
+    /// Body of the response
+    pub body: String,
+    /// Time since the metadata was cached or last revalidated
+    pub age: Duration,
+}
+
+/// Gets the release metadata cached for the `url`, if any.
+#[cfg(feature = "github")]
+pub(crate) fn get_metadata(cache_dir: &Path, url: &str) -> Option<CachedMetadata> {
+    let metadata_dir = cache_dir.join(METADATA_DIR).join(cache_name(url));
+    let body_file = metadata_dir.join(METADATA_BODY_FILE);
+    let body = fs::read_to_string(&body_file).ok()?;
+    let age = fs::metadata(&body_file)
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok())
+        .unwrap_or_default();
+    let etag = fs::read_to_string(metadata_dir.join(METADATA_ETAG_FILE)).ok();
+    Some(CachedMetadata { etag, body, age })
+}
+
+/// Stores the release metadata `body` of the `url` with its `etag` in the cache; storing
+/// metadata again resets its age.
+///
+/// # Errors
+/// * If the metadata cannot be written.
+#[cfg(feature = "github")]
+pub(crate) fn store_metadata(
+    cache_dir: &Path,
+    url: &str,
+    etag: Option<&str>,
+    body: &str,
+) -> Result<()> {
+    let metadata_dir = cache_dir.join(METADATA_DIR).join(cache_name(url));
+    create_cache_dir(cache_dir)?;
+    create_dir_all(&metadata_dir)?;
+    let etag_file = metadata_dir.join(METADATA_ETAG_FILE);
+    match etag {
+        Some(etag) => fs::write(etag_file, etag)?,
+        None if etag_file.exists() => fs::remove_file(etag_file)?,
+        None => {}
+    }
+    let temp_file = metadata_dir.join(format!("{METADATA_BODY_FILE}.{}.tmp", std::process::id()));
+    fs::write(&temp_file, body)?;
+    fs::rename(&temp_file, metadata_dir.join(METADATA_BODY_FILE))?;
+    Ok(())
+}
+
+/// An archive cached from a repository; e.g. a `PostgreSQL` or an extension archive
+#[derive(Clone, Debug, PartialEq)]
+pub struct CachedArchive {
+    /// URL of the repository the archive was downloaded from
+    pub url: String,
+    /// Version of the archive
+    pub version: Version,
+    /// Path of the archive file
+    pub path: PathBuf,
+    /// Size of the archive, in bytes
+    pub size: u64,
+    /// Time the archive was cached
+    pub modified: SystemTime,
+}
+
+/// Cache of the archives downloaded from repositories. The `PostgreSQL` archives and the archives
+/// of extensions are cached alike, so that their size is accounted for, they are
+/// [pruned](ArchiveCache::prune) and they are resolved [offline](crate::DownloadOptions::offline)
+/// in the same way.
+pub trait ArchiveCache: Debug + Send + Sync {
+    /// Gets the versions of the archives cached for the repository `url`, in descending order.
+    fn versions(&self, url: &str) -> Vec<Version>;
+
+    /// Gets the latest cached version of the repository `url` that matches the [version
+    /// requirement](VersionReq) in the [channel](Channel), and the path of its archive; archives
+    /// that do not match the hash stored when they were cached are ignored.
+    fn get(
+        &self,
+        url: &str,
+        version_req: &VersionReq,
+        channel: Channel,
+    ) -> Option<(Version, PathBuf)>;
+
+    /// Stores the `archive` of the repository `url` in the cache.
+    ///
+    /// # Errors
+    /// * If the archive cannot be written.
+    fn store(&self, url: &str, archive: &Archive) -> Result<PathBuf>;
+
+    /// Stores the archive file at `path` with the `version` and `name` of the repository `url` in
+    /// the cache.
+    ///
+    /// # Errors
+    /// * If the archive cannot be copied.
+    fn store_file(&self, url: &str, version: &Version, name: &str, path: &Path) -> Result<PathBuf>;
+
+    /// Lists the cached archives of all repositories, latest first.
+    ///
+    /// # Errors
+    /// * If the cache cannot be read.
+    fn list(&self) -> Result<Vec<CachedArchive>>;
+
+    /// Removes the cached `archive`.
+    ///
+    /// # Errors
+    /// * If the archive cannot be removed.
+    fn remove(&self, archive: &CachedArchive) -> Result<()>;
+
+    /// Gets the total size of the cached archives, in bytes.
+    ///
+    /// # Errors
+    /// * If the cache cannot be read.
+    fn size(&self) -> Result<u64> {
+        Ok(self
+            .list()?
+            .iter()
+            .fold(0u64, |size, archive| size.saturating_add(archive.size)))
+    }
+
+    /// Removes the cached archives of all repositories that are not kept by the `policy`; the
+    /// version requirement of a [keep](PrunePolicy::Keep) policy applies to the archives of all
+    /// repositories, so [prune the archives of a repository](Self::prune_repository) to apply a
+    /// policy to one kind of archive, such as the archives of an extension. Returns the removed
+    /// archives.
+    ///
+    /// # Errors
+    /// * If the cache cannot be read, or an archive cannot be removed.
+    fn prune(&self, policy: &PrunePolicy) -> Result<Vec<CachedArchive>> {
+        prune_archives(self, self.list()?, policy)
+    }
+
+    /// Removes the cached archives of the repository `url` that are not kept by the `policy`; the
+    /// archives of other repositories are neither removed nor count towards the
+    /// [maximum size](PrunePolicy::MaxSize). Returns the removed archives.
+    ///
+    /// # Errors
+    /// * If the cache cannot be read, or an archive cannot be removed.
+    fn prune_repository(&self, url: &str, policy: &PrunePolicy) -> Result<Vec<CachedArchive>> {
+        let archives = self
+            .list()?
+            .into_iter()
+            .filter(|archive| archive.url == url)
+            .collect();
+        prune_archives(self, archives, policy)
+    }
+}
+
+/// Removes the `archives` of the `cache`, latest first, that are not kept by the `policy`.
+fn prune_archives<C: ArchiveCache + ?Sized>(
+    cache: &C,
+    archives: Vec<CachedArchive>,
+    policy: &PrunePolicy,
+) -> Result<Vec<CachedArchive>> {
+    let now = SystemTime::now();
+    let mut total_size = 0u64;
+    let mut removed = Vec::new();
+    for archive in archives {
+        total_size = total_size.saturating_add(archive.size);
+        if policy.keeps(&archive.version, archive.modified, total_size, now) {
+            continue;
+        }
+        debug!("Pruning cached archive {}", archive.path.to_string_lossy());
+        cache.remove(&archive)?;
+        total_size = total_size.saturating_sub(archive.size);
+        removed.push(archive);
+    }
+    Ok(removed)
+}
+
+/// [Archive cache](ArchiveCache) in the `archives` directory of a cache directory; e.g. the
+/// [cache directory](crate::DownloadOptions::cache_dir) of the download options. Each repository
+/// has a directory with a directory per version, which contains the archive with its original
+/// name and a `.sha256` file with the hash of the archive, which the archive is
+/// verified against whenever it is read.
+#[derive(Clone, Debug, PartialEq)]
+pub struct FileSystemCache {
+    cache_dir: PathBuf,
+}
+
+impl FileSystemCache {
+    /// Creates a new [`FileSystemCache`] in the `cache_dir`.
+    #[must_use]
+    pub fn new<P: Into<PathBuf>>(cache_dir: P) -> Self {
+        Self {
+            cache_dir: cache_dir.into(),
+        }
+    }
+
+    /// Stores an archive in the cache by calling `write` with the path of a temporary file, and
+    /// renaming the file once it is written, so that a partially written archive is never read
+    /// from the cache.
+    fn store_with<F>(&self, url: &str, version: &Version, name: &str, write: F) -> Result<PathBuf>
+    where
+        F: FnOnce(&Path) -> Result<()>,
+    {
+        let repository_dir = archive_dir(&self.cache_dir, url);
+        let version_dir = repository_dir.join(version.to_string());
+        create_cache_dir(&self.cache_dir)?;
+        create_dir_all(&version_dir)?;
+        fs::write(repository_dir.join(URL_FILE), url)?;
+        let path = version_dir.join(name);
+        let temp_path = version_dir.join(format!("{name}.{}.tmp", std::process::id()));
+        let hash = write(&temp_path).and_then(|()| hash_file(&temp_path));
+        let hash = match hash {
+            Ok(hash) => hash,
+            Err(error) => {
+                let _ = fs::remove_file(&temp_path);
+                return Err(error);
+            }
+        };
+        // The hash is written first, so that an archive is never read without its hash
+        fs::write(hash_path(&path), hash)?;
+        fs::rename(&temp_path, &path)?;
+        debug!("Cached archive {}", path.to_string_lossy());
+        Ok(path)
+    }
+}
+
+impl ArchiveCache for FileSystemCache {
+    fn versions(&self, url: &str) -> Vec<Version> {
+        let Ok(entries) = fs::read_dir(archive_dir(&self.cache_dir, url)) else {
+            return Vec::new();
+        };
+        let mut versions = entries
+            .filter_map(|entry| {
+                let entry = entry.ok()?;
+                let version = Version::parse(&entry.file_name().to_string_lossy()).ok()?;
+                cached_archive(&entry.path()).map(|_| version)
+            })
+            .collect::<Vec<_>>();
+        versions.sort_by(|a, b| b.cmp(a));
+        versions
+    }
+
+    fn get(
+        &self,
+        url: &str,
+        version_req: &VersionReq,
+        channel: Channel,
+    ) -> Option<(Version, PathBuf)> {
+        let archive_dir = archive_dir(&self.cache_dir, url);
+        self.versions(url)
+            .into_iter()
+            .find(|version| channel.matches(version_req, version))
+            .and_then(|version| {
+                let path = cached_archive(&archive_dir.join(version.to_string()))?;
+                if let Err(error) = verify_archive(&path) {
+                    warn!(
+                        "Ignoring cached archive {}: {error}",
+                        path.to_string_lossy()
+                    );
+                    return None;
+                }
+                Some((version, path))
+            })
+    }
+
+    fn store(&self, url: &str, archive: &Archive) -> Result<PathBuf> {
+        self.store_with(url, archive.version(), archive.name(), |path| {
+            fs::write(path, archive.bytes())?;
+            Ok(())
+        })
+    }
+
+    fn store_file(&self, url: &str, version: &Version, name: &str, path: &Path) -> Result<PathBuf> {
+        self.store_with(url, version, name, |temp_path| {
+            fs::copy(path, temp_path)?;
+            Ok(())
+        })
+    }
+
+    fn list(&self) -> Result<Vec<CachedArchive>> {
+        let archives_dir = self.cache_dir.join(ARCHIVES_DIR);
+        if !archives_dir.is_dir() {
+            return Ok(Vec::new());
+        }
+        let mut archives = Vec::new();
+        for repository_entry in fs::read_dir(archives_dir)? {
+            let repository_dir = repository_entry?.path();
+            if !repository_dir.is_dir() {
+                continue;
+            }
+            // Archives cached before the URL was recorded are listed with the directory name
+            let url = fs::read_to_string(repository_dir.join(URL_FILE)).unwrap_or_else(|_| {
+                repository_dir
+                    .file_name()
+                    .map(|name| name.to_string_lossy().to_string())
+                    .unwrap_or_default()
+            });
+            for version_entry in fs::read_dir(&repository_dir)? {
+                let version_entry = version_entry?;
+                let Ok(version) = Version::parse(&version_entry.file_name().to_string_lossy())
+                else {
+                    continue;
+                };
+                let Some(path) = cached_archive(&version_entry.path()) else {
+                    continue;
+                };
+                let metadata = fs::metadata(&path)?;
+                archives.push(CachedArchive {
+                    url: url.clone(),
+                    version,
+                    path,
+                    size: metadata.len(),
+                    modified: metadata.modified()?,
+                });
+            }
+        }
+        archives.sort_by_key(|archive| Reverse(archive.modified));
+        Ok(archives)
+    }
+
+    fn remove(&self, archive: &CachedArchive) -> Result<()> {
+        match archive.path.parent() {
+            Some(version_dir) => fs::remove_dir_all(version_dir)?,
+            None => fs::remove_file(&archive.path)?,
+        }
+        Ok(())
+    }
+}
+
+/// Gets the path of the archive in the version directory `version_dir`, if any.
+fn cached_archive(version_dir: &Path) -> Option<PathBuf> {
+    fs::read_dir(version_dir)
+        .ok()?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .find(|path| {
+            path.is_file()
+                && !path.file_name().is_some_and(|name| {
+                    let name = name.to_string_lossy();
+                    name.ends_with(".tmp") || name.ends_with(&format!(".{HASH_EXTENSION}"))
+                })
+        })
+}
+
+/// Gets the path of the file with the hash of the cached archive at `path`.
+fn hash_path(path: &Path) -> PathBuf {
+    let mut hash_path = path.as_os_str().to_owned();
+    hash_path.push(format!(".{HASH_EXTENSION}"));
+    PathBuf::from(hash_path)
+}
+
+/// Gets the hash stored when the archive at `path` was cached.
+///
+/// # Errors
+/// * If the archive has no stored hash.
+fn stored_hash(path: &Path) -> Result<String> {
+    fs::read_to_string(hash_path(path))
+        .map(|hash| hash.trim().to_string())
+        .map_err(|_| AssetHashNotFound(path.to_string_lossy().to_string()))
+}
+
+/// Verifies the `archive_hash` of the cached archive at `path` against its stored hash.
+///
+/// # Errors
+/// * If the archive has no stored hash, or the hashes do not match.
+fn check_hash(path: &Path, archive_hash: String) -> Result<()> {
+    let hash = stored_hash(path)?;
+    if archive_hash != hash {
+        return Err(ArchiveHashMismatch { archive_hash, hash });
+    }
+    Ok(())
+}
+
+/// Verifies the cached archive at `path` against the hash stored when it was cached.
+///
+/// # Errors
+/// * If the archive cannot be read, has no stored hash, or does not match the stored hash.
+pub(crate) fn verify_archive(path: &Path) -> Result<()> {
+    check_hash(path, hash_file(path)?)
+}
+
+/// Copies the cached archive at `path` to the file at `to`, without reading it into memory, and
+/// verifies the copy against the hash stored when the archive was cached; the copy is removed if
+/// it does not match.
+///
+/// # Errors
+/// * If the archive cannot be copied, has no stored hash, or does not match the stored hash.
+pub(crate) fn copy_archive(path: &Path, to: &Path) -> Result<()> {
+    fs::copy(path, to)?;
+    let result = hash_file(to).and_then(|archive_hash| check_hash(path, archive_hash));
+    if result.is_err() {
+        let _ = fs::remove_file(to);
+    }
+    result
+}
+
+/// Reads the cached archive at `path`, and verifies the bytes that were read against the hash
+/// stored when the archive was cached.
+///
+/// # Errors
+/// * If the archive cannot be read, has no stored hash, or does not match the stored hash.
+pub(crate) fn read_archive(path: &Path) -> Result<Vec<u8>> {
+    let bytes = fs::read(path)?;
+    check_hash(path, hash_bytes(&bytes)?)?;
+    Ok(bytes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const URL: &str = "https://github.com/theseus-rs/postgresql-binaries";
+
+    #[test]
+    fn test_cache() -> Result<()> {
+        let cache_dir = tempfile::tempdir()?;
+        let cache = FileSystemCache::new(cache_dir.path());
+        assert!(cache.versions(URL).is_empty());
+
+        for version in [Version::new(16, 3, 0), Version::new(16, 4, 0)] {
+            let archive = Archive::new(
+                format!("postgresql-{version}.tar.gz"),
+                version,
+                vec![1, 2, 3],
+            );
+            cache.store(URL, &archive)?;
+        }
+        assert_eq!(
+            vec![Version::new(16, 4, 0), Version::new(16, 3, 0)],
+            cache.versions(URL)
+        );
+
+        let (version, path) = cache
+            .get(URL, &VersionReq::parse("=16.3.0")?, Channel::Stable)
+            .expect("cached archive");
+        assert_eq!(Version::new(16, 3, 0), version);
+        assert!(path.ends_with("postgresql-16.3.0.tar.gz"));
+        assert_eq!(vec![1, 2, 3], read_archive(&path)?);
+        assert!(cache
+            .get(URL, &VersionReq::parse("=17")?, Channel::Stable)
+            .is_none());
+        assert!(cache.versions("https://example.com").is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_cache_verifies_archives() -> Result<()> {
+        let cache_dir = tempfile::tempdir()?;
+        let cache = FileSystemCache::new(cache_dir.path());
+        let version_req = VersionReq::parse("=16.4.0")?;
+        let archive = Archive::new(
+            "postgresql-16.4.0.tar.gz".to_string(),
+            Version::new(16, 4, 0),
+            vec![1, 2, 3],
+        );
+        let path = cache.store(URL, &archive)?;
+        assert!(verify_archive(&path).is_ok());
+
+        fs::write(&path, [3, 2, 1])?;
+        assert!(matches!(
+            read_archive(&path),
+            Err(ArchiveHashMismatch { .. })
+        ));
+        assert!(cache.get(URL, &version_req, Channel::Stable).is_none());
+
+        fs::remove_file(hash_path(&path))?;
+        assert!(matches!(read_archive(&path), Err(AssetHashNotFound(_))));
+        assert!(cache.get(URL, &version_req, Channel::Stable).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn test_archive_cache_prune() -> Result<()> {
+        let cache_dir = tempfile::tempdir()?;
+        let cache = FileSystemCache::new(cache_dir.path());
+        assert!(cache.list()?.is_empty());
+        let extension_url = "https://github.com/tensorchord/pgvecto.rs?postgresql_version=16";
+        for (url, version) in [
+            (URL, Version::new(15, 8, 0)),
+            (URL, Version::new(16, 4, 0)),
+            (extension_url, Version::new(0, 3, 0)),
+        ] {
+            let archive = Archive::new(format!("archive-{version}.tar.gz"), version, vec![1, 2]);
+            cache.store(url, &archive)?;
+        }
+
+        let archives = cache.list()?;
+        assert_eq!(3, archives.len());
+        assert!(archives.iter().any(|archive| archive.url == extension_url));
+        assert_eq!(6, cache.size()?);
+
+        let removed = cache.prune(&PrunePolicy::Keep(VersionReq::parse(">=0.3")?))?;
+        assert!(removed.is_empty());
+        let removed = cache.prune(&PrunePolicy::MaxSize(4))?;
+        assert_eq!(1, removed.len());
+        assert_eq!(2, cache.list()?.len());
+        assert_eq!(4, cache.size()?);
+
+        // Only the archives of the repository are pruned by a policy of the repository
+        let removed =
+            cache.prune_repository(URL, &PrunePolicy::Keep(VersionReq::parse(">=16")?))?;
+        assert!(removed.iter().all(|archive| archive.url == URL));
+        assert!(cache
+            .list()?
+            .iter()
+            .all(|archive| archive.url != URL || archive.version >= Version::new(16, 0, 0)));
+        Ok(())
+    }
+
+    fn install(cache_dir: &Path, version: &Version, contents: &str) -> Result<PathBuf> {
+        let installation_dir = cache_dir.join(version.to_string());
+        create_dir_all(installation_dir.join("bin"))?;
+        fs::write(installation_dir.join("bin").join("postgres"), contents)?;
+        write_manifest(&installation_dir)?;
+        Ok(installation_dir)
+    }
+
+    #[test]
+    #[cfg(feature = "github")]
+    fn test_metadata() -> Result<()> {
+        let cache_dir = tempfile::tempdir()?;
+        let cache_dir = cache_dir.path();
+        let url = "https://api.github.com/repos/theseus-rs/postgresql-binaries/releases?page=1";
+        assert!(get_metadata(cache_dir, url).is_none());
+
+        store_metadata(cache_dir, url, Some("\"etag\""), "[]")?;
+        let metadata = get_metadata(cache_dir, url).expect("cached metadata");
+        assert_eq!(Some("\"etag\"".to_string()), metadata.etag);
+        assert_eq!("[]", metadata.body);
+        assert!(metadata.age < Duration::from_secs(60));
+
+        store_metadata(cache_dir, url, None, "[{}]")?;
+        let metadata = get_metadata(cache_dir, url).expect("cached metadata");
+        assert_eq!(None, metadata.etag);
+        assert_eq!("[{}]", metadata.body);
+        Ok(())
+    }
+
+    #[test]
+    fn test_list_installed() -> Result<()> {
+        let cache_dir = tempfile::tempdir()?;
+        let cache_dir = cache_dir.path();
+        assert!(list_installed(&cache_dir.join("missing"))?.is_empty());
+        install(cache_dir, &Version::new(15, 8, 0), "15")?;
+        install(cache_dir, &Version::new(16, 4, 0), "16")?;
+        create_dir_all(cache_dir.join("not-a-version"))?;
+
+        let entries = list_installed(cache_dir)?;
+        let versions = entries
+            .iter()
+            .map(|entry| entry.version.clone())
+            .collect::<Vec<_>>();
+        assert_eq!(
+            vec![Version::new(16, 4, 0), Version::new(15, 8, 0)],
+            versions
+        );
+        assert!(entries.iter().all(|entry| entry.size > 2));
+        Ok(())
+    }
+
+    #[test]
+    fn test_prune() -> Result<()> {
+        let cache_dir = tempfile::tempdir()?;
+        let cache_dir = cache_dir.path();
+        for version in [
+            Version::new(14, 13, 0),
+            Version::new(15, 8, 0),
+            Version::new(16, 4, 0),
+        ] {
+            install(cache_dir, &version, &version.to_string())?;
+        }
+
+        let removed = prune(
+            cache_dir,
+            &PrunePolicy::Keep(VersionReq::parse(">=15")?),
+            &[Version::new(14, 13, 0)],
+        )?;
+        assert!(removed.is_empty());
+        let removed = prune(
+            cache_dir,
+            &PrunePolicy::Keep(VersionReq::parse(">=15")?),
+            &[],
+        )?;
+        assert_eq!(
+            vec![Version::new(14, 13, 0)],
+            vec![removed[0].version.clone()]
+        );
+
+        let removed = prune(
+            cache_dir,
+            &PrunePolicy::MaxAge(Duration::from_secs(3600)),
+            &[],
+        )?;
+        assert!(removed.is_empty());
+
+        let size = list_installed(cache_dir)?[0].size;
+        let removed = prune(cache_dir, &PrunePolicy::MaxSize(size), &[])?;
+        assert_eq!(Version::new(15, 8, 0), removed[0].version);
+        assert_eq!(1, list_installed(cache_dir)?.len());
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify() -> Result<()> {
+        let cache_dir = tempfile::tempdir()?;
+        let cache_dir = cache_dir.path();
+        let version = Version::new(16, 4, 0);
+        assert!(verify(cache_dir, &version).is_err());
+        let installation_dir = install(cache_dir, &version, "16")?;
+        assert!(verify(cache_dir, &version)?.is_empty());
+
+        fs::write(installation_dir.join("bin").join("postgres"), "modified")?;
+        assert_eq!(
+            vec![PathBuf::from("bin/postgres")],
+            verify(cache_dir, &version)?
+        );
+        fs::remove_file(installation_dir.join(MANIFEST_FILE))?;
+        assert!(verify(cache_dir, &version).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_installed_filter() -> Result<()> {
+        let installation_dir = tempfile::tempdir()?;
+        let installation_dir = installation_dir.path();
+        assert!(installed_filter(installation_dir)?.is_empty());
+        write_filter(installation_dir, &ExtractFilter::new())?;
+        assert!(!installation_dir.join(FILTER_FILE).exists());
+
+        let filter = ExtractFilter::runtime()?;
+        write_filter(installation_dir, &filter)?;
+        assert_eq!(filter, installed_filter(installation_dir)?);
+        write_manifest(installation_dir)?;
+        let manifest = fs::read_to_string(installation_dir.join(MANIFEST_FILE))?;
+        assert!(!manifest.contains(FILTER_FILE));
+        Ok(())
+    }
+
+    #[test]
+    fn test_clear() -> Result<()> {
+        let cache_dir = tempfile::tempdir()?;
+        let cache_dir = cache_dir.path();
+        install(cache_dir, &Version::new(16, 4, 0), "16")?;
+        let archive = Archive::new(
+            "postgresql.tar.gz".to_string(),
+            Version::new(16, 4, 0),
+            vec![1],
+        );
+        let cache = FileSystemCache::new(cache_dir);
+        cache.store(URL, &archive)?;
+        fs::write(cache_dir.join("settings.txt"), "keep")?;
+
+        clear(cache_dir)?;
+        assert!(list_installed(cache_dir)?.is_empty());
+        assert!(cache.versions(URL).is_empty());
+        assert!(cache_dir.join("settings.txt").is_file());
+        Ok(())
+    }
+}
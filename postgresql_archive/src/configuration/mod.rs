@@ -1,4 +1,9 @@
-#[cfg(feature = "theseus")]
+#[cfg(any(
+    feature = "theseus",
+    feature = "file",
+    feature = "gitlab",
+    feature = "http"
+))]
 pub mod theseus;
 #[cfg(feature = "zonky")]
 pub mod zonky;
@@ -1,13 +1,18 @@
+use crate::version::postgresql_version_name;
 use semver::Version;
 
-/// Matcher for PostgreSQL binaries from <https://github.com/theseus-rs/postgresql-binaries>
+/// Matcher for PostgreSQL binaries from <https://github.com/theseus-rs/postgresql-binaries>;
+/// pre-release assets may be named with either the semantic version (e.g. `18.0.0-beta.1`) or
+/// the PostgreSQL version (e.g. `18beta1`). Assets are matched for the `target` triple (see
+/// [`get_archive_for_target`](crate::get_archive_for_target)).
 ///
 /// # Errors
 /// * If the asset matcher fails.
-pub fn matcher(_url: &str, name: &str, version: &Version) -> crate::Result<bool> {
-    let target = target_triple::TARGET;
+pub fn matcher(_url: &str, name: &str, version: &Version, target: &str) -> crate::Result<bool> {
     let expected_name = format!("postgresql-{version}-{target}.tar.gz");
-    Ok(name == expected_name)
+    let postgresql_version = postgresql_version_name(version);
+    let postgresql_name = format!("postgresql-{postgresql_version}-{target}.tar.gz");
+    Ok(name == expected_name || name == postgresql_name)
 }
 
 #[cfg(test)]
@@ -22,7 +27,36 @@ mod tests {
         let target = target_triple::TARGET;
         let name = format!("postgresql-{version}-{target}.tar.gz");
 
-        assert!(matcher(url, name.as_str(), &version)?, "{}", name);
+        assert!(matcher(url, name.as_str(), &version, target)?, "{}", name);
+        Ok(())
+    }
+
+    #[test]
+    fn test_asset_match_prerelease() -> Result<()> {
+        let url = "";
+        let version = Version::parse("18.0.0-beta.1")?;
+        let target = target_triple::TARGET;
+        for name in [
+            format!("postgresql-{version}-{target}.tar.gz"),
+            format!("postgresql-18beta1-{target}.tar.gz"),
+        ] {
+            assert!(matcher(url, name.as_str(), &version, target)?, "{}", name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_asset_match_target() -> Result<()> {
+        let url = crate::configuration::theseus::URL;
+        let target = "aarch64-unknown-linux-musl";
+        let version = Version::parse("16.4.0")?;
+        let name = format!("postgresql-{version}-{target}.tar.gz");
+        assert!(matcher(url, name.as_str(), &version, target)?, "{}", name);
+
+        let name = format!("postgresql-{version}-{}.tar.gz", target_triple::TARGET);
+        if target != target_triple::TARGET {
+            assert!(!matcher(url, name.as_str(), &version, target)?, "{}", name);
+        }
         Ok(())
     }
 
@@ -40,7 +74,7 @@ mod tests {
         ];
 
         for name in names {
-            assert!(!matcher(url, name.as_str(), &version)?, "{}", name);
+            assert!(!matcher(url, name.as_str(), &version, target)?, "{}", name);
         }
         Ok(())
     }
@@ -3,5 +3,5 @@ mod matcher;
 
 pub const URL: &str = "https://github.com/theseus-rs/postgresql-binaries";
 
-pub use extractor::extract;
+pub use extractor::{extract, extract_file};
 pub use matcher::matcher;
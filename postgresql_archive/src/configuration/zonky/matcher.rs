@@ -3,32 +3,41 @@ use semver::Version;
 use std::env;
 
 /// Matcher for PostgreSQL binaries from <https://github.com/zonkyio/embedded-postgres-binaries>
+/// for the `target` triple
 ///
 /// # Errors
 /// * If the asset matcher fails.
-pub fn matcher(_url: &str, name: &str, version: &Version) -> Result<bool> {
-    let os = get_os();
-    let arch = get_arch();
+pub fn matcher(_url: &str, name: &str, version: &Version, target: &str) -> Result<bool> {
+    let os = get_os(target);
+    let arch = get_arch(target);
     let expected_name = format!("embedded-postgres-binaries-{os}-{arch}-{version}.jar");
     Ok(name == expected_name)
 }
 
-/// Returns the operating system of the current system.
-pub(crate) fn get_os() -> &'static str {
-    match env::consts::OS {
-        "macos" => "darwin",
-        os => os,
+/// Returns the operating system of the `target` triple, as named by the Zonky artifacts.
+pub(crate) fn get_os(target: &str) -> &str {
+    if target.contains("-apple-darwin") {
+        "darwin"
+    } else if target.contains("-windows") {
+        "windows"
+    } else if target.contains("-linux") {
+        "linux"
+    } else {
+        match env::consts::OS {
+            "macos" => "darwin",
+            os => os,
+        }
     }
 }
 
-/// Returns the architecture of the current system.
-pub(crate) fn get_arch() -> &'static str {
-    match env::consts::ARCH {
-        "arm" => "arm32v7",
+/// Returns the architecture of the `target` triple, as named by the Zonky artifacts.
+pub(crate) fn get_arch(target: &str) -> &str {
+    match target.split('-').next().unwrap_or_default() {
+        "arm" | "armv7" => "arm32v7",
         "x86_64" => "amd64",
         "aarch64" => "arm64v8",
-        "powerpc64" => "ppc64le",
-        "x86" => "i386",
+        "powerpc64" | "powerpc64le" => "ppc64le",
+        "i586" | "i686" => "i386",
         arch => arch,
     }
 }
@@ -41,20 +50,42 @@ mod tests {
     #[test]
     fn test_asset_match_success() -> Result<()> {
         let url = "";
-        let os = get_os();
-        let arch = get_arch();
+        let target = target_triple::TARGET;
+        let os = get_os(target);
+        let arch = get_arch(target);
         let version = Version::parse("16.4.0")?;
         let name = format!("embedded-postgres-binaries-{os}-{arch}-{version}.jar");
 
-        assert!(matcher(url, name.as_str(), &version)?, "{}", name);
+        assert!(matcher(url, name.as_str(), &version, target)?, "{}", name);
+        Ok(())
+    }
+
+    #[test]
+    fn test_asset_match_target() -> Result<()> {
+        let version = Version::parse("16.4.0")?;
+        let name = format!("embedded-postgres-binaries-darwin-arm64v8-{version}.jar");
+        assert!(matcher(
+            "",
+            name.as_str(),
+            &version,
+            "aarch64-apple-darwin"
+        )?);
+        let name = format!("embedded-postgres-binaries-linux-i386-{version}.jar");
+        assert!(matcher(
+            "",
+            name.as_str(),
+            &version,
+            "i686-unknown-linux-gnu"
+        )?);
         Ok(())
     }
 
     #[test]
     fn test_asset_match_errors() -> Result<()> {
         let url = "";
-        let os = get_os();
-        let arch = get_arch();
+        let target = target_triple::TARGET;
+        let os = get_os(target);
+        let arch = get_arch(target);
         let version = Version::parse("16.4.0")?;
         let names = vec![
             format!("foo-{os}-{arch}-{version}.jar"),
@@ -65,7 +96,7 @@ mod tests {
         ];
 
         for name in names {
-            assert!(!matcher(url, name.as_str(), &version)?, "{}", name);
+            assert!(!matcher(url, name.as_str(), &version, target)?, "{}", name);
         }
         Ok(())
     }
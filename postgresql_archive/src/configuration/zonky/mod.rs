@@ -4,6 +4,6 @@ mod repository;
 
 pub const URL: &str = "https://github.com/zonkyio/embedded-postgres-binaries";
 
-pub use extractor::extract;
+pub use extractor::{extract, extract_file};
 pub use matcher::matcher;
 pub use repository::Zonky;
@@ -2,7 +2,7 @@ use crate::configuration::zonky::matcher::{get_arch, get_os};
 use crate::repository::maven::repository::Maven;
 use crate::repository::model::Repository;
 use crate::repository::Archive;
-use crate::Result;
+use crate::{scoped, Channel, Result};
 use async_trait::async_trait;
 use semver::{Version, VersionReq};
 use tracing::instrument;
@@ -20,14 +20,16 @@ const MAVEN_URL: &str = "https://repo1.maven.org/maven2/io/zonky/test/postgres";
 
 impl Zonky {
     /// Creates a new Zonky repository from the specified URL in the format
-    /// <https://github.com/zonkyio/embedded-postgres-binaries>
+    /// <https://github.com/zonkyio/embedded-postgres-binaries>, for the artifact of the scoped
+    /// [target](crate::ScopedOptions::target).
     ///
     /// # Errors
     /// * If the URL is invalid.
     #[expect(clippy::new_ret_no_self)]
     pub fn new(_url: &str) -> Result<Box<dyn Repository>> {
-        let os = get_os();
-        let arch = get_arch();
+        let target = scoped::target();
+        let os = get_os(&target);
+        let arch = get_arch(&target);
         let archive = format!("embedded-postgres-binaries-{os}-{arch}");
         let url = format!("{MAVEN_URL}/{archive}");
         let maven = Maven::new(url.as_str())?;
@@ -42,6 +44,11 @@ impl Repository for Zonky {
         "Zonky"
     }
 
+    #[instrument(level = "debug")]
+    async fn list_versions(&self) -> Result<Vec<Version>> {
+        self.maven.list_versions().await
+    }
+
     #[instrument(level = "debug")]
     async fn get_version(&self, version_req: &VersionReq) -> Result<Version> {
         self.maven.get_version(version_req).await
@@ -51,6 +58,28 @@ impl Repository for Zonky {
     async fn get_archive(&self, version_req: &VersionReq) -> Result<Archive> {
         self.maven.get_archive(version_req).await
     }
+
+    #[instrument(level = "debug")]
+    async fn get_version_in_channel(
+        &self,
+        version_req: &VersionReq,
+        channel: Channel,
+    ) -> Result<Version> {
+        self.maven
+            .get_version_in_channel(version_req, channel)
+            .await
+    }
+
+    #[instrument]
+    async fn get_archive_in_channel(
+        &self,
+        version_req: &VersionReq,
+        channel: Channel,
+    ) -> Result<Archive> {
+        self.maven
+            .get_archive_in_channel(version_req, channel)
+            .await
+    }
 }
 
 #[cfg(test)]
@@ -102,8 +131,8 @@ mod tests {
     #[tokio::test]
     async fn test_get_archive() -> Result<()> {
         let zonky = Zonky::new(zonky::URL)?;
-        let os = get_os();
-        let arch = get_arch();
+        let os = get_os(target_triple::TARGET);
+        let arch = get_arch(target_triple::TARGET);
         let version = Version::new(16, 2, 0);
         let version_req = VersionReq::parse(format!("={version}").as_str())?;
         let archive = zonky.get_archive(&version_req).await?;
@@ -0,0 +1,262 @@
+use crate::Error::{ParseError, PoisonedLock};
+use crate::Result;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION};
+use std::fmt::{Debug, Formatter};
+use std::sync::{Arc, LazyLock, RwLock};
+use url::Url;
+
+static CREDENTIALS_PROVIDERS: LazyLock<RwLock<Vec<Arc<dyn CredentialsProvider>>>> =
+    LazyLock::new(|| RwLock::new(Vec::new()));
+
+/// Provides the credentials for the requests that repositories make, so that applications can
+/// supply tokens from their own secret stores rather than environment variables. Functions and
+/// closures with the signature of [`headers`](CredentialsProvider::headers) are providers.
+pub trait CredentialsProvider: Send + Sync {
+    /// Gets the headers that authenticate a request to the `url`; e.g. an `Authorization` header.
+    /// Returns no headers if the provider has no credentials for the `url`.
+    ///
+    /// # Errors
+    /// * If the credentials cannot be retrieved.
+    fn headers(&self, url: &str) -> Result<Vec<(String, String)>>;
+}
+
+impl<F> CredentialsProvider for F
+where
+    F: Fn(&str) -> Result<Vec<(String, String)>> + Send + Sync,
+{
+    fn headers(&self, url: &str) -> Result<Vec<(String, String)>> {
+        self(url)
+    }
+}
+
+/// Fixed credentials for the requests to the URLs under a prefix; e.g. a bearer token for a
+/// private mirror. The values of the headers are not included in the debug output.
+#[derive(Clone, Default)]
+pub struct StaticCredentials {
+    url_prefix: String,
+    headers: Vec<(String, String)>,
+}
+
+impl StaticCredentials {
+    /// Creates new [`StaticCredentials`] without headers for the URLs under the `url_prefix`;
+    /// e.g. `https://mirror.example.com/`. A URL is under the prefix if it has the same scheme,
+    /// host and port, and its path is the path of the prefix or below it.
+    #[must_use]
+    pub fn new<S: Into<String>>(url_prefix: S) -> Self {
+        Self {
+            url_prefix: url_prefix.into(),
+            headers: Vec::new(),
+        }
+    }
+
+    /// Adds the header with the `name` and `value`.
+    #[must_use]
+    pub fn header<N: Into<String>, V: Into<String>>(mut self, name: N, value: V) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    /// Adds an `Authorization` header with the bearer `token`.
+    #[must_use]
+    pub fn bearer_token<S: AsRef<str>>(self, token: S) -> Self {
+        let token = token.as_ref();
+        self.header(AUTHORIZATION.as_str(), format!("Bearer {token}"))
+    }
+}
+
+impl Debug for StaticCredentials {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let names = self
+            .headers
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        f.debug_struct("StaticCredentials")
+            .field("url_prefix", &self.url_prefix)
+            .field("headers", &names)
+            .finish()
+    }
+}
+
+impl CredentialsProvider for StaticCredentials {
+    fn headers(&self, url: &str) -> Result<Vec<(String, String)>> {
+        if is_under_prefix(&self.url_prefix, url) {
+            Ok(self.headers.clone())
+        } else {
+            Ok(Vec::new())
+        }
+    }
+}
+
+/// Returns `true` if the `url` has the same scheme, host and port as the `url_prefix`, and the
+/// path segments of the prefix are the leading path segments of the `url`; e.g. the prefix
+/// `https://host.example/a` matches `https://host.example/a/b`, but not `https://host.example/ab`
+/// or `https://host.example.evil/a`.
+fn is_under_prefix(url_prefix: &str, url: &str) -> bool {
+    let (Ok(url_prefix), Ok(url)) = (Url::parse(url_prefix), Url::parse(url)) else {
+        return false;
+    };
+    if url_prefix.origin() != url.origin() {
+        return false;
+    }
+    let path_segments = |url: &Url| {
+        url.path_segments()
+            .map(|segments| {
+                segments
+                    .filter(|segment| !segment.is_empty())
+                    .map(str::to_string)
+                    .collect::<Vec<_>>()
+            })
+            .unwrap_or_default()
+    };
+    path_segments(&url).starts_with(&path_segments(&url_prefix))
+}
+
+/// Registers a [credentials provider](CredentialsProvider). The headers of newly registered
+/// providers override the headers of existing providers, and the headers that repositories
+/// configure from the environment; e.g. the `GITHUB_TOKEN` environment variable.
+///
+/// # Errors
+/// * If the lock is poisoned.
+pub fn register_credentials_provider<P: CredentialsProvider + 'static>(provider: P) -> Result<()> {
+    let mut providers = CREDENTIALS_PROVIDERS
+        .write()
+        .map_err(|error| PoisonedLock(error.to_string()))?;
+    providers.push(Arc::new(provider));
+    Ok(())
+}
+
+/// Removes all registered [credentials providers](CredentialsProvider).
+///
+/// # Errors
+/// * If the lock is poisoned.
+pub fn clear_credentials_providers() -> Result<()> {
+    let mut providers = CREDENTIALS_PROVIDERS
+        .write()
+        .map_err(|error| PoisonedLock(error.to_string()))?;
+    providers.clear();
+    Ok(())
+}
+
+/// Adds the headers of the registered [credentials providers](CredentialsProvider) for the `url`
+/// to the `headers`, replacing any existing headers with the same names.
+///
+/// # Errors
+/// * If the lock is poisoned.
+/// * If a provider fails, or returns an invalid header.
+pub(crate) fn apply_credentials(url: &str, headers: &mut HeaderMap) -> Result<()> {
+    let providers = CREDENTIALS_PROVIDERS
+        .read()
+        .map_err(|error| PoisonedLock(error.to_string()))?
+        .clone();
+    for provider in providers {
+        for (name, value) in provider.headers(url)? {
+            let header_name = HeaderName::from_bytes(name.as_bytes())
+                .map_err(|error| ParseError(format!("invalid header name '{name}': {error}")))?;
+            let mut header_value = HeaderValue::from_str(&value).map_err(|error| {
+                ParseError(format!("invalid value for header '{name}': {error}"))
+            })?;
+            header_value.set_sensitive(true);
+            headers.insert(header_name, header_value);
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_static_credentials() -> Result<()> {
+        let credentials = StaticCredentials::new("https://mirror.example.com/")
+            .bearer_token("secret")
+            .header("X-Custom", "value");
+        assert_eq!(
+            vec![
+                ("authorization".to_string(), "Bearer secret".to_string()),
+                ("X-Custom".to_string(), "value".to_string()),
+            ],
+            credentials.headers("https://mirror.example.com/postgresql.tar.gz")?
+        );
+        assert!(credentials
+            .headers("https://github.com/theseus-rs/postgresql-binaries")?
+            .is_empty());
+        assert!(!format!("{credentials:?}").contains("secret"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_is_under_prefix() {
+        let prefix = "https://mirror.example.com/postgresql";
+        assert!(is_under_prefix(
+            prefix,
+            "https://mirror.example.com/postgresql"
+        ));
+        assert!(is_under_prefix(
+            prefix,
+            "https://MIRROR.example.com:443/postgresql/16.4.0.tar.gz"
+        ));
+        assert!(is_under_prefix(
+            "https://mirror.example.com",
+            "https://mirror.example.com/postgresql"
+        ));
+        assert!(!is_under_prefix(
+            prefix,
+            "https://mirror.example.com/postgresql-evil"
+        ));
+        assert!(!is_under_prefix(
+            prefix,
+            "https://mirror.example.com.evil/postgresql"
+        ));
+        assert!(!is_under_prefix(
+            prefix,
+            "http://mirror.example.com/postgresql"
+        ));
+        assert!(!is_under_prefix(
+            prefix,
+            "https://mirror.example.com:8443/postgresql"
+        ));
+        assert!(!is_under_prefix("not a url", "https://mirror.example.com/"));
+    }
+
+    #[test]
+    fn test_apply_credentials() -> Result<()> {
+        let url = "https://credentials.example.com/postgresql.tar.gz";
+        register_credentials_provider(StaticCredentials::new(url).bearer_token("first"))?;
+        register_credentials_provider(|url: &str| {
+            if url.starts_with("https://credentials.example.com/") {
+                Ok(vec![(
+                    "Authorization".to_string(),
+                    "Bearer second".to_string(),
+                )])
+            } else {
+                Ok(Vec::new())
+            }
+        })?;
+
+        let mut headers = HeaderMap::new();
+        headers.insert(
+            AUTHORIZATION,
+            HeaderValue::from_static("Bearer environment"),
+        );
+        apply_credentials(url, &mut headers)?;
+        assert_eq!("Bearer second", headers[AUTHORIZATION].to_str().unwrap());
+        assert!(headers[AUTHORIZATION].is_sensitive());
+
+        let mut headers = HeaderMap::new();
+        apply_credentials("https://other.example.com/", &mut headers)?;
+        assert!(!headers.contains_key(AUTHORIZATION));
+        Ok(())
+    }
+
+    #[test]
+    fn test_apply_credentials_invalid_header() -> Result<()> {
+        register_credentials_provider(
+            StaticCredentials::new("https://invalid.example.com/").header("Invalid Name", "value"),
+        )?;
+        let mut headers = HeaderMap::new();
+        assert!(apply_credentials("https://invalid.example.com/", &mut headers).is_err());
+        Ok(())
+    }
+}
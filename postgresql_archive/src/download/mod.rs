@@ -0,0 +1,202 @@
+use crate::Error::{OfflineResourceMissing, PoisonedLock};
+use crate::Result;
+use std::ffi::OsStr;
+use std::fs::create_dir_all;
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, RwLock};
+use std::time::Duration;
+
+#[cfg(any(feature = "github", feature = "gitlab", feature = "http"))]
+mod resumable;
+
+#[cfg(any(feature = "github", feature = "gitlab", feature = "http"))]
+pub(crate) use resumable::{download, download_to_file};
+
+static DEFAULT_DOWNLOAD_OPTIONS: LazyLock<RwLock<DownloadOptions>> =
+    LazyLock::new(|| RwLock::new(DownloadOptions::default()));
+
+/// Options for downloading archives. Partial downloads are persisted to the cache directory, so
+/// that a download interrupted by a dropped connection is resumed with an HTTP range request
+/// rather than restarted; either by the next attempt of the download, or by the next process that
+/// downloads the same archive. Completed archives can also be cached, so that they can be
+/// retrieved [offline](Self::offline), and release metadata is cached to avoid querying the
+/// releases of a repository on every version resolution.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DownloadOptions {
+    /// Directory for partial downloads, cached archives and release metadata; downloads are not
+    /// resumable if `None`. On Unix, the directory is restricted to the current user if it is
+    /// writable by other users
+    pub cache_dir: Option<PathBuf>,
+    /// Number of chunks of an archive that are downloaded in parallel; `1` downloads the archive
+    /// sequentially
+    pub parallel_chunks: usize,
+    /// Cache downloaded archives in the `archives` directory of the cache directory
+    pub cache_archives: bool,
+    /// Resolve versions and retrieve archives from the cached archives only, without accessing
+    /// the network; an [`OfflineCacheMiss`](crate::Error::OfflineCacheMiss) error is returned
+    /// if no cached archive satisfies the version requirement, and an
+    /// [`OfflineResourceMissing`](crate::Error::OfflineResourceMissing) error is returned for any
+    /// other request that would access the network. Repositories of local `file://` URLs are
+    /// used as when online
+    pub offline: bool,
+    /// Duration that release metadata cached in the `metadata` directory of the cache directory is
+    /// used without revalidating it with the repository; once expired, the metadata is
+    /// revalidated with its entity tag, so that unchanged metadata is not downloaded again
+    pub metadata_ttl: Duration,
+}
+
+impl DownloadOptions {
+    /// Creates new [`DownloadOptions`] that persist partial downloads to the
+    /// `.theseus/postgresql_archive` directory of the home directory of the current user, and
+    /// download archives sequentially without caching them. Release metadata is cached for an
+    /// hour.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            cache_dir: home::home_dir()
+                .map(|home| home.join(".theseus").join("postgresql_archive")),
+            parallel_chunks: 1,
+            cache_archives: false,
+            offline: false,
+            metadata_ttl: Duration::from_secs(60 * 60),
+        }
+    }
+}
+
+impl DownloadOptions {
+    /// Checks that the resource at the `url` may be requested; resources on the network are not
+    /// requested when [offline](Self::offline), whereas local `file://` URLs are.
+    ///
+    /// # Errors
+    /// * If the options are offline and the `url` is not a local file URL.
+    pub(crate) fn require_online(&self, url: &str) -> Result<()> {
+        if self.is_offline_for(url) {
+            return Err(OfflineResourceMissing(url.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Returns `true` if the resource at the `url` is not requested, as the options are
+    /// [offline](Self::offline) and the `url` is not a local `file://` URL; archives of such
+    /// URLs are retrieved from the cache instead.
+    pub(crate) fn is_offline_for(&self, url: &str) -> bool {
+        self.offline && !is_file_url(url)
+    }
+}
+
+/// Returns `true` if the `url` is a local `file://` URL, which is accessed when offline.
+pub(crate) fn is_file_url(url: &str) -> bool {
+    url.starts_with("file://")
+}
+
+/// Gets the path of the temporary file next to `path` that a download to `path` is written to, so
+/// that the file at `path` only exists once the download is complete and verified.
+pub(crate) fn temporary_path(path: &Path) -> PathBuf {
+    let mut name = path
+        .file_name()
+        .map(OsStr::to_os_string)
+        .unwrap_or_default();
+    name.push(format!(".{}.download", std::process::id()));
+    path.with_file_name(name)
+}
+
+impl Default for DownloadOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Creates the `cache_dir` if it does not exist. On Unix, a cache directory that is writable by
+/// other users is restricted to the current user, so that other users cannot plant archives,
+/// partial downloads or release metadata in it.
+///
+/// # Errors
+/// * If the directory cannot be created, or its permissions cannot be restricted; e.g. because
+///   it is owned by another user.
+pub(crate) fn create_cache_dir(cache_dir: &Path) -> Result<()> {
+    create_dir_all(cache_dir)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(cache_dir)?.permissions().mode();
+        if mode & 0o022 != 0 {
+            std::fs::set_permissions(cache_dir, std::fs::Permissions::from_mode(0o700))?;
+        }
+    }
+    Ok(())
+}
+
+/// Gets the default [download options](DownloadOptions).
+#[must_use]
+pub fn default_download_options() -> DownloadOptions {
+    match DEFAULT_DOWNLOAD_OPTIONS.read() {
+        Ok(options) => options.clone(),
+        Err(error) => (**error.get_ref()).clone(),
+    }
+}
+
+/// Sets the default [download options](DownloadOptions).
+///
+/// # Errors
+/// * If the lock is poisoned.
+pub fn set_default_download_options(options: DownloadOptions) -> Result<()> {
+    let mut default_options = DEFAULT_DOWNLOAD_OPTIONS
+        .write()
+        .map_err(|error| PoisonedLock(error.to_string()))?;
+    *default_options = options;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_download_options() {
+        let options = DownloadOptions::default();
+        assert!(options.cache_dir.is_some());
+        assert_eq!(1, options.parallel_chunks);
+        assert!(!options.cache_archives);
+        assert!(!options.offline);
+        assert_eq!(Duration::from_secs(3600), options.metadata_ttl);
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_create_cache_dir() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+        let temp_dir = tempfile::tempdir()?;
+        let cache_dir = temp_dir.path().join("cache");
+        create_cache_dir(&cache_dir)?;
+        assert!(cache_dir.is_dir());
+        std::fs::set_permissions(&cache_dir, std::fs::Permissions::from_mode(0o777))?;
+        create_cache_dir(&cache_dir)?;
+        let mode = std::fs::metadata(&cache_dir)?.permissions().mode();
+        assert_eq!(0, mode & 0o022);
+        Ok(())
+    }
+
+    #[test]
+    fn test_require_online() {
+        let url = "https://github.com/theseus-rs/postgresql-binaries";
+        let options = DownloadOptions::default();
+        assert!(options.require_online(url).is_ok());
+        let options = DownloadOptions {
+            offline: true,
+            ..DownloadOptions::default()
+        };
+        assert!(options.require_online("file:///opt/postgresql").is_ok());
+        assert!(!options.is_offline_for("file:///opt/postgresql"));
+        assert!(options.is_offline_for(url));
+        let error = options.require_online(url).expect_err("offline");
+        assert!(matches!(error, OfflineResourceMissing(resource) if resource == url));
+    }
+
+    #[test]
+    fn test_default_download_options() -> Result<()> {
+        let options = default_download_options();
+        set_default_download_options(options.clone())?;
+        assert_eq!(options, default_download_options());
+        Ok(())
+    }
+}
@@ -0,0 +1,722 @@
+//! Resumable downloads of release assets
+use crate::download::create_cache_dir;
+use crate::lock::LockFile;
+use crate::Error::{IoError, RepositoryFailure};
+use crate::{reporter, scoped, DownloadOptions, Result};
+use futures_util::future::try_join_all;
+use futures_util::StreamExt;
+use reqwest::header::{HeaderMap, ETAG, IF_RANGE, LAST_MODIFIED, RANGE};
+use reqwest::StatusCode;
+use reqwest_middleware::ClientWithMiddleware;
+use std::fs::{read_dir, read_to_string, remove_file, rename, write, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Mutex;
+use tracing::{debug, warn};
+#[cfg(feature = "indicatif")]
+use tracing_indicatif::span_ext::IndicatifSpanExt;
+
+/// Downloads the `url` to memory. If the [options](DownloadOptions) have a cache directory, which
+/// is private to the current user, the download is persisted to a partial file in that directory
+/// as it is received, and a partial file left by an earlier download of the `url` is resumed with
+/// a range request that is only honoured if the content of the `url` has not changed. If the `size`
+/// of the download is known, it is split into the number of parallel chunks of the options, each
+/// of which is resumed separately. A download that fails part way through is resumed up to the
+/// maximum attempts of the scoped [retry policy](crate::ScopedOptions::retry_policy).
+///
+/// # Errors
+/// * If the download fails.
+pub(crate) async fn download(
+    client: &ClientWithMiddleware,
+    url: &str,
+    headers: &HeaderMap,
+    size: Option<u64>,
+    options: &DownloadOptions,
+) -> Result<Vec<u8>> {
+    let progress = Progress::new(url, size);
+    match download_partial(client, url, headers, size, options, &progress).await? {
+        Some(partial) => {
+            let bytes = std::fs::read(&partial.path)?;
+            partial.remove();
+            Ok(bytes)
+        }
+        None => fetch(client, url, headers, &progress).await,
+    }
+}
+
+/// Downloads the `url` to the file at `path`, without holding the download in memory; partial
+/// downloads are resumed as with [`download`].
+///
+/// # Errors
+/// * If the download fails.
+pub(crate) async fn download_to_file(
+    client: &ClientWithMiddleware,
+    url: &str,
+    headers: &HeaderMap,
+    size: Option<u64>,
+    options: &DownloadOptions,
+    path: &Path,
+) -> Result<()> {
+    let progress = Progress::new(url, size);
+    if let Some(partial) = download_partial(client, url, headers, size, options, &progress).await? {
+        // The cache directory may be on a different file system than the path
+        if rename(&partial.path, path).is_err() {
+            std::fs::copy(&partial.path, path)?;
+        }
+        partial.remove();
+        return Ok(());
+    }
+
+    if path.exists() {
+        remove_file(path)?;
+    }
+    let result = fetch_range(client, url, headers, path, 0, None, &progress).await;
+    let _ = remove_file(validator_path(path));
+    result
+}
+
+/// Partial file of a download in the cache directory, locked by the current process
+struct PartialDownload {
+    path: PathBuf,
+    cache_dir: PathBuf,
+    file_name: String,
+    _lock: LockFile,
+}
+
+impl PartialDownload {
+    /// Removes the partial files of the download.
+    fn remove(self) {
+        remove_partial_files(&self.cache_dir, &self.file_name);
+    }
+}
+
+/// Downloads the `url` to a partial file in the cache directory of the `options`, resuming an
+/// earlier partial download. Returns `None` if the options do not have a cache directory, or
+/// another process is downloading the same URL.
+async fn download_partial(
+    client: &ClientWithMiddleware,
+    url: &str,
+    headers: &HeaderMap,
+    size: Option<u64>,
+    options: &DownloadOptions,
+    progress: &Progress,
+) -> Result<Option<PartialDownload>> {
+    let Some(cache_dir) = &options.cache_dir else {
+        return Ok(None);
+    };
+    if let Err(error) = create_cache_dir(cache_dir) {
+        warn!(
+            "Cache directory {} is not usable: {error}; downloading without resuming",
+            cache_dir.to_string_lossy()
+        );
+        return Ok(None);
+    }
+    let file_name = partial_file_name(url);
+    // Another process downloading the same URL owns the partial file; download without
+    // resuming rather than wait for it
+    let Some(lock) = LockFile::try_acquire(&cache_dir.join(format!("{file_name}.lock")))? else {
+        debug!("Partial download of {url} is locked; downloading without resuming");
+        return Ok(None);
+    };
+
+    let partial_path = cache_dir.join(format!("{file_name}.part"));
+    let ranges = match size {
+        Some(size) if options.parallel_chunks > 1 => chunk_ranges(size, options.parallel_chunks),
+        _ => vec![(0, None)],
+    };
+    if ranges.len() > 1 {
+        let chunk_paths = (0..ranges.len())
+            .map(|index| cache_dir.join(format!("{file_name}.part.{index}")))
+            .collect::<Vec<_>>();
+        try_join_all(ranges.iter().zip(&chunk_paths).map(|((start, end), path)| {
+            fetch_range(client, url, headers, path, *start, *end, progress)
+        }))
+        .await?;
+
+        let mut partial_file = File::create(&partial_path)?;
+        for chunk_path in &chunk_paths {
+            std::io::copy(&mut File::open(chunk_path)?, &mut partial_file)?;
+        }
+    } else {
+        fetch_range(client, url, headers, &partial_path, 0, None, progress).await?;
+    }
+
+    Ok(Some(PartialDownload {
+        path: partial_path,
+        cache_dir: cache_dir.clone(),
+        file_name,
+        _lock: lock,
+    }))
+}
+
+/// Downloads the `url` to memory.
+async fn fetch(
+    client: &ClientWithMiddleware,
+    url: &str,
+    headers: &HeaderMap,
+    progress: &Progress,
+) -> Result<Vec<u8>> {
+    let response = client
+        .get(url)
+        .headers(headers.clone())
+        .send()
+        .await?
+        .error_for_status()?;
+    progress.set_length(response.content_length());
+    let mut bytes = Vec::new();
+    let mut source = response.bytes_stream();
+    while let Some(chunk) = source.next().await {
+        let chunk = chunk?;
+        bytes.write_all(&chunk)?;
+        progress.add(chunk.len());
+    }
+    Ok(bytes)
+}
+
+/// Downloads the bytes `start..=end` of the `url`, or all bytes from `start` if `end` is `None`,
+/// to the file at `path`; resuming from the bytes already in the file, and again each time the
+/// download fails with a transient error up to the maximum attempts of the scoped
+/// [retry policy](crate::ScopedOptions::retry_policy), after its backoff. Responses with a
+/// `404 Not Found` or `416 Range Not Satisfiable` status are not retried.
+async fn fetch_range(
+    client: &ClientWithMiddleware,
+    url: &str,
+    headers: &HeaderMap,
+    path: &Path,
+    start: u64,
+    end: Option<u64>,
+    progress: &Progress,
+) -> Result<()> {
+    progress.add_bytes(path.metadata().map(|metadata| metadata.len()).unwrap_or(0));
+    let retry_policy = scoped::retry_policy();
+    let mut attempt = 1;
+    loop {
+        match fetch_range_attempt(client, url, headers, path, start, end, progress).await {
+            Ok(()) => return Ok(()),
+            Err(error) if !is_permanent(&*error) && retry_policy.should_retry(attempt, &*error) => {
+                let backoff = retry_policy.backoff(attempt - 1);
+                warn!(
+                    "Download of {url} failed (attempt {attempt}/{}): {error}; resuming in \
+                     {backoff:?}",
+                    retry_policy.max_attempts
+                );
+                tokio::time::sleep(backoff).await;
+                attempt += 1;
+            }
+            Err(error) => {
+                return Err(match error.downcast::<crate::Error>() {
+                    Ok(error) => *error,
+                    Err(error) => IoError(error.to_string()),
+                })
+            }
+        }
+    }
+}
+
+/// Returns `true` if the `error` is a response that is not changed by retrying the request; i.e.
+/// a `404 Not Found` or `416 Range Not Satisfiable` status.
+fn is_permanent(error: &(dyn std::error::Error + 'static)) -> bool {
+    error
+        .downcast_ref::<reqwest::Error>()
+        .and_then(reqwest::Error::status)
+        .is_some_and(|status| {
+            status == StatusCode::NOT_FOUND || status == StatusCode::RANGE_NOT_SATISFIABLE
+        })
+}
+
+/// Makes a single attempt to download the remainder of the bytes `start..=end` of the `url` to
+/// the file at `path`. See [`fetch_range`]. The bytes already in the file are only resumed if the
+/// [validator](validator_path) of the content they were downloaded from is known, and the range
+/// is requested with an `If-Range` header, so that the server sends the complete content instead
+/// of the range if the content has changed. Errors are returned unconverted, so that transient
+/// errors can be classified by the retry policy.
+async fn fetch_range_attempt(
+    client: &ClientWithMiddleware,
+    url: &str,
+    headers: &HeaderMap,
+    path: &Path,
+    start: u64,
+    end: Option<u64>,
+    progress: &Progress,
+) -> core::result::Result<(), Box<dyn std::error::Error + Send + Sync>> {
+    let validator_path = validator_path(path);
+    let mut downloaded = path.metadata().map(|metadata| metadata.len()).unwrap_or(0);
+    let validator = read_to_string(&validator_path).ok();
+    if downloaded > 0 && validator.is_none() {
+        // The content the bytes were downloaded from is not known, so they cannot be resumed
+        debug!("Discarding partial download of {url} without a validator");
+        remove_file(path)?;
+        downloaded = 0;
+    }
+    let offset = start + downloaded;
+    if let Some(end) = end {
+        if offset > end {
+            debug!("Range {start}-{end} of {url} already downloaded");
+            return Ok(());
+        }
+    }
+
+    let mut request = client.get(url).headers(headers.clone());
+    if offset > 0 || end.is_some() {
+        debug!("Resuming download of {url} from byte {offset}");
+        request = request.header(RANGE, range_header(offset, end));
+        if let (true, Some(validator)) = (downloaded > 0, &validator) {
+            request = request.header(IF_RANGE, validator.as_str());
+        }
+    }
+    let response = request.send().await?;
+    if response.status() == StatusCode::RANGE_NOT_SATISFIABLE && downloaded > 0 {
+        // The partial file does not match the current content of the URL; it is removed, so that
+        // the next download starts over
+        remove_file(path)?;
+        let _ = remove_file(&validator_path);
+    }
+    let response = response.error_for_status()?;
+    match response_validator(&response) {
+        Some(validator) => write(&validator_path, validator)?,
+        None => {
+            let _ = remove_file(&validator_path);
+        }
+    }
+
+    let mut file = if response.status() == StatusCode::PARTIAL_CONTENT {
+        OpenOptions::new().create(true).append(true).open(path)?
+    } else if start == 0 {
+        // The server ignored the range, or the content changed, and sent the complete content
+        if downloaded > 0 {
+            debug!("Partial download of {url} cannot be resumed; restarting download");
+        }
+        File::create(path)?
+    } else {
+        let _ = remove_file(path);
+        return Err(RepositoryFailure(format!(
+            "Range requests are not supported for {url}, or its content changed"
+        ))
+        .into());
+    };
+
+    let mut source = response.bytes_stream();
+    while let Some(chunk) = source.next().await {
+        let chunk = chunk?;
+        file.write_all(&chunk)?;
+        progress.add(chunk.len());
+    }
+
+    if let Some(end) = end {
+        let length = file.metadata()?.len();
+        if start + length != end + 1 {
+            // The connection was closed before the range was complete
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::UnexpectedEof,
+                format!("Range {start}-{end} of {url} is incomplete; downloaded {length} bytes"),
+            )
+            .into());
+        }
+    }
+    Ok(())
+}
+
+/// Gets the path of the file with the validator of the content that the partial download at
+/// `path` was downloaded from.
+fn validator_path(path: &Path) -> PathBuf {
+    let mut validator_path = path.as_os_str().to_owned();
+    validator_path.push(".validator");
+    PathBuf::from(validator_path)
+}
+
+/// Gets the validator of the content of the `response` that can be used in an `If-Range` header;
+/// a strong `ETag`, or else the `Last-Modified` date.
+fn response_validator(response: &reqwest::Response) -> Option<String> {
+    let headers = response.headers();
+    headers
+        .get(ETAG)
+        .and_then(|etag| etag.to_str().ok())
+        .filter(|etag| !etag.starts_with("W/"))
+        .or_else(|| {
+            headers
+                .get(LAST_MODIFIED)
+                .and_then(|modified| modified.to_str().ok())
+        })
+        .map(ToString::to_string)
+}
+
+/// Gets the value of the `Range` header for the bytes `start..=end`, or all bytes from `start` if
+/// `end` is `None`.
+fn range_header(start: u64, end: Option<u64>) -> String {
+    match end {
+        Some(end) => format!("bytes={start}-{end}"),
+        None => format!("bytes={start}-"),
+    }
+}
+
+/// Splits a download of `size` bytes into up to `chunks` inclusive byte ranges of equal size.
+fn chunk_ranges(size: u64, chunks: usize) -> Vec<(u64, Option<u64>)> {
+    let chunks = u64::try_from(chunks)
+        .unwrap_or(u64::MAX)
+        .clamp(1, size.max(1));
+    let chunk_size = size.div_ceil(chunks).max(1);
+    (0..chunks)
+        .map(|index| index * chunk_size)
+        .take_while(|start| *start < size)
+        .map(|start| (start, Some((start + chunk_size).min(size) - 1)))
+        .collect()
+}
+
+/// Gets the name of the partial files of the download of the `url`.
+fn partial_file_name(url: &str) -> String {
+    let url = url.split_once("://").map_or(url, |(_scheme, url)| url);
+    url.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '.' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Removes the partial files of the download with the `file_name` from the `cache_dir`.
+fn remove_partial_files(cache_dir: &Path, file_name: &str) {
+    let prefix = format!("{file_name}.part");
+    let Ok(entries) = read_dir(cache_dir) else {
+        return;
+    };
+    let paths = entries
+        .flatten()
+        .filter(|entry| entry.file_name().to_string_lossy().starts_with(&prefix))
+        .map(|entry| entry.path())
+        .collect::<Vec<PathBuf>>();
+    for path in paths {
+        if let Err(error) = remove_file(&path) {
+            warn!(
+                "Failed to remove partial download {}: {error}",
+                path.to_string_lossy()
+            );
+        }
+    }
+}
+
+/// Progress of a download, reported to the progress bar of the span of the download and the
+/// [progress reporter](crate::ScopedOptions::progress_reporter) of the scope
+struct Progress {
+    url: String,
+    position: AtomicU64,
+    length: Mutex<Option<u64>>,
+    #[cfg(feature = "indicatif")]
+    span: tracing::Span,
+}
+
+impl Progress {
+    /// Creates a new progress for a download of `length` bytes from the `url`, if known.
+    fn new(url: &str, length: Option<u64>) -> Self {
+        let progress = Self {
+            url: url.to_string(),
+            position: AtomicU64::new(0),
+            length: Mutex::new(None),
+            #[cfg(feature = "indicatif")]
+            span: tracing::Span::current(),
+        };
+        progress.set_length(length);
+        progress
+    }
+
+    /// Sets the length of the download, if known.
+    fn set_length(&self, length: Option<u64>) {
+        let Some(length) = length else {
+            return;
+        };
+        if let Ok(mut current_length) = self.length.lock() {
+            *current_length = Some(length);
+        }
+        #[cfg(feature = "indicatif")]
+        self.span.pb_set_length(length);
+    }
+
+    /// Adds `bytes` received to the progress.
+    fn add(&self, bytes: usize) {
+        self.add_bytes(u64::try_from(bytes).unwrap_or(u64::MAX));
+    }
+
+    /// Adds `bytes` received, or previously downloaded, to the progress.
+    fn add_bytes(&self, bytes: u64) {
+        let position = self.position.fetch_add(bytes, Ordering::Relaxed) + bytes;
+        #[cfg(feature = "indicatif")]
+        self.span.pb_set_position(position);
+        let length = self.length.lock().map(|length| *length).unwrap_or_default();
+        reporter::report_download(&self.url, position, length);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest_middleware::ClientBuilder;
+    use std::io::{BufRead, BufReader};
+    use std::net::TcpListener;
+    use std::sync::{Arc, Mutex};
+
+    /// `ETag` of the content served by [`serve`]
+    const ETAG_VALUE: &str = "\"v1\"";
+
+    /// Serves `body` on a local port with the [`ETAG_VALUE`], honouring `Range` headers unless
+    /// an `If-Range` header does not match. The first response is cut off after `cut_off` bytes,
+    /// if set. Returns the URL and the `Range` headers of the requests.
+    fn serve(body: Vec<u8>, cut_off: Option<usize>) -> (String, Arc<Mutex<Vec<Option<String>>>>) {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("listener");
+        let url = format!(
+            "http://{}/archive.tar.gz",
+            listener.local_addr().expect("addr")
+        );
+        let ranges = Arc::new(Mutex::new(Vec::new()));
+        let requests = ranges.clone();
+        std::thread::spawn(move || {
+            let mut cut_off = cut_off;
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    continue;
+                };
+                let mut range = None;
+                let mut if_range = None;
+                let mut reader = BufReader::new(stream.try_clone().expect("stream"));
+                let mut line = String::new();
+                while reader.read_line(&mut line).is_ok_and(|length| length > 2) {
+                    if let Some(value) = line.to_lowercase().strip_prefix("range: bytes=") {
+                        range = Some(value.trim().to_string());
+                    }
+                    if let Some((name, value)) = line.split_once(':') {
+                        if name.eq_ignore_ascii_case("if-range") {
+                            if_range = Some(value.trim().to_string());
+                        }
+                    }
+                    line.clear();
+                }
+                requests.lock().expect("requests").push(range.clone());
+
+                let range = range.filter(|_| if_range.is_none_or(|value| value == ETAG_VALUE));
+                let (status, start, end) = match range.as_deref().and_then(|r| r.split_once('-')) {
+                    Some((start, end)) => {
+                        let start = start.parse::<usize>().expect("start");
+                        let end = end.parse::<usize>().unwrap_or(body.len() - 1);
+                        ("206 Partial Content", start, end)
+                    }
+                    None => ("200 OK", 0, body.len() - 1),
+                };
+                let content = &body[start..=end];
+                let header = format!(
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nContent-Range: bytes {start}-{end}/{}\r\nETag: {ETAG_VALUE}\r\nConnection: close\r\n\r\n",
+                    content.len(),
+                    body.len()
+                );
+                let _ = stream.write_all(header.as_bytes());
+                let length = cut_off.take().unwrap_or(content.len());
+                let _ = stream.write_all(&content[..length]);
+            }
+        });
+        (url, ranges)
+    }
+
+    fn client() -> ClientWithMiddleware {
+        ClientBuilder::new(reqwest::Client::new()).build()
+    }
+
+    fn body() -> Vec<u8> {
+        (0..10_000u32).flat_map(u32::to_le_bytes).collect()
+    }
+
+    #[tokio::test]
+    async fn test_download_resumes() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let options = DownloadOptions {
+            cache_dir: Some(temp_dir.path().to_path_buf()),
+            parallel_chunks: 1,
+            ..DownloadOptions::default()
+        };
+        let body = body();
+        let (url, ranges) = serve(body.clone(), Some(1_000));
+
+        let bytes = download(&client(), &url, &HeaderMap::new(), None, &options).await?;
+        assert_eq!(body, bytes);
+        assert_eq!(
+            vec![None, Some("1000-".to_string())],
+            *ranges.lock().expect("ranges")
+        );
+        assert_eq!(0, read_dir(temp_dir.path())?.count());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_download_resumes_partial_file() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let options = DownloadOptions {
+            cache_dir: Some(temp_dir.path().to_path_buf()),
+            parallel_chunks: 1,
+            ..DownloadOptions::default()
+        };
+        let body = body();
+        let (url, ranges) = serve(body.clone(), None);
+        let partial_path = temp_dir
+            .path()
+            .join(format!("{}.part", partial_file_name(&url)));
+        std::fs::write(&partial_path, &body[..5_000])?;
+        std::fs::write(validator_path(&partial_path), ETAG_VALUE)?;
+
+        let bytes = download(&client(), &url, &HeaderMap::new(), None, &options).await?;
+        assert_eq!(body, bytes);
+        assert_eq!(
+            vec![Some("5000-".to_string())],
+            *ranges.lock().expect("ranges")
+        );
+        assert!(!partial_path.exists());
+        assert!(!validator_path(&partial_path).exists());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_download_restarts_changed_partial_file() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let options = DownloadOptions {
+            cache_dir: Some(temp_dir.path().to_path_buf()),
+            parallel_chunks: 1,
+            ..DownloadOptions::default()
+        };
+        let body = body();
+        let (url, ranges) = serve(body.clone(), None);
+        let partial_path = temp_dir
+            .path()
+            .join(format!("{}.part", partial_file_name(&url)));
+        std::fs::write(&partial_path, vec![0u8; 5_000])?;
+        std::fs::write(validator_path(&partial_path), "\"v0\"")?;
+
+        let bytes = download(&client(), &url, &HeaderMap::new(), None, &options).await?;
+        assert_eq!(body, bytes);
+        assert_eq!(
+            vec![Some("5000-".to_string())],
+            *ranges.lock().expect("ranges")
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_download_restarts_partial_file_without_validator() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let options = DownloadOptions {
+            cache_dir: Some(temp_dir.path().to_path_buf()),
+            parallel_chunks: 1,
+            ..DownloadOptions::default()
+        };
+        let body = body();
+        let (url, ranges) = serve(body.clone(), None);
+        let partial_path = temp_dir
+            .path()
+            .join(format!("{}.part", partial_file_name(&url)));
+        std::fs::write(&partial_path, vec![0u8; 5_000])?;
+
+        let bytes = download(&client(), &url, &HeaderMap::new(), None, &options).await?;
+        assert_eq!(body, bytes);
+        assert_eq!(vec![None], *ranges.lock().expect("ranges"));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_download_parallel_chunks() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let options = DownloadOptions {
+            cache_dir: Some(temp_dir.path().to_path_buf()),
+            parallel_chunks: 4,
+            ..DownloadOptions::default()
+        };
+        let body = body();
+        let (url, ranges) = serve(body.clone(), None);
+        let size = u64::try_from(body.len()).expect("size");
+
+        let bytes = download(&client(), &url, &HeaderMap::new(), Some(size), &options).await?;
+        assert_eq!(body, bytes);
+        assert_eq!(4, ranges.lock().expect("ranges").len());
+        assert_eq!(0, read_dir(temp_dir.path())?.count());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_download_to_file() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let options = DownloadOptions {
+            cache_dir: Some(temp_dir.path().join("cache")),
+            parallel_chunks: 1,
+            ..DownloadOptions::default()
+        };
+        let body = body();
+        let (url, _ranges) = serve(body.clone(), Some(1_000));
+        let path = temp_dir.path().join("archive.tar.gz");
+
+        download_to_file(&client(), &url, &HeaderMap::new(), None, &options, &path).await?;
+        assert_eq!(body, std::fs::read(&path)?);
+        assert_eq!(0, read_dir(temp_dir.path().join("cache"))?.count());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_download_to_file_without_cache_dir() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let options = DownloadOptions {
+            cache_dir: None,
+            parallel_chunks: 1,
+            ..DownloadOptions::default()
+        };
+        let body = body();
+        let (url, ranges) = serve(body.clone(), Some(1_000));
+        let path = temp_dir.path().join("archive.tar.gz");
+        std::fs::write(&path, "stale")?;
+
+        download_to_file(&client(), &url, &HeaderMap::new(), None, &options, &path).await?;
+        assert_eq!(body, std::fs::read(&path)?);
+        assert_eq!(
+            vec![None, Some("1000-".to_string())],
+            *ranges.lock().expect("ranges")
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_download_without_cache_dir() -> Result<()> {
+        let options = DownloadOptions {
+            cache_dir: None,
+            parallel_chunks: 1,
+            ..DownloadOptions::default()
+        };
+        let body = body();
+        let (url, _ranges) = serve(body.clone(), None);
+
+        let bytes = download(&client(), &url, &HeaderMap::new(), None, &options).await?;
+        assert_eq!(body, bytes);
+        Ok(())
+    }
+
+    #[test]
+    fn test_chunk_ranges() {
+        assert_eq!(
+            vec![(0, Some(3)), (4, Some(7)), (8, Some(9))],
+            chunk_ranges(10, 3)
+        );
+        assert_eq!(vec![(0, Some(9))], chunk_ranges(10, 1));
+        assert_eq!(vec![(0, Some(0)), (1, Some(1))], chunk_ranges(2, 4));
+        assert!(chunk_ranges(0, 2).is_empty());
+    }
+
+    #[test]
+    fn test_range_header() {
+        assert_eq!("bytes=10-", range_header(10, None));
+        assert_eq!("bytes=0-9", range_header(0, Some(9)));
+    }
+
+    #[test]
+    fn test_partial_file_name() {
+        assert_eq!(
+            "github.com_owner_repo_releases_download_16.4.0_postgresql-16.4.0.tar.gz",
+            partial_file_name(
+                "https://github.com/owner/repo/releases/download/16.4.0/postgresql-16.4.0.tar.gz"
+            )
+        );
+    }
+}
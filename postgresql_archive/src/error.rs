@@ -19,12 +19,32 @@ pub enum Error {
     /// IO error
     #[error("{0}")]
     IoError(String),
+    /// Error when no cached archive satisfies the version requirement in offline mode
+    #[error(
+        "offline: no cached archive satisfies version requirement '{version_req}'; \
+        cached versions: [{}]",
+        .cached_versions.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+    )]
+    OfflineCacheMiss {
+        version_req: String,
+        cached_versions: Vec<semver::Version>,
+    },
+    /// Error when a resource that is not available locally would be requested from the network
+    /// in offline mode
+    #[error("offline: '{0}' is not available locally and network access is disabled")]
+    OfflineResourceMissing(String),
     /// Parse error
     #[error("{0}")]
     ParseError(String),
     /// Poisoned lock
     #[error("poisoned lock '{0}'")]
     PoisonedLock(String),
+    /// Error when a repository with a registered public key has no signature for an asset
+    #[error("signature not found for asset '{0}'")]
+    SignatureNotFound(String),
+    /// Error when the signature of an archive does not match the archive
+    #[error("signature verification failed: {0}")]
+    SignatureVerificationFailed(String),
     /// Repository failure
     #[error("{0}")]
     RepositoryFailure(String),
@@ -0,0 +1,187 @@
+use crate::scoped;
+use std::io;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Windows error code when a file is in use by another process
+#[cfg(windows)]
+const ERROR_SHARING_VIOLATION: i32 = 32;
+/// Windows error code when a region of a file is locked by another process
+#[cfg(windows)]
+const ERROR_LOCK_VIOLATION: i32 = 33;
+/// Name of the alternate data stream that records the zone a file was downloaded from
+#[cfg(windows)]
+const ZONE_IDENTIFIER_STREAM: &str = ":Zone.Identifier";
+
+/// Returns the extended-length form (`\\?\`) of the `path` on Windows, so that the files of an
+/// archive can be extracted to paths longer than `MAX_PATH` (260 characters); other paths are
+/// returned unchanged. The path is made absolute and normalized, as extended-length paths are not
+/// normalized by Windows.
+#[must_use]
+pub(crate) fn extended_length_path(path: &Path) -> PathBuf {
+    #[cfg(windows)]
+    {
+        const PREFIX: &str = r"\\?\";
+        let Ok(path) = std::path::absolute(path) else {
+            return path.to_path_buf();
+        };
+        let path_string = path.to_string_lossy().into_owned();
+        if path_string.starts_with(PREFIX) {
+            path
+        } else if let Some(unc_path) = path_string.strip_prefix(r"\\") {
+            PathBuf::from(format!(r"{PREFIX}UNC\{unc_path}"))
+        } else {
+            PathBuf::from(format!("{PREFIX}{path_string}"))
+        }
+    }
+    #[cfg(not(windows))]
+    {
+        path.to_path_buf()
+    }
+}
+
+/// Removes the Mark-of-the-Web (the `Zone.Identifier` alternate data stream) from the files in
+/// the `dir` on Windows. Files that are marked as downloaded from the internet can be blocked
+/// from executing, which fails with "Access is denied (os error 5)".
+///
+/// # Errors
+/// * If the directory cannot be read, or a stream cannot be removed.
+#[cfg_attr(not(windows), expect(clippy::unnecessary_wraps))]
+pub(crate) fn unblock(dir: &Path) -> io::Result<()> {
+    #[cfg(windows)]
+    for entry in std::fs::read_dir(dir)? {
+        let entry = entry?;
+        let path = entry.path();
+        if entry.file_type()?.is_dir() {
+            unblock(&path)?;
+            continue;
+        }
+        let mut stream = path.into_os_string();
+        stream.push(ZONE_IDENTIFIER_STREAM);
+        match std::fs::remove_file(&stream) {
+            Err(error) if error.kind() != io::ErrorKind::NotFound => return Err(error),
+            _ => {}
+        }
+    }
+    #[cfg(not(windows))]
+    let _ = dir;
+    Ok(())
+}
+
+/// Runs the file system `operation`, retrying it with the backoff of the scoped
+/// [retry policy](crate::ScopedOptions::retry_policy) while the file is locked by another process;
+/// e.g. while an antivirus scanner inspects a file that was just written on Windows.
+///
+/// # Errors
+/// * The error of the last attempt if the operation does not succeed.
+pub(crate) fn retry_locked<T, F>(mut operation: F) -> io::Result<T>
+where
+    F: FnMut() -> io::Result<T>,
+{
+    let policy = scoped::retry_policy();
+    let mut attempt = 1;
+    loop {
+        match operation() {
+            Err(error) if attempt < policy.max_attempts && is_locked(&error) => {
+                let backoff = policy.backoff(attempt - 1);
+                debug!("File is locked: {error}; retrying in {backoff:?}");
+                std::thread::sleep(backoff);
+                attempt += 1;
+            }
+            result => return result,
+        }
+    }
+}
+
+/// Returns `true` if the `error` is caused by a file that is locked by another process.
+fn is_locked(error: &io::Error) -> bool {
+    #[cfg(windows)]
+    {
+        matches!(
+            error.raw_os_error(),
+            Some(ERROR_SHARING_VIOLATION | ERROR_LOCK_VIOLATION)
+        )
+    }
+    #[cfg(not(windows))]
+    {
+        let _ = error;
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retry_locked() -> io::Result<()> {
+        let mut attempts = 0;
+        let value = retry_locked(|| {
+            attempts += 1;
+            Ok::<_, io::Error>(attempts)
+        })?;
+        assert_eq!(1, value);
+
+        let mut attempts = 0;
+        let result = retry_locked(|| {
+            attempts += 1;
+            Err::<(), _>(io::Error::new(io::ErrorKind::NotFound, "not found"))
+        });
+        assert!(result.is_err());
+        assert_eq!(1, attempts);
+        Ok(())
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_is_locked() {
+        assert!(is_locked(&io::Error::from_raw_os_error(
+            ERROR_SHARING_VIOLATION
+        )));
+        assert!(is_locked(&io::Error::from_raw_os_error(
+            ERROR_LOCK_VIOLATION
+        )));
+        assert!(!is_locked(&io::Error::from(
+            io::ErrorKind::PermissionDenied
+        )));
+    }
+
+    #[test]
+    fn test_unblock() -> io::Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let bin_dir = temp_dir.path().join("bin");
+        std::fs::create_dir_all(&bin_dir)?;
+        std::fs::write(bin_dir.join("postgres"), "postgres")?;
+        unblock(temp_dir.path())?;
+        assert!(bin_dir.join("postgres").exists());
+        Ok(())
+    }
+
+    #[cfg(not(windows))]
+    #[test]
+    fn test_extended_length_path() {
+        let path = Path::new("relative/path");
+        assert_eq!(path, extended_length_path(path));
+    }
+
+    #[cfg(windows)]
+    #[test]
+    fn test_extended_length_path() {
+        assert_eq!(
+            PathBuf::from(r"\\?\C:\postgresql\16.4.0"),
+            extended_length_path(Path::new(r"C:\postgresql\16.4.0"))
+        );
+        assert_eq!(
+            PathBuf::from(r"\\?\C:\postgresql\16.4.0"),
+            extended_length_path(Path::new(r"C:\postgresql\bin\..\16.4.0"))
+        );
+        assert_eq!(
+            PathBuf::from(r"\\?\UNC\server\share\postgresql"),
+            extended_length_path(Path::new(r"\\server\share\postgresql"))
+        );
+        assert_eq!(
+            PathBuf::from(r"\\?\C:\postgresql"),
+            extended_length_path(Path::new(r"\\?\C:\postgresql"))
+        );
+    }
+}
@@ -0,0 +1,265 @@
+use crate::Error::ParseError;
+use crate::Result;
+use regex_lite::Regex;
+use std::fmt::Display;
+use std::str::FromStr;
+
+/// Glob pattern matched against the paths of the files in an archive
+#[derive(Clone, Debug)]
+struct Glob {
+    pattern: String,
+    regex: Regex,
+}
+
+impl Glob {
+    /// Creates a new glob from the `pattern`; `*` matches any characters other than `/`, `?`
+    /// matches a single character other than `/`, and `**` matches any characters, including
+    /// `/`. A `**/` prefix also matches no directories, e.g. `**/*.a` matches `libpq.a`.
+    ///
+    /// # Errors
+    /// * If the pattern cannot be converted to a regular expression.
+    fn new(pattern: &str) -> Result<Self> {
+        let mut regex = String::from("^");
+        let mut chars = pattern.chars().peekable();
+        while let Some(char) = chars.next() {
+            match char {
+                '*' if chars.peek() == Some(&'*') => {
+                    chars.next();
+                    if chars.peek() == Some(&'/') {
+                        chars.next();
+                        regex.push_str("(?:.*/)?");
+                    } else {
+                        regex.push_str(".*");
+                    }
+                }
+                '*' => regex.push_str("[^/]*"),
+                '?' => regex.push_str("[^/]"),
+                _ => regex.push_str(&regex_lite::escape(&char.to_string())),
+            }
+        }
+        regex.push('$');
+        Ok(Self {
+            pattern: pattern.to_string(),
+            regex: Regex::new(&regex)?,
+        })
+    }
+
+    /// Returns `true` if the glob matches the `path`.
+    fn is_match(&self, path: &str) -> bool {
+        self.regex.is_match(path)
+    }
+}
+
+/// Filters the files extracted from an archive with include and exclude glob patterns matched
+/// against the path of each file relative to the root of the installation; e.g. `bin/postgres`
+/// or `include/libpq-fe.h`. A file is extracted if it matches any include pattern, or there are
+/// no include patterns, and it does not match any exclude pattern. Directories are always
+/// created.
+#[derive(Clone, Debug, Default)]
+pub struct ExtractFilter {
+    include: Vec<Glob>,
+    exclude: Vec<Glob>,
+}
+
+impl ExtractFilter {
+    /// Creates a new filter that extracts all files.
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Creates a new filter that only extracts the files needed to run a server: the `bin`,
+    /// `lib` and `share` directories, without static libraries, the files used to build
+    /// extensions, documentation or translations.
+    ///
+    /// # Errors
+    /// * If a pattern is invalid.
+    pub fn runtime() -> Result<Self> {
+        Self::new()
+            .include("bin/**")?
+            .include("lib/**")?
+            .include("share/**")?
+            .exclude("**/*.a")?
+            .exclude("lib/**/pgxs/**")?
+            .exclude("share/doc/**")?
+            .exclude("share/locale/**")?
+            .exclude("share/man/**")
+    }
+
+    /// Adds a glob `pattern` of the files to extract.
+    ///
+    /// # Errors
+    /// * If the pattern is invalid.
+    pub fn include(mut self, pattern: &str) -> Result<Self> {
+        self.include.push(Glob::new(pattern)?);
+        Ok(self)
+    }
+
+    /// Adds a glob `pattern` of the files to skip.
+    ///
+    /// # Errors
+    /// * If the pattern is invalid.
+    pub fn exclude(mut self, pattern: &str) -> Result<Self> {
+        self.exclude.push(Glob::new(pattern)?);
+        Ok(self)
+    }
+
+    /// Returns `true` if the filter extracts all files.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Returns `true` if the file at the `path`, relative to the root of the installation, is
+    /// extracted.
+    #[must_use]
+    pub fn is_included(&self, path: &str) -> bool {
+        let path = path.replace('\\', "/");
+        let path = path.trim_start_matches("./");
+        (self.include.is_empty() || self.include.iter().any(|glob| glob.is_match(path)))
+            && !self.exclude.iter().any(|glob| glob.is_match(path))
+    }
+
+    /// Returns `true` if the files extracted with this filter include all the files extracted
+    /// with the `other` filter; e.g. if an installation extracted with this filter can be used
+    /// where the `other` filter is configured.
+    #[must_use]
+    pub fn covers(&self, other: &ExtractFilter) -> bool {
+        self.is_empty() || self == other
+    }
+}
+
+/// Formats the filter with a line per pattern, e.g. `include bin/**`, which is parsed by
+/// [`ExtractFilter::from_str`].
+impl Display for ExtractFilter {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        for glob in &self.include {
+            writeln!(f, "include {}", glob.pattern)?;
+        }
+        for glob in &self.exclude {
+            writeln!(f, "exclude {}", glob.pattern)?;
+        }
+        Ok(())
+    }
+}
+
+impl FromStr for ExtractFilter {
+    type Err = crate::Error;
+
+    fn from_str(filter: &str) -> Result<Self> {
+        let mut extract_filter = Self::new();
+        for line in filter.lines().filter(|line| !line.trim().is_empty()) {
+            extract_filter = match line.split_once(' ') {
+                Some(("include", pattern)) => extract_filter.include(pattern)?,
+                Some(("exclude", pattern)) => extract_filter.exclude(pattern)?,
+                _ => return Err(ParseError(format!("invalid extract filter line: {line}"))),
+            };
+        }
+        Ok(extract_filter)
+    }
+}
+
+impl PartialEq for ExtractFilter {
+    fn eq(&self, other: &Self) -> bool {
+        let patterns = |globs: &[Glob]| {
+            globs
+                .iter()
+                .map(|glob| glob.pattern.clone())
+                .collect::<Vec<_>>()
+        };
+        patterns(&self.include) == patterns(&other.include)
+            && patterns(&self.exclude) == patterns(&other.exclude)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_glob() -> Result<()> {
+        let glob = Glob::new("lib/*.so")?;
+        assert!(glob.is_match("lib/libpq.so"));
+        assert!(!glob.is_match("lib/postgresql/plpgsql.so"));
+        assert!(!glob.is_match("lib/libpq.so.5"));
+
+        let glob = Glob::new("share/**")?;
+        assert!(glob.is_match("share/postgresql/timezone/UTC"));
+        assert!(!glob.is_match("shared"));
+
+        let glob = Glob::new("**/*.a")?;
+        assert!(glob.is_match("libpq.a"));
+        assert!(glob.is_match("lib/libpq.a"));
+        assert!(!glob.is_match("lib/libpq.so"));
+
+        let glob = Glob::new("bin/pg_?ump")?;
+        assert!(glob.is_match("bin/pg_dump"));
+        assert!(!glob.is_match("bin/pg_dumpall"));
+
+        let glob = Glob::new("lib/libpq+.so")?;
+        assert!(glob.is_match("lib/libpq+.so"));
+        assert!(!glob.is_match("lib/libpqq.so"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_filter_empty() {
+        let filter = ExtractFilter::new();
+        assert!(filter.is_empty());
+        assert!(filter.is_included("include/libpq-fe.h"));
+    }
+
+    #[test]
+    fn test_extract_filter() -> Result<()> {
+        let filter = ExtractFilter::new()
+            .include("bin/**")?
+            .include("lib/**")?
+            .exclude("**/*.a")?;
+        assert!(!filter.is_empty());
+        assert!(filter.is_included("bin/postgres"));
+        assert!(filter.is_included("./bin/initdb"));
+        assert!(filter.is_included("lib\\libpq.dll"));
+        assert!(!filter.is_included("lib/libpq.a"));
+        assert!(!filter.is_included("include/libpq-fe.h"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_filter_runtime() -> Result<()> {
+        let filter = ExtractFilter::runtime()?;
+        assert!(filter.is_included("bin/postgres"));
+        assert!(filter.is_included("lib/libpq.so.5"));
+        assert!(filter.is_included("lib/postgresql/plpgsql.so"));
+        assert!(filter.is_included("share/postgresql/postgresql.conf.sample"));
+        assert!(!filter.is_included("include/libpq-fe.h"));
+        assert!(!filter.is_included("lib/libpgcommon.a"));
+        assert!(!filter.is_included("lib/postgresql/pgxs/src/Makefile.global"));
+        assert!(!filter.is_included("share/locale/de/LC_MESSAGES/postgres-16.mo"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_filter_from_str() -> Result<()> {
+        let filter = ExtractFilter::runtime()?;
+        assert_eq!(filter, ExtractFilter::from_str(&filter.to_string())?);
+        assert_eq!(ExtractFilter::new(), ExtractFilter::from_str("")?);
+        assert!(ExtractFilter::from_str("ignore bin/**").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_filter_covers() -> Result<()> {
+        let runtime = ExtractFilter::runtime()?;
+        assert!(ExtractFilter::new().covers(&runtime));
+        assert!(runtime.covers(&ExtractFilter::runtime()?));
+        assert!(!runtime.covers(&ExtractFilter::new()));
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_filter_eq() -> Result<()> {
+        assert_eq!(ExtractFilter::runtime()?, ExtractFilter::runtime()?);
+        assert_ne!(ExtractFilter::new(), ExtractFilter::runtime()?);
+        Ok(())
+    }
+}
@@ -0,0 +1,355 @@
+use crate::extractor::filesystem::retry_locked;
+use crate::Error::Unexpected;
+use crate::Result;
+use std::fs::{copy, create_dir_all, read_dir, remove_dir_all, remove_file, symlink_metadata};
+use std::io;
+use std::path::{Component, Path, PathBuf};
+use tracing::debug;
+
+/// Links and directory modes of the members of an archive that are applied once all files of the
+/// archive are written, as the targets of the links may be written by
+/// [workers](crate::extractor::ExtractOptions::threads) or appear later in the archive. Links are
+/// copied from their targets on file systems that do not support them.
+#[derive(Debug, Default)]
+pub(crate) struct Links {
+    hard: Vec<(PathBuf, PathBuf)>,
+    copies: Vec<(PathBuf, PathBuf)>,
+    directory_modes: Vec<(PathBuf, u32)>,
+}
+
+impl Links {
+    /// Creates a symbolic link at the `link` path to the `target`, replacing any existing file.
+    /// The target is copied to the `link` path once the archive is extracted if the symbolic link
+    /// cannot be created.
+    ///
+    /// # Errors
+    /// * If the `target` is not within the `extract_dir`.
+    /// * If an existing file at the `link` path cannot be removed.
+    pub(crate) fn symlink(&mut self, link: &Path, extract_dir: &Path, target: &Path) -> Result<()> {
+        check_symlink(link, extract_dir, target)?;
+        retry_locked(|| remove_existing(link))?;
+        match create_symlink(target, link) {
+            Ok(()) => return Ok(()),
+            Err(error) => debug!(
+                "Failed to create symbolic link {} -> {}: {error}; copying the target",
+                link.display(),
+                target.display()
+            ),
+        }
+        let target = match link.parent() {
+            Some(parent) => parent.join(target),
+            None => target.to_path_buf(),
+        };
+        self.copies.push((link.to_path_buf(), target));
+        Ok(())
+    }
+
+    /// Creates a hard link at the `link` path to the `target` once the archive is extracted; the
+    /// `target` is the path of the member of the archive relative to the `extract_dir`.
+    ///
+    /// # Errors
+    /// * If the `target` is not within the `extract_dir`.
+    pub(crate) fn hard_link(
+        &mut self,
+        link: &Path,
+        extract_dir: &Path,
+        target: &Path,
+    ) -> Result<()> {
+        if target.is_absolute()
+            || target
+                .components()
+                .any(|component| matches!(component, Component::ParentDir))
+        {
+            return Err(Unexpected(format!(
+                "Hard link {} target is outside of the archive: {}",
+                link.display(),
+                target.display()
+            )));
+        }
+        self.hard
+            .push((link.to_path_buf(), extract_dir.join(target)));
+        Ok(())
+    }
+
+    /// Sets the `mode` of the `directory` once the archive is extracted. The owner of the
+    /// directory can always read, write and list the directory, so that it can be removed.
+    pub(crate) fn directory_mode(&mut self, directory: &Path, mode: u32) {
+        self.directory_modes
+            .push((directory.to_path_buf(), mode | 0o700));
+    }
+
+    /// Creates the hard links and copies, and sets the modes of the directories.
+    ///
+    /// # Errors
+    /// * If a link cannot be created or copied, or a mode cannot be set.
+    pub(crate) fn apply(self) -> Result<()> {
+        for (link, target) in self.hard {
+            retry_locked(|| remove_existing(&link))?;
+            if let Err(error) = std::fs::hard_link(&target, &link) {
+                debug!(
+                    "Failed to create hard link {} -> {}: {error}; copying the target",
+                    link.display(),
+                    target.display()
+                );
+                copy(&target, &link)?;
+            }
+        }
+        for (link, target) in self.copies {
+            copy_all(&target, &link)?;
+        }
+        set_directory_modes(&self.directory_modes)?;
+        Ok(())
+    }
+}
+
+/// Checks that the symbolic link at the `link` path to the `target` resolves to a path within the
+/// `extract_dir`. The target must be relative, and may only lead up the directories of the link
+/// before any other component, so that it resolves the same whether or not the directories it
+/// passes through are links; the directories of the link within the `extract_dir` must not be
+/// symbolic links, so that leading up resolves within the `extract_dir`.
+///
+/// # Errors
+/// * If the `target` is not within the `extract_dir`.
+fn check_symlink(link: &Path, extract_dir: &Path, target: &Path) -> Result<()> {
+    let outside = || {
+        Unexpected(format!(
+            "Symbolic link {} target is outside of the archive: {}",
+            link.display(),
+            target.display()
+        ))
+    };
+    let Some(link_dir) = link.strip_prefix(extract_dir).ok().and_then(Path::parent) else {
+        return Err(outside());
+    };
+
+    let mut depth = 0usize;
+    let mut directory = extract_dir.to_path_buf();
+    for component in link_dir.components() {
+        match component {
+            Component::Normal(name) => {
+                directory.push(name);
+                if symlink_metadata(&directory).is_ok_and(|metadata| metadata.is_symlink()) {
+                    return Err(outside());
+                }
+                depth += 1;
+            }
+            Component::CurDir => {}
+            _ => return Err(outside()),
+        }
+    }
+
+    let mut descended = false;
+    for component in target.components() {
+        match component {
+            Component::Normal(_) => {
+                descended = true;
+                depth += 1;
+            }
+            Component::CurDir => {}
+            Component::ParentDir if !descended && depth > 0 => depth -= 1,
+            _ => return Err(outside()),
+        }
+    }
+    Ok(())
+}
+
+/// Removes the file, link or directory at the `path`, if any.
+fn remove_existing(path: &Path) -> io::Result<()> {
+    match symlink_metadata(path) {
+        Ok(metadata) if metadata.is_dir() => remove_dir_all(path),
+        Ok(_) => remove_file(path),
+        Err(error) if error.kind() == io::ErrorKind::NotFound => Ok(()),
+        Err(error) => Err(error),
+    }
+}
+
+/// Creates a symbolic link at the `link` path to the `target`.
+fn create_symlink(target: &Path, link: &Path) -> io::Result<()> {
+    #[cfg(unix)]
+    {
+        std::os::unix::fs::symlink(target, link)
+    }
+    #[cfg(windows)]
+    {
+        std::os::windows::fs::symlink_file(target, link)
+    }
+    #[cfg(not(any(unix, windows)))]
+    {
+        let _ = (target, link);
+        Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            "symbolic links are not supported",
+        ))
+    }
+}
+
+/// Copies the file or directory at the `source` to the `destination`. Symbolic links are not
+/// followed, as they may lead outside of the archive; they are skipped.
+fn copy_all(source: &Path, destination: &Path) -> io::Result<()> {
+    let metadata = symlink_metadata(source)?;
+    if metadata.is_symlink() {
+        debug!("Skipping copy of symbolic link {}", source.display());
+    } else if metadata.is_dir() {
+        create_dir_all(destination)?;
+        for entry in read_dir(source)? {
+            let entry = entry?;
+            copy_all(&entry.path(), &destination.join(entry.file_name()))?;
+        }
+    } else {
+        copy(source, destination)?;
+    }
+    Ok(())
+}
+
+/// Sets the modes of the `directory_modes` on Unix, from the deepest directory up, so that the
+/// mode of a directory does not prevent the mode of its subdirectories from being set.
+#[cfg_attr(not(unix), expect(clippy::unnecessary_wraps))]
+fn set_directory_modes(directory_modes: &[(PathBuf, u32)]) -> io::Result<()> {
+    #[cfg(unix)]
+    for (directory, mode) in directory_modes.iter().rev() {
+        use std::os::unix::fs::PermissionsExt;
+        std::fs::set_permissions(directory, std::fs::Permissions::from_mode(*mode))?;
+    }
+    #[cfg(not(unix))]
+    let _ = directory_modes;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{read_to_string, write};
+
+    #[test]
+    fn test_symlink() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let target = temp_dir.path().join("libpq.so.5.16");
+        let link = temp_dir.path().join("libpq.so.5");
+        write(&target, "libpq")?;
+        write(&link, "existing")?;
+
+        let mut links = Links::default();
+        links.symlink(&link, temp_dir.path(), Path::new("libpq.so.5.16"))?;
+        links.apply()?;
+        assert_eq!("libpq", read_to_string(&link)?);
+        #[cfg(unix)]
+        assert!(symlink_metadata(&link)?.file_type().is_symlink());
+        Ok(())
+    }
+
+    #[test]
+    fn test_symlink_outside_archive() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let extract_dir = temp_dir.path();
+        let link = extract_dir.join("lib").join("libpq.so");
+        create_dir_all(extract_dir.join("lib"))?;
+        let mut links = Links::default();
+        links.symlink(&link, extract_dir, Path::new("../share/libpq.so"))?;
+        links.symlink(&link, extract_dir, Path::new("./libpq.so.5"))?;
+        for target in [
+            "/etc/passwd",
+            "../../etc/passwd",
+            "libpq/../../../etc/passwd",
+        ] {
+            assert!(links
+                .symlink(&link, extract_dir, Path::new(target))
+                .is_err());
+        }
+        assert!(links
+            .symlink(&link, &extract_dir.join("share"), Path::new("libpq.so"))
+            .is_err());
+
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(".", extract_dir.join("current"))?;
+            let link = extract_dir.join("current").join("libpq.so");
+            assert!(links
+                .symlink(&link, extract_dir, Path::new("../etc/passwd"))
+                .is_err());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_hard_link() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let link = temp_dir.path().join("postmaster");
+        let mut links = Links::default();
+        links.hard_link(&link, temp_dir.path(), Path::new("postgres"))?;
+        // The target is written after the hard link is recorded, as with worker threads
+        write(temp_dir.path().join("postgres"), "postgres")?;
+        links.apply()?;
+        assert_eq!("postgres", read_to_string(&link)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_hard_link_outside_archive() {
+        let mut links = Links::default();
+        let result = links.hard_link(
+            Path::new("link"),
+            Path::new("extract"),
+            Path::new("../postgres"),
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_copy_all() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let source = temp_dir.path().join("source");
+        create_dir_all(source.join("nested"))?;
+        write(source.join("nested").join("file"), "contents")?;
+
+        let mut links = Links::default();
+        links
+            .copies
+            .push((temp_dir.path().join("destination"), source));
+        links.apply()?;
+        assert_eq!(
+            "contents",
+            read_to_string(
+                temp_dir
+                    .path()
+                    .join("destination")
+                    .join("nested")
+                    .join("file")
+            )?
+        );
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_copy_all_does_not_follow_links() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let outside = temp_dir.path().join("outside");
+        create_dir_all(&outside)?;
+        write(outside.join("secret"), "secret")?;
+        let source = temp_dir.path().join("source");
+        create_dir_all(&source)?;
+        std::os::unix::fs::symlink(&outside, source.join("outside"))?;
+
+        let destination = temp_dir.path().join("destination");
+        copy_all(&source, &destination)?;
+        assert!(destination.is_dir());
+        assert!(!destination.join("outside").exists());
+        Ok(())
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_directory_mode() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+
+        let temp_dir = tempfile::tempdir()?;
+        let directory = temp_dir.path().join("share");
+        create_dir_all(&directory)?;
+        let mut links = Links::default();
+        links.directory_mode(&directory, 0o500);
+        links.apply()?;
+        let mode = std::fs::metadata(&directory)?.permissions().mode();
+        assert_eq!(0o700, mode & 0o777);
+        Ok(())
+    }
+}
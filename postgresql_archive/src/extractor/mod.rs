@@ -1,10 +1,34 @@
+mod filesystem;
+mod filter;
+mod links;
 mod model;
+mod options;
+pub(crate) mod progress;
 pub mod registry;
+mod staging;
+mod tar_extractor;
 mod tar_gz_extractor;
 mod tar_xz_extractor;
+#[cfg(feature = "tar-zst")]
+mod tar_zst_extractor;
+mod writer;
 mod zip_extractor;
 
+pub use filter::ExtractFilter;
 pub use model::ExtractDirectories;
+pub use options::{default_extract_options, set_default_extract_options, ExtractOptions};
+pub use progress::ExtractProgress;
+pub use staging::extract_staged;
+pub use tar_extractor::extract as tar_extract;
+pub use tar_extractor::extract_reader as tar_extract_reader;
+pub use tar_extractor::Compression;
 pub use tar_gz_extractor::extract as tar_gz_extract;
+pub use tar_gz_extractor::extract_reader as tar_gz_extract_reader;
 pub use tar_xz_extractor::extract as tar_xz_extract;
+pub use tar_xz_extractor::extract_reader as tar_xz_extract_reader;
+#[cfg(feature = "tar-zst")]
+pub use tar_zst_extractor::extract as tar_zst_extract;
+#[cfg(feature = "tar-zst")]
+pub use tar_zst_extractor::extract_reader as tar_zst_extract_reader;
 pub use zip_extractor::extract as zip_extract;
+pub use zip_extractor::extract_reader as zip_extract_reader;
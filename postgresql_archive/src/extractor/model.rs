@@ -1,20 +1,26 @@
+use crate::extractor::ExtractFilter;
 use crate::{Error, Result};
 use regex_lite::Regex;
 use std::fmt::Display;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 /// Extract directories manage the directories to extract a file in an archive to based upon the
-/// associated regex matching the file path.
+/// associated regex matching the file path, and the [filter](ExtractFilter) of the files to
+/// extract.
 #[derive(Debug)]
 pub struct ExtractDirectories {
     mappings: Vec<(Regex, PathBuf)>,
+    filter: ExtractFilter,
 }
 
 impl ExtractDirectories {
     /// Creates a new ExtractDirectories instance.
     #[must_use]
     pub fn new(mappings: Vec<(Regex, PathBuf)>) -> Self {
-        Self { mappings }
+        Self {
+            mappings,
+            filter: ExtractFilter::default(),
+        }
     }
 
     /// Adds a new mapping to the ExtractDirectories instance.
@@ -22,6 +28,24 @@ impl ExtractDirectories {
         self.mappings.push((regex, path));
     }
 
+    /// Sets the filter of the files to extract.
+    pub fn set_filter(&mut self, filter: ExtractFilter) {
+        self.filter = filter;
+    }
+
+    /// Returns the filter of the files to extract.
+    #[must_use]
+    pub fn filter(&self) -> &ExtractFilter {
+        &self.filter
+    }
+
+    /// Returns `true` if the file at the `file_path`, relative to the root of the installation,
+    /// is extracted.
+    #[must_use]
+    pub fn is_included(&self, file_path: &Path) -> bool {
+        self.filter.is_included(&file_path.to_string_lossy())
+    }
+
     /// Returns the path associated with the first regex that matches the file path.
     /// If no regex matches, then the file path is returned.
     ///
@@ -99,6 +123,17 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_is_included() -> Result<()> {
+        let mut extract_directories = ExtractDirectories::default();
+        assert!(extract_directories.is_included(Path::new("include/libpq-fe.h")));
+        extract_directories.set_filter(ExtractFilter::new().exclude("include/**")?);
+        assert!(!extract_directories.is_included(Path::new("include/libpq-fe.h")));
+        assert!(extract_directories.is_included(Path::new("bin/postgres")));
+        assert!(!extract_directories.filter().is_empty());
+        Ok(())
+    }
+
     #[test]
     fn test_display() -> Result<()> {
         let mappings = vec![
@@ -0,0 +1,90 @@
+use crate::extractor::ExtractFilter;
+use crate::Error::PoisonedLock;
+use crate::Result;
+use std::num::NonZeroUsize;
+use std::sync::{LazyLock, RwLock};
+
+/// Maximum number of threads that write extracted files by default; writing files is bound by
+/// the file system rather than the number of cores
+const MAX_DEFAULT_THREADS: usize = 8;
+
+static DEFAULT_EXTRACT_OPTIONS: LazyLock<RwLock<ExtractOptions>> =
+    LazyLock::new(|| RwLock::new(ExtractOptions::default()));
+
+/// Options for extracting archives. Tar archives are decompressed sequentially, while the
+/// extracted files are written by a pool of worker threads, so that the decompression of the
+/// archive is not held up by the file system.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ExtractOptions {
+    /// Number of threads that write the extracted files; `1` writes the files sequentially on
+    /// the thread that decompresses the archive, e.g. on machines with few cores
+    pub threads: usize,
+    /// Filter of the files extracted from the installation archives; e.g.
+    /// [`ExtractFilter::runtime`] to skip headers, static libraries and translations. The
+    /// [extract filter](crate::ScopedOptions::extract_filter) of a scope takes precedence.
+    pub filter: ExtractFilter,
+}
+
+impl ExtractOptions {
+    /// Creates new [`ExtractOptions`] that write the extracted files with a thread per available
+    /// core, up to 8 threads, and extract all files.
+    #[must_use]
+    pub fn new() -> Self {
+        let threads = std::thread::available_parallelism()
+            .map(NonZeroUsize::get)
+            .unwrap_or(1)
+            .min(MAX_DEFAULT_THREADS);
+        Self {
+            threads,
+            filter: ExtractFilter::default(),
+        }
+    }
+}
+
+impl Default for ExtractOptions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Gets the default [extract options](ExtractOptions).
+#[must_use]
+pub fn default_extract_options() -> ExtractOptions {
+    match DEFAULT_EXTRACT_OPTIONS.read() {
+        Ok(options) => options.clone(),
+        Err(error) => (**error.get_ref()).clone(),
+    }
+}
+
+/// Sets the default [extract options](ExtractOptions).
+///
+/// # Errors
+/// * If the lock is poisoned.
+pub fn set_default_extract_options(options: ExtractOptions) -> Result<()> {
+    let mut default_options = DEFAULT_EXTRACT_OPTIONS
+        .write()
+        .map_err(|error| PoisonedLock(error.to_string()))?;
+    *default_options = options;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_extract_options() {
+        let options = ExtractOptions::default();
+        assert!(options.threads >= 1);
+        assert!(options.threads <= MAX_DEFAULT_THREADS);
+        assert!(options.filter.is_empty());
+    }
+
+    #[test]
+    fn test_default_extract_options() -> Result<()> {
+        let options = default_extract_options();
+        set_default_extract_options(options.clone())?;
+        assert_eq!(options, default_extract_options());
+        Ok(())
+    }
+}
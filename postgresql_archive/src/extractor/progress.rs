@@ -0,0 +1,171 @@
+use std::cell::RefCell;
+use std::io::{self, Read};
+use std::sync::Arc;
+#[cfg(feature = "indicatif")]
+use tracing_indicatif::span_ext::IndicatifSpanExt;
+
+/// Callback that is passed the [progress](ExtractProgress) of an extraction
+pub(crate) type ProgressCallback = Arc<dyn Fn(&ExtractProgress) + Send + Sync>;
+
+thread_local! {
+    /// Progress of the extraction running on the thread, if it is tracked
+    static CURRENT: RefCell<Option<Tracker>> = const { RefCell::new(None) };
+}
+
+/// Progress of the extraction of an archive
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct ExtractProgress {
+    /// Number of files extracted
+    pub files: u64,
+    /// Number of files in the archive, if known before the extraction completes
+    pub files_total: Option<u64>,
+    /// Number of bytes written to the extracted files
+    pub bytes: u64,
+    /// Number of bytes of the archive read
+    pub archive_bytes: u64,
+    /// Size of the archive in bytes, if known
+    pub archive_bytes_total: Option<u64>,
+}
+
+/// Tracks the progress of an extraction, reporting it to the progress bar of the span the
+/// extraction was started in and to the callback, if any
+struct Tracker {
+    progress: ExtractProgress,
+    callback: Option<ProgressCallback>,
+    #[cfg(feature = "indicatif")]
+    span: tracing::Span,
+}
+
+/// Tracks the progress of the extraction performed by `extract_fn` on the current thread; the
+/// extractors report the files extracted and the archive bytes read while it runs.
+pub(crate) fn track<T>(
+    archive_bytes_total: Option<u64>,
+    callback: Option<ProgressCallback>,
+    extract_fn: impl FnOnce() -> T,
+) -> T {
+    let tracker = Tracker {
+        progress: ExtractProgress {
+            archive_bytes_total,
+            ..ExtractProgress::default()
+        },
+        callback,
+        #[cfg(feature = "indicatif")]
+        span: tracing::Span::current(),
+    };
+    #[cfg(feature = "indicatif")]
+    if let Some(length) = archive_bytes_total {
+        tracker.span.pb_set_length(length);
+    }
+    let previous = CURRENT.with(|current| current.replace(Some(tracker)));
+    let result = extract_fn();
+    CURRENT.with(|current| current.replace(previous));
+    result
+}
+
+/// Updates the progress of the extraction tracked on the current thread with `update_fn`, and
+/// reports the progress if `notify` is set.
+fn update(notify: bool, update_fn: impl FnOnce(&mut ExtractProgress)) {
+    let report = CURRENT.with(|current| {
+        let mut current = current.borrow_mut();
+        let tracker = current.as_mut()?;
+        update_fn(&mut tracker.progress);
+        #[cfg(feature = "indicatif")]
+        tracker.span.pb_set_position(tracker.progress.archive_bytes);
+        if notify {
+            tracker
+                .callback
+                .clone()
+                .map(|callback| (callback, tracker.progress))
+        } else {
+            None
+        }
+    });
+    // The callback is called once the tracker is released, so that it may extract archives
+    if let Some((callback, progress)) = report {
+        callback(&progress);
+    }
+}
+
+/// Sets the number of files in the archive being extracted.
+pub(crate) fn set_files_total(files_total: u64) {
+    update(true, |progress| progress.files_total = Some(files_total));
+}
+
+/// Records that a file of `bytes` was extracted.
+pub(crate) fn file_extracted(bytes: u64) {
+    update(true, |progress| {
+        progress.files += 1;
+        progress.bytes += bytes;
+    });
+}
+
+/// Records that `bytes` of the archive were read.
+pub(crate) fn archive_read(bytes: u64) {
+    update(false, |progress| progress.archive_bytes += bytes);
+}
+
+/// Reader that records the bytes read from the archive in the progress of the extraction
+pub(crate) struct ProgressReader<R> {
+    inner: R,
+}
+
+impl<R: Read> ProgressReader<R> {
+    /// Creates a new reader that records the bytes read from the `inner` reader.
+    pub(crate) fn new(inner: R) -> Self {
+        Self { inner }
+    }
+}
+
+impl<R: Read> Read for ProgressReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let bytes = self.inner.read(buf)?;
+        archive_read(u64::try_from(bytes).unwrap_or(u64::MAX));
+        Ok(bytes)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_track() -> io::Result<()> {
+        let reports = Arc::new(Mutex::new(Vec::new()));
+        let callback_reports = reports.clone();
+        let callback: ProgressCallback = Arc::new(move |progress: &ExtractProgress| {
+            callback_reports.lock().expect("lock").push(*progress);
+        });
+
+        let bytes = track(Some(4), Some(callback), || {
+            let mut buffer = Vec::new();
+            ProgressReader::new(Cursor::new(vec![1, 2, 3, 4])).read_to_end(&mut buffer)?;
+            file_extracted(10);
+            file_extracted(5);
+            Ok::<_, io::Error>(buffer.len())
+        })?;
+        assert_eq!(4, bytes);
+
+        let reports = reports.lock().expect("lock");
+        assert_eq!(2, reports.len());
+        assert_eq!(
+            ExtractProgress {
+                files: 2,
+                files_total: None,
+                bytes: 15,
+                archive_bytes: 4,
+                archive_bytes_total: Some(4),
+            },
+            reports[1]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_untracked() {
+        file_extracted(10);
+        archive_read(10);
+        CURRENT.with(|current| assert!(current.borrow().is_none()));
+    }
+}
@@ -1,11 +1,24 @@
-#[cfg(feature = "theseus")]
+#[cfg(any(
+    feature = "theseus",
+    feature = "file",
+    feature = "gitlab",
+    feature = "http"
+))]
 use crate::configuration::theseus;
 #[cfg(feature = "zonky")]
 use crate::configuration::zonky;
+#[cfg(all(feature = "tar-zst", feature = "theseus"))]
+use crate::extractor::Compression;
 use crate::extractor::ExtractDirectories;
+#[cfg(feature = "file")]
+use crate::repository::file;
+#[cfg(feature = "gitlab")]
+use crate::repository::gitlab;
+#[cfg(feature = "http")]
+use crate::repository::http;
 use crate::Error::{PoisonedLock, UnsupportedExtractor};
 use crate::Result;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use std::sync::{Arc, LazyLock, Mutex, RwLock};
 
 static REGISTRY: LazyLock<Arc<Mutex<RepositoryRegistry>>> =
@@ -13,11 +26,13 @@ static REGISTRY: LazyLock<Arc<Mutex<RepositoryRegistry>>> =
 
 type SupportsFn = fn(&str) -> Result<bool>;
 type ExtractFn = fn(&Vec<u8>, ExtractDirectories) -> Result<Vec<PathBuf>>;
+type ExtractFileFn = fn(&Path, ExtractDirectories) -> Result<Vec<PathBuf>>;
 
 /// Singleton struct to store extractors
 #[expect(clippy::type_complexity)]
 struct RepositoryRegistry {
     extractors: Vec<(Arc<RwLock<SupportsFn>>, Arc<RwLock<ExtractFn>>)>,
+    file_extractors: Vec<(Arc<RwLock<SupportsFn>>, Arc<RwLock<ExtractFileFn>>)>,
 }
 
 impl RepositoryRegistry {
@@ -25,6 +40,7 @@ impl RepositoryRegistry {
     fn new() -> Self {
         Self {
             extractors: Vec::new(),
+            file_extractors: Vec::new(),
         }
     }
 
@@ -58,6 +74,38 @@ impl RepositoryRegistry {
 
         Err(UnsupportedExtractor(url.to_string()))
     }
+
+    /// Registers a file extractor. Newly registered extractors take precedence over existing
+    /// ones.
+    fn register_file(&mut self, supports_fn: SupportsFn, extract_file_fn: ExtractFileFn) {
+        self.file_extractors.insert(
+            0,
+            (
+                Arc::new(RwLock::new(supports_fn)),
+                Arc::new(RwLock::new(extract_file_fn)),
+            ),
+        );
+    }
+
+    /// Gets a file extractor that supports the specified URL
+    ///
+    /// # Errors
+    /// * If the URL is not supported.
+    fn get_file(&self, url: &str) -> Result<ExtractFileFn> {
+        for (supports_fn, extract_file_fn) in &self.file_extractors {
+            let supports_function = supports_fn
+                .read()
+                .map_err(|error| PoisonedLock(error.to_string()))?;
+            if supports_function(url)? {
+                let extract_file_function = extract_file_fn
+                    .read()
+                    .map_err(|error| PoisonedLock(error.to_string()))?;
+                return Ok(*extract_file_function);
+            }
+        }
+
+        Err(UnsupportedExtractor(url.to_string()))
+    }
 }
 
 impl Default for RepositoryRegistry {
@@ -66,12 +114,43 @@ impl Default for RepositoryRegistry {
         let mut registry = Self::new();
         #[cfg(feature = "theseus")]
         registry.register(|url| Ok(url.starts_with(theseus::URL)), theseus::extract);
+        #[cfg(feature = "theseus")]
+        registry.register_file(
+            |url| Ok(url.starts_with(theseus::URL)),
+            theseus::extract_file,
+        );
         #[cfg(feature = "zonky")]
         registry.register(|url| Ok(url.starts_with(zonky::URL)), zonky::extract);
+        #[cfg(feature = "zonky")]
+        registry.register_file(|url| Ok(url.starts_with(zonky::URL)), zonky::extract_file);
+        #[cfg(feature = "file")]
+        registry.register(|url| Ok(url.starts_with(file::URL)), theseus::extract);
+        #[cfg(feature = "file")]
+        registry.register_file(|url| Ok(url.starts_with(file::URL)), theseus::extract_file);
+        #[cfg(feature = "http")]
+        registry.register(|url| Ok(url.starts_with(http::URL)), theseus::extract);
+        #[cfg(feature = "http")]
+        registry.register_file(|url| Ok(url.starts_with(http::URL)), theseus::extract_file);
+        #[cfg(feature = "gitlab")]
+        registry.register(|url| Ok(gitlab::supports(url)), theseus::extract);
+        #[cfg(feature = "gitlab")]
+        registry.register_file(|url| Ok(gitlab::supports(url)), theseus::extract_file);
+        #[cfg(all(feature = "tar-zst", feature = "theseus"))]
+        registry.register(|url| Ok(is_tar_zst(url)), theseus::extract);
+        #[cfg(all(feature = "tar-zst", feature = "theseus"))]
+        registry.register_file(|url| Ok(is_tar_zst(url)), theseus::extract_file);
         registry
     }
 }
 
+/// Returns whether the `url` is the URL of a Zstandard compressed tar archive; e.g. a
+/// [file](crate::repository::file) repository for a single archive.
+#[cfg(all(feature = "tar-zst", feature = "theseus"))]
+fn is_tar_zst(url: &str) -> bool {
+    let path = url.split(['?', '#']).next().unwrap_or_default();
+    Compression::from_name(path) == Some(Compression::Zstd)
+}
+
 /// Registers an extractor. Newly registered extractors take precedence over existing ones.
 ///
 /// # Errors
@@ -95,6 +174,101 @@ pub fn get(url: &str) -> Result<ExtractFn> {
     registry.get(url)
 }
 
+/// Registers a file extractor, which extracts an archive from a file without reading it into
+/// memory; see [`extract_from_path`](crate::extract_from_path). Newly registered extractors take
+/// precedence over existing ones.
+///
+/// # Errors
+/// * If the registry is poisoned.
+pub fn register_file(supports_fn: SupportsFn, extract_file_fn: ExtractFileFn) -> Result<()> {
+    let mut registry = REGISTRY
+        .lock()
+        .map_err(|error| PoisonedLock(error.to_string()))?;
+    registry.register_file(supports_fn, extract_file_fn);
+    Ok(())
+}
+
+/// Gets a file extractor that supports the specified URL
+///
+/// # Errors
+/// * If the URL is not supported.
+pub fn get_file(url: &str) -> Result<ExtractFileFn> {
+    let registry = REGISTRY
+        .lock()
+        .map_err(|error| PoisonedLock(error.to_string()))?;
+    registry.get_file(url)
+}
+
+/// A read-only snapshot of the registered extractors, used to debug which extractor is used for a
+/// URL; extractors registered after the snapshot is taken are not included.
+#[must_use]
+#[expect(clippy::type_complexity)]
+pub struct Snapshot {
+    extractors: Vec<(Arc<RwLock<SupportsFn>>, Arc<RwLock<ExtractFn>>)>,
+    file_extractors: Vec<(Arc<RwLock<SupportsFn>>, Arc<RwLock<ExtractFileFn>>)>,
+}
+
+impl Snapshot {
+    /// Returns the number of extractors in the snapshot.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.extractors.len()
+    }
+
+    /// Returns whether the snapshot has no extractors.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.extractors.is_empty()
+    }
+
+    /// Returns the position, in order of precedence, of the extractor used for the specified URL.
+    ///
+    /// # Errors
+    /// * If the registry is poisoned.
+    pub fn position(&self, url: &str) -> Result<Option<usize>> {
+        position(&self.extractors, url)
+    }
+
+    /// Returns the position, in order of precedence, of the file extractor used for the specified
+    /// URL.
+    ///
+    /// # Errors
+    /// * If the registry is poisoned.
+    pub fn file_position(&self, url: &str) -> Result<Option<usize>> {
+        position(&self.file_extractors, url)
+    }
+}
+
+/// Returns the position of the first entry whose supports function supports the URL.
+///
+/// # Errors
+/// * If the registry is poisoned.
+fn position<T>(entries: &[(Arc<RwLock<SupportsFn>>, T)], url: &str) -> Result<Option<usize>> {
+    for (index, (supports_fn, _)) in entries.iter().enumerate() {
+        let supports_function = supports_fn
+            .read()
+            .map_err(|error| PoisonedLock(error.to_string()))?;
+        if supports_function(url)? {
+            return Ok(Some(index));
+        }
+    }
+    Ok(None)
+}
+
+/// Takes a read-only snapshot of the registered extractors.
+///
+/// # Errors
+/// * If the registry is poisoned.
+pub fn snapshot() -> Result<Snapshot> {
+    let registry = REGISTRY
+        .lock()
+        .map_err(|error| PoisonedLock(error.to_string()))?;
+    Ok(Snapshot {
+        extractors: registry.extractors.clone(),
+        file_extractors: registry.file_extractors.clone(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -111,6 +285,42 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_register_file() -> Result<()> {
+        register_file(
+            |url| Ok(url == "https://file.foo.com"),
+            |_, _| Ok(Vec::new()),
+        )?;
+        let extractor = get_file("https://file.foo.com")?;
+        let mut extract_directories = ExtractDirectories::default();
+        extract_directories.add_mapping(Regex::new(".*")?, PathBuf::from("test"));
+        assert!(extractor(Path::new("test.tar.gz"), extract_directories).is_ok());
+        assert!(get_file("foo").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_snapshot() -> Result<()> {
+        let url = "https://snapshot.foo.com";
+        let snapshot = snapshot()?;
+        assert_eq!(None, snapshot.position(url)?);
+        register(
+            |url| Ok(url == "https://snapshot.foo.com"),
+            |_, _| Ok(Vec::new()),
+        )?;
+        register_file(
+            |url| Ok(url == "https://snapshot.foo.com"),
+            |_, _| Ok(Vec::new()),
+        )?;
+        let current = super::snapshot()?;
+        assert_eq!(Some(0), current.position(url)?);
+        assert_eq!(Some(0), current.file_position(url)?);
+        assert!(current.len() > snapshot.len());
+        assert_eq!(None, snapshot.position(url)?);
+        assert!(get(url).is_ok());
+        Ok(())
+    }
+
     #[test]
     fn test_get_error() {
         let error = get("foo").unwrap_err();
@@ -121,5 +331,6 @@ mod tests {
     #[cfg(feature = "theseus")]
     fn test_get_theseus_postgresql_binaries() {
         assert!(get(theseus::URL).is_ok());
+        assert!(get_file(theseus::URL).is_ok());
     }
 }
@@ -0,0 +1,186 @@
+use crate::extractor::filesystem::{extended_length_path, retry_locked, unblock};
+use crate::lock::LockFile;
+use crate::Result;
+use std::fs::{create_dir_all, read_dir, remove_dir_all, rename};
+use std::path::{Path, PathBuf};
+use tracing::{debug, instrument, warn};
+
+/// Name of the lock file, in the parent of the output directory, held during extraction
+const LOCK_FILE: &str = "postgresql-archive.lock";
+
+/// Extracts an archive to the `out_dir` with `extract_fn`, such that the `out_dir` only exists
+/// once the extraction is complete. The archive is extracted to a staging directory next to the
+/// `out_dir`, which is renamed to the `out_dir` when `extract_fn` succeeds and removed when it
+/// fails. Staging directories left behind by a process that was killed during an extraction
+/// are removed. Extractions by other processes to the same parent directory are serialized with
+/// a lock file; if the `out_dir` already exists, nothing is extracted. On Windows, `extract_fn`
+/// is passed the extended-length path of the staging directory, so that paths longer than
+/// `MAX_PATH` can be extracted, and the Mark-of-the-Web is removed from the extracted files.
+///
+/// Returns the paths of the extracted files within the `out_dir`.
+///
+/// # Errors
+/// Returns an error if the lock cannot be acquired, or the extraction or rename fails.
+#[instrument(skip(extract_fn))]
+pub fn extract_staged<F>(out_dir: &Path, extract_fn: F) -> Result<Vec<PathBuf>>
+where
+    F: FnOnce(&Path) -> Result<Vec<PathBuf>>,
+{
+    let parent_dir = if let Some(parent) = out_dir.parent() {
+        parent
+    } else {
+        debug!("No parent directory for {}", out_dir.to_string_lossy());
+        out_dir
+    };
+    create_dir_all(parent_dir)?;
+
+    let _lock = LockFile::acquire(&parent_dir.join(LOCK_FILE), LockFile::WAIT_TIMEOUT)?;
+    // If the directory already exists, then the archive has already been
+    // extracted by another process.
+    if out_dir.exists() {
+        debug!(
+            "Directory already exists {}; skipping extraction",
+            out_dir.to_string_lossy()
+        );
+        return Ok(Vec::new());
+    }
+
+    let prefix = staging_prefix(out_dir);
+    remove_orphaned_staging_dirs(parent_dir, &prefix);
+    let staging_dir = tempfile::Builder::new()
+        .prefix(&prefix)
+        .tempdir_in(parent_dir)?;
+    debug!(
+        "Extracting archive to {}",
+        staging_dir.path().to_string_lossy()
+    );
+    // Extended-length paths are used on Windows, so that long paths in the archive can be extracted
+    let extract_dir = extended_length_path(staging_dir.path());
+    // The staging directory is removed when it is dropped if the extraction fails
+    let files = extract_fn(&extract_dir)?;
+    if let Err(error) = unblock(&extract_dir) {
+        warn!("Failed to unblock the extracted files: {error}");
+    }
+    let staging_dir = staging_dir.into_path();
+
+    debug!(
+        "Renaming {} to {}",
+        staging_dir.to_string_lossy(),
+        out_dir.to_string_lossy()
+    );
+    if let Err(error) = retry_locked(|| rename(&staging_dir, out_dir)) {
+        let _ = remove_dir_all(&staging_dir);
+        return Err(error.into());
+    }
+
+    let files = files
+        .into_iter()
+        .map(|file| {
+            match file
+                .strip_prefix(&extract_dir)
+                .or_else(|_| file.strip_prefix(&staging_dir))
+            {
+                Ok(relative_path) => out_dir.join(relative_path),
+                Err(_) => file,
+            }
+        })
+        .collect();
+    Ok(files)
+}
+
+/// Get the name prefix of the staging directories for the `out_dir`
+fn staging_prefix(out_dir: &Path) -> String {
+    let name = out_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    format!(".{name}.extract-")
+}
+
+/// Remove the staging directories with the `prefix` in the `parent_dir`; while the extraction lock
+/// is held, any such directory was left behind by a process that did not complete an extraction.
+fn remove_orphaned_staging_dirs(parent_dir: &Path, prefix: &str) {
+    let Ok(entries) = read_dir(parent_dir) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        if !entry.file_name().to_string_lossy().starts_with(prefix) {
+            continue;
+        }
+        let path = entry.path();
+        debug!(
+            "Removing orphaned staging directory {}",
+            path.to_string_lossy()
+        );
+        if let Err(error) = remove_dir_all(&path) {
+            warn!(
+                "Failed to remove orphaned staging directory {}: {error}",
+                path.to_string_lossy()
+            );
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Error::Unexpected;
+
+    #[test]
+    fn test_extract_staged() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let out_dir = temp_dir.path().join("16.4.0");
+        let files = extract_staged(&out_dir, |staging_dir| {
+            assert_ne!(out_dir, staging_dir);
+            let file = staging_dir.join("README");
+            std::fs::write(&file, "test")?;
+            Ok(vec![file])
+        })?;
+
+        assert_eq!(vec![out_dir.join("README")], files);
+        assert_eq!("test", std::fs::read_to_string(out_dir.join("README"))?);
+        assert_eq!(1, read_dir(temp_dir.path())?.count());
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_staged_exists() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let out_dir = temp_dir.path().join("16.4.0");
+        create_dir_all(&out_dir)?;
+        let files = extract_staged(&out_dir, |_| panic!("extracted existing directory"))?;
+        assert!(files.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_staged_failure() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let out_dir = temp_dir.path().join("16.4.0");
+        let result = extract_staged(&out_dir, |staging_dir| {
+            std::fs::write(staging_dir.join("README"), "test")?;
+            Err(Unexpected("extraction failed".to_string()))
+        });
+
+        assert!(result.is_err());
+        assert!(!out_dir.exists());
+        assert_eq!(0, read_dir(temp_dir.path())?.count());
+        Ok(())
+    }
+
+    #[test]
+    fn test_extract_staged_removes_orphans() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let out_dir = temp_dir.path().join("16.4.0");
+        let orphan = temp_dir.path().join(".16.4.0.extract-orphan");
+        create_dir_all(orphan.join("bin"))?;
+        let other = temp_dir.path().join("16.3.0");
+        create_dir_all(&other)?;
+
+        extract_staged(&out_dir, |_| Ok(Vec::new()))?;
+        assert!(!orphan.exists());
+        assert!(other.exists());
+        assert!(out_dir.exists());
+        Ok(())
+    }
+}
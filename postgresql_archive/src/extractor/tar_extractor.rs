@@ -0,0 +1,232 @@
+use crate::extractor::ExtractDirectories;
+use crate::Result;
+use std::io::{BufRead, BufReader, Cursor};
+use std::path::{Path, PathBuf};
+use tracing::{debug, instrument};
+
+/// Magic bytes of a gzip stream
+const GZIP_MAGIC: &[u8] = &[0x1f, 0x8b];
+/// Magic bytes of an xz stream
+const XZ_MAGIC: &[u8] = &[0xfd, 0x37, 0x7a, 0x58, 0x5a, 0x00];
+/// Magic bytes of a Zstandard frame
+const ZSTD_MAGIC: &[u8] = &[0x28, 0xb5, 0x2f, 0xfd];
+
+/// Compression of a tar archive
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Compression {
+    /// gzip (`.tar.gz`)
+    Gzip,
+    /// xz (`.tar.xz`)
+    Xz,
+    /// Zstandard (`.tar.zst`); requires the `tar-zst` feature
+    Zstd,
+}
+
+impl Compression {
+    /// Detects the compression from the magic bytes at the start of the archive `bytes`, or
+    /// `None` if the compression is not recognized.
+    #[must_use]
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.starts_with(GZIP_MAGIC) {
+            Some(Self::Gzip)
+        } else if bytes.starts_with(XZ_MAGIC) {
+            Some(Self::Xz)
+        } else if bytes.starts_with(ZSTD_MAGIC) {
+            Some(Self::Zstd)
+        } else {
+            None
+        }
+    }
+
+    /// Gets the compression from the extension of the archive `name`; e.g.
+    /// `postgresql-16.4.0-x86_64-unknown-linux-gnu.tar.zst`.
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        let path = Path::new(name);
+        let extension = path.extension()?.to_str()?.to_ascii_lowercase();
+        let is_tar = path
+            .file_stem()
+            .and_then(|stem| Path::new(stem).extension())
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("tar"));
+        match (extension.as_str(), is_tar) {
+            ("gz", true) | ("tgz", _) => Some(Self::Gzip),
+            ("xz", true) | ("txz", _) => Some(Self::Xz),
+            ("zst", true) | ("tzst", _) => Some(Self::Zstd),
+            _ => None,
+        }
+    }
+}
+
+/// Extracts the compressed tar `bytes` to paths defined in `extract_directories`, detecting the
+/// compression from the magic bytes of the archive; see [`extract_reader`].
+///
+/// # Errors
+/// Returns an error if the extraction fails.
+#[instrument(skip(bytes))]
+pub fn extract(bytes: &Vec<u8>, extract_directories: ExtractDirectories) -> Result<Vec<PathBuf>> {
+    extract_reader(Cursor::new(bytes), extract_directories)
+}
+
+/// Extracts the compressed tar archive read from the `reader` to paths defined in
+/// `extract_directories`, detecting the compression from the magic bytes of the archive.
+/// Archives with an unrecognized compression are extracted as gzip compressed archives.
+///
+/// # Errors
+/// Returns an error if the extraction fails, or the compression is not supported.
+#[instrument(skip(reader))]
+pub fn extract_reader<R: std::io::Read>(
+    reader: R,
+    extract_directories: ExtractDirectories,
+) -> Result<Vec<PathBuf>> {
+    let mut reader = BufReader::new(reader);
+    let compression = Compression::detect(reader.fill_buf()?).unwrap_or(Compression::Gzip);
+    debug!("Extracting {compression:?} compressed tar archive");
+    match compression {
+        Compression::Gzip => super::tar_gz_extract_reader(reader, extract_directories),
+        Compression::Xz => super::tar_xz_extract_reader(reader, extract_directories),
+        #[cfg(feature = "tar-zst")]
+        Compression::Zstd => super::tar_zst_extract_reader(reader, extract_directories),
+        #[cfg(not(feature = "tar-zst"))]
+        Compression::Zstd => Err(crate::Error::UnsupportedExtractor(
+            "Zstandard compressed archive; enable the tar-zst feature".to_string(),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compression_detect() {
+        assert_eq!(
+            Some(Compression::Gzip),
+            Compression::detect(&[0x1f, 0x8b, 0x08])
+        );
+        assert_eq!(Some(Compression::Xz), Compression::detect(XZ_MAGIC));
+        assert_eq!(
+            Some(Compression::Zstd),
+            Compression::detect(&[0x28, 0xb5, 0x2f, 0xfd, 0])
+        );
+        assert_eq!(None, Compression::detect(&[]));
+        assert_eq!(None, Compression::detect(b"PK\x03\x04"));
+    }
+
+    #[test]
+    fn test_compression_from_name() {
+        assert_eq!(
+            Some(Compression::Gzip),
+            Compression::from_name("postgresql.tar.gz")
+        );
+        assert_eq!(
+            Some(Compression::Xz),
+            Compression::from_name("postgresql.tar.xz")
+        );
+        assert_eq!(
+            Some(Compression::Zstd),
+            Compression::from_name("postgresql.tar.zst")
+        );
+        assert_eq!(
+            Some(Compression::Gzip),
+            Compression::from_name("postgresql.TAR.GZ")
+        );
+        assert_eq!(None, Compression::from_name("postgresql.gz"));
+        assert_eq!(None, Compression::from_name("postgresql.zip"));
+    }
+
+    #[test]
+    fn test_extract_links_and_modes() -> Result<()> {
+        use std::io::Write;
+
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Directory);
+        header.set_size(0);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder.append_data(&mut header, "postgresql/bin/", std::io::empty())?;
+        let contents = b"#!/bin/sh";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o755);
+        header.set_cksum();
+        builder.append_data(&mut header, "postgresql/bin/postgres", &contents[..])?;
+        // Links are appended before and after their targets
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_size(0);
+        header.set_mode(0o777);
+        builder.append_link(&mut header, "postgresql/lib/libpq.so", "libpq.so.5")?;
+        let mut header = tar::Header::new_gnu();
+        header.set_size(5);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, "postgresql/lib/libpq.so.5", &b"libpq"[..])?;
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Link);
+        header.set_size(0);
+        header.set_mode(0o755);
+        builder.append_link(
+            &mut header,
+            "postgresql/bin/postmaster",
+            "postgresql/bin/postgres",
+        )?;
+        let tar = builder.into_inner()?;
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::fast());
+        encoder.write_all(&tar)?;
+        let bytes = encoder.finish()?;
+
+        let temp_dir = tempfile::tempdir()?;
+        let mut extract_directories = ExtractDirectories::default();
+        extract_directories.add_mapping(regex_lite::Regex::new(".*")?, temp_dir.path().into());
+        let files = extract(&bytes, extract_directories)?;
+        assert_eq!(4, files.len());
+
+        let bin_dir = temp_dir.path().join("bin");
+        let lib_dir = temp_dir.path().join("lib");
+        assert_eq!("libpq", std::fs::read_to_string(lib_dir.join("libpq.so"))?);
+        assert_eq!(
+            "#!/bin/sh",
+            std::fs::read_to_string(bin_dir.join("postmaster"))?
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+
+            let libpq = std::fs::symlink_metadata(lib_dir.join("libpq.so"))?;
+            assert!(libpq.file_type().is_symlink());
+            for file in ["postgres", "postmaster"] {
+                let mode = std::fs::metadata(bin_dir.join(file))?.permissions().mode();
+                assert_eq!(0o755, mode & 0o777);
+            }
+        }
+        Ok(())
+    }
+
+    #[cfg(feature = "tar-zst")]
+    #[test]
+    fn test_extract_tar_zst() -> Result<()> {
+        let mut builder = tar::Builder::new(Vec::new());
+        let contents = b"PostgreSQL";
+        let mut header = tar::Header::new_gnu();
+        header.set_size(contents.len() as u64);
+        header.set_mode(0o644);
+        header.set_cksum();
+        builder.append_data(&mut header, "postgresql/README", &contents[..])?;
+        let tar = builder.into_inner()?;
+        let bytes = zstd::encode_all(Cursor::new(tar), 0)?;
+        assert_eq!(Some(Compression::Zstd), Compression::detect(&bytes));
+
+        let temp_dir = tempfile::tempdir()?;
+        let mut extract_directories = ExtractDirectories::default();
+        extract_directories.add_mapping(regex_lite::Regex::new(".*")?, temp_dir.path().into());
+        let files = extract(&bytes, extract_directories)?;
+        assert_eq!(1, files.len());
+        // The top level directory of the archive is stripped, as for gzip compressed archives
+        assert_eq!(
+            "PostgreSQL",
+            std::fs::read_to_string(temp_dir.path().join("README"))?
+        );
+        Ok(())
+    }
+}
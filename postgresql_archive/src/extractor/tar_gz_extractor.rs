@@ -1,10 +1,13 @@
+use crate::extractor::links::Links;
+use crate::extractor::progress::{self, ProgressReader};
+use crate::extractor::writer::with_file_writer;
 use crate::extractor::ExtractDirectories;
 use crate::Error::Unexpected;
 use crate::Result;
 use flate2::bufread::GzDecoder;
 use num_format::{Locale, ToFormattedString};
-use std::fs::{create_dir_all, File};
-use std::io::{copy, BufReader, Cursor};
+use std::fs::create_dir_all;
+use std::io::{BufReader, Cursor, Read};
 use std::path::PathBuf;
 use tar::Archive;
 use tracing::{debug, instrument, warn};
@@ -15,58 +18,90 @@ use tracing::{debug, instrument, warn};
 /// Returns an error if the extraction fails.
 #[instrument(skip(bytes))]
 pub fn extract(bytes: &Vec<u8>, extract_directories: ExtractDirectories) -> Result<Vec<PathBuf>> {
+    extract_reader(Cursor::new(bytes), extract_directories)
+}
+
+/// Extracts the compressed tar archive read from the `reader` to paths defined in
+/// `extract_directories`; the archive is decompressed and extracted as it is read, so that it
+/// does not need to be held in memory.
+///
+/// # Errors
+/// Returns an error if the extraction fails.
+#[instrument(skip(reader))]
+pub fn extract_reader<R: Read>(
+    reader: R,
+    extract_directories: ExtractDirectories,
+) -> Result<Vec<PathBuf>> {
     let mut files = Vec::new();
-    let input = BufReader::new(Cursor::new(bytes));
+    let input = BufReader::new(ProgressReader::new(reader));
     let decoder = GzDecoder::new(input);
     let mut archive = Archive::new(decoder);
-    let mut extracted_bytes = 0;
-
-    for archive_entry in archive.entries()? {
-        let mut entry = archive_entry?;
-        let entry_header = entry.header();
-        let entry_type = entry_header.entry_type();
-        let entry_size = entry_header.size()?;
-        #[cfg(unix)]
-        let file_mode = entry_header.mode()?;
+    let mut links = Links::default();
+    let extracted_bytes = with_file_writer(|writer| {
+        let mut extracted_bytes = 0;
+        for archive_entry in archive.entries()? {
+            let mut entry = archive_entry?;
+            let entry_header = entry.header();
+            let entry_type = entry_header.entry_type();
+            let entry_size = entry_header.size()?;
+            let file_mode = entry_header.mode()?;
 
-        let entry_header_path = entry_header.path()?.to_path_buf();
-        let prefix = match entry_header_path.components().next() {
-            Some(component) => component.as_os_str().to_str().unwrap_or_default(),
-            None => {
-                return Err(Unexpected(
-                    "Failed to get file header path prefix".to_string(),
-                ));
-            }
-        };
-        let stripped_entry_header_path = entry_header_path.strip_prefix(prefix)?.to_path_buf();
-        let Ok(extract_dir) = extract_directories.get_path(prefix) else {
-            continue;
-        };
-        let mut entry_name = extract_dir.clone();
-        entry_name.push(stripped_entry_header_path);
-
-        if entry_type.is_dir() || entry_name.is_dir() {
-            create_dir_all(&entry_name)?;
-        } else if entry_type.is_file() {
-            let mut output_file = File::create(&entry_name)?;
-            copy(&mut entry, &mut output_file)?;
-            extracted_bytes += entry_size;
-
-            #[cfg(unix)]
+            let entry_header_path = entry_header.path()?.to_path_buf();
+            let prefix = match entry_header_path.components().next() {
+                Some(component) => component.as_os_str().to_str().unwrap_or_default(),
+                None => {
+                    return Err(Unexpected(
+                        "Failed to get file header path prefix".to_string(),
+                    ));
+                }
+            };
+            let stripped_entry_header_path = entry_header_path.strip_prefix(prefix)?.to_path_buf();
+            let Ok(extract_dir) = extract_directories.get_path(prefix) else {
+                continue;
+            };
+            if !entry_type.is_dir() && !extract_directories.is_included(&stripped_entry_header_path)
             {
-                use std::os::unix::fs::PermissionsExt;
-                output_file.set_permissions(std::fs::Permissions::from_mode(file_mode))?;
+                continue;
             }
-            files.push(entry_name);
-        } else if entry_type.is_symlink() {
-            #[cfg(unix)]
-            if let Some(symlink_target) = entry.link_name()? {
-                let symlink_path = entry_name.clone();
-                std::os::unix::fs::symlink(symlink_target.as_ref(), symlink_path)?;
+            let mut entry_name = extract_dir.clone();
+            entry_name.push(stripped_entry_header_path);
+
+            if entry_type.is_dir() || entry_name.is_dir() {
+                create_dir_all(&entry_name)?;
+                if entry_type.is_dir() {
+                    links.directory_mode(&entry_name, file_mode);
+                }
+            } else if entry_type.is_file() {
+                if let Some(parent) = entry_name.parent() {
+                    create_dir_all(parent)?;
+                }
+                writer.write(entry_name.clone(), &mut entry, entry_size, Some(file_mode))?;
+                extracted_bytes += entry_size;
+                progress::file_extracted(entry_size);
+                files.push(entry_name);
+            } else if entry_type.is_symlink() || entry_type.is_hard_link() {
+                let Some(link_target) = entry.link_name()? else {
+                    continue;
+                };
+                if let Some(parent) = entry_name.parent() {
+                    create_dir_all(parent)?;
+                }
+                if entry_type.is_symlink() {
+                    links.symlink(&entry_name, &extract_dir, &link_target)?;
+                } else {
+                    links.hard_link(
+                        &entry_name,
+                        &extract_dir,
+                        link_target.strip_prefix(prefix).unwrap_or(&link_target),
+                    )?;
+                }
                 files.push(entry_name);
+                progress::file_extracted(0);
             }
         }
-    }
+        Ok(extracted_bytes)
+    })?;
+    links.apply()?;
 
     let number_of_files = files.len();
     debug!(
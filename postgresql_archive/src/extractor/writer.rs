@@ -0,0 +1,207 @@
+use crate::extractor::filesystem::retry_locked;
+use crate::extractor::options::default_extract_options;
+use crate::scoped;
+use crate::Error::Unexpected;
+use crate::Result;
+use std::fs::File;
+use std::io::{copy, Read, Write};
+use std::path::PathBuf;
+use std::sync::mpsc::{sync_channel, Receiver, SyncSender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Number of files per thread that may be held in memory waiting to be written
+const QUEUED_FILES_PER_THREAD: usize = 4;
+
+/// File read from an archive, to be written by a worker
+struct QueuedFile {
+    path: PathBuf,
+    contents: Vec<u8>,
+    mode: Option<u32>,
+}
+
+/// Writes the files extracted from an archive, either on the calling thread or with a pool of
+/// worker threads while the calling thread continues to read the archive; see
+/// [`ExtractOptions`](crate::extractor::ExtractOptions).
+pub(crate) struct FileWriter {
+    sender: Option<SyncSender<QueuedFile>>,
+    error: Arc<Mutex<Option<std::io::Error>>>,
+}
+
+impl FileWriter {
+    /// Writes the file at the `path` with the contents of the `reader`, and sets the `mode` of
+    /// the file on Unix. The file is written by a worker if the writer has workers, in which case
+    /// the contents are read into memory first.
+    ///
+    /// # Errors
+    /// * If the file cannot be read or written, or a worker failed to write an earlier file.
+    pub(crate) fn write<R: Read>(
+        &self,
+        path: PathBuf,
+        reader: &mut R,
+        size: u64,
+        mode: Option<u32>,
+    ) -> Result<()> {
+        let Some(sender) = &self.sender else {
+            let mut file = retry_locked(|| File::create(&path))?;
+            copy(reader, &mut file)?;
+            set_mode(&file, mode)?;
+            return Ok(());
+        };
+        self.check()?;
+        let mut contents = Vec::with_capacity(usize::try_from(size).unwrap_or_default());
+        reader.read_to_end(&mut contents)?;
+        sender
+            .send(QueuedFile {
+                path,
+                contents,
+                mode,
+            })
+            .map_err(|error| Unexpected(format!("failed to queue file: {error}")))
+    }
+
+    /// Returns the first error of the workers, if any.
+    fn check(&self) -> Result<()> {
+        let mut error = self
+            .error
+            .lock()
+            .map_err(|error| Unexpected(error.to_string()))?;
+        match error.take() {
+            Some(error) => Err(error.into()),
+            None => Ok(()),
+        }
+    }
+}
+
+/// Calls `extract_fn` with a [`FileWriter`] that writes files with the number of threads of the
+/// [default extract options](crate::extractor::default_extract_options), and waits for all files
+/// to be written before returning.
+///
+/// # Errors
+/// * If `extract_fn` fails, or a file cannot be written.
+pub(crate) fn with_file_writer<T, F>(extract_fn: F) -> Result<T>
+where
+    F: FnOnce(&FileWriter) -> Result<T>,
+{
+    let threads = default_extract_options().threads;
+    if threads <= 1 {
+        let writer = FileWriter {
+            sender: None,
+            error: Arc::default(),
+        };
+        return extract_fn(&writer);
+    }
+
+    let (sender, receiver) = sync_channel(threads * QUEUED_FILES_PER_THREAD);
+    let receiver = Arc::new(Mutex::new(receiver));
+    let mut writer = FileWriter {
+        sender: Some(sender),
+        error: Arc::default(),
+    };
+    let result = thread::scope(|scope| {
+        for _ in 0..threads {
+            let receiver = receiver.clone();
+            let error = writer.error.clone();
+            let options = scoped::current();
+            scope.spawn(move || scoped::enter(options, || write_queued_files(&receiver, &error)));
+        }
+        // Only the workers hold the receiver, so that files cannot be queued if they all stop
+        drop(receiver);
+        let result = extract_fn(&writer);
+        // Closing the channel stops the workers once the queued files are written
+        writer.sender = None;
+        result
+    });
+    let value = result?;
+    writer.check()?;
+    Ok(value)
+}
+
+/// Writes the files received from the `receiver` until the channel is closed, recording the
+/// first failure in `error`; files received after a failure are discarded.
+fn write_queued_files(
+    receiver: &Mutex<Receiver<QueuedFile>>,
+    error: &Mutex<Option<std::io::Error>>,
+) {
+    loop {
+        let queued_file = match receiver.lock() {
+            Ok(receiver) => receiver.recv(),
+            Err(_) => return,
+        };
+        let Ok(queued_file) = queued_file else {
+            return;
+        };
+        let failed = error.lock().map_or(true, |error| error.is_some());
+        if failed {
+            continue;
+        }
+        if let Err(write_error) = write_file(&queued_file) {
+            if let Ok(mut error) = error.lock() {
+                error.get_or_insert(write_error);
+            }
+        }
+    }
+}
+
+/// Writes the `queued_file` to its path.
+fn write_file(queued_file: &QueuedFile) -> std::io::Result<()> {
+    let mut file = retry_locked(|| File::create(&queued_file.path))?;
+    file.write_all(&queued_file.contents)?;
+    set_mode(&file, queued_file.mode)
+}
+
+/// Sets the `mode` of the `file` on Unix.
+#[cfg_attr(not(unix), expect(clippy::unnecessary_wraps))]
+fn set_mode(file: &File, mode: Option<u32>) -> std::io::Result<()> {
+    #[cfg(unix)]
+    if let Some(mode) = mode {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(std::fs::Permissions::from_mode(mode))?;
+    }
+    #[cfg(not(unix))]
+    let _ = (file, mode);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Cursor;
+
+    #[test]
+    fn test_with_file_writer() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let paths = with_file_writer(|writer| {
+            let mut paths = Vec::new();
+            for i in 0..32 {
+                let path = temp_dir.path().join(format!("file{i}"));
+                let contents = format!("contents {i}");
+                writer.write(
+                    path.clone(),
+                    &mut Cursor::new(contents.as_bytes()),
+                    contents.len() as u64,
+                    Some(0o644),
+                )?;
+                paths.push(path);
+            }
+            Ok(paths)
+        })?;
+
+        for (i, path) in paths.iter().enumerate() {
+            assert!(path.is_file());
+            assert_eq!(format!("contents {i}"), std::fs::read_to_string(path)?);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_with_file_writer_error() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let result = with_file_writer(|writer| {
+            let path = temp_dir.path().join("missing").join("file");
+            writer.write(path, &mut Cursor::new(b"contents"), 8, None)
+        });
+        assert!(result.is_err());
+        Ok(())
+    }
+}
@@ -1,8 +1,9 @@
+use crate::extractor::progress;
 use crate::extractor::ExtractDirectories;
 use crate::Result;
 use num_format::{Locale, ToFormattedString};
 use std::fs::create_dir_all;
-use std::io::Cursor;
+use std::io::{Cursor, Read, Seek};
 use std::path::PathBuf;
 use std::{fs, io};
 use tracing::{debug, instrument, warn};
@@ -14,30 +15,49 @@ use zip::ZipArchive;
 /// Returns an error if the extraction fails.
 #[instrument(skip(bytes))]
 pub fn extract(bytes: &Vec<u8>, extract_directories: ExtractDirectories) -> Result<Vec<PathBuf>> {
+    extract_reader(Cursor::new(bytes), extract_directories)
+}
+
+/// Extracts the zip archive read from the `reader`, such as a file, to paths defined in
+/// `extract_directories`.
+///
+/// # Errors
+/// Returns an error if the extraction fails.
+#[instrument(skip(reader))]
+pub fn extract_reader<R: Read + Seek>(
+    reader: R,
+    extract_directories: ExtractDirectories,
+) -> Result<Vec<PathBuf>> {
     let mut files = Vec::new();
-    let reader = Cursor::new(bytes);
     let mut archive =
         ZipArchive::new(reader).map_err(|_| io::Error::new(io::ErrorKind::Other, "Zip error"))?;
     let mut extracted_bytes = 0;
+    progress::set_files_total(u64::try_from(archive.len()).unwrap_or(u64::MAX));
 
     for i in 0..archive.len() {
         let mut file = archive
             .by_index(i)
             .map_err(|_| io::Error::new(io::ErrorKind::Other, "Zip error"))?;
-        let file_path = PathBuf::from(file.name());
-        let file_path = PathBuf::from(file_path.file_name().unwrap_or_default());
+        let entry_path = PathBuf::from(file.name());
+        let file_path = PathBuf::from(entry_path.file_name().unwrap_or_default());
         let file_name = file_path.to_string_lossy();
+        progress::archive_read(file.compressed_size());
 
         let Ok(extract_dir) = extract_directories.get_path(&file_name) else {
             continue;
         };
+        // The filter matches the path of the entry in the archive, rather than the file name
+        // that the entry is extracted to
+        if !extract_directories.is_included(&entry_path) {
+            continue;
+        }
         create_dir_all(&extract_dir)?;
 
-        let mut out = Vec::new();
-        io::copy(&mut file, &mut out)?;
-        extracted_bytes += out.len() as u64;
         let path = PathBuf::from(&extract_dir).join(file_path);
-        fs::write(&path, out)?;
+        let mut out = fs::File::create(&path)?;
+        let bytes = io::copy(&mut file, &mut out)?;
+        extracted_bytes += bytes;
+        progress::file_extracted(bytes);
         files.push(path);
     }
 
@@ -0,0 +1,38 @@
+use crate::Result;
+use std::io::Read;
+
+/// Hashes the data using BLAKE3.
+///
+/// # Errors
+/// * If the data cannot be hashed.
+#[expect(clippy::ptr_arg)] // The signature of a `HasherFn`
+pub fn hash(data: &Vec<u8>) -> Result<String> {
+    let hash = blake3::hash(data);
+    Ok(hash.to_hex().to_string())
+}
+
+/// Hashes the data read from the `reader` using BLAKE3, without reading it into memory.
+///
+/// # Errors
+/// * If the data cannot be read.
+pub fn hash_reader(reader: &mut dyn Read) -> Result<String> {
+    let mut hasher = blake3::Hasher::new();
+    std::io::copy(reader, &mut hasher)?;
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hash() -> Result<()> {
+        assert_eq!(
+            "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262",
+            hash(&Vec::new())?
+        );
+        let data = vec![4, 2];
+        assert_eq!(hash(&data)?, hash_reader(&mut data.as_slice())?);
+        Ok(())
+    }
+}
@@ -1,5 +1,6 @@
 use crate::Result;
 use md5::{Digest, Md5};
+use std::io::Read;
 
 /// Hashes the data using MD5.
 ///
@@ -12,6 +13,17 @@ pub fn hash(data: &Vec<u8>) -> Result<String> {
     Ok(hash)
 }
 
+/// Hashes the data read from the `reader` using MD5, without reading it into memory.
+///
+/// # Errors
+/// * If the data cannot be read.
+pub fn hash_reader(reader: &mut dyn Read) -> Result<String> {
+    let mut hasher = Md5::new();
+    std::io::copy(reader, &mut hasher)?;
+    let hash = hex::encode(hasher.finalize());
+    Ok(hash)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -20,6 +32,7 @@ mod tests {
     fn test_hash() -> Result<()> {
         let data = vec![4, 2];
         let hash = hash(&data)?;
+        assert_eq!(hash, hash_reader(&mut data.as_slice())?);
         assert_eq!("21fb3d1d1a91a7e80dff456205f3380b", hash);
         Ok(())
     }
@@ -0,0 +1,161 @@
+use crate::Error::{ParseError, PoisonedLock, SignatureVerificationFailed};
+use crate::Result;
+use minisign_verify::{PublicKey, Signature};
+use std::collections::HashMap;
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+use std::sync::{LazyLock, Mutex};
+
+static PUBLIC_KEYS: LazyLock<Mutex<HashMap<String, String>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Extension of the detached minisign signature of an asset; e.g.
+/// `postgresql-16.4.0-x86_64-unknown-linux-gnu.tar.gz.minisig`
+pub const SIGNATURE_EXTENSION: &str = "minisig";
+
+/// Registers the minisign `public_key` that the archives of the repositories with URLs that start
+/// with the `url` must be signed with. The key is either the base64 encoded key, or the contents
+/// of a minisign public key file. Archives of repositories with a registered key are rejected if
+/// they do not have a valid detached [signature](SIGNATURE_EXTENSION).
+///
+/// # Errors
+/// * If the public key is invalid.
+/// * If the registry is poisoned.
+pub fn register(url: &str, public_key: &str) -> Result<()> {
+    parse_public_key(public_key)?;
+    let mut public_keys = PUBLIC_KEYS
+        .lock()
+        .map_err(|error| PoisonedLock(error.to_string()))?;
+    public_keys.insert(url.to_string(), public_key.to_string());
+    Ok(())
+}
+
+/// Removes the public key registered for the `url`.
+///
+/// # Errors
+/// * If the registry is poisoned.
+pub fn unregister(url: &str) -> Result<()> {
+    let mut public_keys = PUBLIC_KEYS
+        .lock()
+        .map_err(|error| PoisonedLock(error.to_string()))?;
+    public_keys.remove(url);
+    Ok(())
+}
+
+/// Gets the public key registered for the repository `url`; the key of the longest registered URL
+/// that the `url` starts with.
+///
+/// # Errors
+/// * If the registry is poisoned.
+pub(crate) fn public_key(url: &str) -> Result<Option<String>> {
+    let public_keys = PUBLIC_KEYS
+        .lock()
+        .map_err(|error| PoisonedLock(error.to_string()))?;
+    let public_key = public_keys
+        .iter()
+        .filter(|(registered_url, _)| url.starts_with(registered_url.as_str()))
+        .max_by_key(|(registered_url, _)| registered_url.len())
+        .map(|(_, public_key)| public_key.clone());
+    Ok(public_key)
+}
+
+/// Parses the `public_key`, either a base64 encoded key or the contents of a public key file.
+///
+/// # Errors
+/// * If the public key is invalid.
+fn parse_public_key(public_key: &str) -> Result<PublicKey> {
+    let public_key = public_key.trim();
+    let result = if public_key.contains('\n') {
+        PublicKey::decode(public_key)
+    } else {
+        PublicKey::from_base64(public_key)
+    };
+    result.map_err(|error| ParseError(format!("invalid minisign public key: {error}")))
+}
+
+/// Parses the contents of a detached minisign `signature` file.
+///
+/// # Errors
+/// * If the signature is invalid.
+fn parse_signature(signature: &str) -> Result<Signature> {
+    Signature::decode(signature)
+        .map_err(|error| ParseError(format!("invalid minisign signature: {error}")))
+}
+
+/// Verifies the detached minisign `signature` of the `data` with the `public_key`; both
+/// pre-hashed and legacy signatures are supported.
+///
+/// # Errors
+/// * If the public key or signature is invalid.
+/// * If the signature does not match the data.
+pub fn verify(public_key: &str, data: &[u8], signature: &str) -> Result<()> {
+    let public_key = parse_public_key(public_key)?;
+    let signature = parse_signature(signature)?;
+    public_key
+        .verify(data, &signature, true)
+        .map_err(|error| SignatureVerificationFailed(error.to_string()))
+}
+
+/// Verifies the detached minisign `signature` of the file at `path` with the `public_key`. The
+/// file is verified as it is read, which requires a pre-hashed signature; the default signature
+/// of `minisign` 0.10 and later.
+///
+/// # Errors
+/// * If the public key or signature is invalid.
+/// * If the file cannot be read.
+/// * If the signature does not match the file.
+pub fn verify_file(public_key: &str, path: &Path, signature: &str) -> Result<()> {
+    let public_key = parse_public_key(public_key)?;
+    let signature = parse_signature(signature)?;
+    let mut verifier = public_key
+        .verify_stream(&signature)
+        .map_err(|error| SignatureVerificationFailed(error.to_string()))?;
+    let mut file = File::open(path)?;
+    let mut buffer = vec![0; 64 * 1024];
+    loop {
+        let bytes_read = file.read(&mut buffer)?;
+        if bytes_read == 0 {
+            break;
+        }
+        verifier.update(&buffer[..bytes_read]);
+    }
+    verifier
+        .finalize()
+        .map_err(|error| SignatureVerificationFailed(error.to_string()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Test vectors of the minisign-verify crate
+    const PUBLIC_KEY: &str = "RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3";
+    const SIGNATURE: &str = "untrusted comment: signature from minisign secret key
+RWQf6LRCGA9i59SLOFxz6NxvASXDJeRtuZykwQepbDEGt87ig1BNpWaVWuNrm73YiIiJbq71Wi+dP9eKL8OC351vwIasSSbXxwA=
+trusted comment: timestamp:1555779966\tfile:test
+QtKMXWyYcwdpZAlPF7tE2ENJkRd1ujvKjlj1m9RtHTBnZPa5WKU5uWRs5GoP5M/VqE81QFuMKI5k/SfNQUaOAA==";
+
+    #[test]
+    fn test_register() -> Result<()> {
+        let url = "https://github.com/theseus-rs/postgresql-binaries-minisign";
+        assert!(register(url, "invalid").is_err());
+        register(url, PUBLIC_KEY)?;
+        assert_eq!(
+            Some(PUBLIC_KEY.to_string()),
+            public_key(&format!("{url}#x86_64-unknown-linux-gnu"))?
+        );
+        assert_eq!(None, public_key("https://github.com/theseus-rs/other")?);
+        unregister(url)?;
+        assert_eq!(None, public_key(url)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_verify() -> Result<()> {
+        verify(PUBLIC_KEY, b"test", SIGNATURE)?;
+        assert!(verify(PUBLIC_KEY, b"tampered", SIGNATURE).is_err());
+        assert!(verify(PUBLIC_KEY, b"test", "invalid").is_err());
+        Ok(())
+    }
+}
@@ -1,5 +1,9 @@
+#[cfg(feature = "blake3")]
+pub mod blake3;
 #[cfg(feature = "md5")]
 pub mod md5;
+#[cfg(feature = "minisign")]
+pub mod minisign;
 pub mod registry;
 #[cfg(feature = "sha1")]
 pub mod sha1;
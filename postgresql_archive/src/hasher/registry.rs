@@ -1,17 +1,22 @@
 #[cfg(feature = "theseus")]
 use crate::configuration::theseus;
+#[cfg(feature = "blake3")]
+use crate::hasher::blake3;
 #[cfg(feature = "md5")]
 use crate::hasher::md5;
 #[cfg(feature = "sha1")]
 use crate::hasher::sha1;
 #[cfg(feature = "sha2")]
 use crate::hasher::sha2_256;
-#[cfg(feature = "sha2")]
+#[cfg(feature = "maven")]
 use crate::hasher::sha2_512;
+#[cfg(feature = "gitlab")]
+use crate::repository::gitlab;
 #[cfg(feature = "maven")]
 use crate::repository::maven;
 use crate::Error::{PoisonedLock, UnsupportedHasher};
 use crate::Result;
+use std::io::Read;
 use std::sync::{Arc, LazyLock, Mutex, RwLock};
 
 static REGISTRY: LazyLock<Arc<Mutex<HasherRegistry>>> =
@@ -19,11 +24,13 @@ static REGISTRY: LazyLock<Arc<Mutex<HasherRegistry>>> =
 
 pub type SupportsFn = fn(&str, &str) -> Result<bool>;
 pub type HasherFn = fn(&Vec<u8>) -> Result<String>;
+pub type ReaderHasherFn = fn(&mut dyn Read) -> Result<String>;
 
 /// Singleton struct to store hashers
 #[expect(clippy::type_complexity)]
 struct HasherRegistry {
     hashers: Vec<(Arc<RwLock<SupportsFn>>, Arc<RwLock<HasherFn>>)>,
+    reader_hashers: Vec<(Arc<RwLock<SupportsFn>>, Arc<RwLock<ReaderHasherFn>>)>,
 }
 
 impl HasherRegistry {
@@ -31,6 +38,7 @@ impl HasherRegistry {
     fn new() -> Self {
         Self {
             hashers: Vec::new(),
+            reader_hashers: Vec::new(),
         }
     }
 
@@ -67,17 +75,72 @@ impl HasherRegistry {
 
         Err(UnsupportedHasher(url.to_string()))
     }
+
+    /// Registers a reader hasher for a supports function. Newly registered hashers will take
+    /// precedence over existing ones.
+    fn register_reader(&mut self, supports_fn: SupportsFn, hasher_fn: ReaderHasherFn) {
+        self.reader_hashers.insert(
+            0,
+            (
+                Arc::new(RwLock::new(supports_fn)),
+                Arc::new(RwLock::new(hasher_fn)),
+            ),
+        );
+    }
+
+    /// Get a reader hasher for the specified url and extension.
+    ///
+    /// # Errors
+    /// * If the registry is poisoned.
+    fn get_reader<S: AsRef<str>>(&self, url: S, extension: S) -> Result<ReaderHasherFn> {
+        let url = url.as_ref();
+        let extension = extension.as_ref();
+        for (supports_fn, hasher_fn) in &self.reader_hashers {
+            let supports_function = supports_fn
+                .read()
+                .map_err(|error| PoisonedLock(error.to_string()))?;
+            if supports_function(url, extension)? {
+                let hasher_function = hasher_fn
+                    .read()
+                    .map_err(|error| PoisonedLock(error.to_string()))?;
+                return Ok(*hasher_function);
+            }
+        }
+
+        Err(UnsupportedHasher(url.to_string()))
+    }
 }
 
 impl Default for HasherRegistry {
     /// Creates a new hasher registry with the default hashers registered.
     fn default() -> Self {
         let mut registry = Self::new();
+        // BLAKE3 checksums are not published by a built-in repository; they are registered for
+        // all repositories with the lowest precedence, for custom repositories that publish them.
+        #[cfg(feature = "blake3")]
+        registry.register(|_, extension| Ok(is_blake3(extension)), blake3::hash);
+        #[cfg(feature = "blake3")]
+        registry.register_reader(|_, extension| Ok(is_blake3(extension)), blake3::hash_reader);
         #[cfg(feature = "theseus")]
         registry.register(
             |url, extension| Ok(url.starts_with(theseus::URL) && extension == "sha256"),
             sha2_256::hash,
         );
+        #[cfg(feature = "theseus")]
+        registry.register_reader(
+            |url, extension| Ok(url.starts_with(theseus::URL) && extension == "sha256"),
+            sha2_256::hash_reader,
+        );
+        #[cfg(feature = "gitlab")]
+        registry.register(
+            |url, extension| Ok(gitlab::supports(url) && extension == "sha256"),
+            sha2_256::hash,
+        );
+        #[cfg(feature = "gitlab")]
+        registry.register_reader(
+            |url, extension| Ok(gitlab::supports(url) && extension == "sha256"),
+            sha2_256::hash_reader,
+        );
         // Register the Maven hashers: https://maven.apache.org/resolver/about-checksums.html#implemented-checksum-algorithms
         #[cfg(feature = "maven")]
         registry.register(
@@ -99,10 +162,37 @@ impl Default for HasherRegistry {
             |url, extension| Ok(url.starts_with(maven::URL) && extension == "sha512"),
             sha2_512::hash,
         );
+        #[cfg(feature = "maven")]
+        registry.register_reader(
+            |url, extension| Ok(url.starts_with(maven::URL) && extension == "md5"),
+            md5::hash_reader,
+        );
+        #[cfg(feature = "maven")]
+        registry.register_reader(
+            |url, extension| Ok(url.starts_with(maven::URL) && extension == "sha1"),
+            sha1::hash_reader,
+        );
+        #[cfg(feature = "maven")]
+        registry.register_reader(
+            |url, extension| Ok(url.starts_with(maven::URL) && extension == "sha256"),
+            sha2_256::hash_reader,
+        );
+        #[cfg(feature = "maven")]
+        registry.register_reader(
+            |url, extension| Ok(url.starts_with(maven::URL) && extension == "sha512"),
+            sha2_512::hash_reader,
+        );
         registry
     }
 }
 
+/// Returns whether the checksum file `extension` is the extension of a BLAKE3 checksum; e.g.
+/// `postgresql-16.4.0-x86_64-unknown-linux-gnu.tar.gz.b3`.
+#[cfg(feature = "blake3")]
+fn is_blake3(extension: &str) -> bool {
+    extension == "b3" || extension == "blake3"
+}
+
 /// Registers a hasher for a supports function. Newly registered hashers will take precedence
 /// over existing ones.
 ///
@@ -127,6 +217,108 @@ pub fn get<S: AsRef<str>>(url: S, extension: S) -> Result<HasherFn> {
     registry.get(url, extension)
 }
 
+/// Registers a hasher that reads the data to hash from a reader, such as a downloaded archive
+/// file, rather than from memory. Newly registered hashers will take precedence over existing
+/// ones.
+///
+/// # Errors
+/// * If the registry is poisoned.
+pub fn register_reader(supports_fn: SupportsFn, hasher_fn: ReaderHasherFn) -> Result<()> {
+    let mut registry = REGISTRY
+        .lock()
+        .map_err(|error| PoisonedLock(error.to_string()))?;
+    registry.register_reader(supports_fn, hasher_fn);
+    Ok(())
+}
+
+/// Get a reader hasher for the specified url and extension.
+///
+/// # Errors
+/// * If the registry is poisoned.
+/// * If the url and extension are not supported.
+pub fn get_reader<S: AsRef<str>>(url: S, extension: S) -> Result<ReaderHasherFn> {
+    let registry = REGISTRY
+        .lock()
+        .map_err(|error| PoisonedLock(error.to_string()))?;
+    registry.get_reader(url, extension)
+}
+
+/// A read-only snapshot of the registered hashers, used to debug which hasher is used for a
+/// URL; hashers registered after the snapshot is taken are not included.
+#[must_use]
+#[expect(clippy::type_complexity)]
+pub struct Snapshot {
+    hashers: Vec<(Arc<RwLock<SupportsFn>>, Arc<RwLock<HasherFn>>)>,
+    reader_hashers: Vec<(Arc<RwLock<SupportsFn>>, Arc<RwLock<ReaderHasherFn>>)>,
+}
+
+impl Snapshot {
+    /// Returns the number of hashers in the snapshot.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.hashers.len()
+    }
+
+    /// Returns whether the snapshot has no hashers.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.hashers.is_empty()
+    }
+
+    /// Returns the position, in order of precedence, of the hasher used for the specified url and
+    /// extension.
+    ///
+    /// # Errors
+    /// * If the registry is poisoned.
+    pub fn position<S: AsRef<str>>(&self, url: S, extension: S) -> Result<Option<usize>> {
+        position(&self.hashers, url.as_ref(), extension.as_ref())
+    }
+
+    /// Returns the position, in order of precedence, of the reader hasher used for the specified
+    /// url and extension.
+    ///
+    /// # Errors
+    /// * If the registry is poisoned.
+    pub fn reader_position<S: AsRef<str>>(&self, url: S, extension: S) -> Result<Option<usize>> {
+        position(&self.reader_hashers, url.as_ref(), extension.as_ref())
+    }
+}
+
+/// Returns the position of the first entry whose supports function supports the url and
+/// extension.
+///
+/// # Errors
+/// * If the registry is poisoned.
+fn position<T>(
+    entries: &[(Arc<RwLock<SupportsFn>>, T)],
+    url: &str,
+    extension: &str,
+) -> Result<Option<usize>> {
+    for (index, (supports_fn, _)) in entries.iter().enumerate() {
+        let supports_function = supports_fn
+            .read()
+            .map_err(|error| PoisonedLock(error.to_string()))?;
+        if supports_function(url, extension)? {
+            return Ok(Some(index));
+        }
+    }
+    Ok(None)
+}
+
+/// Takes a read-only snapshot of the registered hashers.
+///
+/// # Errors
+/// * If the registry is poisoned.
+pub fn snapshot() -> Result<Snapshot> {
+    let registry = REGISTRY
+        .lock()
+        .map_err(|error| PoisonedLock(error.to_string()))?;
+    Ok(Snapshot {
+        hashers: registry.hashers.clone(),
+        reader_hashers: registry.reader_hashers.clone(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -148,6 +340,40 @@ mod tests {
         test_hasher("test", "42")
     }
 
+    #[test]
+    fn test_register_reader() -> Result<()> {
+        register_reader(
+            |_, extension| Ok(extension == "test-reader"),
+            |_| Ok("42".to_string()),
+        )?;
+        let hasher = get_reader("https://foo.com", "test-reader")?;
+        assert_eq!("42", hasher(&mut [1, 2, 3].as_slice())?);
+        assert!(get_reader("https://foo.com", "foo").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_snapshot() -> Result<()> {
+        let url = "https://foo.com";
+        let snapshot = snapshot()?;
+        assert_eq!(None, snapshot.position(url, "snapshot")?);
+        register(
+            |_, extension| Ok(extension == "snapshot"),
+            |_| Ok("42".to_string()),
+        )?;
+        register_reader(
+            |_, extension| Ok(extension == "snapshot"),
+            |_| Ok("42".to_string()),
+        )?;
+        let current = super::snapshot()?;
+        assert_eq!(Some(0), current.position(url, "snapshot")?);
+        assert_eq!(Some(0), current.reader_position(url, "snapshot")?);
+        assert!(current.len() > snapshot.len());
+        assert_eq!(None, snapshot.position(url, "snapshot")?);
+        assert!(get(url, "snapshot").is_ok());
+        Ok(())
+    }
+
     #[test]
     fn test_get_invalid_url_error() {
         let error = get("https://foo.com", "foo").unwrap_err();
@@ -171,6 +397,17 @@ mod tests {
     #[cfg(feature = "theseus")]
     fn test_get_theseus_postgresql_binaries() {
         assert!(get(theseus::URL, "sha256").is_ok());
+        assert!(get_reader(theseus::URL, "sha256").is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "blake3")]
+    fn test_get_blake3() -> Result<()> {
+        let data = vec![1, 2, 3, 4, 5, 6, 7, 8, 9, 0];
+        assert_eq!(blake3::hash(&data)?, get("https://foo.com", "b3")?(&data)?);
+        assert!(get("https://foo.com", "blake3").is_ok());
+        assert!(get_reader("https://foo.com", "b3").is_ok());
+        Ok(())
     }
 
     #[test]
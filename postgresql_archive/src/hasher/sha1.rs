@@ -1,5 +1,6 @@
 use crate::Result;
 use sha1::{Digest, Sha1};
+use std::io::Read;
 
 /// Hashes the data using SHA1.
 ///
@@ -12,6 +13,17 @@ pub fn hash(data: &Vec<u8>) -> Result<String> {
     Ok(hash)
 }
 
+/// Hashes the data read from the `reader` using SHA1, without reading it into memory.
+///
+/// # Errors
+/// * If the data cannot be read.
+pub fn hash_reader(reader: &mut dyn Read) -> Result<String> {
+    let mut hasher = Sha1::new();
+    std::io::copy(reader, &mut hasher)?;
+    let hash = hex::encode(hasher.finalize());
+    Ok(hash)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -20,6 +32,7 @@ mod tests {
     fn test_hash() -> Result<()> {
         let data = vec![4, 2];
         let hash = hash(&data)?;
+        assert_eq!(hash, hash_reader(&mut data.as_slice())?);
         assert_eq!("1f3e1678e699640dfa5173d3a52b004f5e164d87", hash);
         Ok(())
     }
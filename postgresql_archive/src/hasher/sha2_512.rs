@@ -1,5 +1,6 @@
 use crate::Result;
 use sha2::{Digest, Sha512};
+use std::io::Read;
 
 /// Hashes the data using SHA2-512.
 ///
@@ -12,6 +13,17 @@ pub fn hash(data: &Vec<u8>) -> Result<String> {
     Ok(hash)
 }
 
+/// Hashes the data read from the `reader` using SHA2-512, without reading it into memory.
+///
+/// # Errors
+/// * If the data cannot be read.
+pub fn hash_reader(reader: &mut dyn Read) -> Result<String> {
+    let mut hasher = Sha512::new();
+    std::io::copy(reader, &mut hasher)?;
+    let hash = hex::encode(hasher.finalize());
+    Ok(hash)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -20,6 +32,7 @@ mod tests {
     fn test_hash() -> Result<()> {
         let data = vec![4, 2];
         let hash = hash(&data)?;
+        assert_eq!(hash, hash_reader(&mut data.as_slice())?);
         assert_eq!(
             "7df6418d1791a6fe80e726319f16f107534a663346f99e0d155e359a54f6c74391e2f3be19c995c3c903926d348bd86c339bd982e10f09aa776e4ff85d36387a",
             hash
@@ -68,13 +68,21 @@
 //! | `theseus` | Enables theseus PostgreSQL binaries | Yes      |
 //! | `zonky`   | Enables zonky PostgreSQL binaries   | No       |
 //!
+//! ### Extractors
+//!
+//! | Name      | Description                             | Default? |
+//! |-----------|-----------------------------------------|----------|
+//! | `tar-zst` | Enables Zstandard (`.tar.zst`) archives | No       |
+//!
 //! ### Hashers
 //!
-//! | Name   | Description          | Default? |
-//! |--------|----------------------|----------|
-//! | `md5`  | Enables md5 hashers  | No       |
-//! | `sha1` | Enables sha1 hashers | No       |
-//! | `sha2` | Enables sha2 hashers | Yes¹     |
+//! | Name       | Description                                | Default? |
+//! |------------|--------------------------------------------|----------|
+//! | `blake3`   | Enables blake3 hashers                     | No       |
+//! | `md5`      | Enables md5 hashers                        | No       |
+//! | `minisign` | Enables minisign signature verification    | No       |
+//! | `sha1`     | Enables sha1 hashers                       | No       |
+//! | `sha2`     | Enables sha2 hashers and archive caching   | Yes¹     |
 //!
 //! ¹ enabled by the `theseus` feature flag.
 //!
@@ -82,7 +90,10 @@
 //!
 //! | Name     | Description               | Default? |
 //! |----------|---------------------------|----------|
+//! | `file`   | Enables file repository   | No       |
 //! | `github` | Enables github repository | Yes¹     |
+//! | `gitlab` | Enables gitlab repository | No       |
+//! | `http`   | Enables http repository   | No       |
 //! | `maven`  | Enables maven repository  | No       |
 //!
 //! ¹ enabled by the `theseus` feature flag.
@@ -118,15 +129,45 @@
 mod archive;
 #[cfg(feature = "blocking")]
 pub mod blocking;
+pub mod cache;
 pub mod configuration;
+mod credentials;
+mod download;
 mod error;
 pub mod extractor;
 pub mod hasher;
+mod lock;
 pub mod matcher;
+mod proxy;
+mod reporter;
 pub mod repository;
+mod retry;
+mod scoped;
 mod version;
 
-pub use archive::{extract, get_archive, get_version};
+pub use archive::{
+    extract, extract_from_path, extract_with_progress, get_archive, get_archive_for_target,
+    get_archive_in_channel, get_archive_to_file, get_release_info, get_version,
+    get_version_in_channel, latest_of, list_versions,
+};
+pub use credentials::{
+    clear_credentials_providers, register_credentials_provider, CredentialsProvider,
+    StaticCredentials,
+};
+pub use download::{default_download_options, set_default_download_options, DownloadOptions};
 pub use error::{Error, Result};
+pub use extractor::{
+    default_extract_options, set_default_extract_options, ExtractFilter, ExtractOptions,
+    ExtractProgress,
+};
+pub use lock::LockFile;
+pub use proxy::{default_proxy_options, set_default_proxy_options, ProxyOptions};
+pub use reporter::ProgressReporter;
+pub use repository::ReleaseInfo;
+pub use retry::{
+    default_retry_policy, is_transient, set_default_retry_policy, RetryPolicy, RetryableFn,
+    DEFAULT_RETRY_STATUS_CODES,
+};
+pub use scoped::{with_scoped_options, with_scoped_options_blocking, ScopedOptions};
 pub use semver::{Version, VersionReq};
-pub use version::{ExactVersion, ExactVersionReq};
+pub use version::{Channel, ExactVersion, ExactVersionReq};
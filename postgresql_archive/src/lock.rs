@@ -0,0 +1,246 @@
+use crate::Error::Unexpected;
+use crate::Result;
+use std::fs::{remove_file, File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::{channel, RecvTimeoutError, Sender};
+use std::thread::{sleep, JoinHandle};
+use std::time::{Duration, Instant, SystemTime};
+use tracing::{debug, instrument, warn};
+
+/// Interval between attempts to acquire a lock held by another process
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+/// Interval at which the modification time of a held lock file is refreshed, so that the lock
+/// is not assumed to be stale while it is held
+const HEARTBEAT_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Exclusive lock across processes, held by creating a lock file; e.g. to serialize the
+/// extraction of an archive to a directory that is shared by multiple applications. While the
+/// lock is held, the modification time of the lock file is refreshed every minute by a
+/// background thread. The lock is released, and the lock file removed, when the value is
+/// dropped.
+#[derive(Debug)]
+pub struct LockFile {
+    path: PathBuf,
+    heartbeat: Option<(Sender<()>, JoinHandle<()>)>,
+}
+
+impl LockFile {
+    /// Age after which a lock file that has not been refreshed is assumed to have been left
+    /// behind by a process that exited without releasing it; waiters should wait longer than
+    /// this for a lock to be released.
+    pub const STALE_AGE: Duration = Duration::from_secs(600);
+    /// Maximum time to wait for a lock held by another process; longer than the
+    /// [stale age](Self::STALE_AGE), so that a lock left behind by a process that exited is
+    /// reclaimed before waiting gives up.
+    pub const WAIT_TIMEOUT: Duration = Self::STALE_AGE.saturating_add(Duration::from_secs(300));
+
+    /// Acquire the lock file at `path`, waiting up to `timeout` while it is held by another
+    /// process. See [`try_acquire`](Self::try_acquire).
+    ///
+    /// # Errors
+    /// * If the lock file cannot be created, or is not released within the `timeout`.
+    #[instrument(level = "debug")]
+    pub fn acquire(path: &Path, timeout: Duration) -> Result<Self> {
+        let started = Instant::now();
+        loop {
+            if let Some(lock) = Self::try_acquire(path)? {
+                return Ok(lock);
+            }
+            if started.elapsed() >= timeout {
+                return Err(Unexpected(format!(
+                    "Failed to acquire lock {} within {timeout:?}",
+                    path.to_string_lossy()
+                )));
+            }
+            debug!("Waiting for lock: {}", path.to_string_lossy());
+            sleep(POLL_INTERVAL);
+        }
+    }
+
+    /// Acquire the lock file at `path` if it is not held by another process; returns `None` if
+    /// it is. A lock file last modified more than [ten minutes](Self::STALE_AGE) ago is assumed
+    /// to have been left behind by a process that exited without releasing it, and is removed.
+    ///
+    /// # Errors
+    /// * If the lock file cannot be created.
+    pub fn try_acquire(path: &Path) -> Result<Option<Self>> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        for _ in 0..2 {
+            match OpenOptions::new().write(true).create_new(true).open(path) {
+                Ok(mut file) => {
+                    // The process ID is informational; it identifies the holder when debugging
+                    let _ = write!(file, "{}", std::process::id());
+                    debug!("Lock acquired: {}", path.to_string_lossy());
+                    return Ok(Some(Self {
+                        path: path.to_path_buf(),
+                        heartbeat: Some(heartbeat(path)),
+                    }));
+                }
+                Err(error) if error.kind() == ErrorKind::AlreadyExists => {}
+                // On Windows, a lock file that is being removed cannot be opened
+                Err(error) if error.kind() == ErrorKind::PermissionDenied => {}
+                Err(error) => return Err(error.into()),
+            }
+
+            if !is_stale(path) {
+                break;
+            }
+            warn!(
+                "Stale lock file detected; removing file to attempt process recovery: {}",
+                path.to_string_lossy()
+            );
+            if let Err(error) = remove_file(path) {
+                if error.kind() != ErrorKind::NotFound {
+                    debug!("Failed to remove stale lock file: {error}");
+                    break;
+                }
+            }
+        }
+        Ok(None)
+    }
+
+    /// Get the path of the lock file
+    #[must_use]
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+/// Returns `true` if the lock file at `path` was last modified more than
+/// [`STALE_AGE`](LockFile::STALE_AGE) ago.
+fn is_stale(path: &Path) -> bool {
+    path.metadata()
+        .and_then(|metadata| metadata.modified())
+        .ok()
+        .and_then(|modified| modified.elapsed().ok())
+        .is_some_and(|age| age > LockFile::STALE_AGE)
+}
+
+/// Starts a thread that refreshes the modification time of the lock file at `path` every
+/// [`HEARTBEAT_INTERVAL`], until the returned sender is dropped.
+fn heartbeat(path: &Path) -> (Sender<()>, JoinHandle<()>) {
+    let (sender, receiver) = channel::<()>();
+    let path = path.to_path_buf();
+    let handle = std::thread::spawn(move || {
+        while let Err(RecvTimeoutError::Timeout) = receiver.recv_timeout(HEARTBEAT_INTERVAL) {
+            let refreshed = File::options()
+                .write(true)
+                .open(&path)
+                .and_then(|file| file.set_modified(SystemTime::now()));
+            if let Err(error) = refreshed {
+                warn!(
+                    "Failed to refresh lock file {}: {error}",
+                    path.to_string_lossy()
+                );
+            }
+        }
+    });
+    (sender, handle)
+}
+
+/// Release the lock by stopping the heartbeat and removing the lock file
+impl Drop for LockFile {
+    fn drop(&mut self) {
+        if let Some((sender, handle)) = self.heartbeat.take() {
+            drop(sender);
+            let _ = handle.join();
+        }
+        debug!("Removing lock file: {}", self.path.to_string_lossy());
+        if let Err(error) = remove_file(&self.path) {
+            if error.kind() != ErrorKind::NotFound {
+                warn!(
+                    "Failed to remove lock file {}: {error}",
+                    self.path.to_string_lossy()
+                );
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_acquire_and_release() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("test.lock");
+        let lock = LockFile::acquire(&path, Duration::from_secs(1))?;
+        assert_eq!(path, lock.path());
+        assert_eq!(
+            std::process::id().to_string(),
+            std::fs::read_to_string(&path)?
+        );
+        drop(lock);
+        assert!(!path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_acquire_held() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("test.lock");
+        let _lock = LockFile::acquire(&path, Duration::from_secs(1))?;
+        let error = LockFile::acquire(&path, Duration::from_millis(500)).expect_err("lock held");
+        assert!(error.to_string().starts_with("Failed to acquire lock"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_acquire_held() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("test.lock");
+        let lock = LockFile::try_acquire(&path)?;
+        assert!(lock.is_some());
+        assert!(LockFile::try_acquire(&path)?.is_none());
+        drop(lock);
+        assert!(LockFile::try_acquire(&path)?.is_some());
+        Ok(())
+    }
+
+    #[test]
+    fn test_acquire_stale() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("test.lock");
+        let file = std::fs::File::create(&path)?;
+        file.set_modified(SystemTime::now() - LockFile::STALE_AGE * 2)?;
+        drop(file);
+        let lock = LockFile::acquire(&path, Duration::from_secs(1))?;
+        assert_eq!(
+            std::process::id().to_string(),
+            std::fs::read_to_string(lock.path())?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_release_stops_heartbeat() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("test.lock");
+        let started = Instant::now();
+        let lock = LockFile::acquire(&path, Duration::from_secs(1))?;
+        assert!(lock.heartbeat.is_some());
+        drop(lock);
+        assert!(started.elapsed() < HEARTBEAT_INTERVAL);
+        assert!(!path.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_acquire_after_release() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("test.lock");
+        let lock = LockFile::acquire(&path, Duration::from_secs(1))?;
+        let waiter = {
+            let path = path.clone();
+            std::thread::spawn(move || LockFile::acquire(&path, Duration::from_secs(10)).is_ok())
+        };
+        sleep(Duration::from_millis(100));
+        drop(lock);
+        assert!(waiter.join().expect("waiter"));
+        Ok(())
+    }
+}
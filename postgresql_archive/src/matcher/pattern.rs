@@ -0,0 +1,350 @@
+use crate::matcher::registry;
+use crate::scoped;
+use crate::version::postgresql_version_name;
+use crate::Error::{ParseError, PoisonedLock};
+use crate::Result;
+use regex_lite::{escape, Regex};
+use semver::Version;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{LazyLock, Mutex, Once};
+
+static PATTERNS: LazyLock<Mutex<HashMap<String, Pattern>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+static REGISTER: Once = Once::new();
+
+/// Asset name pattern compiled into a matcher, for repositories with naming schemes that do not
+/// have a dedicated matcher (e.g. nightly or internal builds). A pattern is the asset name with
+/// the following placeholders:
+///
+/// | Placeholder            | Value                                                         |
+/// |------------------------|---------------------------------------------------------------|
+/// | `{version}`            | Semantic version (e.g. `16.4.0` or `18.0.0-beta.1`)           |
+/// | `{postgresql_version}` | PostgreSQL version (e.g. `16.4.0` or `18beta1`)               |
+/// | `{major}`              | Major version                                                 |
+/// | `{minor}`              | Minor version                                                 |
+/// | `{patch}`              | Patch version                                                 |
+/// | `{os}`                 | Operating system (e.g. `linux`, `macos` or `windows`)         |
+/// | `{arch}`               | Architecture (e.g. `x86_64` or `aarch64`)                     |
+/// | `{target}`             | Target triple (e.g. `x86_64-unknown-linux-gnu`)               |
+///
+/// The operating system, architecture and target triple are those of the target that assets are
+/// matched for. A `*` matches any sequence of characters, such as a build date. For example,
+/// `postgres-{version}-{os}-{arch}.tar.gz` matches `postgres-16.4.0-linux-x86_64.tar.gz` on
+/// 64-bit Linux.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Pattern {
+    tokens: Vec<Token>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+enum Token {
+    Literal(String),
+    Wildcard,
+    Version,
+    PostgresqlVersion,
+    Major,
+    Minor,
+    Patch,
+    Os,
+    Arch,
+    Target,
+}
+
+impl Token {
+    /// Get the token for the placeholder `name`.
+    fn placeholder(pattern: &str, name: &str) -> Result<Self> {
+        let token = match name {
+            "version" => Token::Version,
+            "postgresql_version" => Token::PostgresqlVersion,
+            "major" => Token::Major,
+            "minor" => Token::Minor,
+            "patch" => Token::Patch,
+            "os" => Token::Os,
+            "arch" => Token::Arch,
+            "target" => Token::Target,
+            _ => {
+                return Err(ParseError(format!(
+                    "unsupported placeholder '{{{name}}}' in pattern '{pattern}'"
+                )))
+            }
+        };
+        Ok(token)
+    }
+
+    /// Get the regular expression matching the token for the specified version and `target`
+    /// triple.
+    fn regex(&self, version: &Version, target: &str) -> String {
+        match self {
+            Token::Literal(literal) => escape(literal),
+            Token::Wildcard => ".*".to_string(),
+            Token::Version => escape(&version.to_string()),
+            Token::PostgresqlVersion => escape(&postgresql_version_name(version)),
+            Token::Major => version.major.to_string(),
+            Token::Minor => version.minor.to_string(),
+            Token::Patch => version.patch.to_string(),
+            Token::Os => escape(target_os(target)),
+            Token::Arch => escape(target_arch(target)),
+            Token::Target => escape(target),
+        }
+    }
+}
+
+/// Get the operating system of the `target` triple, as named by [`std::env::consts::OS`].
+fn target_os(target: &str) -> &str {
+    if target == target_triple::TARGET {
+        std::env::consts::OS
+    } else if target.contains("-apple-darwin") {
+        "macos"
+    } else if target.contains("-windows") {
+        "windows"
+    } else if target.contains("-linux") {
+        "linux"
+    } else {
+        target.split('-').nth(2).unwrap_or_default()
+    }
+}
+
+/// Get the architecture of the `target` triple, as named by [`std::env::consts::ARCH`].
+fn target_arch(target: &str) -> &str {
+    if target == target_triple::TARGET {
+        return std::env::consts::ARCH;
+    }
+    match target.split('-').next().unwrap_or_default() {
+        "i586" | "i686" => "x86",
+        arch if arch.starts_with("arm") => "arm",
+        arch => arch,
+    }
+}
+
+impl Pattern {
+    /// Determine if the asset `name` matches the pattern for the specified version and `target`
+    /// triple.
+    ///
+    /// # Errors
+    /// * If the pattern cannot be compiled.
+    pub fn matches(&self, name: &str, version: &Version, target: &str) -> Result<bool> {
+        let expression: String = self
+            .tokens
+            .iter()
+            .map(|token| token.regex(version, target))
+            .collect();
+        let regex = Regex::new(&format!("^{expression}$"))?;
+        Ok(regex.is_match(name))
+    }
+}
+
+impl FromStr for Pattern {
+    type Err = crate::Error;
+
+    fn from_str(pattern: &str) -> Result<Self> {
+        let mut tokens = Vec::new();
+        let mut literal = String::new();
+        let mut chars = pattern.chars();
+        while let Some(character) = chars.next() {
+            match character {
+                '{' => {
+                    let mut name = String::new();
+                    let mut closed = false;
+                    for character in chars.by_ref() {
+                        if character == '}' {
+                            closed = true;
+                            break;
+                        }
+                        name.push(character);
+                    }
+                    if !closed {
+                        return Err(ParseError(format!("unclosed placeholder in '{pattern}'")));
+                    }
+                    if !literal.is_empty() {
+                        tokens.push(Token::Literal(std::mem::take(&mut literal)));
+                    }
+                    tokens.push(Token::placeholder(pattern, &name)?);
+                }
+                '*' => {
+                    if !literal.is_empty() {
+                        tokens.push(Token::Literal(std::mem::take(&mut literal)));
+                    }
+                    tokens.push(Token::Wildcard);
+                }
+                _ => literal.push(character),
+            }
+        }
+        if !literal.is_empty() {
+            tokens.push(Token::Literal(literal));
+        }
+        if tokens.is_empty() {
+            return Err(ParseError("empty pattern".to_string()));
+        }
+        Ok(Self { tokens })
+    }
+}
+
+/// Registers the asset name `pattern` as the matcher for the specified repository URL for all
+/// operations of the process. The pattern replaces any pattern previously registered for the URL,
+/// and takes precedence over the matchers registered with [`register`](registry::register) before
+/// the first pattern was registered. To match the assets of a single operation, use the
+/// [asset pattern](crate::ScopedOptions::asset_pattern) of its scope instead.
+///
+/// # Errors
+/// * If the pattern is invalid.
+/// * If the registry is poisoned.
+pub fn register<S: AsRef<str>>(url: S, pattern: &str) -> Result<()> {
+    let pattern = Pattern::from_str(pattern)?;
+    let mut result = Ok(());
+    REGISTER.call_once(|| result = registry::register(supports, matcher));
+    result?;
+    PATTERNS
+        .lock()
+        .map_err(|error| PoisonedLock(error.to_string()))?
+        .insert(url.as_ref().to_string(), pattern);
+    Ok(())
+}
+
+/// Determine if a pattern is registered for the URL.
+fn supports(url: &str) -> Result<bool> {
+    let patterns = PATTERNS
+        .lock()
+        .map_err(|error| PoisonedLock(error.to_string()))?;
+    Ok(patterns.contains_key(url))
+}
+
+/// Matcher for the pattern registered for the URL.
+///
+/// # Errors
+/// * If the pattern cannot be compiled.
+fn matcher(url: &str, name: &str, version: &Version, target: &str) -> Result<bool> {
+    let patterns = PATTERNS
+        .lock()
+        .map_err(|error| PoisonedLock(error.to_string()))?;
+    match patterns.get(url) {
+        Some(pattern) => pattern.matches(name, version, target),
+        None => Ok(false),
+    }
+}
+
+/// Matcher for the [asset pattern](crate::ScopedOptions::asset_pattern) of the current scope.
+///
+/// # Errors
+/// * If the pattern is invalid.
+pub(crate) fn scoped_matcher(
+    _url: &str,
+    name: &str,
+    version: &Version,
+    target: &str,
+) -> Result<bool> {
+    match scoped::asset_pattern() {
+        Some(pattern) => Pattern::from_str(&pattern)?.matches(name, version, target),
+        None => Ok(false),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::env::consts::{ARCH, OS};
+    use target_triple::TARGET;
+
+    #[test]
+    fn test_pattern_matches() -> Result<()> {
+        let pattern = Pattern::from_str("postgres-{version}-{os}-{arch}.tar.gz")?;
+        let version = Version::new(16, 4, 0);
+        assert!(pattern.matches(
+            &format!("postgres-16.4.0-{OS}-{ARCH}.tar.gz"),
+            &version,
+            TARGET
+        )?);
+        assert!(!pattern.matches(
+            &format!("postgres-16.3.0-{OS}-{ARCH}.tar.gz"),
+            &version,
+            TARGET
+        )?);
+        assert!(!pattern.matches(
+            &format!("postgres-16.4.0-{OS}-{ARCH}.tar"),
+            &version,
+            TARGET
+        )?);
+        assert!(!pattern.matches(
+            &format!("postgres-16.4.0-{OS}-{ARCH}.tar.gz.sha256"),
+            &version,
+            TARGET
+        )?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pattern_target() -> Result<()> {
+        let pattern = Pattern::from_str("postgres-{version}-{os}-{arch}-{target}.tar.gz")?;
+        let version = Version::new(16, 4, 0);
+        let name = "postgres-16.4.0-macos-aarch64-aarch64-apple-darwin.tar.gz";
+        assert!(pattern.matches(name, &version, "aarch64-apple-darwin")?);
+        let name = "postgres-16.4.0-linux-x86-i686-unknown-linux-gnu.tar.gz";
+        assert!(pattern.matches(name, &version, "i686-unknown-linux-gnu")?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pattern_components() -> Result<()> {
+        let pattern = Pattern::from_str("pg{major}_{minor}_{patch}-nightly-*.zip")?;
+        let version = Version::new(17, 2, 1);
+        assert!(pattern.matches("pg17_2_1-nightly-20240101.zip", &version, TARGET)?);
+        assert!(!pattern.matches("pg17_2_1-stable-20240101.zip", &version, TARGET)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pattern_postgresql_version() -> Result<()> {
+        let pattern = Pattern::from_str("postgresql-{postgresql_version}.tar.gz")?;
+        let version = Version::parse("18.0.0-beta.1")?;
+        assert!(pattern.matches("postgresql-18beta1.tar.gz", &version, TARGET)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pattern_escapes_literals() -> Result<()> {
+        let pattern = Pattern::from_str("postgres+{version}.tar.gz")?;
+        let version = Version::new(16, 4, 0);
+        assert!(pattern.matches("postgres+16.4.0.tar.gz", &version, TARGET)?);
+        assert!(!pattern.matches("postgres+16.4.0-tar-gz", &version, TARGET)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_pattern_errors() {
+        assert!(Pattern::from_str("").is_err());
+        assert!(Pattern::from_str("postgres-{foo}.tar.gz").is_err());
+        assert!(Pattern::from_str("postgres-{version.tar.gz").is_err());
+        assert!(Pattern::from_str("{major}-{major").is_err());
+    }
+
+    #[test]
+    fn test_register() -> Result<()> {
+        let url = "https://pattern.example.com";
+        register(url, "postgres-{version}.tar.gz")?;
+        let matcher = registry::get(url)?;
+        let version = Version::new(16, 4, 0);
+        assert!(matcher(url, "postgres-16.4.0.tar.gz", &version, TARGET)?);
+        assert!(!matcher(url, "postgres-16.3.0.tar.gz", &version, TARGET)?);
+        assert!(registry::get("https://unregistered.example.com").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_scoped_matcher() -> Result<()> {
+        let url = "https://scoped.pattern.example.com";
+        let options = crate::ScopedOptions {
+            asset_pattern: Some("postgres-{version}.tar.gz".to_string()),
+            ..crate::ScopedOptions::default()
+        };
+        let version = Version::new(16, 4, 0);
+        crate::with_scoped_options_blocking(options, || -> Result<()> {
+            let matcher = registry::get(url)?;
+            assert!(matcher(url, "postgres-16.4.0.tar.gz", &version, TARGET)?);
+            assert!(!matcher(url, "postgres-16.3.0.tar.gz", &version, TARGET)?);
+            Ok(())
+        })?;
+        // The pattern is not registered for the URL outside of the scope
+        assert!(registry::get(url).is_err());
+        Ok(())
+    }
+}
@@ -1,7 +1,13 @@
-#[cfg(feature = "theseus")]
+#[cfg(any(feature = "theseus", feature = "file", feature = "gitlab"))]
 use crate::configuration::theseus;
 #[cfg(feature = "zonky")]
 use crate::configuration::zonky;
+use crate::matcher::pattern;
+#[cfg(feature = "file")]
+use crate::repository::file;
+#[cfg(feature = "gitlab")]
+use crate::repository::gitlab;
+use crate::scoped;
 use crate::Error::{PoisonedLock, UnsupportedMatcher};
 use crate::Result;
 use semver::Version;
@@ -11,7 +17,9 @@ static REGISTRY: LazyLock<Arc<Mutex<MatchersRegistry>>> =
     LazyLock::new(|| Arc::new(Mutex::new(MatchersRegistry::default())));
 
 pub type SupportsFn = fn(&str) -> Result<bool>;
-pub type MatcherFn = fn(&str, &str, &Version) -> Result<bool>;
+/// Matches an asset name of the repository URL for a version and target triple; e.g.
+/// `|url, name, version, target| Ok(name == format!("postgresql-{version}-{target}.tar.gz"))`.
+pub type MatcherFn = fn(&str, &str, &Version, &str) -> Result<bool>;
 
 /// Singleton struct to store matchers
 #[expect(clippy::type_complexity)]
@@ -66,9 +74,13 @@ impl Default for MatchersRegistry {
     fn default() -> Self {
         let mut registry = Self::new();
         #[cfg(feature = "theseus")]
-        registry.register(|url| Ok(url == theseus::URL), theseus::matcher);
+        registry.register(|url| Ok(url.starts_with(theseus::URL)), theseus::matcher);
         #[cfg(feature = "zonky")]
         registry.register(|url| Ok(url == zonky::URL), zonky::matcher);
+        #[cfg(feature = "file")]
+        registry.register(|url| Ok(url.starts_with(file::URL)), theseus::matcher);
+        #[cfg(feature = "gitlab")]
+        registry.register(|url| Ok(gitlab::supports(url)), theseus::matcher);
         registry
     }
 }
@@ -86,17 +98,76 @@ pub fn register(supports_fn: SupportsFn, matcher_fn: MatcherFn) -> Result<()> {
     Ok(())
 }
 
-/// Get a matcher for the specified URL.
+/// Get a matcher for the specified URL; the matcher of the
+/// [asset pattern](crate::ScopedOptions::asset_pattern) of the current scope, if any, takes
+/// precedence over the registered matchers.
 ///
 /// # Errors
 /// * If the registry is poisoned.
 pub fn get<S: AsRef<str>>(url: S) -> Result<MatcherFn> {
+    if scoped::asset_pattern().is_some() {
+        return Ok(pattern::scoped_matcher);
+    }
     let registry = REGISTRY
         .lock()
         .map_err(|error| PoisonedLock(error.to_string()))?;
     registry.get(url)
 }
 
+/// A read-only snapshot of the registered matchers, used to debug which matcher is used for a
+/// URL; matchers registered after the snapshot is taken are not included.
+#[must_use]
+#[expect(clippy::type_complexity)]
+pub struct Snapshot {
+    matchers: Vec<(Arc<RwLock<SupportsFn>>, Arc<RwLock<MatcherFn>>)>,
+}
+
+impl Snapshot {
+    /// Returns the number of matchers in the snapshot.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.matchers.len()
+    }
+
+    /// Returns whether the snapshot has no matchers.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.matchers.is_empty()
+    }
+
+    /// Returns the position, in order of precedence, of the matcher used for the specified URL; the
+    /// [asset pattern](crate::ScopedOptions::asset_pattern) of the current scope, if any, takes
+    /// precedence over all positions.
+    ///
+    /// # Errors
+    /// * If the registry is poisoned.
+    pub fn position<S: AsRef<str>>(&self, url: S) -> Result<Option<usize>> {
+        let url = url.as_ref();
+        for (index, (supports_fn, _)) in self.matchers.iter().enumerate() {
+            let supports_function = supports_fn
+                .read()
+                .map_err(|error| PoisonedLock(error.to_string()))?;
+            if supports_function(url)? {
+                return Ok(Some(index));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Takes a read-only snapshot of the registered matchers.
+///
+/// # Errors
+/// * If the registry is poisoned.
+pub fn snapshot() -> Result<Snapshot> {
+    let registry = REGISTRY
+        .lock()
+        .map_err(|error| PoisonedLock(error.to_string()))?;
+    Ok(Snapshot {
+        matchers: registry.matchers.clone(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -105,13 +176,30 @@ mod tests {
     fn test_register() -> Result<()> {
         register(
             |url| Ok(url == "https://foo.com"),
-            |_url, name, _version| Ok(name == "foo"),
+            |_url, name, _version, _target| Ok(name == "foo"),
         )?;
 
         let matcher = get("https://foo.com")?;
         let version = Version::new(16, 3, 0);
 
-        assert!(matcher("", "foo", &version)?);
+        assert!(matcher("", "foo", &version, target_triple::TARGET)?);
+        Ok(())
+    }
+
+    #[test]
+    fn test_snapshot() -> Result<()> {
+        let url = "https://snapshot.foo.com";
+        let snapshot = snapshot()?;
+        assert_eq!(None, snapshot.position(url)?);
+        register(
+            |url| Ok(url == "https://snapshot.foo.com"),
+            |_url, name, _version, _target| Ok(name == "foo"),
+        )?;
+        let current = super::snapshot()?;
+        assert_eq!(Some(0), current.position(url)?);
+        assert!(current.len() > snapshot.len());
+        assert_eq!(None, snapshot.position(url)?);
+        assert!(get(url).is_ok());
         Ok(())
     }
 
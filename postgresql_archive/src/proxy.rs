@@ -0,0 +1,151 @@
+use crate::Error::{IoError, PoisonedLock};
+use crate::{scoped, Result};
+use reqwest::{NoProxy, Proxy};
+use std::fmt::{Debug, Formatter};
+use std::sync::{LazyLock, RwLock};
+
+static DEFAULT_PROXY_OPTIONS: LazyLock<RwLock<ProxyOptions>> =
+    LazyLock::new(|| RwLock::new(ProxyOptions::default()));
+
+/// Options for the proxy that repositories download through. If no proxy URL is set, the proxy
+/// of the `HTTPS_PROXY`, `HTTP_PROXY` and `ALL_PROXY` environment variables is used, excluding
+/// the hosts of the `NO_PROXY` environment variable.
+#[derive(Clone, Default, PartialEq)]
+pub struct ProxyOptions {
+    /// URL of the proxy for HTTP and HTTPS requests; e.g. `http://proxy.example.com:3128`
+    pub url: Option<String>,
+    /// User name for basic authentication with the proxy
+    pub username: Option<String>,
+    /// Password for basic authentication with the proxy
+    pub password: Option<String>,
+    /// Comma separated hosts, domains and IP networks that are not proxied, in the format of the
+    /// `NO_PROXY` environment variable; e.g. `localhost,.example.com,10.0.0.0/8`. Defaults to the
+    /// `NO_PROXY` environment variable
+    pub no_proxy: Option<String>,
+}
+
+impl ProxyOptions {
+    /// Creates new [`ProxyOptions`] for the proxy at the `url`.
+    #[must_use]
+    pub fn new<S: Into<String>>(url: S) -> Self {
+        Self {
+            url: Some(url.into()),
+            ..Self::default()
+        }
+    }
+
+    /// Gets the proxy for the options, or `None` if no proxy URL is set.
+    ///
+    /// # Errors
+    /// * If the proxy URL is invalid.
+    fn proxy(&self) -> Result<Option<Proxy>> {
+        let Some(url) = &self.url else {
+            return Ok(None);
+        };
+        let mut proxy = Proxy::all(url.as_str())?;
+        if let Some(username) = &self.username {
+            proxy = proxy.basic_auth(username, self.password.as_deref().unwrap_or_default());
+        }
+        let no_proxy = match &self.no_proxy {
+            Some(no_proxy) => NoProxy::from_string(no_proxy),
+            None => NoProxy::from_env(),
+        };
+        Ok(Some(proxy.no_proxy(no_proxy)))
+    }
+}
+
+/// Formats the options with the password redacted, so that it is not logged.
+impl Debug for ProxyOptions {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProxyOptions")
+            .field("url", &self.url)
+            .field("username", &self.username)
+            .field("password", &self.password.as_ref().map(|_| "[REDACTED]"))
+            .field("no_proxy", &self.no_proxy)
+            .finish()
+    }
+}
+
+/// Gets the default [proxy options](ProxyOptions).
+#[must_use]
+pub fn default_proxy_options() -> ProxyOptions {
+    match DEFAULT_PROXY_OPTIONS.read() {
+        Ok(options) => options.clone(),
+        Err(error) => (**error.get_ref()).clone(),
+    }
+}
+
+/// Sets the default [proxy options](ProxyOptions).
+///
+/// # Errors
+/// * If the lock is poisoned.
+pub fn set_default_proxy_options(options: ProxyOptions) -> Result<()> {
+    let mut default_options = DEFAULT_PROXY_OPTIONS
+        .write()
+        .map_err(|error| PoisonedLock(error.to_string()))?;
+    *default_options = options;
+    Ok(())
+}
+
+/// Creates a new reqwest client that uses the proxy options of the current
+/// [scope](crate::ScopedOptions::proxy), or the [default proxy options](default_proxy_options).
+///
+/// # Errors
+/// * If the proxy URL is invalid, or the client cannot be created.
+pub(crate) fn http_client() -> Result<reqwest::Client> {
+    build_http_client(http_client_builder()?)
+}
+
+/// Creates a new reqwest client builder that uses the proxy options of the current
+/// [scope](crate::ScopedOptions::proxy), or the [default proxy options](default_proxy_options);
+/// e.g. for a client with its own redirect policy.
+///
+/// # Errors
+/// * If the proxy URL is invalid.
+pub(crate) fn http_client_builder() -> Result<reqwest::ClientBuilder> {
+    let mut builder = reqwest::Client::builder();
+    if let Some(proxy) = scoped::proxy_options().proxy()? {
+        builder = builder.proxy(proxy);
+    }
+    Ok(builder)
+}
+
+/// Builds the reqwest client of the `builder`.
+///
+/// # Errors
+/// * If the client cannot be created.
+pub(crate) fn build_http_client(builder: reqwest::ClientBuilder) -> Result<reqwest::Client> {
+    builder
+        .build()
+        .map_err(|error| IoError(format!("failed to create HTTP client: {error}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_proxy_options() -> Result<()> {
+        assert!(ProxyOptions::default().proxy()?.is_none());
+        let options = ProxyOptions {
+            username: Some("user".to_string()),
+            password: Some("password".to_string()),
+            no_proxy: Some("localhost,.example.com".to_string()),
+            ..ProxyOptions::new("http://proxy.example.com:3128")
+        };
+        assert!(options.proxy()?.is_some());
+        let debug = format!("{options:?}");
+        assert!(debug.contains("[REDACTED]"));
+        assert!(!debug.contains("\"password\""));
+        Ok(())
+    }
+
+    #[test]
+    fn test_default_proxy_options() -> Result<()> {
+        let options = default_proxy_options();
+        set_default_proxy_options(options.clone())?;
+        assert_eq!(options, default_proxy_options());
+        assert!(http_client().is_ok());
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+use crate::extractor::ExtractProgress;
+use crate::scoped;
+use std::fmt::Debug;
+use std::path::Path;
+
+/// Receives the progress of the downloads and extractions of archives, so that applications, such
+/// as GUI applications, can display progress without a tracing subscriber. A reporter receives
+/// the progress of the operations of the [scope](crate::ScopedOptions::progress_reporter) it is
+/// set for, and is called synchronously by the task or thread performing the operation, so it
+/// should return quickly.
+pub trait ProgressReporter: Debug + Send + Sync {
+    /// Called as the archive at the `url` is downloaded with the number of `bytes` downloaded and
+    /// the `total` size of the archive, if known.
+    fn download(&self, _url: &str, _bytes: u64, _total: Option<u64>) {}
+
+    /// Called as an archive is extracted to the `out_dir` with the [progress](ExtractProgress) of
+    /// the extraction; e.g. the number of files extracted.
+    fn extract(&self, _out_dir: &Path, _progress: &ExtractProgress) {}
+}
+
+/// Reports the `bytes` downloaded of the archive at the `url` to the reporter of the current
+/// scope, if any.
+pub(crate) fn report_download(url: &str, bytes: u64, total: Option<u64>) {
+    if let Some(reporter) = scoped::progress_reporter() {
+        reporter.download(url, bytes, total);
+    }
+}
+
+/// Reports the `progress` of the extraction to the `out_dir` to the reporter of the current
+/// scope, if any.
+pub(crate) fn report_extract(out_dir: &Path, progress: &ExtractProgress) {
+    if let Some(reporter) = scoped::progress_reporter() {
+        reporter.extract(out_dir, progress);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{with_scoped_options_blocking, ScopedOptions};
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Default)]
+    struct TestReporter {
+        downloads: Mutex<Vec<(String, u64, Option<u64>)>>,
+        extractions: Mutex<Vec<ExtractProgress>>,
+    }
+
+    impl ProgressReporter for Arc<TestReporter> {
+        fn download(&self, url: &str, bytes: u64, total: Option<u64>) {
+            self.downloads
+                .lock()
+                .expect("lock")
+                .push((url.to_string(), bytes, total));
+        }
+
+        fn extract(&self, _out_dir: &Path, progress: &ExtractProgress) {
+            self.extractions.lock().expect("lock").push(*progress);
+        }
+    }
+
+    #[test]
+    fn test_progress_reporter() {
+        let reporter = Arc::new(TestReporter::default());
+        let options = ScopedOptions {
+            progress_reporter: Some(Arc::new(reporter.clone())),
+            ..ScopedOptions::default()
+        };
+        let url = "https://reporter.example.com/archive.tar.gz";
+        let progress = ExtractProgress {
+            files: 1,
+            ..ExtractProgress::default()
+        };
+        with_scoped_options_blocking(options, || {
+            report_download(url, 10, Some(20));
+            report_extract(Path::new("reporter"), &progress);
+        });
+        report_download(url, 20, Some(20));
+
+        assert_eq!(
+            vec![(url.to_string(), 10, Some(20))],
+            *reporter.downloads.lock().expect("lock")
+        );
+        assert_eq!(vec![progress], *reporter.extractions.lock().expect("lock"));
+    }
+}
@@ -0,0 +1,364 @@
+#[cfg(feature = "minisign")]
+use crate::hasher::minisign;
+use crate::repository::model::Repository;
+use crate::repository::Archive;
+use crate::version::parse_version;
+use crate::Error::{RepositoryFailure, VersionNotFound};
+use crate::{matcher, reporter, scoped, Channel, Result};
+use async_trait::async_trait;
+use regex_lite::Regex;
+use semver::{Version, VersionReq};
+use std::fs::read_dir;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use tracing::field::Empty;
+use tracing::{debug, instrument};
+use url::Url;
+
+static ARCHIVE_VERSION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(\d+\.\d+\.\d+(?:-(?:alpha|beta|rc)\.\d+)?|\d+(?:alpha|beta|rc)\d+)")
+        .expect("regex")
+});
+
+/// Local file system repository.
+///
+/// This repository is used to install archives from a local mirror for environments that cannot
+/// reach a remote repository (e.g. `file:///opt/postgresql/archives`). The URL is either the path
+/// of a single archive, or a directory of archives in which the archives may also be grouped
+/// into a subdirectory per release (e.g. `16.4.0/postgresql-16.4.0-x86_64-unknown-linux-gnu.tar.gz`).
+/// The version of an archive is parsed from the archive name, and archives in a directory are
+/// selected with the matcher registered for the URL.
+#[derive(Debug)]
+pub struct FileSystem {
+    url: String,
+    path: PathBuf,
+}
+
+impl FileSystem {
+    /// Creates a new file system repository from the specified URL in the format
+    /// <file:///opt/postgresql/archives>
+    ///
+    /// # Errors
+    /// * If the URL is invalid.
+    #[expect(clippy::new_ret_no_self)]
+    pub fn new(url: &str) -> Result<Box<dyn Repository>> {
+        let parsed_url = Url::parse(url)?;
+        let Ok(path) = parsed_url.to_file_path() else {
+            return Err(RepositoryFailure(format!("{url} is not a file URL")));
+        };
+        Ok(Box::new(Self {
+            url: url.to_string(),
+            path,
+        }))
+    }
+
+    /// Gets the version of the archive from the archive `name`, or `None` if the name does not
+    /// contain a version.
+    fn get_version_from_name(name: &str) -> Option<Version> {
+        let captures = ARCHIVE_VERSION.captures(name)?;
+        parse_version(&captures[1]).ok()
+    }
+
+    /// Gets the archives in the repository directory, and in the subdirectories of the
+    /// repository directory.
+    ///
+    /// # Errors
+    /// * If the directory cannot be read.
+    fn get_archives(&self) -> Result<Vec<PathBuf>> {
+        let mut archives = Vec::new();
+        for entry in read_dir(&self.path)? {
+            let path = entry?.path();
+            if path.is_dir() {
+                for entry in read_dir(&path)? {
+                    let path = entry?.path();
+                    if path.is_file() {
+                        archives.push(path);
+                    }
+                }
+            } else if path.is_file() {
+                archives.push(path);
+            }
+        }
+        Ok(archives)
+    }
+
+    /// Gets the paths and versions of the archives in the repository that pass the matcher.
+    ///
+    /// # Errors
+    /// * If the directory cannot be read.
+    fn get_archive_versions(&self) -> Result<Vec<(PathBuf, Version)>> {
+        if self.path.is_file() {
+            let version = file_name(&self.path).and_then(Self::get_version_from_name);
+            return Ok(version
+                .map(|version| vec![(self.path.clone(), version)])
+                .unwrap_or_default());
+        }
+        let matcher = matcher::registry::get(&self.url)?;
+        let target = scoped::target();
+        let mut archives = Vec::new();
+        for path in self.get_archives()? {
+            let Some(name) = file_name(&path) else {
+                continue;
+            };
+            let Some(version) = Self::get_version_from_name(name) else {
+                continue;
+            };
+            if matcher(&self.url, name, &version, &target)? {
+                archives.push((path, version));
+            }
+        }
+        Ok(archives)
+    }
+
+    /// Gets the path and version of the latest archive that matches the specified version
+    /// requirement in the release [channel](Channel).
+    ///
+    /// # Errors
+    /// * If the version requirement does not match any archives.
+    #[instrument(level = "debug")]
+    fn get_archive_path(
+        &self,
+        version_req: &VersionReq,
+        channel: Channel,
+    ) -> Result<(PathBuf, Version)> {
+        debug!("Attempting to locate archive for version requirement {version_req}");
+        let result = self
+            .get_archive_versions()?
+            .into_iter()
+            .filter(|(_, version)| channel.matches(version_req, version))
+            .max_by(|(_, a), (_, b)| a.cmp(b));
+
+        match result {
+            Some((path, version)) => {
+                debug!(
+                    "Archive {} found for version requirement {version_req}",
+                    path.to_string_lossy()
+                );
+                Ok((path, version))
+            }
+            None => Err(VersionNotFound(version_req.to_string())),
+        }
+    }
+}
+
+/// Gets the file name of the `path` as a string.
+fn file_name(path: &Path) -> Option<&str> {
+    path.file_name().and_then(|name| name.to_str())
+}
+
+/// Reports the `bytes` of the archive at the `path` that were read or copied, as the progress of
+/// a download that completed at once.
+fn report_copy(path: &Path, bytes: u64) {
+    let url = Url::from_file_path(path).map_or_else(
+        |()| path.to_string_lossy().to_string(),
+        |url| url.to_string(),
+    );
+    reporter::report_download(&url, bytes, Some(bytes));
+}
+
+#[async_trait]
+impl Repository for FileSystem {
+    #[instrument(level = "debug")]
+    fn name(&self) -> &str {
+        "FileSystem"
+    }
+
+    #[instrument(level = "debug")]
+    async fn list_versions(&self) -> Result<Vec<Version>> {
+        let mut versions = self
+            .get_archive_versions()?
+            .into_iter()
+            .map(|(_, version)| version)
+            .collect::<Vec<_>>();
+        versions.sort_by(|a, b| b.cmp(a));
+        versions.dedup();
+        Ok(versions)
+    }
+
+    #[instrument(level = "debug")]
+    async fn get_version(&self, version_req: &VersionReq) -> Result<Version> {
+        self.get_version_in_channel(version_req, Channel::Stable)
+            .await
+    }
+
+    #[instrument(level = "debug")]
+    async fn get_archive(&self, version_req: &VersionReq) -> Result<Archive> {
+        self.get_archive_in_channel(version_req, Channel::Stable)
+            .await
+    }
+
+    #[instrument(level = "debug")]
+    async fn get_version_in_channel(
+        &self,
+        version_req: &VersionReq,
+        channel: Channel,
+    ) -> Result<Version> {
+        let (_, version) = self.get_archive_path(version_req, channel)?;
+        Ok(version)
+    }
+
+    #[instrument(fields(operation = "read", version = Empty, bytes_total = Empty))]
+    async fn get_archive_in_channel(
+        &self,
+        version_req: &VersionReq,
+        channel: Channel,
+    ) -> Result<Archive> {
+        let (path, version) = self.get_archive_path(version_req, channel)?;
+        let name = file_name(&path).unwrap_or_default().to_string();
+        let bytes = std::fs::read(&path)?;
+        report_copy(&path, bytes.len() as u64);
+        let current_span = tracing::Span::current();
+        current_span.record("version", version.to_string());
+        current_span.record("bytes_total", bytes.len());
+        Ok(Archive::new(name, version, bytes))
+    }
+
+    #[instrument(fields(operation = "copy", version = Empty, bytes_total = Empty))]
+    async fn get_archive_to_file(&self, version_req: &VersionReq, path: &Path) -> Result<Version> {
+        let (archive_path, version) = self.get_archive_path(version_req, Channel::Stable)?;
+        let bytes_total = std::fs::copy(&archive_path, path)?;
+        report_copy(&archive_path, bytes_total);
+        let current_span = tracing::Span::current();
+        current_span.record("version", version.to_string());
+        current_span.record("bytes_total", bytes_total);
+        Ok(version)
+    }
+
+    #[cfg(feature = "minisign")]
+    #[instrument(level = "debug")]
+    async fn get_signature(&self, version: &Version) -> Result<Option<String>> {
+        let version_req = VersionReq::parse(&format!("={version}"))?;
+        let (path, _) = self.get_archive_path(&version_req, Channel::Stable)?;
+        let name = file_name(&path).unwrap_or_default();
+        let signature_path =
+            path.with_file_name(format!("{name}.{}", minisign::SIGNATURE_EXTENSION));
+        if !signature_path.is_file() {
+            return Ok(None);
+        }
+        Ok(Some(std::fs::read_to_string(signature_path)?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fs::{create_dir_all, write};
+
+    fn archive_name(version: &str) -> String {
+        format!("postgresql-{version}-{}.tar.gz", target_triple::TARGET)
+    }
+
+    fn file_url(path: &Path) -> Result<String> {
+        let url = Url::from_file_path(path)
+            .map_err(|()| RepositoryFailure(path.to_string_lossy().to_string()))?;
+        Ok(url.to_string())
+    }
+
+    #[test]
+    fn test_name() -> Result<()> {
+        let repository = FileSystem::new("file:///tmp")?;
+        assert_eq!("FileSystem", repository.name());
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_error() {
+        assert!(FileSystem::new("https://foo.com").is_err());
+    }
+
+    #[test]
+    fn test_get_version_from_name() {
+        assert_eq!(
+            Some(Version::new(16, 4, 0)),
+            FileSystem::get_version_from_name(&archive_name("16.4.0"))
+        );
+        assert_eq!(
+            Some(Version::parse("18.0.0-beta.1").expect("version")),
+            FileSystem::get_version_from_name(&archive_name("18beta1"))
+        );
+        assert_eq!(None, FileSystem::get_version_from_name("postgresql.tar.gz"));
+    }
+
+    #[tokio::test]
+    async fn test_get_archive_from_directory() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        write(temp_dir.path().join(archive_name("15.8.0")), "15")?;
+        write(
+            temp_dir.path().join("postgresql-16.4.0-unknown.tar.gz"),
+            "x",
+        )?;
+        let release_dir = temp_dir.path().join("16.4.0");
+        create_dir_all(&release_dir)?;
+        write(release_dir.join(archive_name("16.4.0")), "16")?;
+        let repository = FileSystem::new(&file_url(temp_dir.path())?)?;
+
+        let version = repository.get_version(&VersionReq::STAR).await?;
+        assert_eq!(Version::new(16, 4, 0), version);
+        assert_eq!(
+            vec![Version::new(16, 4, 0), Version::new(15, 8, 0)],
+            repository.list_versions().await?
+        );
+        let archive = repository
+            .get_archive(&VersionReq::parse("=15.8.0")?)
+            .await?;
+        assert_eq!(archive_name("15.8.0"), archive.name());
+        assert_eq!(&Version::new(15, 8, 0), archive.version());
+        assert_eq!(b"15", archive.bytes());
+        assert!(repository
+            .get_version(&VersionReq::parse("=17.0.0")?)
+            .await
+            .is_err());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_archive_from_file() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let archive_path = temp_dir.path().join("postgresql-16.4.0.tar.gz");
+        write(&archive_path, "16")?;
+        let repository = FileSystem::new(&file_url(&archive_path)?)?;
+
+        let path = temp_dir.path().join("archive.tar.gz");
+        let version = repository
+            .get_archive_to_file(&VersionReq::STAR, &path)
+            .await?;
+        assert_eq!(Version::new(16, 4, 0), version);
+        assert_eq!(b"16", std::fs::read(&path)?.as_slice());
+        Ok(())
+    }
+
+    #[derive(Debug, Default)]
+    struct TestReporter(std::sync::Mutex<Vec<(String, u64, Option<u64>)>>);
+
+    impl crate::ProgressReporter for std::sync::Arc<TestReporter> {
+        fn download(&self, url: &str, bytes: u64, total: Option<u64>) {
+            self.0
+                .lock()
+                .expect("lock")
+                .push((url.to_string(), bytes, total));
+        }
+    }
+
+    #[tokio::test]
+    async fn test_get_archive_reports_progress() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let archive_path = temp_dir.path().join("postgresql-16.4.0.tar.gz");
+        write(&archive_path, "16")?;
+        let url = file_url(&archive_path)?;
+        let repository = FileSystem::new(&url)?;
+        let reporter = std::sync::Arc::new(TestReporter::default());
+        let options = crate::ScopedOptions {
+            progress_reporter: Some(std::sync::Arc::new(reporter.clone())),
+            ..crate::ScopedOptions::default()
+        };
+
+        let path = temp_dir.path().join("archive.tar.gz");
+        crate::with_scoped_options(
+            options,
+            repository.get_archive_to_file(&VersionReq::STAR, &path),
+        )
+        .await?;
+        assert_eq!(vec![(url, 2, Some(2))], *reporter.0.lock().expect("lock"));
+        Ok(())
+    }
+}
@@ -13,6 +13,8 @@ pub(crate) struct Release {
     pub name: String,
     pub draft: bool,
     pub prerelease: bool,
+    #[serde(default)]
+    pub published_at: Option<String>,
     pub assets: Vec<Asset>,
 }
 
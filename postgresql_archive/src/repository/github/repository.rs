@@ -1,27 +1,34 @@
+use crate::credentials::apply_credentials;
+use crate::download::{download, download_to_file, temporary_path};
+#[cfg(feature = "minisign")]
+use crate::hasher::minisign;
 use crate::hasher::registry::HasherFn;
+use crate::proxy::http_client;
 use crate::repository::github::models::{Asset, Release};
 use crate::repository::model::Repository;
-use crate::repository::Archive;
+use crate::repository::{Archive, ReleaseInfo};
+use crate::retry::RetryStatusStrategy;
+use crate::version::parse_version;
 use crate::Error::{
-    ArchiveHashMismatch, AssetHashNotFound, AssetNotFound, RepositoryFailure, VersionNotFound,
+    ArchiveHashMismatch, AssetHashNotFound, AssetNotFound, ParseError, RepositoryFailure,
+    VersionNotFound,
 };
-use crate::{hasher, matcher, Result};
+use crate::{cache, hasher, matcher, scoped, Channel, Result};
 use async_trait::async_trait;
-use futures_util::StreamExt;
 use regex_lite::Regex;
-use reqwest::header::HeaderMap;
+use reqwest::header::{HeaderMap, HeaderValue, ETAG, IF_NONE_MATCH};
+use reqwest::StatusCode;
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
-use reqwest_retry::policies::ExponentialBackoff;
 use reqwest_retry::RetryTransientMiddleware;
 use reqwest_tracing::TracingMiddleware;
 use semver::{Version, VersionReq};
 use std::env;
-use std::io::Write;
-use std::str::FromStr;
+use std::fs::{remove_file, rename, File};
+use std::io::BufReader;
+use std::path::Path;
 use std::sync::LazyLock;
+use tracing::field::Empty;
 use tracing::{debug, instrument, warn};
-#[cfg(feature = "indicatif")]
-use tracing_indicatif::span_ext::IndicatifSpanExt;
 
 use url::Url;
 
@@ -89,64 +96,75 @@ impl GitHub {
     fn get_version_from_tag_name(tag_name: &str) -> Result<Version> {
         // Trim and prefix characters from the tag name (e.g., "v16.4.0" -> "16.4.0").
         let tag_name = tag_name.trim_start_matches(|c: char| !c.is_numeric());
-        match Version::from_str(tag_name) {
+        match parse_version(tag_name) {
             Ok(version) => Ok(version),
             Err(error) => {
                 warn!("Failed to parse version {tag_name}");
-                Err(error.into())
+                Err(error)
             }
         }
     }
 
-    /// Gets the release for the specified [version requirement](VersionReq). If a release for the
-    /// [version requirement](VersionReq) is not found, then an error is returned.
+    /// Gets all the releases of the repository, from the cached release `metadata` if allowed.
     ///
     /// # Errors
-    /// * If the release is not found.
+    /// * If the releases cannot be retrieved.
     #[instrument(level = "debug")]
-    async fn get_release(&self, version_req: &VersionReq) -> Result<Release> {
-        debug!("Attempting to locate release for version requirement {version_req}");
-        let client = reqwest_client();
-        let mut result: Option<Release> = None;
+    async fn get_releases(&self, metadata: Metadata) -> Result<Vec<Release>> {
+        let client = reqwest_client()?;
+        let mut releases = Vec::new();
         let mut page = 1;
 
         loop {
-            let request = client
-                .get(&self.releases_url)
-                .headers(Self::headers())
-                .query(&[("page", page.to_string().as_str()), ("per_page", "100")]);
-            let response = request.send().await?.error_for_status()?;
-            let response_releases = response.json::<Vec<Release>>().await?;
+            let page_url = format!("{}?page={page}&per_page=100", self.releases_url);
+            let body = Self::get_metadata(&client, &page_url, metadata).await?;
+            let response_releases = serde_json::from_str::<Vec<Release>>(&body)
+                .map_err(|error| ParseError(error.to_string()))?;
             if response_releases.is_empty() {
                 break;
             }
+            releases.extend(response_releases);
+            page += 1;
+        }
 
-            for release in response_releases {
-                let tag_name = release.tag_name.clone();
-                let Ok(release_version) = Self::get_version_from_tag_name(tag_name.as_str()) else {
-                    warn!("Failed to parse release version {tag_name}");
-                    continue;
-                };
-
-                if version_req.matches(&release_version) {
-                    if let Some(result_release) = &result {
-                        let result_version =
-                            Self::get_version_from_tag_name(result_release.tag_name.as_str())?;
-                        if release_version > result_version {
-                            result = Some(release);
-                        }
-                    } else {
-                        result = Some(release);
+        Ok(releases)
+    }
+
+    /// Gets the release for the specified [version requirement](VersionReq) in the release
+    /// [channel](Channel), from the cached release `metadata` if allowed. If a release for the
+    /// [version requirement](VersionReq) is not found, then an error is returned.
+    ///
+    /// # Errors
+    /// * If the release is not found.
+    #[instrument(level = "debug")]
+    async fn get_release(
+        &self,
+        version_req: &VersionReq,
+        channel: Channel,
+        metadata: Metadata,
+    ) -> Result<Release> {
+        debug!("Attempting to locate release for version requirement {version_req}");
+        let mut result: Option<(Release, Version)> = None;
+
+        for release in self.get_releases(metadata).await? {
+            let tag_name = release.tag_name.clone();
+            let Ok(release_version) = Self::get_version_from_tag_name(tag_name.as_str()) else {
+                warn!("Failed to parse release version {tag_name}");
+                continue;
+            };
+
+            if channel.matches(version_req, &release_version) {
+                if let Some((_, result_version)) = &result {
+                    if release_version <= *result_version {
+                        continue;
                     }
                 }
+                result = Some((release, release_version));
             }
-
-            page += 1;
         }
 
         match result {
-            Some(release) => {
-                let version = Self::get_version_from_tag_name(&release.tag_name)?;
+            Some((release, version)) => {
                 debug!("Version {version} found for version requirement {version_req}");
                 Ok(release)
             }
@@ -154,6 +172,65 @@ impl GitHub {
         }
     }
 
+    /// Gets the body of the GitHub API response for the `url`. The response is cached in the
+    /// cache directory of the [download options](crate::DownloadOptions), and used without a
+    /// request for the [metadata TTL](crate::DownloadOptions::metadata_ttl); once expired, the
+    /// cached response is revalidated with its ETag, which does not count against the rate limit
+    /// if the response is unchanged. [Fresh](Metadata::Fresh) metadata is always requested, and
+    /// only stored in the cache.
+    ///
+    /// # Errors
+    /// * If the request fails.
+    async fn get_metadata(
+        client: &ClientWithMiddleware,
+        url: &str,
+        metadata: Metadata,
+    ) -> Result<String> {
+        let options = scoped::download_options();
+        let cached = match metadata {
+            Metadata::Cached => options
+                .cache_dir
+                .as_deref()
+                .and_then(|cache_dir| cache::get_metadata(cache_dir, url)),
+            Metadata::Fresh => None,
+        };
+        if let Some(cached) = &cached {
+            if cached.age <= options.metadata_ttl {
+                debug!("Using cached release metadata for {url}");
+                return Ok(cached.body.clone());
+            }
+        }
+
+        let mut headers = Self::headers(url)?;
+        if let Some(etag) = cached.as_ref().and_then(|cached| cached.etag.as_deref()) {
+            if let Ok(etag) = HeaderValue::from_str(etag) {
+                headers.insert(IF_NONE_MATCH, etag);
+            }
+        }
+        let response = client.get(url).headers(headers).send().await?;
+        let (etag, body) = match cached {
+            Some(cached) if response.status() == StatusCode::NOT_MODIFIED => {
+                debug!("Cached release metadata for {url} is not modified");
+                (cached.etag, cached.body)
+            }
+            _ => {
+                let response = response.error_for_status()?;
+                let etag = response
+                    .headers()
+                    .get(ETAG)
+                    .and_then(|etag| etag.to_str().ok())
+                    .map(ToString::to_string);
+                (etag, response.text().await?)
+            }
+        };
+        if let Some(cache_dir) = &options.cache_dir {
+            if let Err(error) = cache::store_metadata(cache_dir, url, etag.as_deref(), &body) {
+                warn!("Failed to cache release metadata for {url}: {error}");
+            }
+        }
+        Ok(body)
+    }
+
     /// Gets the asset for the specified release that passes the supplied matcher. If an asset for
     /// that passes the matcher is not found, then an [AssetNotFound] error is returned.
     ///
@@ -166,9 +243,10 @@ impl GitHub {
         release: &Release,
     ) -> Result<(Asset, Option<Asset>, Option<HasherFn>)> {
         let matcher = matcher::registry::get(&self.url)?;
+        let target = scoped::target();
         let mut release_asset: Option<Asset> = None;
         for asset in &release.assets {
-            if matcher(&self.url, asset.name.as_str(), version)? {
+            if matcher(&self.url, asset.name.as_str(), version, &target)? {
                 release_asset = Some(asset.clone());
                 break;
             }
@@ -200,8 +278,72 @@ impl GitHub {
         Ok((asset, asset_hash, asset_hasher_fn))
     }
 
-    /// Returns the headers for the GitHub request.
-    fn headers() -> HeaderMap {
+    /// Downloads the hash of the `asset` from the `asset_hash` asset, and returns the first
+    /// hexadecimal hash of `hash_len` characters that it contains.
+    ///
+    /// # Errors
+    /// * If the hash cannot be downloaded.
+    /// * If the hash is not found.
+    async fn get_asset_hash(
+        client: &ClientWithMiddleware,
+        asset: &Asset,
+        asset_hash: &Asset,
+        hash_len: usize,
+    ) -> Result<String> {
+        debug!(
+            "Downloading archive hash {}",
+            asset_hash.browser_download_url
+        );
+        let request = client
+            .get(&asset_hash.browser_download_url)
+            .headers(Self::headers(&asset_hash.browser_download_url)?);
+        let response = request.send().await?.error_for_status()?;
+        let text = response.text().await?;
+        let re = Regex::new(&format!(r"[0-9a-f]{{{hash_len}}}"))?;
+        let hash = match re.find(&text) {
+            Some(hash) => hash.as_str().to_string(),
+            None => return Err(AssetHashNotFound(asset.name.clone())),
+        };
+        debug!(
+            "Archive hash {} downloaded: {}",
+            asset_hash.browser_download_url,
+            text.len(),
+        );
+        Ok(hash)
+    }
+
+    /// Hashes the downloaded archive at `path` with the reader hasher registered for the
+    /// extension of the `asset_hash`, so that the archive is not read into memory; falls back to
+    /// reading the archive for the `asset_hasher_fn` if there is no reader hasher.
+    ///
+    /// # Errors
+    /// * If the archive cannot be read.
+    /// * If there is no hasher for the asset.
+    fn hash_file(
+        &self,
+        asset: &Asset,
+        asset_hash: &Asset,
+        asset_hasher_fn: Option<HasherFn>,
+        path: &Path,
+    ) -> Result<String> {
+        let extension = asset_hash
+            .name
+            .strip_prefix(format!("{}.", asset.name.as_str()).as_str())
+            .unwrap_or_default();
+        if let Ok(hasher_fn) = hasher::registry::get_reader(&self.url, &extension.to_string()) {
+            return hasher_fn(&mut BufReader::new(File::open(path)?));
+        }
+        match asset_hasher_fn {
+            Some(hasher_fn) => hasher_fn(&std::fs::read(path)?),
+            None => Err(AssetHashNotFound(asset.name.clone())),
+        }
+    }
+
+    /// Returns the headers for the GitHub request to the `url`.
+    ///
+    /// # Errors
+    /// * If the credentials for the request cannot be retrieved.
+    fn headers(url: &str) -> Result<HeaderMap> {
         let mut headers = HeaderMap::new();
         headers.append(
             GITHUB_API_VERSION_HEADER,
@@ -211,10 +353,21 @@ impl GitHub {
         if let Some(token) = &*GITHUB_TOKEN {
             headers.append("Authorization", format!("Bearer {token}").parse().unwrap());
         }
-        headers
+        apply_credentials(url, &mut headers)?;
+        Ok(headers)
     }
 }
 
+/// Whether the release metadata of a repository may be taken from the cache
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum Metadata {
+    /// Cached metadata is used within the metadata TTL, and revalidated once it expires; e.g. to
+    /// resolve versions
+    Cached,
+    /// Metadata is requested from the repository; e.g. for the URLs of the assets to download
+    Fresh,
+}
+
 #[async_trait]
 impl Repository for GitHub {
     #[instrument(level = "debug")]
@@ -222,72 +375,105 @@ impl Repository for GitHub {
         "GitHub"
     }
 
+    #[instrument(level = "debug")]
+    async fn list_versions(&self) -> Result<Vec<Version>> {
+        let mut versions = self
+            .get_releases(Metadata::Cached)
+            .await?
+            .iter()
+            .filter_map(|release| Self::get_version_from_tag_name(&release.tag_name).ok())
+            .collect::<Vec<_>>();
+        versions.sort_by(|a, b| b.cmp(a));
+        versions.dedup();
+        Ok(versions)
+    }
+
     #[instrument(level = "debug")]
     async fn get_version(&self, version_req: &VersionReq) -> Result<Version> {
-        let release = self.get_release(version_req).await?;
+        self.get_version_in_channel(version_req, Channel::Stable)
+            .await
+    }
+
+    #[instrument(level = "debug")]
+    async fn get_archive(&self, version_req: &VersionReq) -> Result<Archive> {
+        self.get_archive_in_channel(version_req, Channel::Stable)
+            .await
+    }
+
+    #[instrument(level = "debug")]
+    async fn get_version_in_channel(
+        &self,
+        version_req: &VersionReq,
+        channel: Channel,
+    ) -> Result<Version> {
+        let release = self
+            .get_release(version_req, channel, Metadata::Cached)
+            .await?;
         let version = Self::get_version_from_tag_name(release.tag_name.as_str())?;
         Ok(version)
     }
 
-    #[instrument]
-    async fn get_archive(&self, version_req: &VersionReq) -> Result<Archive> {
-        let release = self.get_release(version_req).await?;
+    #[instrument(level = "debug")]
+    async fn get_release_info(
+        &self,
+        version_req: &VersionReq,
+        channel: Channel,
+    ) -> Result<ReleaseInfo> {
+        let release = self
+            .get_release(version_req, channel, Metadata::Cached)
+            .await?;
+        let version = Self::get_version_from_tag_name(release.tag_name.as_str())?;
+        let size = match self.get_asset(&version, &release) {
+            Ok((asset, _, _)) => u64::try_from(asset.size).ok(),
+            Err(_) => None,
+        };
+        Ok(ReleaseInfo {
+            version,
+            published_at: release.published_at,
+            size,
+        })
+    }
+
+    #[instrument(fields(operation = "download", version = Empty, bytes_total = Empty))]
+    async fn get_archive_in_channel(
+        &self,
+        version_req: &VersionReq,
+        channel: Channel,
+    ) -> Result<Archive> {
+        // The URLs of the archive and its hash are never taken from cached metadata
+        let release = self
+            .get_release(version_req, channel, Metadata::Fresh)
+            .await?;
         let version = Self::get_version_from_tag_name(release.tag_name.as_str())?;
         let (asset, asset_hash, asset_hasher_fn) = self.get_asset(&version, &release)?;
         let name = asset.name.clone();
 
-        let client = reqwest_client();
+        let client = reqwest_client()?;
         debug!("Downloading archive {}", asset.browser_download_url);
-        let request = client
-            .get(&asset.browser_download_url)
-            .headers(Self::headers());
-        let response = request.send().await?.error_for_status()?;
-        #[cfg(feature = "indicatif")]
-        let span = tracing::Span::current();
-        #[cfg(feature = "indicatif")]
-        {
-            let content_length = response.content_length().unwrap_or_default();
-            span.pb_set_length(content_length);
-        }
-        let mut bytes = Vec::new();
-        let mut source = response.bytes_stream();
-        while let Some(chunk) = source.next().await {
-            bytes.write_all(&chunk?)?;
-            #[cfg(feature = "indicatif")]
-            span.pb_set_position(bytes.len() as u64);
-        }
+        let bytes = download(
+            &client,
+            &asset.browser_download_url,
+            &Self::headers(&asset.browser_download_url)?,
+            u64::try_from(asset.size).ok(),
+            &scoped::download_options(),
+        )
+        .await?;
         debug!(
             "Archive {} downloaded: {}",
             asset.browser_download_url,
             bytes.len(),
         );
+        let current_span = tracing::Span::current();
+        current_span.record("version", version.to_string());
+        current_span.record("bytes_total", bytes.len());
 
         if let Some(asset_hash) = asset_hash {
             let archive_hash = match asset_hasher_fn {
                 Some(hasher_fn) => hasher_fn(&bytes)?,
                 None => return Err(AssetHashNotFound(asset.name))?,
             };
-            let hash_len = archive_hash.len();
-
-            debug!(
-                "Downloading archive hash {}",
-                asset_hash.browser_download_url
-            );
-            let request = client
-                .get(&asset_hash.browser_download_url)
-                .headers(Self::headers());
-            let response = request.send().await?.error_for_status()?;
-            let text = response.text().await?;
-            let re = Regex::new(&format!(r"[0-9a-f]{{{hash_len}}}"))?;
-            let hash = match re.find(&text) {
-                Some(hash) => hash.as_str().to_string(),
-                None => return Err(AssetHashNotFound(asset.name)),
-            };
-            debug!(
-                "Archive hash {} downloaded: {}",
-                asset_hash.browser_download_url,
-                text.len(),
-            );
+            let hash =
+                Self::get_asset_hash(&client, &asset, &asset_hash, archive_hash.len()).await?;
 
             if archive_hash != hash {
                 return Err(ArchiveHashMismatch { archive_hash, hash });
@@ -297,15 +483,113 @@ impl Repository for GitHub {
         let archive = Archive::new(name, version, bytes);
         Ok(archive)
     }
+
+    #[instrument(fields(operation = "download", version = Empty, bytes_total = Empty))]
+    async fn get_archive_to_file(&self, version_req: &VersionReq, path: &Path) -> Result<Version> {
+        let release = self
+            .get_release(version_req, Channel::Stable, Metadata::Fresh)
+            .await?;
+        let version = Self::get_version_from_tag_name(release.tag_name.as_str())?;
+        let (asset, asset_hash, asset_hasher_fn) = self.get_asset(&version, &release)?;
+
+        let client = reqwest_client()?;
+        debug!(
+            "Downloading archive {} to {}",
+            asset.browser_download_url,
+            path.to_string_lossy()
+        );
+        // The archive is downloaded to a temporary file that is only moved to the path once it is
+        // verified, so that an unverified archive is never left at the path
+        let temp_path = temporary_path(path);
+        let result = async {
+            download_to_file(
+                &client,
+                &asset.browser_download_url,
+                &Self::headers(&asset.browser_download_url)?,
+                u64::try_from(asset.size).ok(),
+                &scoped::download_options(),
+                &temp_path,
+            )
+            .await?;
+            let bytes_total = temp_path.metadata()?.len();
+            debug!(
+                "Archive {} downloaded: {bytes_total}",
+                asset.browser_download_url,
+            );
+            let current_span = tracing::Span::current();
+            current_span.record("version", version.to_string());
+            current_span.record("bytes_total", bytes_total);
+
+            if let Some(asset_hash) = &asset_hash {
+                let archive_hash =
+                    self.hash_file(&asset, asset_hash, asset_hasher_fn, &temp_path)?;
+                let hash =
+                    Self::get_asset_hash(&client, &asset, asset_hash, archive_hash.len()).await?;
+                if archive_hash != hash {
+                    return Err(ArchiveHashMismatch { archive_hash, hash });
+                }
+            }
+            Ok(())
+        }
+        .await;
+        if let Err(error) = result {
+            let _ = remove_file(&temp_path);
+            return Err(error);
+        }
+        rename(&temp_path, path)?;
+
+        Ok(version)
+    }
+
+    #[cfg(feature = "minisign")]
+    #[instrument(level = "debug")]
+    async fn get_signature(&self, version: &Version) -> Result<Option<String>> {
+        // The URL of the signature is never taken from cached metadata
+        let version_req = VersionReq::parse(&format!("={version}"))?;
+        let release = self
+            .get_release(&version_req, Channel::Stable, Metadata::Fresh)
+            .await?;
+        let (asset, _, _) = self.get_asset(version, &release)?;
+        let signature_name = format!("{}.{}", asset.name, minisign::SIGNATURE_EXTENSION);
+        let Some(signature_asset) = release
+            .assets
+            .iter()
+            .find(|asset| asset.name == signature_name)
+        else {
+            return Ok(None);
+        };
+
+        let client = reqwest_client()?;
+        debug!(
+            "Downloading archive signature {}",
+            signature_asset.browser_download_url
+        );
+        let request = client
+            .get(&signature_asset.browser_download_url)
+            .headers(Self::headers(&signature_asset.browser_download_url)?);
+        let response = request.send().await?.error_for_status()?;
+        Ok(Some(response.text().await?))
+    }
 }
 
-/// Creates a new reqwest client with middleware for tracing, and retrying transient errors.
-fn reqwest_client() -> ClientWithMiddleware {
-    let retry_policy = ExponentialBackoff::builder().build_with_max_retries(3);
-    ClientBuilder::new(reqwest::Client::new())
+/// Creates a new reqwest client with middleware for tracing, and retrying transient errors and
+/// responses with the retry status codes of the scoped
+/// [retry policy](crate::ScopedOptions::retry_policy). Requests are made through the scoped
+/// [proxy options](crate::ScopedOptions::proxy).
+///
+/// # Errors
+/// * If the client cannot be created.
+fn reqwest_client() -> Result<ClientWithMiddleware> {
+    let retry_policy = scoped::retry_policy();
+    let retry_strategy = RetryStatusStrategy::new(&retry_policy);
+    let client = ClientBuilder::new(http_client()?)
         .with(TracingMiddleware::default())
-        .with(RetryTransientMiddleware::new_with_policy(retry_policy))
-        .build()
+        .with(RetryTransientMiddleware::new_with_policy_and_strategy(
+            retry_policy,
+            retry_strategy,
+        ))
+        .build();
+    Ok(client)
 }
 
 #[cfg(test)]
@@ -330,6 +614,17 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_get_version_from_tag_name_prerelease() -> Result<()> {
+        let versions = vec!["18beta1", "v18beta1", "18.0.0-beta.1"];
+        for version in versions {
+            let version = GitHub::get_version_from_tag_name(version)?;
+            assert_eq!(Version::parse("18.0.0-beta.1")?, version);
+        }
+
+        Ok(())
+    }
+
     #[test]
     fn test_get_version_from_tag_name_error() {
         let error = GitHub::get_version_from_tag_name("foo").unwrap_err();
@@ -339,6 +634,17 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn test_list_versions() -> Result<()> {
+        let github = GitHub::new(URL)?;
+        let versions = github.list_versions().await?;
+        assert!(versions.contains(&Version::new(16, 4, 0)));
+        assert!(versions
+            .windows(2)
+            .all(|versions| versions[0] > versions[1]));
+        Ok(())
+    }
+
     //
     // get_version tests
     //
@@ -388,6 +694,18 @@ mod tests {
         Ok(())
     }
 
+    #[tokio::test]
+    async fn test_get_archive_to_file() -> Result<()> {
+        let github = GitHub::new(URL)?;
+        let version_req = VersionReq::parse("=16.4.0")?;
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("postgresql.tar.gz");
+        let version = github.get_archive_to_file(&version_req, &path).await?;
+        assert_eq!(Version::new(16, 4, 0), version);
+        assert!(path.metadata()?.len() > 0);
+        Ok(())
+    }
+
     //
     // Plugin Support
     //
@@ -0,0 +1,13 @@
+pub(crate) mod models;
+pub mod repository;
+
+/// Prefix of the URLs of [GitLab repositories](repository::GitLab) hosted on private instances;
+/// e.g. `gitlab+https://gitlab.example.com/group/project`
+pub const URL: &str = "gitlab+";
+/// URL of projects hosted on [gitlab.com](https://gitlab.com)
+pub const GITLAB_COM_URL: &str = "https://gitlab.com/";
+
+/// Returns true if the `url` is the URL of a [GitLab repository](repository::GitLab).
+pub(crate) fn supports(url: &str) -> bool {
+    url.starts_with(URL) || url.starts_with(GITLAB_COM_URL)
+}
@@ -0,0 +1,37 @@
+//! Structs for GitLab API responses
+use serde::{Deserialize, Serialize};
+
+/// Represents a GitLab release
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(crate) struct Release {
+    pub tag_name: String,
+    pub name: Option<String>,
+    #[serde(default, rename = "upcoming_release")]
+    pub upcoming: bool,
+    pub assets: Assets,
+}
+
+/// Represents the assets of a GitLab release
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(crate) struct Assets {
+    #[serde(default)]
+    pub links: Vec<Link>,
+}
+
+/// Represents a GitLab release asset link
+#[derive(Clone, Debug, Deserialize, Serialize)]
+pub(crate) struct Link {
+    pub id: i64,
+    pub name: String,
+    pub url: String,
+    pub direct_asset_url: Option<String>,
+    #[serde(rename = "link_type")]
+    pub kind: Option<String>,
+}
+
+impl Link {
+    /// Gets the URL to download the asset from
+    pub fn download_url(&self) -> &str {
+        self.direct_asset_url.as_deref().unwrap_or(&self.url)
+    }
+}
@@ -0,0 +1,648 @@
+use crate::credentials::apply_credentials;
+use crate::download::{download, download_to_file};
+#[cfg(feature = "minisign")]
+use crate::hasher::minisign;
+use crate::hasher::registry::HasherFn;
+use crate::proxy::{build_http_client, http_client, http_client_builder};
+use crate::repository::gitlab::models::{Link, Release};
+use crate::repository::gitlab::URL;
+use crate::repository::model::Repository;
+use crate::repository::Archive;
+use crate::retry::RetryStatusStrategy;
+use crate::version::parse_version;
+use crate::Error::{
+    ArchiveHashMismatch, AssetHashNotFound, AssetNotFound, ParseError, RepositoryFailure,
+    VersionNotFound,
+};
+use crate::{hasher, matcher, scoped, Channel, Result};
+use async_trait::async_trait;
+use regex_lite::Regex;
+use reqwest::header::{HeaderMap, HeaderValue, LOCATION};
+use reqwest::redirect::Policy;
+use reqwest::{Response, StatusCode};
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use reqwest_retry::RetryTransientMiddleware;
+use reqwest_tracing::TracingMiddleware;
+use semver::{Version, VersionReq};
+use std::env;
+use std::fs::{remove_file, File};
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::LazyLock;
+use tracing::field::Empty;
+use tracing::{debug, instrument, warn};
+use url::{form_urlencoded, Origin, Url};
+
+const RATE_LIMIT_REMAINING_HEADER: &str = "RateLimit-Remaining";
+const RATE_LIMIT_RESET_HEADER: &str = "RateLimit-Reset";
+const RETRY_AFTER_HEADER: &str = "Retry-After";
+const GITLAB_TOKEN_HEADER: &str = "PRIVATE-TOKEN";
+const MAX_REDIRECTS: usize = 10;
+
+static GITLAB_TOKEN: LazyLock<Option<String>> = LazyLock::new(|| match env::var("GITLAB_TOKEN") {
+    Ok(token) => {
+        debug!("GITLAB_TOKEN environment variable found");
+        Some(token)
+    }
+    Err(_) => None,
+});
+
+static USER_AGENT: LazyLock<String> = LazyLock::new(|| {
+    format!(
+        "{PACKAGE}/{VERSION}",
+        PACKAGE = env!("CARGO_PKG_NAME"),
+        VERSION = env!("CARGO_PKG_VERSION")
+    )
+});
+
+/// Asset link of a release, with the hash link, the extension of the hash and the hasher of the
+/// asset if the release has one
+type ReleaseAsset = (Link, Option<(Link, String)>, Option<HasherFn>);
+
+/// GitLab repository.
+///
+/// This repository is used to interact with GitLab releases. The configuration url should be in
+/// the format <https://gitlab.com/group/project>, or prefixed with `gitlab+` for projects on other
+/// GitLab instances (e.g. `gitlab+https://gitlab.example.com/group/subgroup/project`). The
+/// `GITLAB_TOKEN` environment variable is used to authenticate with private projects; the token
+/// is only sent to the GitLab instance of the URL, and not to the hosts that assets redirect to.
+#[derive(Debug)]
+pub struct GitLab {
+    url: String,
+    origin: Origin,
+    releases_url: String,
+}
+
+impl GitLab {
+    /// Creates a new GitLab repository from the specified URL in the format
+    /// <https://gitlab.com/group/project>
+    ///
+    /// # Errors
+    /// * If the URL is invalid.
+    #[expect(clippy::new_ret_no_self)]
+    pub fn new(url: &str) -> Result<Box<dyn Repository>> {
+        Ok(Box::new(Self::parse(url)?))
+    }
+
+    /// Parses the GitLab instance and releases URL of the project from the URL.
+    ///
+    /// # Errors
+    /// * If the URL is invalid.
+    fn parse(url: &str) -> Result<Self> {
+        let parsed_url = Url::parse(url.strip_prefix(URL).unwrap_or(url))?;
+        let project = parsed_url
+            .path()
+            .trim_matches('/')
+            .trim_end_matches(".git")
+            .to_string();
+        if project.split('/').count() < 2 {
+            return Err(RepositoryFailure(format!(
+                "No group and project in URL {url}"
+            )));
+        }
+        let Some(host) = parsed_url.host_str() else {
+            return Err(RepositoryFailure(format!("No host in URL {url}")));
+        };
+        let port = parsed_url
+            .port()
+            .map(|port| format!(":{port}"))
+            .unwrap_or_default();
+        let project_id = form_urlencoded::byte_serialize(project.as_bytes()).collect::<String>();
+        let releases_url = format!(
+            "{scheme}://{host}{port}/api/v4/projects/{project_id}/releases",
+            scheme = parsed_url.scheme()
+        );
+
+        Ok(Self {
+            url: url.to_string(),
+            origin: parsed_url.origin(),
+            releases_url,
+        })
+    }
+
+    /// Gets the version from the specified tag name.
+    ///
+    /// # Errors
+    /// * If the version cannot be parsed.
+    fn get_version_from_tag_name(tag_name: &str) -> Result<Version> {
+        // Trim and prefix characters from the tag name (e.g., "v16.4.0" -> "16.4.0").
+        let tag_name = tag_name.trim_start_matches(|c: char| !c.is_numeric());
+        match parse_version(tag_name) {
+            Ok(version) => Ok(version),
+            Err(error) => {
+                warn!("Failed to parse version {tag_name}");
+                Err(error)
+            }
+        }
+    }
+
+    /// Checks the rate limit headers of a GitLab API `response`. A response that is rate limited
+    /// after the retries of the scoped [retry policy](crate::ScopedOptions::retry_policy) is
+    /// returned as an error stating when the rate limit resets; a warning is logged when the rate
+    /// limit is exhausted so that the next request is likely to be rate limited.
+    ///
+    /// # Errors
+    /// * If the response is rate limited.
+    fn check_rate_limit(response: &Response) -> Result<()> {
+        let header = |name: &str| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+                .map(ToString::to_string)
+        };
+        if response.status() == StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = header(RETRY_AFTER_HEADER).unwrap_or_else(|| "unknown".to_string());
+            return Err(RepositoryFailure(format!(
+                "GitLab rate limit exceeded for {}; retry after {retry_after} seconds, or set \
+                the GITLAB_TOKEN environment variable to authenticate",
+                response.url()
+            )));
+        }
+        if header(RATE_LIMIT_REMAINING_HEADER).as_deref() == Some("0") {
+            let reset = header(RATE_LIMIT_RESET_HEADER).unwrap_or_else(|| "unknown".to_string());
+            warn!("GitLab rate limit exhausted; the rate limit resets at {reset}");
+        }
+        Ok(())
+    }
+
+    /// Gets all the releases of the repository; upcoming releases are ignored.
+    ///
+    /// # Errors
+    /// * If the releases cannot be retrieved.
+    #[instrument(level = "debug")]
+    async fn get_releases(&self) -> Result<Vec<Release>> {
+        let client = reqwest_client()?;
+        let mut releases = Vec::new();
+        let mut page = 1;
+
+        loop {
+            let request = client
+                .get(&self.releases_url)
+                .headers(self.headers(&self.releases_url)?)
+                .query(&[("page", page.to_string().as_str()), ("per_page", "100")]);
+            let response = request.send().await?;
+            Self::check_rate_limit(&response)?;
+            let response = response.error_for_status()?;
+            let response_releases = response.json::<Vec<Release>>().await?;
+            if response_releases.is_empty() {
+                break;
+            }
+            releases.extend(
+                response_releases
+                    .into_iter()
+                    .filter(|release| !release.upcoming),
+            );
+            page += 1;
+        }
+
+        Ok(releases)
+    }
+
+    /// Gets the release for the specified [version requirement](VersionReq) in the release
+    /// [channel](Channel). If a release for the [version requirement](VersionReq) is not found,
+    /// then an error is returned. Upcoming releases are ignored.
+    ///
+    /// # Errors
+    /// * If the release is not found.
+    #[instrument(level = "debug")]
+    async fn get_release(&self, version_req: &VersionReq, channel: Channel) -> Result<Release> {
+        debug!("Attempting to locate release for version requirement {version_req}");
+        let mut result: Option<(Release, Version)> = None;
+
+        for release in self.get_releases().await? {
+            let tag_name = release.tag_name.clone();
+            let Ok(release_version) = Self::get_version_from_tag_name(tag_name.as_str()) else {
+                warn!("Failed to parse release version {tag_name}");
+                continue;
+            };
+
+            if channel.matches(version_req, &release_version) {
+                if let Some((_, result_version)) = &result {
+                    if release_version <= *result_version {
+                        continue;
+                    }
+                }
+                result = Some((release, release_version));
+            }
+        }
+
+        match result {
+            Some((release, version)) => {
+                debug!("Version {version} found for version requirement {version_req}");
+                Ok(release)
+            }
+            None => Err(VersionNotFound(version_req.to_string())),
+        }
+    }
+
+    /// Gets the asset link for the specified release that passes the supplied matcher, and the
+    /// hash link and hasher for the asset if the release has one. If an asset that passes the
+    /// matcher is not found, then an [AssetNotFound] error is returned.
+    ///
+    /// # Errors
+    /// * If the asset is not found.
+    #[instrument(level = "debug", skip(version, release))]
+    fn get_asset(&self, version: &Version, release: &Release) -> Result<ReleaseAsset> {
+        let matcher = matcher::registry::get(&self.url)?;
+        let target = scoped::target();
+        let mut release_asset: Option<Link> = None;
+        for link in &release.assets.links {
+            if matcher(&self.url, link.name.as_str(), version, &target)? {
+                release_asset = Some(link.clone());
+                break;
+            }
+        }
+
+        let Some(asset) = release_asset else {
+            return Err(AssetNotFound);
+        };
+
+        // Attempt to find the asset hash for the asset.
+        for link in &release.assets.links {
+            let Some(extension) = link.name.strip_prefix(format!("{}.", asset.name).as_str())
+            else {
+                continue;
+            };
+            if let Ok(hasher_fn) = hasher::registry::get(&self.url, &extension.to_string()) {
+                let asset_hash = (link.clone(), extension.to_string());
+                return Ok((asset, Some(asset_hash), Some(hasher_fn)));
+            }
+        }
+
+        Ok((asset, None, None))
+    }
+
+    /// Downloads the hash of the `asset` from the `asset_hash` link, and returns the first
+    /// hexadecimal hash of `hash_len` characters that it contains.
+    ///
+    /// # Errors
+    /// * If the hash cannot be downloaded.
+    /// * If the hash is not found.
+    async fn get_asset_hash(
+        &self,
+        client: &ClientWithMiddleware,
+        asset: &Link,
+        asset_hash: &Link,
+        hash_len: usize,
+    ) -> Result<String> {
+        let hash_url = self.resolve_download_url(asset_hash.download_url()).await?;
+        debug!("Downloading archive hash {hash_url}");
+        let request = client.get(&hash_url).headers(self.headers(&hash_url)?);
+        let response = request.send().await?;
+        Self::check_rate_limit(&response)?;
+        let text = response.error_for_status()?.text().await?;
+        let re = Regex::new(&format!(r"[0-9a-f]{{{hash_len}}}"))?;
+        let hash = match re.find(&text) {
+            Some(hash) => hash.as_str().to_string(),
+            None => return Err(AssetHashNotFound(asset.name.clone())),
+        };
+        debug!("Archive hash {hash_url} downloaded: {}", text.len());
+        Ok(hash)
+    }
+
+    /// Returns the headers for the GitLab request to the `url`.
+    ///
+    /// # Errors
+    /// * If the `GITLAB_TOKEN` is not a valid header value.
+    /// * If the credentials for the request cannot be retrieved.
+    fn headers(&self, url: &str) -> Result<HeaderMap> {
+        self.headers_with_token(url, GITLAB_TOKEN.as_deref())
+    }
+
+    /// Returns the headers for the request to the `url`, with the `token` if the URL is on the
+    /// GitLab instance of the repository.
+    ///
+    /// # Errors
+    /// * If the `token` is not a valid header value.
+    /// * If the credentials for the request cannot be retrieved.
+    fn headers_with_token(&self, url: &str, token: Option<&str>) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        headers.append("User-Agent", USER_AGENT.parse().unwrap());
+        if let Some(token) = token {
+            if Url::parse(url)?.origin() == self.origin {
+                let token = HeaderValue::from_str(token)
+                    .map_err(|error| ParseError(format!("invalid GITLAB_TOKEN: {error}")))?;
+                headers.append(GITLAB_TOKEN_HEADER, token);
+            }
+        }
+        apply_credentials(url, &mut headers)?;
+        Ok(headers)
+    }
+
+    /// Follows the redirects of the download `url` on the GitLab instance, and returns the URL
+    /// that the asset is downloaded from. The redirects are followed with a client that does not
+    /// follow redirects itself, so that the token is not sent to the host of an asset link that
+    /// redirects to another origin (e.g. an object storage); the asset is downloaded from such a
+    /// host without the token.
+    ///
+    /// # Errors
+    /// * If a redirect cannot be requested.
+    async fn resolve_download_url(&self, url: &str) -> Result<String> {
+        if GITLAB_TOKEN.is_none() {
+            return Ok(url.to_string());
+        }
+        let client = build_http_client(http_client_builder()?.redirect(Policy::none()))?;
+        let mut url = Url::parse(url)?;
+        for _ in 0..MAX_REDIRECTS {
+            if url.origin() != self.origin {
+                break;
+            }
+            let response = client
+                .head(url.as_str())
+                .headers(self.headers(url.as_str())?)
+                .send()
+                .await?;
+            if !response.status().is_redirection() {
+                break;
+            }
+            let Some(location) = response
+                .headers()
+                .get(LOCATION)
+                .and_then(|location| location.to_str().ok())
+            else {
+                break;
+            };
+            url = url.join(location)?;
+        }
+        Ok(url.to_string())
+    }
+}
+
+#[async_trait]
+impl Repository for GitLab {
+    #[instrument(level = "debug")]
+    fn name(&self) -> &str {
+        "GitLab"
+    }
+
+    #[instrument(level = "debug")]
+    async fn list_versions(&self) -> Result<Vec<Version>> {
+        let mut versions = self
+            .get_releases()
+            .await?
+            .iter()
+            .filter_map(|release| Self::get_version_from_tag_name(&release.tag_name).ok())
+            .collect::<Vec<_>>();
+        versions.sort_by(|a, b| b.cmp(a));
+        versions.dedup();
+        Ok(versions)
+    }
+
+    #[instrument(level = "debug")]
+    async fn get_version(&self, version_req: &VersionReq) -> Result<Version> {
+        self.get_version_in_channel(version_req, Channel::Stable)
+            .await
+    }
+
+    #[instrument(level = "debug")]
+    async fn get_archive(&self, version_req: &VersionReq) -> Result<Archive> {
+        self.get_archive_in_channel(version_req, Channel::Stable)
+            .await
+    }
+
+    #[instrument(level = "debug")]
+    async fn get_version_in_channel(
+        &self,
+        version_req: &VersionReq,
+        channel: Channel,
+    ) -> Result<Version> {
+        let release = self.get_release(version_req, channel).await?;
+        let version = Self::get_version_from_tag_name(release.tag_name.as_str())?;
+        Ok(version)
+    }
+
+    #[instrument(fields(operation = "download", version = Empty, bytes_total = Empty))]
+    async fn get_archive_in_channel(
+        &self,
+        version_req: &VersionReq,
+        channel: Channel,
+    ) -> Result<Archive> {
+        let release = self.get_release(version_req, channel).await?;
+        let version = Self::get_version_from_tag_name(release.tag_name.as_str())?;
+        let (asset, asset_hash, asset_hasher_fn) = self.get_asset(&version, &release)?;
+        let name = asset.name.clone();
+
+        let client = reqwest_client()?;
+        let download_url = self.resolve_download_url(asset.download_url()).await?;
+        debug!("Downloading archive {download_url}");
+        let bytes = download(
+            &client,
+            &download_url,
+            &self.headers(&download_url)?,
+            None,
+            &scoped::download_options(),
+        )
+        .await?;
+        debug!("Archive {download_url} downloaded: {}", bytes.len());
+        let current_span = tracing::Span::current();
+        current_span.record("version", version.to_string());
+        current_span.record("bytes_total", bytes.len());
+
+        if let (Some((asset_hash, _)), Some(hasher_fn)) = (asset_hash, asset_hasher_fn) {
+            let archive_hash = hasher_fn(&bytes)?;
+            let hash = self
+                .get_asset_hash(&client, &asset, &asset_hash, archive_hash.len())
+                .await?;
+            if archive_hash != hash {
+                return Err(ArchiveHashMismatch { archive_hash, hash });
+            }
+        }
+
+        let archive = Archive::new(name, version, bytes);
+        Ok(archive)
+    }
+
+    #[instrument(fields(operation = "download", version = Empty, bytes_total = Empty))]
+    async fn get_archive_to_file(&self, version_req: &VersionReq, path: &Path) -> Result<Version> {
+        let release = self.get_release(version_req, Channel::Stable).await?;
+        let version = Self::get_version_from_tag_name(release.tag_name.as_str())?;
+        let (asset, asset_hash, asset_hasher_fn) = self.get_asset(&version, &release)?;
+
+        let client = reqwest_client()?;
+        let download_url = self.resolve_download_url(asset.download_url()).await?;
+        debug!(
+            "Downloading archive {download_url} to {}",
+            path.to_string_lossy()
+        );
+        download_to_file(
+            &client,
+            &download_url,
+            &self.headers(&download_url)?,
+            None,
+            &scoped::download_options(),
+            path,
+        )
+        .await?;
+        let bytes_total = path.metadata()?.len();
+        debug!("Archive {download_url} downloaded: {bytes_total}");
+        let current_span = tracing::Span::current();
+        current_span.record("version", version.to_string());
+        current_span.record("bytes_total", bytes_total);
+
+        if let (Some((asset_hash, extension)), Some(hasher_fn)) = (asset_hash, asset_hasher_fn) {
+            let archive_hash = match hasher::registry::get_reader(&self.url, &extension) {
+                Ok(reader_hasher_fn) => reader_hasher_fn(&mut BufReader::new(File::open(path)?))?,
+                Err(_) => hasher_fn(&std::fs::read(path)?)?,
+            };
+            let hash = self
+                .get_asset_hash(&client, &asset, &asset_hash, archive_hash.len())
+                .await?;
+            if archive_hash != hash {
+                remove_file(path)?;
+                return Err(ArchiveHashMismatch { archive_hash, hash });
+            }
+        }
+
+        Ok(version)
+    }
+
+    #[cfg(feature = "minisign")]
+    #[instrument(level = "debug")]
+    async fn get_signature(&self, version: &Version) -> Result<Option<String>> {
+        let version_req = VersionReq::parse(&format!("={version}"))?;
+        let release = self.get_release(&version_req, Channel::Stable).await?;
+        let (asset, _, _) = self.get_asset(version, &release)?;
+        let signature_name = format!("{}.{}", asset.name, minisign::SIGNATURE_EXTENSION);
+        let Some(signature_link) = release
+            .assets
+            .links
+            .iter()
+            .find(|link| link.name == signature_name)
+        else {
+            return Ok(None);
+        };
+
+        let client = reqwest_client()?;
+        let signature_url = self
+            .resolve_download_url(signature_link.download_url())
+            .await?;
+        debug!("Downloading archive signature {signature_url}");
+        let request = client
+            .get(&signature_url)
+            .headers(self.headers(&signature_url)?);
+        let response = request.send().await?;
+        Self::check_rate_limit(&response)?;
+        Ok(Some(response.error_for_status()?.text().await?))
+    }
+}
+
+/// Creates a new reqwest client with middleware for tracing, and retrying transient errors and
+/// responses with the retry status codes of the scoped
+/// [retry policy](crate::ScopedOptions::retry_policy). Requests are made through the scoped
+/// [proxy options](crate::ScopedOptions::proxy).
+///
+/// # Errors
+/// * If the client cannot be created.
+fn reqwest_client() -> Result<ClientWithMiddleware> {
+    let retry_policy = scoped::retry_policy();
+    let retry_strategy = RetryStatusStrategy::new(&retry_policy);
+    let client = ClientBuilder::new(http_client()?)
+        .with(TracingMiddleware::default())
+        .with(RetryTransientMiddleware::new_with_policy_and_strategy(
+            retry_policy,
+            retry_strategy,
+        ))
+        .build();
+    Ok(client)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_name() -> Result<()> {
+        let gitlab = GitLab::new("https://gitlab.com/group/project")?;
+        assert_eq!("GitLab", gitlab.name());
+        Ok(())
+    }
+
+    #[test]
+    fn test_new() -> Result<()> {
+        let gitlab = GitLab::new("https://gitlab.com/group/project")?;
+        assert!(format!("{gitlab:?}")
+            .contains("https://gitlab.com/api/v4/projects/group%2Fproject/releases"));
+        let gitlab = GitLab::new("gitlab+http://gitlab.example.com:8080/group/subgroup/project")?;
+        assert!(format!("{gitlab:?}").contains(
+            "http://gitlab.example.com:8080/api/v4/projects/group%2Fsubgroup%2Fproject/releases"
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_headers_with_token() -> Result<()> {
+        let gitlab = GitLab::parse("gitlab+https://gitlab.example.com/group/project")?;
+        let headers = gitlab.headers_with_token(
+            "https://gitlab.example.com/group/project/-/package_files/1/download",
+            Some("token"),
+        )?;
+        assert_eq!(
+            Some("token"),
+            headers
+                .get(GITLAB_TOKEN_HEADER)
+                .and_then(|token| token.to_str().ok())
+        );
+        for url in [
+            "https://storage.example.com/archive.tar.gz",
+            "http://gitlab.example.com/group/project",
+            "https://gitlab.example.com:8443/group/project",
+        ] {
+            let headers = gitlab.headers_with_token(url, Some("token"))?;
+            assert!(headers.get(GITLAB_TOKEN_HEADER).is_none());
+        }
+        assert!(gitlab
+            .headers_with_token(&gitlab.releases_url, Some("invalid\ntoken"))
+            .is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_error() {
+        assert!(GitLab::new("https://gitlab.com/project").is_err());
+        assert!(GitLab::new("gitlab+project").is_err());
+    }
+
+    #[test]
+    fn test_get_version_from_tag_name() -> Result<()> {
+        assert_eq!(
+            Version::new(16, 4, 0),
+            GitLab::get_version_from_tag_name("v16.4.0")?
+        );
+        assert_eq!(
+            Version::new(16, 4, 0),
+            GitLab::get_version_from_tag_name("16.4.0")?
+        );
+        assert!(GitLab::get_version_from_tag_name("foo").is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_release_models() -> Result<()> {
+        let json = r#"[{
+            "tag_name": "v16.4.0",
+            "name": "PostgreSQL 16.4.0",
+            "upcoming_release": false,
+            "assets": {
+                "count": 2,
+                "sources": [],
+                "links": [{
+                    "id": 1,
+                    "name": "postgresql-16.4.0-x86_64-unknown-linux-gnu.tar.gz",
+                    "url": "https://gitlab.com/group/project/-/releases/v16.4.0/downloads/archive",
+                    "direct_asset_url": "https://gitlab.com/group/project/-/package_files/1/download",
+                    "link_type": "package"
+                }]
+            }
+        }]"#;
+        let releases: Vec<Release> =
+            serde_json::from_str(json).map_err(|error| RepositoryFailure(error.to_string()))?;
+        assert_eq!(1, releases.len());
+        let link = &releases[0].assets.links[0];
+        assert_eq!(
+            "https://gitlab.com/group/project/-/package_files/1/download",
+            link.download_url()
+        );
+        Ok(())
+    }
+}
@@ -0,0 +1,5 @@
+pub mod repository;
+
+/// Prefix of the URLs of [HTTP index repositories](repository::HttpIndex); e.g.
+/// `index+https://mirror.example.com/postgresql`
+pub const URL: &str = "index+";
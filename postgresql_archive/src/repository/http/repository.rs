@@ -0,0 +1,662 @@
+use crate::credentials::apply_credentials;
+use crate::download::{download, download_to_file, temporary_path};
+#[cfg(feature = "minisign")]
+use crate::hasher::minisign;
+use crate::hasher::sha2_256;
+use crate::proxy::http_client;
+use crate::repository::http::URL;
+use crate::repository::model::Repository;
+use crate::repository::Archive;
+use crate::retry::RetryStatusStrategy;
+use crate::version::parse_version;
+use crate::Error::{ArchiveHashMismatch, AssetHashNotFound, RepositoryFailure, VersionNotFound};
+use crate::{scoped, Channel, Result};
+use async_trait::async_trait;
+use regex_lite::Regex;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
+use reqwest_retry::RetryTransientMiddleware;
+use reqwest_tracing::TracingMiddleware;
+use semver::{Version, VersionReq};
+use std::fs::{remove_file, rename, File};
+use std::io::BufReader;
+use std::path::Path;
+use std::sync::LazyLock;
+use tracing::field::Empty;
+use tracing::{debug, instrument};
+use url::Url;
+
+/// Default template of the archive paths, relative to the base URL of the repository
+pub const DEFAULT_TEMPLATE: &str = "postgresql-{version}-{target}.tar.gz";
+
+/// Extension of the files next to the archives that contain their SHA-256 hashes
+pub const HASH_EXTENSION: &str = "sha256";
+
+static HASH: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"[0-9A-Fa-f]{64}").expect("regex"));
+
+static HREF: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r#"(?i)href\s*=\s*["']([^"']+)["']"#).expect("regex"));
+
+static USER_AGENT: LazyLock<String> = LazyLock::new(|| {
+    format!(
+        "{PACKAGE}/{VERSION}",
+        PACKAGE = env!("CARGO_PKG_NAME"),
+        VERSION = env!("CARGO_PKG_VERSION")
+    )
+});
+
+/// HTTP index repository.
+///
+/// This repository is used to interact with mirrors hosted on a static file server (e.g. Nginx
+/// or an Artifactory generic repository). The URL is the base URL of the mirror prefixed with
+/// `index+` (e.g. `index+https://mirror.example.com/postgresql`), and supports the following
+/// query parameters:
+///
+/// * `index`: path of the index relative to the base URL; defaults to the base URL itself. The
+///   index is either a JSON array of versions or archive names (or an object with such an array
+///   in a `versions` field), or an HTML page such as a directory listing whose links are versions
+///   or archive names.
+/// * `template`: path of the archives relative to the base URL, in which `{version}` is replaced
+///   by the version and `{target}` by the target triple; defaults to [`DEFAULT_TEMPLATE`].
+///
+/// The target triple is the fragment of the URL, or the scoped
+/// [target](crate::ScopedOptions::target) of the call.
+///
+/// Each archive must have a file next to it with the [`HASH_EXTENSION`] appended to its name
+/// (e.g. `postgresql-16.4.0-x86_64-unknown-linux-gnu.tar.gz.sha256`) that contains the SHA-256
+/// hash of the archive, such as the output of `sha256sum`; archives without a hash file, or that
+/// do not match the hash, are rejected.
+#[derive(Debug)]
+pub struct HttpIndex {
+    base_url: Url,
+    index_url: Url,
+    template: String,
+    target: String,
+}
+
+impl HttpIndex {
+    /// Creates a new HTTP index repository from the specified URL in the format
+    /// `index+https://mirror.example.com/postgresql?index=index.json`
+    ///
+    /// # Errors
+    /// * If the URL is invalid.
+    #[expect(clippy::new_ret_no_self)]
+    pub fn new(url: &str) -> Result<Box<dyn Repository>> {
+        Ok(Box::new(Self::parse(url)?))
+    }
+
+    /// Parses the base URL, index URL, template and target of the repository from the URL.
+    ///
+    /// # Errors
+    /// * If the URL is invalid.
+    fn parse(url: &str) -> Result<Self> {
+        let Some(base_url) = url.strip_prefix(URL) else {
+            return Err(RepositoryFailure(format!("{url} is not an index URL")));
+        };
+        let mut base_url = Url::parse(base_url)?;
+        let mut index = None;
+        let mut template = DEFAULT_TEMPLATE.to_string();
+        for (name, value) in base_url.query_pairs() {
+            match name.as_ref() {
+                "index" => index = Some(value.to_string()),
+                "template" => template = value.to_string(),
+                _ => {}
+            }
+        }
+        let target = base_url
+            .fragment()
+            .map_or_else(scoped::target, ToString::to_string);
+        base_url.set_query(None);
+        base_url.set_fragment(None);
+        if !base_url.path().ends_with('/') {
+            base_url.set_path(&format!("{}/", base_url.path()));
+        }
+        let index_url = match index {
+            Some(index) => base_url.join(&index)?,
+            None => base_url.clone(),
+        };
+
+        Ok(Self {
+            base_url,
+            index_url,
+            template,
+            target,
+        })
+    }
+
+    /// Gets the regular expression that matches archive paths of the template, capturing the
+    /// version.
+    fn template_regex(&self) -> Result<Regex> {
+        let pattern = regex_lite::escape(&self.template)
+            .replace(r"\{version\}", "([0-9A-Za-z.+-]+?)")
+            .replace(r"\{target\}", &regex_lite::escape(&self.target));
+        Ok(Regex::new(&format!("(?:^|/){pattern}$"))?)
+    }
+
+    /// Gets the entries of the index `text`; the strings of a JSON index, or the links of an HTML
+    /// index.
+    fn parse_index(text: &str) -> Vec<String> {
+        if let Ok(value) = serde_json::from_str::<serde_json::Value>(text) {
+            let values = match value {
+                serde_json::Value::Object(mut object) => object
+                    .remove("versions")
+                    .and_then(|versions| versions.as_array().cloned())
+                    .unwrap_or_default(),
+                serde_json::Value::Array(values) => values,
+                _ => Vec::new(),
+            };
+            return values
+                .iter()
+                .filter_map(|value| value.as_str().map(ToString::to_string))
+                .collect();
+        }
+
+        HREF.captures_iter(text)
+            .map(|captures| captures[1].to_string())
+            .collect()
+    }
+
+    /// Gets the version of an index `entry`; either the version of an archive path that matches
+    /// the template, or the entry itself (e.g. a version directory of a directory listing).
+    fn get_entry_version(template_regex: &Regex, entry: &str) -> Option<Version> {
+        let entry = entry.trim_end_matches('/');
+        if let Some(captures) = template_regex.captures(entry) {
+            return parse_version(&captures[1]).ok();
+        }
+        let name = entry.rsplit('/').next().unwrap_or(entry);
+        parse_version(name.trim_start_matches('v')).ok()
+    }
+
+    /// Gets the versions of the entries of the index.
+    ///
+    /// # Errors
+    /// * If the index cannot be downloaded.
+    #[instrument(level = "debug")]
+    async fn get_index_versions(&self) -> Result<Vec<Version>> {
+        let client = reqwest_client()?;
+        let request = client
+            .get(self.index_url.as_str())
+            .headers(Self::headers(self.index_url.as_str())?);
+        let response = request.send().await?.error_for_status()?;
+        let text = response.text().await?;
+        let template_regex = self.template_regex()?;
+
+        Ok(Self::parse_index(&text)
+            .iter()
+            .filter_map(|entry| Self::get_entry_version(&template_regex, entry))
+            .collect())
+    }
+
+    /// Gets the latest version in the index that matches the specified version requirement in
+    /// the release [channel](Channel).
+    ///
+    /// # Errors
+    /// * If the index cannot be downloaded.
+    /// * If the version requirement does not match any versions.
+    #[instrument(level = "debug")]
+    async fn get_index_version(
+        &self,
+        version_req: &VersionReq,
+        channel: Channel,
+    ) -> Result<Version> {
+        debug!("Attempting to locate version for version requirement {version_req}");
+        let version = self
+            .get_index_versions()
+            .await?
+            .into_iter()
+            .filter(|version| channel.matches(version_req, version))
+            .max();
+        match version {
+            Some(version) => {
+                debug!("Version {version} found for version requirement {version_req}");
+                Ok(version)
+            }
+            None => Err(VersionNotFound(version_req.to_string())),
+        }
+    }
+
+    /// Gets the URL of the archive for the `version`.
+    ///
+    /// # Errors
+    /// * If the URL is invalid.
+    fn get_archive_url(&self, version: &Version) -> Result<Url> {
+        let path = self
+            .template
+            .replace("{version}", &version.to_string())
+            .replace("{target}", &self.target);
+        Ok(self.base_url.join(&path)?)
+    }
+
+    /// Downloads the hash file of the archive at the `archive_url`, and returns the SHA-256 hash
+    /// that it contains.
+    ///
+    /// # Errors
+    /// * If the hash file does not exist or cannot be downloaded.
+    /// * If the hash file does not contain a SHA-256 hash.
+    async fn get_archive_hash(client: &ClientWithMiddleware, archive_url: &Url) -> Result<String> {
+        let hash_url = format!("{archive_url}.{HASH_EXTENSION}");
+        debug!("Downloading archive hash {hash_url}");
+        let request = client.get(&hash_url).headers(Self::headers(&hash_url)?);
+        let response = request.send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Err(AssetHashNotFound(archive_url.to_string()));
+        }
+        let text = response.error_for_status()?.text().await?;
+        match HASH.find(&text) {
+            Some(hash) => Ok(hash.as_str().to_lowercase()),
+            None => Err(AssetHashNotFound(archive_url.to_string())),
+        }
+    }
+
+    /// Returns the headers for the HTTP request to the `url`.
+    ///
+    /// # Errors
+    /// * If the credentials for the request cannot be retrieved.
+    fn headers(url: &str) -> Result<HeaderMap> {
+        let mut headers = HeaderMap::new();
+        headers.append("User-Agent", USER_AGENT.parse().unwrap());
+        apply_credentials(url, &mut headers)?;
+        Ok(headers)
+    }
+}
+
+#[async_trait]
+impl Repository for HttpIndex {
+    #[instrument(level = "debug")]
+    fn name(&self) -> &str {
+        "HttpIndex"
+    }
+
+    #[instrument(level = "debug")]
+    async fn list_versions(&self) -> Result<Vec<Version>> {
+        let mut versions = self.get_index_versions().await?;
+        versions.sort_by(|a, b| b.cmp(a));
+        versions.dedup();
+        Ok(versions)
+    }
+
+    #[instrument(level = "debug")]
+    async fn get_version(&self, version_req: &VersionReq) -> Result<Version> {
+        self.get_version_in_channel(version_req, Channel::Stable)
+            .await
+    }
+
+    #[instrument(level = "debug")]
+    async fn get_archive(&self, version_req: &VersionReq) -> Result<Archive> {
+        self.get_archive_in_channel(version_req, Channel::Stable)
+            .await
+    }
+
+    #[instrument(level = "debug")]
+    async fn get_version_in_channel(
+        &self,
+        version_req: &VersionReq,
+        channel: Channel,
+    ) -> Result<Version> {
+        self.get_index_version(version_req, channel).await
+    }
+
+    #[instrument(fields(operation = "download", version = Empty, bytes_total = Empty))]
+    async fn get_archive_in_channel(
+        &self,
+        version_req: &VersionReq,
+        channel: Channel,
+    ) -> Result<Archive> {
+        let version = self.get_index_version(version_req, channel).await?;
+        let archive_url = self.get_archive_url(&version)?;
+        let name = archive_url
+            .path_segments()
+            .and_then(Iterator::last)
+            .unwrap_or_default()
+            .to_string();
+
+        let client = reqwest_client()?;
+        let hash = Self::get_archive_hash(&client, &archive_url).await?;
+        debug!("Downloading archive {archive_url}");
+        let bytes = download(
+            &client,
+            archive_url.as_str(),
+            &Self::headers(archive_url.as_str())?,
+            None,
+            &scoped::download_options(),
+        )
+        .await?;
+        debug!("Archive {archive_url} downloaded: {}", bytes.len());
+        let current_span = tracing::Span::current();
+        current_span.record("version", version.to_string());
+        current_span.record("bytes_total", bytes.len());
+
+        let archive_hash = sha2_256::hash(&bytes)?;
+        if archive_hash != hash {
+            return Err(ArchiveHashMismatch { archive_hash, hash });
+        }
+
+        Ok(Archive::new(name, version, bytes))
+    }
+
+    #[instrument(fields(operation = "download", version = Empty, bytes_total = Empty))]
+    async fn get_archive_to_file(&self, version_req: &VersionReq, path: &Path) -> Result<Version> {
+        let version = self.get_index_version(version_req, Channel::Stable).await?;
+        let archive_url = self.get_archive_url(&version)?;
+
+        let client = reqwest_client()?;
+        let hash = Self::get_archive_hash(&client, &archive_url).await?;
+        debug!(
+            "Downloading archive {archive_url} to {}",
+            path.to_string_lossy()
+        );
+        // The archive is downloaded to a temporary file that is only moved to the path once it is
+        // verified, so that an unverified archive is never left at the path
+        let temp_path = temporary_path(path);
+        let result = async {
+            download_to_file(
+                &client,
+                archive_url.as_str(),
+                &Self::headers(archive_url.as_str())?,
+                None,
+                &scoped::download_options(),
+                &temp_path,
+            )
+            .await?;
+            let bytes_total = temp_path.metadata()?.len();
+            debug!("Archive {archive_url} downloaded: {bytes_total}");
+            let current_span = tracing::Span::current();
+            current_span.record("version", version.to_string());
+            current_span.record("bytes_total", bytes_total);
+
+            let archive_hash = sha2_256::hash_reader(&mut BufReader::new(File::open(&temp_path)?))?;
+            if archive_hash != hash {
+                return Err(ArchiveHashMismatch { archive_hash, hash });
+            }
+            Ok(())
+        }
+        .await;
+        if let Err(error) = result {
+            let _ = remove_file(&temp_path);
+            return Err(error);
+        }
+        rename(&temp_path, path)?;
+
+        Ok(version)
+    }
+
+    #[cfg(feature = "minisign")]
+    #[instrument(level = "debug")]
+    async fn get_signature(&self, version: &Version) -> Result<Option<String>> {
+        let archive_url = self.get_archive_url(version)?;
+        let signature_url = format!("{archive_url}.{}", minisign::SIGNATURE_EXTENSION);
+        let client = reqwest_client()?;
+        debug!("Downloading archive signature {signature_url}");
+        let request = client
+            .get(&signature_url)
+            .headers(Self::headers(&signature_url)?);
+        let response = request.send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        Ok(Some(response.error_for_status()?.text().await?))
+    }
+}
+
+/// Creates a new reqwest client with middleware for tracing, and retrying transient errors and
+/// responses with the retry status codes of the scoped
+/// [retry policy](crate::ScopedOptions::retry_policy). Requests are made through the scoped
+/// [proxy options](crate::ScopedOptions::proxy).
+///
+/// # Errors
+/// * If the client cannot be created.
+fn reqwest_client() -> Result<ClientWithMiddleware> {
+    let retry_policy = scoped::retry_policy();
+    let retry_strategy = RetryStatusStrategy::new(&retry_policy);
+    let client = ClientBuilder::new(http_client()?)
+        .with(TracingMiddleware::default())
+        .with(RetryTransientMiddleware::new_with_policy_and_strategy(
+            retry_policy,
+            retry_strategy,
+        ))
+        .build();
+    Ok(client)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+    use std::io::{BufRead, BufReader, Write};
+    use std::net::TcpListener;
+
+    const TARGET: &str = "x86_64-unknown-linux-gnu";
+
+    /// Serves the `files` by path from a local HTTP server, and returns the base URL.
+    fn serve(files: HashMap<String, Vec<u8>>) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("listener");
+        let url = format!("http://{}", listener.local_addr().expect("addr"));
+        std::thread::spawn(move || {
+            for stream in listener.incoming() {
+                let Ok(mut stream) = stream else {
+                    continue;
+                };
+                let mut reader = BufReader::new(stream.try_clone().expect("stream"));
+                let mut request_line = String::new();
+                let _ = reader.read_line(&mut request_line);
+                let mut line = String::new();
+                while reader.read_line(&mut line).is_ok_and(|length| length > 2) {
+                    line.clear();
+                }
+                let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+                let (status, body) = match files.get(path) {
+                    Some(body) => ("200 OK", body.clone()),
+                    None => ("404 Not Found", Vec::new()),
+                };
+                let header = format!(
+                    "HTTP/1.1 {status}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                let _ = stream.write_all(header.as_bytes());
+                let _ = stream.write_all(&body);
+            }
+        });
+        url
+    }
+
+    #[test]
+    fn test_name() -> Result<()> {
+        let repository = HttpIndex::new("index+https://mirror.example.com/postgresql")?;
+        assert_eq!("HttpIndex", repository.name());
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse() -> Result<()> {
+        let repository = HttpIndex::parse(
+            "index+https://mirror.example.com/postgresql?index=index.json#aarch64-apple-darwin",
+        )?;
+        assert_eq!(
+            "https://mirror.example.com/postgresql/",
+            repository.base_url.as_str()
+        );
+        assert_eq!(
+            "https://mirror.example.com/postgresql/index.json",
+            repository.index_url.as_str()
+        );
+        assert_eq!(DEFAULT_TEMPLATE, repository.template);
+        assert_eq!("aarch64-apple-darwin", repository.target);
+        Ok(())
+    }
+
+    #[test]
+    fn test_new_error() {
+        assert!(HttpIndex::new("https://mirror.example.com").is_err());
+    }
+
+    #[test]
+    fn test_parse_json_index() {
+        assert_eq!(
+            vec!["16.4.0", "17.0.0"],
+            HttpIndex::parse_index(r#"["16.4.0", "17.0.0"]"#)
+        );
+        assert_eq!(
+            vec!["16.4.0"],
+            HttpIndex::parse_index(r#"{"versions": ["16.4.0"]}"#)
+        );
+    }
+
+    #[test]
+    fn test_parse_html_index() {
+        let html = r#"<html><body>
+            <a href="../">../</a>
+            <a href="16.4.0/">16.4.0/</a>
+            <A HREF='postgresql-17.0.0-x86_64-unknown-linux-gnu.tar.gz'>archive</A>
+            </body></html>"#;
+        assert_eq!(
+            vec![
+                "../",
+                "16.4.0/",
+                "postgresql-17.0.0-x86_64-unknown-linux-gnu.tar.gz"
+            ],
+            HttpIndex::parse_index(html)
+        );
+    }
+
+    #[test]
+    fn test_get_entry_version() -> Result<()> {
+        let repository = HttpIndex::parse(&format!(
+            "index+https://mirror.example.com/postgresql#{TARGET}"
+        ))?;
+        let template_regex = repository.template_regex()?;
+        assert_eq!(
+            Some(Version::new(17, 0, 0)),
+            HttpIndex::get_entry_version(
+                &template_regex,
+                &format!("postgresql-17.0.0-{TARGET}.tar.gz")
+            )
+        );
+        assert_eq!(
+            Some(Version::new(16, 4, 0)),
+            HttpIndex::get_entry_version(&template_regex, "16.4.0/")
+        );
+        assert_eq!(
+            None,
+            HttpIndex::get_entry_version(
+                &template_regex,
+                "postgresql-17.0.0-aarch64-apple-darwin.tar.gz"
+            )
+        );
+        assert_eq!(None, HttpIndex::get_entry_version(&template_regex, "../"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_get_archive_url() -> Result<()> {
+        let repository = HttpIndex::parse(&format!(
+            "index+https://mirror.example.com/postgresql?template={{version}}/pg-{{target}}.tgz#{TARGET}"
+        ))?;
+        assert_eq!(
+            format!("https://mirror.example.com/postgresql/16.4.0/pg-{TARGET}.tgz"),
+            repository
+                .get_archive_url(&Version::new(16, 4, 0))?
+                .to_string()
+        );
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_archive() -> Result<()> {
+        let archive_name = format!("postgresql-16.4.0-{}.tar.gz", target_triple::TARGET);
+        let files = HashMap::from([
+            (
+                "/postgresql/index.json".to_string(),
+                br#"["15.8.0", "16.4.0", "17.0.0-rc.1"]"#.to_vec(),
+            ),
+            (format!("/postgresql/{archive_name}"), b"archive".to_vec()),
+            (
+                format!("/postgresql/{archive_name}.{HASH_EXTENSION}"),
+                format!(
+                    "{}  {archive_name}\n",
+                    sha2_256::hash(&b"archive".to_vec())?
+                )
+                .into_bytes(),
+            ),
+        ]);
+        let url = format!("{URL}{}/postgresql?index=index.json", serve(files));
+        let repository = HttpIndex::new(&url)?;
+
+        let version = repository.get_version(&VersionReq::STAR).await?;
+        assert_eq!(Version::new(16, 4, 0), version);
+        assert_eq!(
+            vec![
+                Version::parse("17.0.0-rc.1")?,
+                Version::new(16, 4, 0),
+                Version::new(15, 8, 0)
+            ],
+            repository.list_versions().await?
+        );
+        let archive = repository.get_archive(&VersionReq::STAR).await?;
+        assert_eq!(archive_name, archive.name());
+        assert_eq!(b"archive", archive.bytes());
+
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("archive.tar.gz");
+        repository
+            .get_archive_to_file(&VersionReq::STAR, &path)
+            .await?;
+        assert_eq!(b"archive", std::fs::read(&path)?.as_slice());
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_archive_hash_not_found() -> Result<()> {
+        let archive_name = format!("postgresql-16.4.0-{}.tar.gz", target_triple::TARGET);
+        let files = HashMap::from([
+            (
+                "/postgresql/index.json".to_string(),
+                br#"["16.4.0"]"#.to_vec(),
+            ),
+            (format!("/postgresql/{archive_name}"), b"archive".to_vec()),
+        ]);
+        let url = format!("{URL}{}/postgresql?index=index.json", serve(files));
+        let repository = HttpIndex::new(&url)?;
+
+        let error = repository
+            .get_archive(&VersionReq::STAR)
+            .await
+            .expect_err("hash not found");
+        assert!(matches!(error, AssetHashNotFound(_)));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn test_get_archive_hash_mismatch() -> Result<()> {
+        let archive_name = format!("postgresql-16.4.0-{}.tar.gz", target_triple::TARGET);
+        let files = HashMap::from([
+            (
+                "/postgresql/index.json".to_string(),
+                br#"["16.4.0"]"#.to_vec(),
+            ),
+            (format!("/postgresql/{archive_name}"), b"archive".to_vec()),
+            (
+                format!("/postgresql/{archive_name}.{HASH_EXTENSION}"),
+                sha2_256::hash(&b"other".to_vec())?.into_bytes(),
+            ),
+        ]);
+        let url = format!("{URL}{}/postgresql?index=index.json", serve(files));
+        let repository = HttpIndex::new(&url)?;
+
+        let error = repository
+            .get_archive(&VersionReq::STAR)
+            .await
+            .expect_err("hash mismatch");
+        assert!(matches!(error, ArchiveHashMismatch { .. }));
+
+        let temp_dir = tempfile::tempdir()?;
+        let path = temp_dir.path().join("archive.tar.gz");
+        let error = repository
+            .get_archive_to_file(&VersionReq::STAR, &path)
+            .await
+            .expect_err("hash mismatch");
+        assert!(matches!(error, ArchiveHashMismatch { .. }));
+        assert_eq!(0, std::fs::read_dir(temp_dir.path())?.count());
+        Ok(())
+    }
+}
@@ -1,19 +1,27 @@
+use crate::credentials::apply_credentials;
+#[cfg(feature = "minisign")]
+use crate::hasher::minisign;
+use crate::proxy::http_client;
 use crate::repository::maven::models::Metadata;
 use crate::repository::model::Repository;
 use crate::repository::Archive;
+use crate::retry::RetryStatusStrategy;
+use crate::version::parse_version;
 use crate::Error::{ArchiveHashMismatch, ParseError, RepositoryFailure, VersionNotFound};
-use crate::{hasher, Result};
+use crate::{hasher, reporter, scoped, Channel, Result};
 use async_trait::async_trait;
 use futures_util::StreamExt;
 use reqwest::header::HeaderMap;
+#[cfg(feature = "minisign")]
+use reqwest::StatusCode;
 use reqwest_middleware::{ClientBuilder, ClientWithMiddleware};
-use reqwest_retry::policies::ExponentialBackoff;
 use reqwest_retry::RetryTransientMiddleware;
 use reqwest_tracing::TracingMiddleware;
 use semver::{Version, VersionReq};
 use std::env;
 use std::io::Write;
 use std::sync::LazyLock;
+use tracing::field::Empty;
 use tracing::{debug, instrument, warn};
 #[cfg(feature = "indicatif")]
 use tracing_indicatif::span_ext::IndicatifSpanExt;
@@ -48,25 +56,38 @@ impl Maven {
         }))
     }
 
-    /// Gets the artifact id and version that matches the specified version requirement.
+    /// Gets the metadata of the artifact.
     ///
     /// # Errors
-    /// * If the version requirement does not match any versions.
+    /// * If the metadata cannot be downloaded or parsed.
     #[instrument(level = "debug")]
-    async fn get_artifact(&self, version_req: &VersionReq) -> Result<(String, Version)> {
-        debug!("Attempting to locate release for version requirement {version_req}");
-        let client = reqwest_client();
+    async fn get_metadata(&self) -> Result<Metadata> {
+        let client = reqwest_client()?;
         let url = format!("{}/maven-metadata.xml", self.url);
-        let request = client.get(&url).headers(Self::headers());
+        let request = client.get(&url).headers(Self::headers(&url)?);
         let response = request.send().await?.error_for_status()?;
         let text = response.text().await?;
-        let metadata: Metadata =
-            quick_xml::de::from_str(&text).map_err(|error| ParseError(error.to_string()))?;
+        quick_xml::de::from_str(&text).map_err(|error| ParseError(error.to_string()))
+    }
+
+    /// Gets the artifact id and version that matches the specified version requirement in the
+    /// release [channel](Channel).
+    ///
+    /// # Errors
+    /// * If the version requirement does not match any versions.
+    #[instrument(level = "debug")]
+    async fn get_artifact(
+        &self,
+        version_req: &VersionReq,
+        channel: Channel,
+    ) -> Result<(String, Version)> {
+        debug!("Attempting to locate release for version requirement {version_req}");
+        let metadata = self.get_metadata().await?;
         let artifact = metadata.artifact_id;
         let mut result = None;
         for version in &metadata.versioning.versions.version {
-            let version = Version::parse(version)?;
-            if version_req.matches(&version) {
+            let version = parse_version(version)?;
+            if channel.matches(version_req, &version) {
                 if let Some(result_version) = result.clone() {
                     if version > result_version {
                         result = Some(version);
@@ -86,11 +107,15 @@ impl Maven {
         }
     }
 
-    /// Returns the headers for the Maven request.
-    fn headers() -> HeaderMap {
+    /// Returns the headers for the Maven request to the `url`.
+    ///
+    /// # Errors
+    /// * If the credentials for the request cannot be retrieved.
+    fn headers(url: &str) -> Result<HeaderMap> {
         let mut headers = HeaderMap::new();
         headers.append("User-Agent", USER_AGENT.parse().unwrap());
-        headers
+        apply_credentials(url, &mut headers)?;
+        Ok(headers)
     }
 }
 
@@ -101,16 +126,52 @@ impl Repository for Maven {
         "Maven"
     }
 
+    #[instrument(level = "debug")]
+    async fn list_versions(&self) -> Result<Vec<Version>> {
+        let metadata = self.get_metadata().await?;
+        let mut versions = metadata
+            .versioning
+            .versions
+            .version
+            .iter()
+            .map(String::as_str)
+            .map(parse_version)
+            .collect::<Result<Vec<_>>>()?;
+        versions.sort_by(|a, b| b.cmp(a));
+        versions.dedup();
+        Ok(versions)
+    }
+
     #[instrument(level = "debug")]
     async fn get_version(&self, version_req: &VersionReq) -> Result<Version> {
+        self.get_version_in_channel(version_req, Channel::Stable)
+            .await
+    }
+
+    #[instrument(level = "debug")]
+    async fn get_archive(&self, version_req: &VersionReq) -> Result<Archive> {
+        self.get_archive_in_channel(version_req, Channel::Stable)
+            .await
+    }
+
+    #[instrument(level = "debug")]
+    async fn get_version_in_channel(
+        &self,
+        version_req: &VersionReq,
+        channel: Channel,
+    ) -> Result<Version> {
         debug!("Attempting to locate release for version requirement {version_req}");
-        let (_, version) = self.get_artifact(version_req).await?;
+        let (_, version) = self.get_artifact(version_req, channel).await?;
         Ok(version)
     }
 
-    #[instrument]
-    async fn get_archive(&self, version_req: &VersionReq) -> Result<Archive> {
-        let (artifact, version) = self.get_artifact(version_req).await?;
+    #[instrument(fields(operation = "download", version = Empty, bytes_total = Empty))]
+    async fn get_archive_in_channel(
+        &self,
+        version_req: &VersionReq,
+        channel: Channel,
+    ) -> Result<Archive> {
+        let (artifact, version) = self.get_artifact(version_req, channel).await?;
         let archive_name = format!("{artifact}-{version}.jar");
         let archive_url = format!("{url}/{version}/{artifact}-{version}.jar", url = self.url,);
 
@@ -129,31 +190,37 @@ impl Repository for Maven {
             )));
         };
         let archive_hash_url = format!("{archive_url}.{extension}");
-        let client = reqwest_client();
+        let client = reqwest_client()?;
         debug!("Downloading archive hash {archive_hash_url}");
-        let request = client.get(&archive_hash_url).headers(Self::headers());
+        let request = client
+            .get(&archive_hash_url)
+            .headers(Self::headers(&archive_hash_url)?);
         let response = request.send().await?.error_for_status()?;
         let hash = response.text().await?;
         debug!("Archive hash {archive_hash_url} downloaded: {}", hash.len(),);
 
         debug!("Downloading archive {archive_url}");
-        let request = client.get(&archive_url).headers(Self::headers());
+        let request = client
+            .get(&archive_url)
+            .headers(Self::headers(&archive_url)?);
         let response = request.send().await?.error_for_status()?;
+        let content_length = response.content_length();
         #[cfg(feature = "indicatif")]
         let span = tracing::Span::current();
         #[cfg(feature = "indicatif")]
-        {
-            let content_length = response.content_length().unwrap_or_default();
-            span.pb_set_length(content_length);
-        }
+        span.pb_set_length(content_length.unwrap_or_default());
         let mut bytes = Vec::new();
         let mut source = response.bytes_stream();
         while let Some(chunk) = source.next().await {
             bytes.write_all(&chunk?)?;
             #[cfg(feature = "indicatif")]
             span.pb_set_position(bytes.len() as u64);
+            reporter::report_download(&archive_url, bytes.len() as u64, content_length);
         }
         debug!("Archive {archive_url} downloaded: {}", bytes.len(),);
+        let current_span = tracing::Span::current();
+        current_span.record("version", version.to_string());
+        current_span.record("bytes_total", bytes.len());
 
         let archive_hash = hasher_fn(&bytes)?;
         if archive_hash != hash {
@@ -163,15 +230,48 @@ impl Repository for Maven {
         let archive = Archive::new(archive_name, version, bytes);
         Ok(archive)
     }
+
+    #[cfg(feature = "minisign")]
+    #[instrument(level = "debug")]
+    async fn get_signature(&self, version: &Version) -> Result<Option<String>> {
+        let version_req = VersionReq::parse(&format!("={version}"))?;
+        let (artifact, version) = self.get_artifact(&version_req, Channel::Stable).await?;
+        let signature_url = format!(
+            "{url}/{version}/{artifact}-{version}.jar.{extension}",
+            url = self.url,
+            extension = minisign::SIGNATURE_EXTENSION,
+        );
+        let client = reqwest_client()?;
+        debug!("Downloading archive signature {signature_url}");
+        let request = client
+            .get(&signature_url)
+            .headers(Self::headers(&signature_url)?);
+        let response = request.send().await?;
+        if response.status() == StatusCode::NOT_FOUND {
+            return Ok(None);
+        }
+        Ok(Some(response.error_for_status()?.text().await?))
+    }
 }
 
-/// Creates a new reqwest client with middleware for tracing, and retrying transient errors.
-fn reqwest_client() -> ClientWithMiddleware {
-    let retry_policy = ExponentialBackoff::builder().build_with_max_retries(3);
-    ClientBuilder::new(reqwest::Client::new())
+/// Creates a new reqwest client with middleware for tracing, and retrying transient errors and
+/// responses with the retry status codes of the scoped
+/// [retry policy](crate::ScopedOptions::retry_policy). Requests are made through the scoped
+/// [proxy options](crate::ScopedOptions::proxy).
+///
+/// # Errors
+/// * If the client cannot be created.
+fn reqwest_client() -> Result<ClientWithMiddleware> {
+    let retry_policy = scoped::retry_policy();
+    let retry_strategy = RetryStatusStrategy::new(&retry_policy);
+    let client = ClientBuilder::new(http_client()?)
         .with(TracingMiddleware::default())
-        .with(RetryTransientMiddleware::new_with_policy(retry_policy))
-        .build()
+        .with(RetryTransientMiddleware::new_with_policy_and_strategy(
+            retry_policy,
+            retry_strategy,
+        ))
+        .build();
+    Ok(client)
 }
 
 #[cfg(test)]
@@ -190,6 +290,17 @@ mod tests {
     // get_version tests
     //
 
+    #[tokio::test]
+    async fn test_list_versions() -> Result<()> {
+        let maven = Maven::new(URL)?;
+        let versions = maven.list_versions().await?;
+        assert!(versions.contains(&Version::new(16, 2, 0)));
+        assert!(versions
+            .windows(2)
+            .all(|versions| versions[0] > versions[1]));
+        Ok(())
+    }
+
     #[tokio::test]
     async fn test_get_version() -> Result<()> {
         let maven = Maven::new(URL)?;
@@ -1,8 +1,14 @@
+#[cfg(feature = "file")]
+pub mod file;
 #[cfg(feature = "github")]
 pub mod github;
+#[cfg(feature = "gitlab")]
+pub mod gitlab;
+#[cfg(feature = "http")]
+pub mod http;
 #[cfg(feature = "maven")]
 pub mod maven;
 pub mod model;
 pub mod registry;
 
-pub use model::{Archive, Repository};
+pub use model::{Archive, ReleaseInfo, Repository};
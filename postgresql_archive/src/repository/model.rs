@@ -1,6 +1,8 @@
+use crate::Channel;
 use async_trait::async_trait;
 use semver::{Version, VersionReq};
 use std::fmt::Debug;
+use std::path::Path;
 
 /// A trait for archive repository implementations.
 #[async_trait]
@@ -16,6 +18,18 @@ pub trait Repository: Debug + Send + Sync {
     /// * If the version is not found.
     async fn get_version(&self, version_req: &VersionReq) -> crate::Result<Version>;
 
+    /// Gets all the versions published by the repository, including pre-releases, in descending
+    /// order. Repositories that cannot list their versions return an error.
+    ///
+    /// # Errors
+    /// * If the versions cannot be retrieved.
+    async fn list_versions(&self) -> crate::Result<Vec<Version>> {
+        Err(crate::Error::RepositoryFailure(format!(
+            "{} repository does not support listing versions",
+            self.name()
+        )))
+    }
+
     /// Gets the archive for a given [version requirement](VersionReq) that passes the default
     /// matcher. If no archive is found for the [version requirement](VersionReq) and matcher then
     /// an [error](crate::error::Error) is returned.
@@ -24,6 +38,102 @@ pub trait Repository: Debug + Send + Sync {
     /// * If the archive is not found.
     /// * If the archive cannot be downloaded.
     async fn get_archive(&self, version_req: &VersionReq) -> crate::Result<Archive>;
+
+    /// Gets the version for the specified [version requirement](VersionReq) in the release
+    /// [channel](Channel). Repositories that do not publish pre-releases resolve stable versions.
+    ///
+    /// # Errors
+    /// * If the version is not found.
+    async fn get_version_in_channel(
+        &self,
+        version_req: &VersionReq,
+        _channel: Channel,
+    ) -> crate::Result<Version> {
+        self.get_version(version_req).await
+    }
+
+    /// Gets the archive for a given [version requirement](VersionReq) in the release
+    /// [channel](Channel) that passes the default matcher. Repositories that do not publish
+    /// pre-releases resolve stable versions.
+    ///
+    /// # Errors
+    /// * If the archive is not found.
+    /// * If the archive cannot be downloaded.
+    async fn get_archive_in_channel(
+        &self,
+        version_req: &VersionReq,
+        _channel: Channel,
+    ) -> crate::Result<Archive> {
+        self.get_archive(version_req).await
+    }
+
+    /// Gets the [release information](ReleaseInfo) of the latest version for the specified
+    /// [version requirement](VersionReq) in the release [channel](Channel). Repositories that do
+    /// not publish release metadata return the version only.
+    ///
+    /// # Errors
+    /// * If the version is not found.
+    async fn get_release_info(
+        &self,
+        version_req: &VersionReq,
+        channel: Channel,
+    ) -> crate::Result<ReleaseInfo> {
+        let version = self.get_version_in_channel(version_req, channel).await?;
+        Ok(ReleaseInfo::new(version))
+    }
+
+    /// Downloads the archive for a given [version requirement](VersionReq) that passes the
+    /// default matcher to the file at `path`, and returns the version of the archive.
+    /// Repositories that cannot stream archives to disk write the downloaded archive to the file.
+    ///
+    /// # Errors
+    /// * If the archive is not found.
+    /// * If the archive cannot be downloaded.
+    /// * If the archive cannot be written to the file.
+    async fn get_archive_to_file(
+        &self,
+        version_req: &VersionReq,
+        path: &Path,
+    ) -> crate::Result<Version> {
+        let archive = self.get_archive(version_req).await?;
+        std::fs::write(path, archive.bytes())?;
+        Ok(archive.version().clone())
+    }
+
+    /// Gets the detached minisign signature of the archive of the `version` that passes the
+    /// default matcher, or `None` if the repository does not publish one. Archives are verified
+    /// against the signature after they are downloaded if a public key is registered for the
+    /// repository. Repositories that do not publish signatures return `None`.
+    ///
+    /// # Errors
+    /// * If the signature cannot be downloaded.
+    #[cfg(feature = "minisign")]
+    async fn get_signature(&self, _version: &Version) -> crate::Result<Option<String>> {
+        Ok(None)
+    }
+}
+
+/// Information about a release of a repository.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReleaseInfo {
+    /// Version of the release
+    pub version: Version,
+    /// Date and time the release was published, in RFC 3339 format; e.g. `2024-08-08T17:05:01Z`
+    pub published_at: Option<String>,
+    /// Size of the archive of the release that passes the default matcher, in bytes
+    pub size: Option<u64>,
+}
+
+impl ReleaseInfo {
+    /// Creates new [`ReleaseInfo`] for the `version` without a publish date and size.
+    #[must_use]
+    pub fn new(version: Version) -> Self {
+        Self {
+            version,
+            published_at: None,
+            size: None,
+        }
+    }
 }
 
 /// A struct representing an archive.
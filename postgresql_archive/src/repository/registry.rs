@@ -2,11 +2,17 @@
 use crate::configuration::theseus;
 #[cfg(feature = "zonky")]
 use crate::configuration::zonky;
+#[cfg(feature = "file")]
+use crate::repository::file::{self, repository::FileSystem};
 #[cfg(feature = "github")]
 use crate::repository::github::repository::GitHub;
+#[cfg(feature = "gitlab")]
+use crate::repository::gitlab::{self, repository::GitLab};
+#[cfg(feature = "http")]
+use crate::repository::http::{self, repository::HttpIndex};
 use crate::repository::model::Repository;
 use crate::Error::{PoisonedLock, UnsupportedRepository};
-use crate::Result;
+use crate::{scoped, Result};
 use std::sync::{Arc, LazyLock, Mutex, RwLock};
 
 static REGISTRY: LazyLock<Arc<Mutex<RepositoryRegistry>>> =
@@ -75,6 +81,18 @@ impl Default for RepositoryRegistry {
             |url| Ok(url.starts_with(zonky::URL)),
             Box::new(zonky::Zonky::new),
         );
+        #[cfg(feature = "file")]
+        registry.register(
+            |url| Ok(url.starts_with(file::URL)),
+            Box::new(FileSystem::new),
+        );
+        #[cfg(feature = "http")]
+        registry.register(
+            |url| Ok(url.starts_with(http::URL)),
+            Box::new(HttpIndex::new),
+        );
+        #[cfg(feature = "gitlab")]
+        registry.register(|url| Ok(gitlab::supports(url)), Box::new(GitLab::new));
         registry
     }
 }
@@ -91,17 +109,72 @@ pub fn register(supports_fn: SupportsFn, new_fn: Box<NewFn>) -> Result<()> {
     Ok(())
 }
 
-/// Gets a repository that supports the specified URL
+/// Gets a repository that supports the specified URL. Repositories access the network, so only
+/// repositories of local `file://` URLs are returned when the download options of the
+/// [scope](crate::ScopedOptions::download_options) are [offline](crate::DownloadOptions::offline).
 ///
 /// # Errors
+/// * If the download options are offline.
 /// * If the URL is not supported.
 pub fn get(url: &str) -> Result<Box<dyn Repository>> {
+    scoped::download_options().require_online(url)?;
     let registry = REGISTRY
         .lock()
         .map_err(|error| PoisonedLock(error.to_string()))?;
     registry.get(url)
 }
 
+/// A read-only snapshot of the registered repositories, used to debug which repository is used for
+/// a URL; repositories registered after the snapshot is taken are not included.
+#[must_use]
+#[expect(clippy::type_complexity)]
+pub struct Snapshot {
+    repositories: Vec<(Arc<RwLock<SupportsFn>>, Arc<RwLock<NewFn>>)>,
+}
+
+impl Snapshot {
+    /// Returns the number of repositories in the snapshot.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.repositories.len()
+    }
+
+    /// Returns whether the snapshot has no repositories.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.repositories.is_empty()
+    }
+
+    /// Returns the position, in order of precedence, of the repository used for the specified URL.
+    ///
+    /// # Errors
+    /// * If the registry is poisoned.
+    pub fn position(&self, url: &str) -> Result<Option<usize>> {
+        for (index, (supports_fn, _)) in self.repositories.iter().enumerate() {
+            let supports_function = supports_fn
+                .read()
+                .map_err(|error| PoisonedLock(error.to_string()))?;
+            if supports_function(url)? {
+                return Ok(Some(index));
+            }
+        }
+        Ok(None)
+    }
+}
+
+/// Takes a read-only snapshot of the registered repositories.
+///
+/// # Errors
+/// * If the registry is poisoned.
+pub fn snapshot() -> Result<Snapshot> {
+    let registry = REGISTRY
+        .lock()
+        .map_err(|error| PoisonedLock(error.to_string()))?;
+    Ok(Snapshot {
+        repositories: registry.repositories.clone(),
+    })
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -151,6 +224,24 @@ mod tests {
         assert_eq!("test", repository.name());
         assert!(repository.get_version(&VersionReq::STAR).await.is_ok());
         assert!(repository.get_archive(&VersionReq::STAR).await.is_ok());
+        assert!(repository.list_versions().await.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_snapshot() -> Result<()> {
+        let url = "https://snapshot.foo.com";
+        let snapshot = snapshot()?;
+        assert_eq!(None, snapshot.position(url)?);
+        register(
+            |url| Ok(url == "https://snapshot.foo.com"),
+            Box::new(TestRepository::new),
+        )?;
+        let current = super::snapshot()?;
+        assert_eq!(Some(0), current.position(url)?);
+        assert!(current.len() > snapshot.len());
+        assert_eq!(None, snapshot.position(url)?);
+        assert!(get(url).is_ok());
         Ok(())
     }
 
@@ -0,0 +1,416 @@
+use crate::Error::PoisonedLock;
+use crate::Result;
+use rand::Rng;
+use reqwest_retry::{
+    default_on_request_failure, RetryDecision, RetryPolicy as ReqwestRetryPolicy, Retryable,
+    RetryableStrategy,
+};
+use std::sync::{LazyLock, RwLock};
+use std::time::{Duration, SystemTime};
+use tracing::debug;
+
+static DEFAULT_RETRY_POLICY: LazyLock<RwLock<RetryPolicy>> =
+    LazyLock::new(|| RwLock::new(RetryPolicy::default()));
+
+/// HTTP status codes of responses that are retried by default: request timeout, too many requests,
+/// and server errors that are usually transient
+pub const DEFAULT_RETRY_STATUS_CODES: &[u16] = &[408, 429, 500, 502, 503, 504];
+
+/// Classifies whether an error is transient and the failed operation should be retried
+pub type RetryableFn = fn(&(dyn std::error::Error + 'static)) -> bool;
+
+/// Policy for retrying operations that fail with transient errors; e.g. downloads, renaming
+/// extracted archives, connecting to the server and starting the server. Retries are delayed with
+/// exponential backoff, starting at the initial backoff and doubling with each retry up to the
+/// maximum backoff.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RetryPolicy {
+    /// Maximum number of attempts, including the first attempt; `1` disables retries
+    pub max_attempts: u32,
+    /// Delay before the first retry
+    pub initial_backoff: Duration,
+    /// Maximum delay between attempts
+    pub max_backoff: Duration,
+    /// Randomize each delay between half and all of the backoff, so that concurrent operations do
+    /// not retry in lockstep
+    pub jitter: bool,
+    /// Classifies whether an error should be retried; only [transient](is_transient) errors are
+    /// retried by default
+    pub retryable: RetryableFn,
+    /// HTTP status codes of download responses that are retried; responses with other status
+    /// codes are not retried. Defaults to [`DEFAULT_RETRY_STATUS_CODES`]
+    pub retry_status_codes: &'static [u16],
+}
+
+impl RetryPolicy {
+    /// Creates a new [`RetryPolicy`] with up to 4 attempts and a backoff from 100 milliseconds to
+    /// 10 seconds with jitter.
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            max_attempts: 4,
+            initial_backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            jitter: true,
+            retryable: is_transient,
+            retry_status_codes: DEFAULT_RETRY_STATUS_CODES,
+        }
+    }
+
+    /// Creates a new [`RetryPolicy`] that does not retry.
+    #[must_use]
+    pub fn none() -> Self {
+        Self {
+            max_attempts: 1,
+            ..Self::new()
+        }
+    }
+
+    /// Gets the delay before the next attempt after `past_retries` retries have been made.
+    #[must_use]
+    pub fn backoff(&self, past_retries: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(past_retries))
+            .min(self.max_backoff);
+        if self.jitter && !backoff.is_zero() {
+            rand::thread_rng().gen_range(backoff / 2..=backoff)
+        } else {
+            backoff
+        }
+    }
+
+    /// Returns `true` if the operation should be retried after `attempt` attempts failed with
+    /// the `error`.
+    #[must_use]
+    pub fn should_retry(&self, attempt: u32, error: &(dyn std::error::Error + 'static)) -> bool {
+        attempt < self.max_attempts && (self.retryable)(error)
+    }
+
+    /// Returns `true` if a download response with the HTTP `status` code should be retried.
+    #[must_use]
+    pub fn is_retry_status(&self, status: u16) -> bool {
+        self.retry_status_codes.contains(&status)
+    }
+
+    /// Runs the blocking `operation`, retrying it according to this policy.
+    ///
+    /// # Errors
+    /// * The error of the last attempt if the operation does not succeed.
+    pub fn retry<T, E, F>(&self, mut operation: F) -> core::result::Result<T, E>
+    where
+        E: std::error::Error + 'static,
+        F: FnMut() -> core::result::Result<T, E>,
+    {
+        let mut attempt = 1;
+        loop {
+            match operation() {
+                Ok(value) => return Ok(value),
+                Err(error) if self.should_retry(attempt, &error) => {
+                    let backoff = self.backoff(attempt - 1);
+                    debug!(
+                        "Attempt {attempt}/{} failed: {error}; retrying in {backoff:?}",
+                        self.max_attempts
+                    );
+                    std::thread::sleep(backoff);
+                    attempt += 1;
+                }
+                Err(error) => return Err(error),
+            }
+        }
+    }
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Retry policy for the reqwest retry middleware used to download archives. Transient errors are
+/// classified by the middleware; the classifier of the policy is not used.
+impl ReqwestRetryPolicy for RetryPolicy {
+    fn should_retry(&self, _request_start_time: SystemTime, n_past_retries: u32) -> RetryDecision {
+        if n_past_retries.saturating_add(1) < self.max_attempts {
+            RetryDecision::Retry {
+                execute_after: SystemTime::now() + self.backoff(n_past_retries),
+            }
+        } else {
+            RetryDecision::DoNotRetry
+        }
+    }
+}
+
+/// Strategy for the reqwest retry middleware that retries the responses with the
+/// [retry status codes](RetryPolicy::retry_status_codes) of a policy, and transient request
+/// failures such as connection errors and timeouts.
+#[derive(Clone, Copy, Debug)]
+pub(crate) struct RetryStatusStrategy {
+    retry_status_codes: &'static [u16],
+}
+
+impl RetryStatusStrategy {
+    /// Creates a new strategy for the status codes of the `policy`.
+    pub(crate) fn new(policy: &RetryPolicy) -> Self {
+        Self {
+            retry_status_codes: policy.retry_status_codes,
+        }
+    }
+}
+
+impl RetryableStrategy for RetryStatusStrategy {
+    fn handle(
+        &self,
+        result: &core::result::Result<reqwest::Response, reqwest_middleware::Error>,
+    ) -> Option<Retryable> {
+        match result {
+            Ok(response) => {
+                let status = response.status().as_u16();
+                if self.retry_status_codes.contains(&status) {
+                    debug!("Retrying response with status {status}");
+                    Some(Retryable::Transient)
+                } else {
+                    None
+                }
+            }
+            Err(error) => default_on_request_failure(error),
+        }
+    }
+}
+
+/// Returns `true` if the `error`, or one of its sources, is a transient I/O or HTTP error; i.e. a
+/// connection that was refused, reset or aborted, a timeout, an interrupted or truncated transfer,
+/// or a response with a `408`, `429` or `5xx` status code.
+#[must_use]
+pub fn is_transient(error: &(dyn std::error::Error + 'static)) -> bool {
+    let mut source = Some(error);
+    while let Some(error) = source {
+        if let Some(error) = error.downcast_ref::<std::io::Error>() {
+            if is_transient_io_error(error) {
+                return true;
+            }
+            // The source of an I/O error skips its inner error, so that is checked first
+            if let Some(inner) = error.get_ref() {
+                if is_transient(inner) {
+                    return true;
+                }
+            }
+        } else if let Some(error) = error.downcast_ref::<reqwest::Error>() {
+            if is_transient_http_error(error) {
+                return true;
+            }
+        } else if let Some(reqwest_middleware::Error::Reqwest(error)) =
+            error.downcast_ref::<reqwest_middleware::Error>()
+        {
+            if is_transient_http_error(error) {
+                return true;
+            }
+        }
+        source = error.source();
+    }
+    false
+}
+
+/// Returns `true` if the I/O `error` is transient.
+fn is_transient_io_error(error: &std::io::Error) -> bool {
+    use std::io::ErrorKind::{
+        BrokenPipe, ConnectionAborted, ConnectionRefused, ConnectionReset, Interrupted,
+        NotConnected, TimedOut, UnexpectedEof, WouldBlock,
+    };
+    matches!(
+        error.kind(),
+        BrokenPipe
+            | ConnectionAborted
+            | ConnectionRefused
+            | ConnectionReset
+            | Interrupted
+            | NotConnected
+            | TimedOut
+            | UnexpectedEof
+            | WouldBlock
+    )
+}
+
+/// Returns `true` if the HTTP `error` is transient.
+fn is_transient_http_error(error: &reqwest::Error) -> bool {
+    if error.is_connect() || error.is_timeout() || error.is_body() {
+        return true;
+    }
+    error.status().is_some_and(|status| {
+        status.is_server_error()
+            || status == reqwest::StatusCode::REQUEST_TIMEOUT
+            || status == reqwest::StatusCode::TOO_MANY_REQUESTS
+    })
+}
+
+/// Gets the default [retry policy](RetryPolicy) used for downloads and extraction.
+#[must_use]
+pub fn default_retry_policy() -> RetryPolicy {
+    match DEFAULT_RETRY_POLICY.read() {
+        Ok(policy) => *policy,
+        Err(error) => **error.get_ref(),
+    }
+}
+
+/// Sets the default [retry policy](RetryPolicy) used for downloads and extraction.
+///
+/// # Errors
+/// * If the lock is poisoned.
+pub fn set_default_retry_policy(policy: RetryPolicy) -> Result<()> {
+    let mut default_policy = DEFAULT_RETRY_POLICY
+        .write()
+        .map_err(|error| PoisonedLock(error.to_string()))?;
+    *default_policy = policy;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::ErrorKind;
+
+    #[test]
+    fn test_backoff() {
+        let policy = RetryPolicy {
+            jitter: false,
+            ..RetryPolicy::default()
+        };
+        assert_eq!(Duration::from_millis(100), policy.backoff(0));
+        assert_eq!(Duration::from_millis(200), policy.backoff(1));
+        assert_eq!(Duration::from_millis(400), policy.backoff(2));
+        assert_eq!(Duration::from_secs(10), policy.backoff(10));
+        assert_eq!(Duration::from_secs(10), policy.backoff(u32::MAX));
+    }
+
+    #[test]
+    fn test_backoff_jitter() {
+        let policy = RetryPolicy::default();
+        for _ in 0..100 {
+            let backoff = policy.backoff(1);
+            assert!(backoff >= Duration::from_millis(100));
+            assert!(backoff <= Duration::from_millis(200));
+        }
+    }
+
+    #[test]
+    fn test_should_retry() {
+        let error = std::io::Error::new(ErrorKind::ConnectionReset, "test");
+        let policy = RetryPolicy::default();
+        assert!(policy.should_retry(1, &error));
+        assert!(!policy.should_retry(4, &error));
+        assert!(!RetryPolicy::none().should_retry(1, &error));
+        let policy = RetryPolicy {
+            retryable: |_| false,
+            ..RetryPolicy::default()
+        };
+        assert!(!policy.should_retry(1, &error));
+    }
+
+    #[test]
+    fn test_is_transient() {
+        for kind in [
+            ErrorKind::ConnectionRefused,
+            ErrorKind::ConnectionReset,
+            ErrorKind::TimedOut,
+            ErrorKind::UnexpectedEof,
+        ] {
+            assert!(is_transient(&std::io::Error::new(kind, "test")));
+        }
+        for kind in [
+            ErrorKind::NotFound,
+            ErrorKind::PermissionDenied,
+            ErrorKind::InvalidData,
+            ErrorKind::Other,
+        ] {
+            assert!(!is_transient(&std::io::Error::new(kind, "test")));
+        }
+        let error = std::io::Error::new(
+            ErrorKind::Other,
+            std::io::Error::new(ErrorKind::TimedOut, "test"),
+        );
+        assert!(is_transient(&error));
+        assert!(!is_transient(&crate::Error::AssetNotFound));
+    }
+
+    #[tokio::test]
+    async fn test_is_transient_http_error() {
+        let error = reqwest::get("http://127.0.0.1:1")
+            .await
+            .expect_err("connection refused");
+        assert!(is_transient(&error));
+        let error = reqwest_middleware::Error::Reqwest(error);
+        assert!(is_transient(&error));
+        let error = reqwest_middleware::Error::Middleware(anyhow::anyhow!("test"));
+        assert!(!is_transient(&error));
+    }
+
+    #[test]
+    fn test_is_retry_status() {
+        let policy = RetryPolicy::default();
+        assert!(policy.is_retry_status(429));
+        assert!(policy.is_retry_status(503));
+        assert!(!policy.is_retry_status(404));
+        let policy = RetryPolicy {
+            retry_status_codes: &[404],
+            ..RetryPolicy::default()
+        };
+        assert!(policy.is_retry_status(404));
+        assert!(!policy.is_retry_status(503));
+    }
+
+    #[test]
+    fn test_retry() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::ZERO,
+            ..RetryPolicy::default()
+        };
+        let mut attempts = 0;
+        let result = policy.retry(|| {
+            attempts += 1;
+            if attempts < 3 {
+                Err(std::io::Error::new(ErrorKind::Interrupted, "test"))
+            } else {
+                Ok(attempts)
+            }
+        });
+        assert_eq!(3, result.expect("result"));
+
+        let mut attempts = 0;
+        let result: core::result::Result<(), _> = policy.retry(|| {
+            attempts += 1;
+            Err(std::io::Error::new(ErrorKind::Interrupted, "test"))
+        });
+        assert!(result.is_err());
+        assert_eq!(4, attempts);
+
+        let mut attempts = 0;
+        let result: core::result::Result<(), _> = policy.retry(|| {
+            attempts += 1;
+            Err(std::io::Error::new(ErrorKind::NotFound, "test"))
+        });
+        assert!(result.is_err());
+        assert_eq!(1, attempts);
+    }
+
+    #[test]
+    fn test_reqwest_retry_policy() {
+        let policy = RetryPolicy::default();
+        let now = SystemTime::now();
+        assert!(matches!(
+            ReqwestRetryPolicy::should_retry(&policy, now, 0),
+            RetryDecision::Retry { .. }
+        ));
+        assert!(matches!(
+            ReqwestRetryPolicy::should_retry(&policy, now, 3),
+            RetryDecision::DoNotRetry
+        ));
+    }
+
+    #[test]
+    fn test_default_retry_policy() -> Result<()> {
+        let policy = default_retry_policy();
+        set_default_retry_policy(policy)?;
+        assert_eq!(policy, default_retry_policy());
+        Ok(())
+    }
+}
@@ -0,0 +1,328 @@
+//! Options that apply to the archive operations of a single call, rather than to all operations
+//! of the process like the default options; e.g. so that each embedded server resolves versions
+//! and downloads archives with its own retry policy, without changing the options of others.
+
+use crate::cache::{ArchiveCache, FileSystemCache};
+use crate::download::{default_download_options, DownloadOptions};
+use crate::extractor::{default_extract_options, ExtractFilter};
+use crate::proxy::{default_proxy_options, ProxyOptions};
+use crate::reporter::ProgressReporter;
+use crate::retry::{default_retry_policy, RetryPolicy};
+use std::cell::RefCell;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::task::{Context, Poll};
+
+thread_local! {
+    static CURRENT: RefCell<Option<Arc<ScopedOptions>>> = const { RefCell::new(None) };
+}
+
+/// Options for the archive operations of a scope; options that are not set are inherited from
+/// the enclosing scope, or else from the process wide defaults.
+#[derive(Clone, Debug, Default)]
+pub struct ScopedOptions {
+    /// Retry policy used instead of the [default retry policy](crate::default_retry_policy)
+    pub retry_policy: Option<RetryPolicy>,
+    /// Download options used instead of the
+    /// [default download options](crate::default_download_options); e.g. for a cache directory
+    /// of an installation
+    pub download_options: Option<DownloadOptions>,
+    /// Target triple that archives are matched for, instead of the target this crate was built
+    /// for; e.g. `x86_64-apple-darwin` to install `x86_64` binaries on Apple Silicon
+    pub target: Option<String>,
+    /// Cache that downloaded archives are stored in and [offline](DownloadOptions::offline)
+    /// archives are retrieved from, instead of the [file system cache](FileSystemCache) in the
+    /// cache directory of the download options when they
+    /// [cache archives](DownloadOptions::cache_archives)
+    pub cache: Option<Arc<dyn ArchiveCache>>,
+    /// Filter of the files extracted from archives, instead of the filter of the
+    /// [default extract options](crate::extractor::default_extract_options)
+    pub extract_filter: Option<ExtractFilter>,
+    /// Asset name [pattern](crate::matcher::pattern::Pattern) that the assets of releases are
+    /// matched with, instead of the matcher registered for the repository URL; e.g. for nightly
+    /// or internal builds
+    pub asset_pattern: Option<String>,
+    /// Proxy options that repositories download through, instead of the
+    /// [default proxy options](crate::default_proxy_options)
+    pub proxy: Option<ProxyOptions>,
+    /// Reporter that receives the progress of the downloads and extractions of archives; e.g. to
+    /// display a progress bar without a tracing subscriber
+    pub progress_reporter: Option<Arc<dyn ProgressReporter>>,
+}
+
+/// Options are equal if they share the same cache and progress reporter, rather than caches with
+/// the same contents or reporters that report alike.
+impl PartialEq for ScopedOptions {
+    fn eq(&self, other: &Self) -> bool {
+        let same_cache = match (&self.cache, &other.cache) {
+            (Some(cache), Some(other)) => Arc::ptr_eq(cache, other),
+            (cache, other) => cache.is_none() && other.is_none(),
+        };
+        let same_progress_reporter = match (&self.progress_reporter, &other.progress_reporter) {
+            (Some(reporter), Some(other)) => Arc::ptr_eq(reporter, other),
+            (reporter, other) => reporter.is_none() && other.is_none(),
+        };
+        self.retry_policy == other.retry_policy
+            && self.download_options == other.download_options
+            && self.target == other.target
+            && same_cache
+            && self.extract_filter == other.extract_filter
+            && self.asset_pattern == other.asset_pattern
+            && self.proxy == other.proxy
+            && same_progress_reporter
+    }
+}
+
+impl ScopedOptions {
+    /// Gets these options, with the options that are not set inherited from the `outer` options.
+    fn inherit(self, outer: Option<&ScopedOptions>) -> Self {
+        let Some(outer) = outer else {
+            return self;
+        };
+        Self {
+            retry_policy: self.retry_policy.or(outer.retry_policy),
+            download_options: self
+                .download_options
+                .or_else(|| outer.download_options.clone()),
+            target: self.target.or_else(|| outer.target.clone()),
+            cache: self.cache.or_else(|| outer.cache.clone()),
+            extract_filter: self.extract_filter.or_else(|| outer.extract_filter.clone()),
+            asset_pattern: self.asset_pattern.or_else(|| outer.asset_pattern.clone()),
+            proxy: self.proxy.or_else(|| outer.proxy.clone()),
+            progress_reporter: self
+                .progress_reporter
+                .or_else(|| outer.progress_reporter.clone()),
+        }
+    }
+}
+
+/// Runs the `future` with the `options`, which apply to the archive operations of the future
+/// until it completes; including operations of the future that run on other threads of the
+/// extraction.
+///
+/// ```no_run
+/// use postgresql_archive::configuration::theseus;
+/// use postgresql_archive::{get_archive, with_scoped_options, RetryPolicy, ScopedOptions};
+/// use postgresql_archive::VersionReq;
+///
+/// # async fn example() -> postgresql_archive::Result<()> {
+/// let options = ScopedOptions {
+///     retry_policy: Some(RetryPolicy::none()),
+///     ..ScopedOptions::default()
+/// };
+/// let (version, archive) =
+///     with_scoped_options(options, get_archive(theseus::URL, &VersionReq::STAR)).await?;
+/// # Ok(())
+/// # }
+/// ```
+pub async fn with_scoped_options<F: Future>(options: ScopedOptions, future: F) -> F::Output {
+    let options = options.inherit(current().as_deref());
+    Scoped {
+        options: Some(Arc::new(options)),
+        future: Box::pin(future),
+    }
+    .await
+}
+
+/// Runs the blocking `operation` with the `options`; see [`with_scoped_options`].
+pub fn with_scoped_options_blocking<T, F: FnOnce() -> T>(
+    options: ScopedOptions,
+    operation: F,
+) -> T {
+    let options = options.inherit(current().as_deref());
+    enter(Some(Arc::new(options)), operation)
+}
+
+/// Future that runs the inner future with the options of its scope.
+struct Scoped<F> {
+    options: Option<Arc<ScopedOptions>>,
+    future: Pin<Box<F>>,
+}
+
+impl<F: Future> Future for Scoped<F> {
+    type Output = F::Output;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let scoped = self.get_mut();
+        let future = &mut scoped.future;
+        enter(scoped.options.clone(), || future.as_mut().poll(cx))
+    }
+}
+
+/// Restores the options of the enclosing scope when dropped, even if the scope panics.
+struct Restore(Option<Arc<ScopedOptions>>);
+
+impl Drop for Restore {
+    fn drop(&mut self) {
+        let outer = self.0.take();
+        CURRENT.with(|current| *current.borrow_mut() = outer);
+    }
+}
+
+/// Gets the options of the current scope, if any; e.g. to [enter](enter) the scope on a worker
+/// thread.
+pub(crate) fn current() -> Option<Arc<ScopedOptions>> {
+    CURRENT.with(|current| current.borrow().clone())
+}
+
+/// Runs the `operation` on the current thread with the `options` of a scope.
+pub(crate) fn enter<T, F: FnOnce() -> T>(options: Option<Arc<ScopedOptions>>, operation: F) -> T {
+    let outer = CURRENT.with(|current| current.replace(options));
+    let _restore = Restore(outer);
+    operation()
+}
+
+/// Gets the download options of the current scope, or the
+/// [default download options](crate::default_download_options).
+pub(crate) fn download_options() -> DownloadOptions {
+    current()
+        .and_then(|options| options.download_options.clone())
+        .unwrap_or_else(default_download_options)
+}
+
+/// Gets the archive cache of the current scope, or the [file system cache](FileSystemCache) in the
+/// cache directory of the [download options](download_options) if they
+/// [cache archives](DownloadOptions::cache_archives); `None` if archives are not cached.
+pub(crate) fn cache() -> Option<Arc<dyn ArchiveCache>> {
+    if let Some(cache) = current().and_then(|options| options.cache.clone()) {
+        return Some(cache);
+    }
+    let options = download_options();
+    let (true, Some(cache_dir)) = (options.cache_archives, options.cache_dir) else {
+        return None;
+    };
+    Some(Arc::new(FileSystemCache::new(cache_dir)))
+}
+
+/// Gets the extract filter of the current scope, or the filter of the
+/// [default extract options](crate::extractor::default_extract_options).
+pub(crate) fn extract_filter() -> ExtractFilter {
+    current()
+        .and_then(|options| options.extract_filter.clone())
+        .unwrap_or_else(|| default_extract_options().filter)
+}
+
+/// Gets the asset name pattern of the current scope, if any.
+pub(crate) fn asset_pattern() -> Option<String> {
+    current().and_then(|options| options.asset_pattern.clone())
+}
+
+/// Gets the proxy options of the current scope, or the
+/// [default proxy options](crate::default_proxy_options).
+pub(crate) fn proxy_options() -> ProxyOptions {
+    current()
+        .and_then(|options| options.proxy.clone())
+        .unwrap_or_else(default_proxy_options)
+}
+
+/// Gets the progress reporter of the current scope, if any.
+pub(crate) fn progress_reporter() -> Option<Arc<dyn ProgressReporter>> {
+    current().and_then(|options| options.progress_reporter.clone())
+}
+
+/// Gets the target triple of the current scope, or the target this crate was built for.
+pub(crate) fn target() -> String {
+    current()
+        .and_then(|options| options.target.clone())
+        .unwrap_or_else(|| target_triple::TARGET.to_string())
+}
+
+/// Gets the retry policy of the current scope, or the
+/// [default retry policy](crate::default_retry_policy).
+pub(crate) fn retry_policy() -> RetryPolicy {
+    current()
+        .and_then(|options| options.retry_policy)
+        .unwrap_or_else(default_retry_policy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_with_scoped_options_blocking() {
+        let policy = RetryPolicy::none();
+        let options = ScopedOptions {
+            retry_policy: Some(policy),
+            ..ScopedOptions::default()
+        };
+        assert!(current().is_none());
+        with_scoped_options_blocking(options, || {
+            assert_eq!(policy, retry_policy());
+            let download_options = DownloadOptions {
+                offline: true,
+                ..DownloadOptions::default()
+            };
+            let options = ScopedOptions {
+                download_options: Some(download_options.clone()),
+                ..ScopedOptions::default()
+            };
+            with_scoped_options_blocking(options, || {
+                assert_eq!(policy, retry_policy());
+                assert_eq!(download_options, self::download_options());
+                assert_eq!(target_triple::TARGET, target());
+            });
+            assert_eq!(default_download_options(), self::download_options());
+        });
+        assert!(current().is_none());
+
+        let cache_dir = tempfile::tempdir().expect("cache directory");
+        let cache: Arc<dyn ArchiveCache> = Arc::new(FileSystemCache::new(cache_dir.path()));
+        let options = ScopedOptions {
+            cache: Some(cache.clone()),
+            ..ScopedOptions::default()
+        };
+        with_scoped_options_blocking(options.clone(), || {
+            assert_eq!(Some(&options), current().as_deref());
+            assert!(self::cache().is_some_and(|scoped| Arc::ptr_eq(&scoped, &cache)));
+        });
+        assert!(current().is_none());
+
+        let filter = ExtractFilter::new().exclude("include/**").expect("filter");
+        let options = ScopedOptions {
+            extract_filter: Some(filter.clone()),
+            ..ScopedOptions::default()
+        };
+        with_scoped_options_blocking(options, || {
+            assert_eq!(filter, extract_filter());
+        });
+        assert_eq!(default_extract_options().filter, extract_filter());
+
+        let proxy = ProxyOptions::new("http://proxy.example.com:3128");
+        let options = ScopedOptions {
+            proxy: Some(proxy.clone()),
+            ..ScopedOptions::default()
+        };
+        with_scoped_options_blocking(options, || {
+            assert_eq!(proxy, proxy_options());
+        });
+        assert_eq!(default_proxy_options(), proxy_options());
+    }
+
+    #[tokio::test]
+    async fn test_with_scoped_options() {
+        let policy = RetryPolicy {
+            max_attempts: 2,
+            ..RetryPolicy::default()
+        };
+        let options = ScopedOptions {
+            retry_policy: Some(policy),
+            ..ScopedOptions::default()
+        };
+        let scoped_policy = with_scoped_options(options, async {
+            tokio::task::yield_now().await;
+            let options = ScopedOptions {
+                target: Some("aarch64-apple-darwin".to_string()),
+                ..ScopedOptions::default()
+            };
+            let target = with_scoped_options(options, async { target() }).await;
+            assert_eq!("aarch64-apple-darwin", target);
+            assert!(asset_pattern().is_none());
+            retry_policy()
+        })
+        .await;
+        assert_eq!(policy, scoped_policy);
+        assert!(current().is_none());
+    }
+}
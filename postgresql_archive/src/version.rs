@@ -1,5 +1,105 @@
 use crate::Result;
-use semver::{Version, VersionReq};
+use regex_lite::Regex;
+use semver::{Prerelease, Version, VersionReq};
+use std::str::FromStr;
+use std::sync::LazyLock;
+
+/// Matches pre-release version names in the `PostgreSQL` format (e.g. `18beta1`, `18rc1`)
+static PRERELEASE_VERSION: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"(?i)^(\d+)(?:\.(\d+))?(?:\.(\d+))?[-_.]?(beta|rc)[.]?(\d+)$").expect("regex")
+});
+
+/// Release channel of `PostgreSQL` versions. Each channel includes the releases of the more
+/// stable channels; e.g. the [beta](Channel::Beta) channel resolves to a release candidate or
+/// stable release if it is newer than the latest beta.
+#[derive(Clone, Copy, Debug, Default, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Channel {
+    /// Stable releases
+    #[default]
+    Stable,
+    /// Release candidates (e.g. `18.0.0-rc.1`)
+    ReleaseCandidate,
+    /// Beta releases (e.g. `18.0.0-beta.1`)
+    Beta,
+}
+
+impl Channel {
+    /// Gets the channel of a [version](Version), or `None` if the version is a pre-release that
+    /// does not belong to a channel (e.g. an alpha release).
+    #[must_use]
+    pub fn of(version: &Version) -> Option<Channel> {
+        let pre = version.pre.as_str().to_lowercase();
+        if pre.is_empty() {
+            Some(Channel::Stable)
+        } else if pre.starts_with("rc") {
+            Some(Channel::ReleaseCandidate)
+        } else if pre.starts_with("beta") {
+            Some(Channel::Beta)
+        } else {
+            None
+        }
+    }
+
+    /// Checks if the [version](Version) matches the [version requirement](VersionReq) in this
+    /// channel. Pre-releases in the channel match if the release they precede matches the
+    /// requirement; e.g. `18.0.0-beta.1` matches `18` in the [beta](Channel::Beta) channel.
+    /// Pre-releases explicitly named by the requirement (e.g. `=18.0.0-beta.1`) match in any
+    /// channel.
+    #[must_use]
+    pub fn matches(&self, version_req: &VersionReq, version: &Version) -> bool {
+        if version_req.matches(version) {
+            return true;
+        }
+        if version.pre.is_empty() {
+            return false;
+        }
+        match Channel::of(version) {
+            Some(channel) if channel <= *self => {
+                let mut release = version.clone();
+                release.pre = Prerelease::EMPTY;
+                version_req.matches(&release)
+            }
+            _ => false,
+        }
+    }
+}
+
+/// Gets the name of a version in the `PostgreSQL` format; pre-release versions are named by major
+/// version and pre-release (e.g. `18.0.0-beta.1` is named `18beta1`).
+pub(crate) fn postgresql_version_name(version: &Version) -> String {
+    if version.pre.is_empty() {
+        version.to_string()
+    } else {
+        format!("{}{}", version.major, version.pre.as_str().replace('.', ""))
+    }
+}
+
+/// Parses a version, including pre-release versions in the `PostgreSQL` format (e.g. `18beta1`
+/// is parsed as `18.0.0-beta.1`).
+///
+/// # Errors
+/// * If the version cannot be parsed.
+pub(crate) fn parse_version(value: &str) -> Result<Version> {
+    let error = match Version::from_str(value) {
+        Ok(version) => return Ok(version),
+        Err(error) => error,
+    };
+    let Some(captures) = PRERELEASE_VERSION.captures(value) else {
+        return Err(error.into());
+    };
+    let number = |index: usize| {
+        captures
+            .get(index)
+            .map_or(Ok(0), |value| value.as_str().parse::<u64>())
+    };
+    let (Ok(major), Ok(minor), Ok(patch)) = (number(1), number(2), number(3)) else {
+        return Err(error.into());
+    };
+    let channel = captures[4].to_lowercase();
+    let mut version = Version::new(major, minor, patch);
+    version.pre = Prerelease::new(&format!("{channel}.{}", &captures[5]))?;
+    Ok(version)
+}
 
 /// A trait for getting the exact version from a [version requirement](VersionReq).
 pub trait ExactVersion {
@@ -19,7 +119,8 @@ impl ExactVersion for VersionReq {
         }
         let minor = comparator.minor?;
         let patch = comparator.patch?;
-        let version = Version::new(comparator.major, minor, patch);
+        let mut version = Version::new(comparator.major, minor, patch);
+        version.pre = comparator.pre.clone();
         Some(version)
     }
 }
@@ -50,6 +151,79 @@ mod tests {
     use super::*;
     use crate::Result;
 
+    #[test]
+    fn test_exact_version_req_prerelease() -> Result<()> {
+        let version_req = VersionReq::parse("=18.0.0-beta.1")?;
+        let version = Version::parse("18.0.0-beta.1")?;
+        assert_eq!(Some(version), version_req.exact_version());
+        Ok(())
+    }
+
+    #[test]
+    fn test_channel_of() -> Result<()> {
+        for (version, channel) in [
+            ("18.0.0", Some(Channel::Stable)),
+            ("18.0.0-rc.1", Some(Channel::ReleaseCandidate)),
+            ("18.0.0-beta.1", Some(Channel::Beta)),
+            ("18.0.0-beta1", Some(Channel::Beta)),
+            ("18.0.0-alpha.1", None),
+        ] {
+            assert_eq!(channel, Channel::of(&Version::parse(version)?));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_channel_matches() -> Result<()> {
+        let version_req = VersionReq::parse("18")?;
+        let beta = Version::parse("18.0.0-beta.1")?;
+        let rc = Version::parse("18.0.0-rc.1")?;
+        let stable = Version::parse("18.0.0")?;
+
+        assert!(!Channel::Stable.matches(&version_req, &beta));
+        assert!(!Channel::Stable.matches(&version_req, &rc));
+        assert!(Channel::Stable.matches(&version_req, &stable));
+        assert!(!Channel::ReleaseCandidate.matches(&version_req, &beta));
+        assert!(Channel::ReleaseCandidate.matches(&version_req, &rc));
+        assert!(Channel::Beta.matches(&version_req, &beta));
+        assert!(Channel::Beta.matches(&version_req, &rc));
+        assert!(Channel::Beta.matches(&version_req, &stable));
+        assert!(!Channel::Beta.matches(&VersionReq::parse("17")?, &beta));
+
+        let version_req = VersionReq::parse("=18.0.0-beta.1")?;
+        assert!(Channel::Stable.matches(&version_req, &beta));
+        Ok(())
+    }
+
+    #[test]
+    fn test_postgresql_version_name() -> Result<()> {
+        assert_eq!(
+            "16.4.0",
+            postgresql_version_name(&Version::parse("16.4.0")?)
+        );
+        assert_eq!(
+            "18beta1",
+            postgresql_version_name(&Version::parse("18.0.0-beta.1")?)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_parse_version() -> Result<()> {
+        for (value, version) in [
+            ("16.4.0", "16.4.0"),
+            ("18.0.0-beta.1", "18.0.0-beta.1"),
+            ("18beta1", "18.0.0-beta.1"),
+            ("18rc2", "18.0.0-rc.2"),
+            ("18_BETA1", "18.0.0-beta.1"),
+            ("18.1-rc1", "18.1.0-rc.1"),
+        ] {
+            assert_eq!(Version::parse(version)?, parse_version(value)?);
+        }
+        assert!(parse_version("foo").is_err());
+        Ok(())
+    }
+
     #[test]
     fn test_exact_version_star() {
         let version_req = VersionReq::STAR;
@@ -1,8 +1,9 @@
 use postgresql_archive::configuration::theseus;
-use postgresql_archive::extract;
+use postgresql_archive::{extract, extract_with_progress, ExtractProgress};
 use postgresql_archive::{get_archive, get_version};
 use semver::VersionReq;
 use std::fs::remove_dir_all;
+use std::sync::{Arc, Mutex};
 use test_log::test;
 
 #[test(tokio::test)]
@@ -45,6 +46,32 @@ async fn test_get_archive_and_extract() -> anyhow::Result<()> {
     Ok(())
 }
 
+#[test(tokio::test)]
+async fn test_get_archive_and_extract_with_progress() -> anyhow::Result<()> {
+    let url = theseus::URL;
+    let version_req = VersionReq::parse("=16.4.0")?;
+    let (_archive_version, archive) = get_archive(url, &version_req).await?;
+
+    let out_dir = tempfile::tempdir()?.path().to_path_buf();
+    let last_progress = Arc::new(Mutex::new(ExtractProgress::default()));
+    let progress = last_progress.clone();
+    let files = extract_with_progress(url, &archive, &out_dir, move |extract_progress| {
+        *progress.lock().expect("lock") = *extract_progress;
+    })
+    .await?;
+
+    let last_progress = *last_progress.lock().expect("lock");
+    assert_eq!(files.len() as u64, last_progress.files);
+    assert!(last_progress.bytes > 0);
+    assert_eq!(
+        Some(archive.len() as u64),
+        last_progress.archive_bytes_total
+    );
+    assert!(last_progress.archive_bytes <= archive.len() as u64);
+    remove_dir_all(&out_dir)?;
+    Ok(())
+}
+
 #[test(tokio::test)]
 async fn test_get_archive_version_not_found() -> postgresql_archive::Result<()> {
     let invalid_version_req = VersionReq::parse("=1.0.0")?;
@@ -1,12 +1,30 @@
+use std::ffi::OsStr;
+
 /// `PostgreSQL` command result type
 pub type Result<T, E = Error> = core::result::Result<T, E>;
 
 /// `PostgreSQL` command errors
 #[derive(Debug, thiserror::Error)]
 pub enum Error {
-    /// Error when a command fails
-    #[error("Command error: stdout={stdout}; stderr={stderr}")]
-    CommandError { stdout: String, stderr: String },
+    /// Error when a command exits with a non-zero exit code; the code is `None` if the command
+    /// was terminated by a signal
+    #[error(
+        "Command error: code={}; stdout={stdout}; stderr={stderr}",
+        .code.map_or_else(|| "none".to_string(), |code| code.to_string())
+    )]
+    CommandError {
+        code: Option<i32>,
+        stdout: String,
+        stderr: String,
+    },
+    /// Error when a command cannot be started; e.g. the program does not exist or is not
+    /// executable
+    #[error("failed to execute {program}: {message}")]
+    SpawnError {
+        program: String,
+        kind: std::io::ErrorKind,
+        message: String,
+    },
     /// Error when IO operations fail
     #[error("{0}")]
     IoError(String),
@@ -15,6 +33,28 @@ pub enum Error {
     TimeoutError(String),
 }
 
+impl Error {
+    /// Create a [spawn error](Error::SpawnError) for the `program` from the `error` returned when
+    /// starting it.
+    #[must_use]
+    pub fn spawn(program: &OsStr, error: &std::io::Error) -> Self {
+        Error::SpawnError {
+            program: program.to_string_lossy().to_string(),
+            kind: error.kind(),
+            message: error.to_string(),
+        }
+    }
+
+    /// Returns the exit code of the command if it exited with a non-zero exit code.
+    #[must_use]
+    pub fn exit_code(&self) -> Option<i32> {
+        match self {
+            Error::CommandError { code, .. } => *code,
+            _ => None,
+        }
+    }
+}
+
 /// Convert [standard IO errors](std::io::Error) to a [embedded errors](Error::IoError)
 impl From<std::io::Error> for Error {
     fn from(error: std::io::Error) -> Self {
@@ -43,6 +83,32 @@ mod test {
         assert_eq!(error.to_string(), "test");
     }
 
+    #[test]
+    fn test_spawn_error() {
+        let io_error = std::io::Error::new(std::io::ErrorKind::NotFound, "not found");
+        let error = Error::spawn(OsStr::new("initdb"), &io_error);
+        assert!(matches!(
+            error,
+            Error::SpawnError {
+                kind: std::io::ErrorKind::NotFound,
+                ..
+            }
+        ));
+        assert_eq!(error.to_string(), "failed to execute initdb: not found");
+        assert_eq!(None, error.exit_code());
+    }
+
+    #[test]
+    fn test_exit_code() {
+        let error = Error::CommandError {
+            code: Some(2),
+            stdout: String::new(),
+            stderr: String::new(),
+        };
+        assert_eq!(Some(2), error.exit_code());
+        assert_eq!(error.to_string(), "Command error: code=2; stdout=; stderr=");
+    }
+
     #[cfg(feature = "tokio")]
     #[tokio::test]
     async fn test_from_elapsed_error() {
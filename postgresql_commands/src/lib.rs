@@ -51,4 +51,4 @@ pub mod vacuumlo;
 pub use error::{Error, Result};
 #[cfg(test)]
 pub use traits::TestSettings;
-pub use traits::{AsyncCommandExecutor, CommandBuilder, CommandExecutor, Settings};
+pub use traits::{AsyncCommandExecutor, CommandBuilder, CommandExecutor, CommandOutput, Settings};
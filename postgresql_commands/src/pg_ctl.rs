@@ -25,6 +25,11 @@ pub struct PgCtlBuilder {
     shutdown_mode: Option<ShutdownMode>,
     signal: Option<OsString>,
     pid: Option<OsString>,
+    service_name: Option<OsString>,
+    service_username: Option<OsString>,
+    service_password: Option<OsString>,
+    start_type: Option<StartType>,
+    event_source: Option<OsString>,
 }
 
 #[derive(Clone, Debug)]
@@ -33,11 +38,13 @@ pub enum Mode {
     Kill,
     LogRotate,
     Promote,
+    Register,
     Restart,
     Reload,
     Start,
     Stop,
     Status,
+    Unregister,
 }
 
 impl Display for Mode {
@@ -47,11 +54,13 @@ impl Display for Mode {
             Mode::Kill => write!(formatter, "kill"),
             Mode::LogRotate => write!(formatter, "logrotate"),
             Mode::Promote => write!(formatter, "promote"),
+            Mode::Register => write!(formatter, "register"),
             Mode::Restart => write!(formatter, "restart"),
             Mode::Reload => write!(formatter, "reload"),
             Mode::Start => write!(formatter, "start"),
             Mode::Stop => write!(formatter, "stop"),
             Mode::Status => write!(formatter, "status"),
+            Mode::Unregister => write!(formatter, "unregister"),
         }
     }
 }
@@ -73,6 +82,21 @@ impl Display for ShutdownMode {
     }
 }
 
+#[derive(Clone, Debug)]
+pub enum StartType {
+    Auto,
+    Demand,
+}
+
+impl Display for StartType {
+    fn fmt(&self, formatter: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            StartType::Auto => write!(formatter, "auto"),
+            StartType::Demand => write!(formatter, "demand"),
+        }
+    }
+}
+
 impl PgCtlBuilder {
     /// Create a new [`PgCtlBuilder`]
     #[must_use]
@@ -196,6 +220,41 @@ impl PgCtlBuilder {
         self.pid = Some(pid.as_ref().to_os_string());
         self
     }
+
+    /// service name with which to register `PostgreSQL` server
+    #[must_use]
+    pub fn service_name<S: AsRef<OsStr>>(mut self, service_name: S) -> Self {
+        self.service_name = Some(service_name.as_ref().to_os_string());
+        self
+    }
+
+    /// user name of account to register `PostgreSQL` server
+    #[must_use]
+    pub fn service_username<S: AsRef<OsStr>>(mut self, service_username: S) -> Self {
+        self.service_username = Some(service_username.as_ref().to_os_string());
+        self
+    }
+
+    /// password of account to register `PostgreSQL` server
+    #[must_use]
+    pub fn service_password<S: AsRef<OsStr>>(mut self, service_password: S) -> Self {
+        self.service_password = Some(service_password.as_ref().to_os_string());
+        self
+    }
+
+    /// service start type to register `PostgreSQL` server
+    #[must_use]
+    pub fn start_type(mut self, start_type: StartType) -> Self {
+        self.start_type = Some(start_type);
+        self
+    }
+
+    /// event source for logging when running as a service
+    #[must_use]
+    pub fn event_source<S: AsRef<OsStr>>(mut self, event_source: S) -> Self {
+        self.event_source = Some(event_source.as_ref().to_os_string());
+        self
+    }
 }
 
 impl CommandBuilder for PgCtlBuilder {
@@ -279,6 +338,31 @@ impl CommandBuilder for PgCtlBuilder {
             args.push(pid.into());
         }
 
+        if let Some(service_name) = &self.service_name {
+            args.push("-N".into());
+            args.push(service_name.into());
+        }
+
+        if let Some(service_username) = &self.service_username {
+            args.push("-U".into());
+            args.push(service_username.into());
+        }
+
+        if let Some(service_password) = &self.service_password {
+            args.push("-P".into());
+            args.push(service_password.into());
+        }
+
+        if let Some(start_type) = &self.start_type {
+            args.push("-S".into());
+            args.push(start_type.to_string().into());
+        }
+
+        if let Some(event_source) = &self.event_source {
+            args.push("-e".into());
+            args.push(event_source.into());
+        }
+
         args
     }
 
@@ -308,11 +392,19 @@ mod tests {
         assert_eq!("kill", Mode::Kill.to_string());
         assert_eq!("logrotate", Mode::LogRotate.to_string());
         assert_eq!("promote", Mode::Promote.to_string());
+        assert_eq!("register", Mode::Register.to_string());
         assert_eq!("restart", Mode::Restart.to_string());
         assert_eq!("reload", Mode::Reload.to_string());
         assert_eq!("start", Mode::Start.to_string());
         assert_eq!("stop", Mode::Stop.to_string());
         assert_eq!("status", Mode::Status.to_string());
+        assert_eq!("unregister", Mode::Unregister.to_string());
+    }
+
+    #[test]
+    fn test_display_start_type() {
+        assert_eq!("auto", StartType::Auto.to_string());
+        assert_eq!("demand", StartType::Demand.to_string());
     }
 
     #[test]
@@ -361,6 +453,11 @@ mod tests {
             .shutdown_mode(ShutdownMode::Smart)
             .signal("HUP")
             .pid("12345")
+            .service_name("postgresql")
+            .service_username("username")
+            .service_password("password")
+            .start_type(StartType::Auto)
+            .event_source("event_source")
             .build();
         #[cfg(not(target_os = "windows"))]
         let command_prefix = r#"PGDATABASE="database" "#;
@@ -369,7 +466,7 @@ mod tests {
 
         assert_eq!(
             format!(
-                r#"{command_prefix}"pg_ctl" "start" "--pgdata" "pgdata" "--silent" "--timeout" "60" "--version" "--wait" "--no-wait" "--help" "--core-files" "--log" "log" "-o" "-c log_connections=on" "-p" "path_to_postgres" "--mode" "smart" "HUP" "12345""#
+                r#"{command_prefix}"pg_ctl" "start" "--pgdata" "pgdata" "--silent" "--timeout" "60" "--version" "--wait" "--no-wait" "--help" "--core-files" "--log" "log" "-o" "-c log_connections=on" "-p" "path_to_postgres" "--mode" "smart" "HUP" "12345" "-N" "postgresql" "-U" "username" "-P" "password" "-S" "auto" "-e" "event_source""#
             ),
             command.to_command_string()
         );
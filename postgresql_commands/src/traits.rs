@@ -4,8 +4,9 @@ use std::ffi::{OsStr, OsString};
 use std::fmt::Debug;
 use std::path::PathBuf;
 use std::process::ExitStatus;
-use std::time::Duration;
-use tracing::debug;
+use std::time::{Duration, Instant};
+use tracing::field::Empty;
+use tracing::{debug, instrument, Span};
 
 /// Interface for `PostgreSQL` settings
 pub trait Settings {
@@ -121,79 +122,151 @@ impl CommandToString for tokio::process::Command {
     }
 }
 
+/// Output of a command that exited successfully
+#[derive(Clone, Debug)]
+pub struct CommandOutput {
+    /// Exit status of the command
+    pub status: ExitStatus,
+    /// Standard output of the command
+    pub stdout: String,
+    /// Standard error of the command
+    pub stderr: String,
+    /// Time taken to execute the command
+    pub duration: Duration,
+}
+
+impl CommandOutput {
+    /// Returns the exit code of the command, if it was not terminated by a signal.
+    #[must_use]
+    pub fn code(&self) -> Option<i32> {
+        self.status.code()
+    }
+
+    /// Create the output of a command that exited with the `status`, or a
+    /// [command error](Error::CommandError) if the command did not exit successfully.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the command exited with a non-zero exit code
+    fn from_status(
+        status: ExitStatus,
+        stdout: String,
+        stderr: String,
+        started: Instant,
+    ) -> Result<Self> {
+        debug!(
+            "Result: {}\nstdout: {}\nstderr: {}",
+            status.code().map_or("None".to_string(), |c| c.to_string()),
+            stdout,
+            stderr
+        );
+        let duration = started.elapsed();
+        record_result(status, duration);
+
+        if status.success() {
+            Ok(Self {
+                status,
+                stdout,
+                stderr,
+                duration,
+            })
+        } else {
+            Err(Error::CommandError {
+                code: status.code(),
+                stdout,
+                stderr,
+            })
+        }
+    }
+}
+
 /// Interface for executing a command
 pub trait CommandExecutor {
-    /// Execute the command and return the stdout and stderr
+    /// Execute the command and return its output
     ///
     /// # Errors
     ///
-    /// Returns an error if the command fails
-    fn execute(&mut self) -> Result<(String, String)>;
+    /// Returns a [spawn error](Error::SpawnError) if the command cannot be started, or a
+    /// [command error](Error::CommandError) if the command exits with a non-zero exit code
+    fn execute(&mut self) -> Result<CommandOutput>;
 }
 
 /// Interface for executing a command
 pub trait AsyncCommandExecutor {
-    /// Execute the command and return the stdout and stderr
-    async fn execute(&mut self, timeout: Option<Duration>) -> Result<(String, String)>;
+    /// Execute the command and return its output
+    ///
+    /// # Errors
+    ///
+    /// Returns a [spawn error](Error::SpawnError) if the command cannot be started, a
+    /// [command error](Error::CommandError) if the command exits with a non-zero exit code, or a
+    /// [timeout error](Error::TimeoutError) if the command does not complete within the `timeout`
+    async fn execute(&mut self, timeout: Option<Duration>) -> Result<CommandOutput>;
 }
 
 /// Implement the [`CommandExecutor`] trait for [`Command`](std::process::Command)
 impl CommandExecutor for std::process::Command {
-    /// Execute the command and return the stdout and stderr
-    fn execute(&mut self) -> Result<(String, String)> {
+    /// Execute the command and return its output
+    #[instrument(
+        level = "debug",
+        name = "command",
+        skip(self),
+        fields(program = %self.get_program().to_string_lossy(), exit_code = Empty, duration_ms = Empty)
+    )]
+    fn execute(&mut self) -> Result<CommandOutput> {
         debug!("Executing command: {}", self.to_command_string());
-        let program = self.get_program().to_string_lossy().to_string();
+        let started = Instant::now();
+        let program = self.get_program().to_os_string();
         let stdout: String;
         let stderr: String;
         let status: ExitStatus;
 
-        if OS == "windows" && program.as_str().ends_with("pg_ctl") {
+        if OS == "windows" && program.to_string_lossy().ends_with("pg_ctl") {
             // The pg_ctl process can hang on Windows when attempting to get stdout/stderr.
             let mut process = self
                 .stdout(std::process::Stdio::piped())
                 .stderr(std::process::Stdio::piped())
-                .spawn()?;
+                .spawn()
+                .map_err(|error| Error::spawn(&program, &error))?;
             stdout = String::new();
             stderr = String::new();
             status = process.wait()?;
         } else {
-            let output = self.output()?;
+            let output = self
+                .output()
+                .map_err(|error| Error::spawn(&program, &error))?;
             stdout = String::from_utf8_lossy(&output.stdout).into_owned();
             stderr = String::from_utf8_lossy(&output.stderr).into_owned();
             status = output.status;
         }
-        debug!(
-            "Result: {}\nstdout: {}\nstderr: {}",
-            status.code().map_or("None".to_string(), |c| c.to_string()),
-            stdout,
-            stderr
-        );
-
-        if status.success() {
-            Ok((stdout, stderr))
-        } else {
-            Err(Error::CommandError { stdout, stderr })
-        }
+        CommandOutput::from_status(status, stdout, stderr, started)
     }
 }
 
 #[cfg(feature = "tokio")]
 /// Implement the [`CommandExecutor`] trait for [`Command`](tokio::process::Command)
 impl AsyncCommandExecutor for tokio::process::Command {
-    /// Execute the command and return the stdout and stderr
-    async fn execute(&mut self, timeout: Option<Duration>) -> Result<(String, String)> {
+    /// Execute the command and return its output
+    #[instrument(
+        level = "debug",
+        name = "command",
+        skip(self),
+        fields(program = %self.as_std().get_program().to_string_lossy(), exit_code = Empty, duration_ms = Empty)
+    )]
+    async fn execute(&mut self, timeout: Option<Duration>) -> Result<CommandOutput> {
         debug!("Executing command: {}", self.to_command_string());
-        let program = self.as_std().get_program().to_string_lossy().to_string();
+        let started = Instant::now();
+        let program = self.as_std().get_program().to_os_string();
         let stdout: String;
         let stderr: String;
         let status: ExitStatus;
 
-        if OS == "windows" && program.as_str().ends_with("pg_ctl") {
+        if OS == "windows" && program.to_string_lossy().ends_with("pg_ctl") {
             // The pg_ctl process can hang on Windows when attempting to get stdout/stderr.
             let mut process = self
                 .stdout(std::process::Stdio::piped())
                 .stderr(std::process::Stdio::piped())
-                .spawn()?;
+                .spawn()
+                .map_err(|error| Error::spawn(&program, &error))?;
             stdout = String::new();
             stderr = String::new();
             status = process.wait().await?;
@@ -201,26 +274,26 @@ impl AsyncCommandExecutor for tokio::process::Command {
             let output = match timeout {
                 Some(duration) => tokio::time::timeout(duration, self.output()).await?,
                 None => self.output().await,
-            }?;
+            }
+            .map_err(|error| Error::spawn(&program, &error))?;
             stdout = String::from_utf8_lossy(&output.stdout).into_owned();
             stderr = String::from_utf8_lossy(&output.stderr).into_owned();
             status = output.status;
         }
+        CommandOutput::from_status(status, stdout, stderr, started)
+    }
+}
 
-        debug!(
-            "Result: {}\nstdout: {}\nstderr: {}",
-            status.code().map_or("None".to_string(), |c| c.to_string()),
-            stdout,
-            stderr
-        );
-
-        if status.success() {
-            Ok((stdout, stderr))
-        } else {
-            Err(Error::CommandError { stdout, stderr })
-        }
+/// Record the exit code and duration of the command on the current span.
+fn record_result(status: ExitStatus, duration: Duration) {
+    let span = Span::current();
+    if let Some(code) = status.code() {
+        span.record("exit_code", code);
     }
+    let duration_ms = u64::try_from(duration.as_millis()).unwrap_or(u64::MAX);
+    span.record("duration_ms", duration_ms);
 }
+
 #[cfg(test)]
 mod test {
     use super::*;
@@ -366,16 +439,37 @@ mod test {
         #[cfg(target_os = "windows")]
         command.args(["/C", "echo foo"]);
 
-        let (stdout, stderr) = command.execute()?;
-        assert!(stdout.starts_with("foo"));
-        assert!(stderr.is_empty());
+        let output = command.execute()?;
+        assert!(output.stdout.starts_with("foo"));
+        assert!(output.stderr.is_empty());
+        assert_eq!(Some(0), output.code());
         Ok(())
     }
 
     #[test(tokio::test)]
     async fn test_standard_command_execute_error() {
         let mut command = std::process::Command::new("bogus_command");
-        assert!(command.execute().is_err());
+        assert!(matches!(
+            command.execute(),
+            Err(Error::SpawnError {
+                kind: std::io::ErrorKind::NotFound,
+                ..
+            })
+        ));
+    }
+
+    #[cfg(not(target_os = "windows"))]
+    #[test(tokio::test)]
+    async fn test_standard_command_execute_exit_code() {
+        let mut command = std::process::Command::new("sh");
+        command.args(["-c", "echo foo >&2; exit 3"]);
+        match command.execute() {
+            Err(Error::CommandError { code, stderr, .. }) => {
+                assert_eq!(Some(3), code);
+                assert!(stderr.starts_with("foo"));
+            }
+            result => panic!("expected command error: {result:?}"),
+        }
     }
 
     #[cfg(feature = "tokio")]
@@ -391,9 +485,10 @@ mod test {
         #[cfg(target_os = "windows")]
         command.args(["/C", "echo foo"]);
 
-        let (stdout, stderr) = command.execute(None).await?;
-        assert!(stdout.starts_with("foo"));
-        assert!(stderr.is_empty());
+        let output = command.execute(None).await?;
+        assert!(output.stdout.starts_with("foo"));
+        assert!(output.stderr.is_empty());
+        assert_eq!(Some(0), output.code());
         Ok(())
     }
 
@@ -401,7 +496,22 @@ mod test {
     #[test(tokio::test)]
     async fn test_tokio_command_execute_error() -> Result<()> {
         let mut command = tokio::process::Command::new("bogus_command");
-        assert!(command.execute(None).await.is_err());
+        assert!(matches!(
+            command.execute(None).await,
+            Err(Error::SpawnError {
+                kind: std::io::ErrorKind::NotFound,
+                ..
+            })
+        ));
         Ok(())
     }
+
+    #[cfg(all(feature = "tokio", not(target_os = "windows")))]
+    #[test(tokio::test)]
+    async fn test_tokio_command_execute_exit_code() {
+        let mut command = tokio::process::Command::new("sh");
+        command.args(["-c", "exit 3"]);
+        let error = command.execute(None).await.expect_err("command error");
+        assert_eq!(Some(3), error.exit_code());
+    }
 }
@@ -1,10 +1,45 @@
 use criterion::{criterion_group, criterion_main, Criterion};
 use postgresql_embedded::blocking::PostgreSQL;
-use postgresql_embedded::Result;
+use postgresql_embedded::{Result, Settings, SettingsBuilder, Timings, VersionReq};
+use std::env;
 use std::time::Duration;
 
 fn benchmarks(criterion: &mut Criterion) {
     bench_lifecycle(criterion).ok();
+    bench_cold_setup(criterion).ok();
+    bench_initialize(criterion).ok();
+    bench_warm_start(criterion).ok();
+    bench_database_operations(criterion).ok();
+}
+
+/// Settings for the version requested with the `POSTGRESQL_VERSION` environment variable, or the
+/// default version.
+fn settings() -> Result<Settings> {
+    let mut settings = Settings::default();
+    if let Ok(version) = env::var("POSTGRESQL_VERSION") {
+        settings.version = VersionReq::parse(&version)?;
+    }
+    Ok(settings)
+}
+
+/// Name of the benchmark for the platform and version, so results can be compared across both.
+fn name(benchmark: &str, settings: &Settings) -> String {
+    format!("{benchmark}/{}/{}", env::consts::OS, settings.version)
+}
+
+/// Sum the duration of an operation over `iterations`, as reported by [`PostgreSQL::timings`].
+fn measure(
+    iterations: u64,
+    mut operation: impl FnMut() -> Result<Timings>,
+    duration: fn(&Timings) -> Option<Duration>,
+) -> Duration {
+    let mut total = Duration::ZERO;
+    for _ in 0..iterations {
+        if let Ok(timings) = operation() {
+            total += duration(&timings).unwrap_or_default();
+        }
+    }
+    total
 }
 
 fn bench_lifecycle(criterion: &mut Criterion) -> Result<()> {
@@ -24,6 +59,98 @@ fn lifecycle() -> Result<()> {
     postgresql.stop()
 }
 
+/// Setup with an empty installation directory; includes downloading and extracting the archive.
+fn bench_cold_setup(criterion: &mut Criterion) -> Result<()> {
+    let settings = settings()?;
+    criterion.bench_function(&name("cold_setup", &settings), |bencher| {
+        bencher.iter(|| {
+            let Ok(installation_dir) = tempfile::tempdir() else {
+                return;
+            };
+            let settings = SettingsBuilder::from_settings(settings.clone())
+                .installation_dir(installation_dir.path())
+                .build();
+            PostgreSQL::new(settings).setup().ok();
+        });
+    });
+
+    Ok(())
+}
+
+/// Initialization of a new data directory with `initdb` for an installed version.
+fn bench_initialize(criterion: &mut Criterion) -> Result<()> {
+    let settings = settings()?;
+    PostgreSQL::new(settings.clone()).setup()?;
+    criterion.bench_function(&name("initialize", &settings), |bencher| {
+        bencher.iter_custom(|iterations| {
+            measure(
+                iterations,
+                || {
+                    let mut postgresql = PostgreSQL::new(settings.clone());
+                    postgresql.setup()?;
+                    Ok(postgresql.timings())
+                },
+                |timings| timings.initialize,
+            )
+        });
+    });
+
+    Ok(())
+}
+
+/// Startup of an installed and initialized server.
+fn bench_warm_start(criterion: &mut Criterion) -> Result<()> {
+    let mut postgresql = PostgreSQL::new(settings()?);
+    postgresql.setup()?;
+    let name = name("warm_start", postgresql.settings());
+    criterion.bench_function(&name, |bencher| {
+        bencher.iter_custom(|iterations| {
+            measure(
+                iterations,
+                || {
+                    postgresql.start()?;
+                    let timings = postgresql.timings();
+                    postgresql.stop()?;
+                    Ok(timings)
+                },
+                |timings| timings.start,
+            )
+        });
+    });
+
+    Ok(())
+}
+
+/// Creating and dropping databases on a running server.
+fn bench_database_operations(criterion: &mut Criterion) -> Result<()> {
+    let mut postgresql = PostgreSQL::new(settings()?);
+    postgresql.setup()?;
+    postgresql.start()?;
+    let database_name = "bench";
+    for (benchmark, duration) in [
+        (
+            "create_database",
+            (|timings: &Timings| timings.create_database) as fn(&Timings) -> Option<Duration>,
+        ),
+        ("drop_database", |timings: &Timings| timings.drop_database),
+    ] {
+        criterion.bench_function(&name(benchmark, postgresql.settings()), |bencher| {
+            bencher.iter_custom(|iterations| {
+                measure(
+                    iterations,
+                    || {
+                        postgresql.create_database(database_name)?;
+                        postgresql.drop_database(database_name)?;
+                        Ok(postgresql.timings())
+                    },
+                    duration,
+                )
+            });
+        });
+    }
+    postgresql.stop()
+}
+
 criterion_group!(
     name = benches;
     config = Criterion::default()
@@ -5,6 +5,9 @@ use anyhow::Result;
 
 #[tokio::main]
 async fn main() -> Result<()> {
+    // Target the crate is built for, to verify that the installed binaries can be executed
+    let target = std::env::var("TARGET").unwrap_or(target_triple::TARGET.to_string());
+    println!("cargo:rustc-env=POSTGRESQL_EMBEDDED_TARGET={target}");
     #[cfg(feature = "bundled")]
     bundle::stage_postgresql_archive().await?;
     Ok(())
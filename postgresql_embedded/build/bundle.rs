@@ -3,7 +3,7 @@
 use anyhow::Result;
 use postgresql_archive::repository::github::repository::GitHub;
 use postgresql_archive::VersionReq;
-use postgresql_archive::{get_archive, repository};
+use postgresql_archive::{get_archive_for_target, repository};
 use std::fs::File;
 use std::io::Write;
 use std::path::PathBuf;
@@ -25,25 +25,35 @@ pub(crate) async fn stage_postgresql_archive() -> Result<()> {
     let postgres_version_req = env::var("POSTGRESQL_VERSION").unwrap_or("*".to_string());
     let version_req = VersionReq::from_str(postgres_version_req.as_str())?;
     println!("PostgreSQL version: {postgres_version_req}");
-    println!("Target: {}", target_triple::TARGET);
+    // The build script is compiled for the host; the archive must match the target being built
+    let target = env::var("TARGET").unwrap_or(target_triple::TARGET.to_string());
+    println!("Target: {target}");
 
     let out_dir = PathBuf::from(env::var("OUT_DIR")?);
     println!("OUT_DIR: {:?}", out_dir);
 
     let mut archive_version_file = out_dir.clone();
     archive_version_file.push("postgresql.version");
+    let mut archive_target_file = out_dir.clone();
+    archive_target_file.push("postgresql.target");
     let mut archive_file = out_dir.clone();
     archive_file.push("postgresql.tar.gz");
 
-    if archive_version_file.exists() && archive_file.exists() {
+    if archive_version_file.exists()
+        && archive_file.exists()
+        && fs::read_to_string(&archive_target_file)
+            .is_ok_and(|archive_target| archive_target == target)
+    {
         println!("PostgreSQL archive exists: {:?}", archive_file);
         return Ok(());
     }
 
     register_github_repository()?;
-    let (asset_version, archive) = get_archive(&releases_url, &version_req).await?;
+    let (asset_version, archive) =
+        get_archive_for_target(&releases_url, &version_req, &target).await?;
 
     fs::write(archive_version_file.clone(), asset_version.to_string())?;
+    fs::write(archive_target_file, &target)?;
     let mut file = File::create(archive_file.clone())?;
     file.write_all(&archive)?;
     file.sync_data()?;
@@ -0,0 +1,94 @@
+use std::fs::File;
+use std::io::Read;
+use std::path::Path;
+
+/// Signature at the start of a custom format archive
+const CUSTOM_SIGNATURE: &[u8] = b"PGDMP";
+/// Offset of the `ustar` magic in the header of a tar archive
+const TAR_MAGIC_OFFSET: usize = 257;
+/// Magic identifying a tar archive
+const TAR_MAGIC: &[u8] = b"ustar";
+
+/// Format of a database backup created with `pg_dump`
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum BackupFormat {
+    /// Plain SQL script; restored with `psql`
+    Plain,
+    /// Compressed custom format archive; restored with `pg_restore`
+    #[default]
+    Custom,
+    /// Directory with one file per table; restored with `pg_restore`
+    Directory,
+    /// Tar archive; restored with `pg_restore`
+    Tar,
+}
+
+impl BackupFormat {
+    /// Get the value of the `pg_dump` / `pg_restore` `--format` option
+    #[must_use]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BackupFormat::Plain => "plain",
+            BackupFormat::Custom => "custom",
+            BackupFormat::Directory => "directory",
+            BackupFormat::Tar => "tar",
+        }
+    }
+
+    /// Detect the format of the backup at `path`; a directory is a [directory](Self::Directory)
+    /// backup, and files are identified by their header, defaulting to [plain](Self::Plain).
+    pub(crate) fn detect(path: &Path) -> std::io::Result<Self> {
+        if path.is_dir() {
+            return Ok(BackupFormat::Directory);
+        }
+
+        let mut header = Vec::with_capacity(TAR_MAGIC_OFFSET + TAR_MAGIC.len());
+        File::open(path)?
+            .take((TAR_MAGIC_OFFSET + TAR_MAGIC.len()) as u64)
+            .read_to_end(&mut header)?;
+        if header.starts_with(CUSTOM_SIGNATURE) {
+            Ok(BackupFormat::Custom)
+        } else if header.get(TAR_MAGIC_OFFSET..) == Some(TAR_MAGIC) {
+            Ok(BackupFormat::Tar)
+        } else {
+            Ok(BackupFormat::Plain)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_as_str() {
+        assert_eq!("plain", BackupFormat::Plain.as_str());
+        assert_eq!("custom", BackupFormat::Custom.as_str());
+        assert_eq!("directory", BackupFormat::Directory.as_str());
+        assert_eq!("tar", BackupFormat::Tar.as_str());
+    }
+
+    #[test]
+    fn test_detect() -> std::io::Result<()> {
+        let backup_dir = tempfile::tempdir()?;
+        assert_eq!(
+            BackupFormat::Directory,
+            BackupFormat::detect(backup_dir.path())?
+        );
+
+        let custom = backup_dir.path().join("backup.dump");
+        std::fs::write(&custom, b"PGDMP\x01\x0e\x00")?;
+        assert_eq!(BackupFormat::Custom, BackupFormat::detect(&custom)?);
+
+        let tar = backup_dir.path().join("backup.tar");
+        let mut header = vec![0u8; 512];
+        header[TAR_MAGIC_OFFSET..TAR_MAGIC_OFFSET + TAR_MAGIC.len()].copy_from_slice(TAR_MAGIC);
+        std::fs::write(&tar, header)?;
+        assert_eq!(BackupFormat::Tar, BackupFormat::detect(&tar)?);
+
+        let plain = backup_dir.path().join("backup.sql");
+        std::fs::write(&plain, "CREATE TABLE test (id INT);")?;
+        assert_eq!(BackupFormat::Plain, BackupFormat::detect(&plain)?);
+        Ok(())
+    }
+}
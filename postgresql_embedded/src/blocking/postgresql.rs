@@ -1,5 +1,16 @@
-use crate::{Result, Settings, Status};
+use crate::{
+    BackupFormat, Description, EventListener, Finding, ReplicationSlot, ReplicationSlotType,
+    Result, SchemaDiff, ServiceKind, ServiceUnitOptions, Settings, SetupReport, ShutdownMode,
+    SqlOutput, Status, Timings, WindowsServiceOptions,
+};
+use postgresql_archive::cache::{CacheEntry, CachedArchive, FileSystemCache, PrunePolicy};
+use postgresql_archive::{ProgressReporter, ReleaseInfo, Version};
+use postgresql_commands::{CommandBuilder, CommandOutput};
+use std::collections::{BTreeMap, HashMap};
+use std::path::{Path, PathBuf};
+use std::process::ExitStatus;
 use std::sync::LazyLock;
+use std::time::Duration;
 use tokio::runtime::Runtime;
 
 static RUNTIME: LazyLock<Runtime> = LazyLock::new(|| Runtime::new().unwrap());
@@ -20,31 +31,170 @@ impl PostgreSQL {
         }
     }
 
+    /// Register a listener for the lifecycle [events](crate::Event) of the server
+    #[must_use]
+    pub fn with_event_listener<L: EventListener + 'static>(self, listener: L) -> Self {
+        Self {
+            inner: self.inner.with_event_listener(listener),
+        }
+    }
+
+    /// Set the [progress reporter](crate::ProgressReporter) that receives the progress of the
+    /// downloads and extractions of archives performed by this server
+    #[must_use]
+    pub fn with_progress_reporter<R: ProgressReporter + 'static>(self, reporter: R) -> Self {
+        Self {
+            inner: self.inner.with_progress_reporter(reporter),
+        }
+    }
+
+    /// Attach to a server that is already running against the data directory of the `settings`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no server is running against the data directory or the settings do
+    /// not match the running server.
+    pub fn attach(settings: Settings) -> Result<Self> {
+        let inner = RUNTIME
+            .handle()
+            .block_on(async move { crate::postgresql::PostgreSQL::attach(settings).await })?;
+        Ok(Self { inner })
+    }
+
+    /// Connect to an externally managed server that is already running at the host and port of
+    /// the `settings`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server cannot be connected to.
+    pub fn external(settings: Settings) -> Result<Self> {
+        let inner = RUNTIME
+            .handle()
+            .block_on(async move { crate::postgresql::PostgreSQL::external(settings).await })?;
+        Ok(Self { inner })
+    }
+
+    /// Connect to an externally managed server with the settings of the `url`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the URL is invalid or the server cannot be connected to.
+    pub fn external_url<S: AsRef<str>>(url: S) -> Result<Self> {
+        let inner = RUNTIME
+            .handle()
+            .block_on(async move { crate::postgresql::PostgreSQL::external_url(url).await })?;
+        Ok(Self { inner })
+    }
+
+    /// Returns `true` if the server is managed externally.
+    #[must_use]
+    pub fn is_external(&self) -> bool {
+        self.inner.is_external()
+    }
+
+    /// Start a throwaway server against a copy of the existing data directory at `data_dir`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data directory cannot be copied or the server fails to start.
+    pub fn inspect<P>(data_dir: P) -> Result<Self>
+    where
+        P: AsRef<Path> + std::fmt::Debug,
+    {
+        Self::inspect_with_settings(data_dir, Settings::default())
+    }
+
+    /// Start a throwaway server with the `settings` against a copy of the existing data directory
+    /// at `data_dir`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data directory cannot be copied or the server fails to start.
+    pub fn inspect_with_settings<P>(data_dir: P, settings: Settings) -> Result<Self>
+    where
+        P: AsRef<Path> + std::fmt::Debug,
+    {
+        let inner = RUNTIME.handle().block_on(async move {
+            crate::postgresql::PostgreSQL::inspect_with_settings(data_dir, settings).await
+        })?;
+        Ok(Self { inner })
+    }
+
     /// Get the [status](Status) of the `PostgreSQL` server
     #[must_use]
     pub fn status(&self) -> Status {
         self.inner.status()
     }
 
+    /// Subscribe to the [status](Status) transitions of the `PostgreSQL` server
+    #[must_use]
+    pub fn state_stream(&self) -> tokio::sync::watch::Receiver<Status> {
+        self.inner.state_stream()
+    }
+
     /// Get the [settings](Settings) of the `PostgreSQL` server
     #[must_use]
     pub fn settings(&self) -> &Settings {
         self.inner.settings()
     }
 
+    /// Get the [durations](Timings) of the most recent operations performed by this instance
+    #[must_use]
+    pub fn timings(&self) -> Timings {
+        self.inner.timings()
+    }
+
     /// Set up the database by extracting the archive and initializing the database.
     /// If the installation directory already exists, the archive will not be extracted.
     /// If the data directory already exists, the database will not be initialized.
+    /// Returns a [report](SetupReport) of the phases that were performed.
     ///
     /// # Errors
     ///
     /// Returns an error if the setup fails.
-    pub fn setup(&mut self) -> Result<()> {
+    pub fn setup(&mut self) -> Result<SetupReport> {
         RUNTIME
             .handle()
             .block_on(async move { self.inner.setup().await })
     }
 
+    /// Set up the database as with [`setup`](Self::setup), discarding the report.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the setup fails.
+    pub fn ensure_setup(&mut self) -> Result<()> {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.ensure_setup().await })
+    }
+
+    /// Upgrade the data directory with `pg_upgrade` if it was initialized by an older major
+    /// version of `PostgreSQL`. Returns the major version the data directory was upgraded from,
+    /// or `None` if no upgrade was required.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the upgrade fails; the existing data directory is left unchanged.
+    pub fn upgrade(&mut self) -> Result<Option<u64>> {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.upgrade().await })
+    }
+
+    /// Upgrade the binaries to the latest minor version of the installed major version, restarting
+    /// the server with the new version if it is running. Returns the version upgraded to, or
+    /// `None` if the latest minor version is already installed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the new version cannot be installed, or the server cannot be restarted.
+    pub fn minor_upgrade(&mut self) -> Result<Option<Version>> {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.minor_upgrade().await })
+    }
+
     /// Start the database and wait for the startup to complete.
     /// If the port is set to `0`, the database will be started on a random port.
     ///
@@ -57,7 +207,53 @@ impl PostgreSQL {
             .block_on(async move { self.inner.start().await })
     }
 
-    /// Stop the database gracefully (smart mode) and wait for the shutdown to complete.
+    /// Run `postgres` attached to the current process until it exits, calling `on_line` with each
+    /// line of the server log as it is written, and return the exit status of the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server cannot be started.
+    pub fn run_foreground<F>(&mut self, on_line: F) -> Result<ExitStatus>
+    where
+        F: FnMut(&str),
+    {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.run_foreground(on_line).await })
+    }
+
+    /// Wait for the server to complete crash recovery and accept connections.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if recovery does not complete before the timeout.
+    pub fn wait_for_recovery(&self, timeout: Duration) -> Result<()> {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.wait_for_recovery(timeout).await })
+    }
+
+    /// Check with `pg_isready` whether the server is accepting connections.
+    pub fn is_ready(&self) -> bool {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.is_ready().await })
+    }
+
+    /// Wait until the server is accepting connections, polling it with `pg_isready` until it is
+    /// ready or the `timeout` elapses.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server is not ready before the timeout.
+    pub fn wait_until_ready(&self, timeout: Duration) -> Result<()> {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.wait_until_ready(timeout).await })
+    }
+
+    /// Stop the database with the [shutdown options](Settings::shutdown) and wait for the shutdown
+    /// to complete.
     ///
     /// # Errors
     ///
@@ -68,6 +264,96 @@ impl PostgreSQL {
             .block_on(async move { self.inner.stop().await })
     }
 
+    /// Stop the database with the shutdown `mode` and wait up to `timeout` for the shutdown to
+    /// complete.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the shutdown fails or does not complete within the timeout and the
+    /// server is not killed.
+    pub fn stop_with(&self, mode: ShutdownMode, timeout: Duration) -> Result<()> {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.stop_with(mode, timeout).await })
+    }
+
+    /// Reload the server configuration with `pg_ctl reload`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration cannot be reloaded.
+    pub fn reload(&self) -> Result<()> {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.reload().await })
+    }
+
+    /// Register the server as a Windows service with `pg_ctl register`. Only supported on Windows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the service cannot be registered.
+    pub fn register_windows_service<S>(
+        &self,
+        service_name: S,
+        options: &WindowsServiceOptions,
+    ) -> Result<()>
+    where
+        S: AsRef<str> + std::fmt::Debug,
+    {
+        RUNTIME.handle().block_on(async move {
+            self.inner
+                .register_windows_service(service_name, options)
+                .await
+        })
+    }
+
+    /// Unregister a Windows service previously registered with
+    /// [`register_windows_service`](Self::register_windows_service). Only supported on Windows.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the service cannot be unregistered.
+    pub fn unregister_windows_service<S>(&self, service_name: S) -> Result<()>
+    where
+        S: AsRef<str> + std::fmt::Debug,
+    {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.unregister_windows_service(service_name).await })
+    }
+
+    /// Generate a systemd unit or launchd property list that runs this server's installed
+    /// `postgres` binary against its data directory, port and configuration.
+    #[must_use]
+    pub fn generate_service_unit(&self, kind: ServiceKind, options: &ServiceUnitOptions) -> String {
+        self.inner.generate_service_unit(kind, options)
+    }
+
+    /// Check the environment for common problems that prevent the server from being installed,
+    /// initialized or started. Returns the [findings](Finding), which are empty if no problems
+    /// were found.
+    #[must_use]
+    pub fn doctor(&self) -> Vec<Finding> {
+        self.inner.doctor()
+    }
+
+    /// Describe the server for external tooling; i.e. its status, version, directories and
+    /// connection settings. The password is replaced with `********` if `mask_secrets` is set.
+    #[must_use]
+    pub fn describe(&self, mask_secrets: bool) -> Description {
+        self.inner.describe(mask_secrets)
+    }
+
+    /// Describe the server as pretty printed JSON; see [`describe`](Self::describe).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the description cannot be serialized.
+    pub fn describe_json(&self, mask_secrets: bool) -> Result<String> {
+        self.inner.describe_json(mask_secrets)
+    }
+
     /// Create a new database with the given name.
     ///
     /// # Errors
@@ -82,6 +368,20 @@ impl PostgreSQL {
             .block_on(async move { self.inner.create_database(database_name).await })
     }
 
+    /// Create databases with the given names concurrently.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the creation of any database fails.
+    pub fn create_databases<S>(&self, database_names: &[S]) -> Result<()>
+    where
+        S: AsRef<str> + std::fmt::Debug,
+    {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.create_databases(database_names).await })
+    }
+
     /// Check if a database with the given name exists.
     ///
     /// # Errors
@@ -109,6 +409,498 @@ impl PostgreSQL {
             .handle()
             .block_on(async move { self.inner.drop_database(database_name).await })
     }
+
+    /// Create a user (a role with the `LOGIN` attribute) with the given name and password.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the user cannot be created.
+    pub fn create_user<S, P>(&self, username: S, password: P) -> Result<()>
+    where
+        S: AsRef<str> + std::fmt::Debug,
+        P: AsRef<str>,
+    {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.create_user(username, password).await })
+    }
+
+    /// Check if a user with the given name exists.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the user existence check fails.
+    pub fn user_exists<S>(&self, username: S) -> Result<bool>
+    where
+        S: AsRef<str> + std::fmt::Debug,
+    {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.user_exists(username).await })
+    }
+
+    /// Change the password of the user with the given name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the password cannot be changed.
+    pub fn alter_user_password<S, P>(&self, username: S, password: P) -> Result<()>
+    where
+        S: AsRef<str> + std::fmt::Debug,
+        P: AsRef<str>,
+    {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.alter_user_password(username, password).await })
+    }
+
+    /// Drop the user with the given name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the user cannot be dropped.
+    pub fn drop_user<S>(&self, username: S) -> Result<()>
+    where
+        S: AsRef<str> + std::fmt::Debug,
+    {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.drop_user(username).await })
+    }
+
+    /// Create a replication slot with the given name and type.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the replication slot cannot be created.
+    pub fn create_replication_slot<S>(
+        &self,
+        slot_name: S,
+        slot_type: &ReplicationSlotType,
+    ) -> Result<()>
+    where
+        S: AsRef<str> + std::fmt::Debug,
+    {
+        RUNTIME.handle().block_on(async move {
+            self.inner
+                .create_replication_slot(slot_name, slot_type)
+                .await
+        })
+    }
+
+    /// Get the replication slots of the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the replication slots cannot be retrieved.
+    pub fn replication_slots(&self) -> Result<Vec<ReplicationSlot>> {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.replication_slots().await })
+    }
+
+    /// Drop the replication slot with the given name.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the replication slot cannot be dropped.
+    pub fn drop_replication_slot<S>(&self, slot_name: S) -> Result<()>
+    where
+        S: AsRef<str> + std::fmt::Debug,
+    {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.drop_replication_slot(slot_name).await })
+    }
+
+    /// Create a named restore point in the write-ahead log and return its location (LSN).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the restore point cannot be created.
+    pub fn create_restore_point<S>(&self, name: S) -> Result<String>
+    where
+        S: AsRef<str> + std::fmt::Debug,
+    {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.create_restore_point(name).await })
+    }
+
+    /// Export the data of all databases to a single archive file at `path`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data cannot be exported.
+    pub fn export_app_data<P>(&self, path: P) -> Result<()>
+    where
+        P: AsRef<Path> + std::fmt::Debug,
+    {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.export_app_data(path).await })
+    }
+
+    /// Export the installation to `target_dir` as a relocatable directory suitable for copying
+    /// into a container image, optionally including the data directory as a pre-initialized
+    /// template.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the installation cannot be exported.
+    pub fn export_installation<P>(&self, target_dir: P, include_data: bool) -> Result<()>
+    where
+        P: AsRef<Path> + std::fmt::Debug,
+    {
+        self.inner.export_installation(target_dir, include_data)
+    }
+
+    /// Validate that the installation at `path` works from its current location.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the installation is not relocatable.
+    pub fn validate_relocation<P>(path: P) -> Result<()>
+    where
+        P: AsRef<Path> + std::fmt::Debug,
+    {
+        crate::postgresql::PostgreSQL::validate_relocation(path)
+    }
+
+    /// Check whether a newer release of the installed major version is available. Returns the
+    /// release information of the latest release, or `None` if the installed version is the
+    /// latest.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the version is not installed, or the release cannot be retrieved.
+    pub fn check_for_updates(&self) -> Result<Option<ReleaseInfo>> {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.check_for_updates().await })
+    }
+
+    /// List the versions published by the releases URL, including pre-releases, in descending
+    /// order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the versions cannot be retrieved.
+    pub fn available_versions(&self) -> Result<Vec<Version>> {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.available_versions().await })
+    }
+
+    /// List the versions installed in the cache directory, in descending order.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache directory cannot be read.
+    pub fn list_installed(&self) -> Result<Vec<CacheEntry>> {
+        self.inner.list_installed()
+    }
+
+    /// Remove the versions installed in the cache directory that are not kept by the `policy`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an installed version cannot be removed.
+    pub fn prune_cache(&self, policy: &PrunePolicy) -> Result<Vec<CacheEntry>> {
+        self.inner.prune_cache(policy)
+    }
+
+    /// List the archives cached by `postgresql_archive` in the cache directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the cache directory cannot be read.
+    pub fn list_cached_archives(&self) -> Result<Vec<CachedArchive>> {
+        self.inner.list_cached_archives()
+    }
+
+    /// Get the archive cache in the cache directory, which the archives of this instance are
+    /// cached in.
+    #[must_use]
+    pub fn archive_cache(&self) -> FileSystemCache {
+        self.inner.archive_cache()
+    }
+
+    /// Remove the PostgreSQL archives cached by `postgresql_archive` that are not kept by the
+    /// `policy`; archives of other repositories are kept.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a cached archive cannot be removed.
+    pub fn prune_cached_archives(&self, policy: &PrunePolicy) -> Result<Vec<CachedArchive>> {
+        self.inner.prune_cached_archives(policy)
+    }
+
+    /// Verify the files of the installed version against the manifest written when it was
+    /// installed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the version is not installed, or has no manifest.
+    pub fn verify_installation(&self) -> Result<Vec<PathBuf>> {
+        self.inner.verify_installation()
+    }
+
+    /// Remove all versions installed in the cache directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server is running, or a version cannot be removed.
+    pub fn clear_cache(&self) -> Result<()> {
+        self.inner.clear_cache()
+    }
+
+    /// Import the data from an archive file created by [`export_app_data`](Self::export_app_data),
+    /// optionally optimizing each imported database.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the data cannot be imported.
+    pub fn import_app_data<P>(&self, path: P, optimize: bool) -> Result<()>
+    where
+        P: AsRef<Path> + std::fmt::Debug,
+    {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.import_app_data(path, optimize).await })
+    }
+
+    /// Back up a database to `path` with `pg_dump` in the given `format`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be backed up.
+    pub fn backup_database<S, P>(
+        &self,
+        database_name: S,
+        path: P,
+        format: BackupFormat,
+    ) -> Result<()>
+    where
+        S: AsRef<str> + std::fmt::Debug,
+        P: AsRef<Path> + std::fmt::Debug,
+    {
+        RUNTIME.handle().block_on(async move {
+            self.inner
+                .backup_database(database_name, path, format)
+                .await
+        })
+    }
+
+    /// Restore a database from a backup at `path` created by
+    /// [`backup_database`](Self::backup_database).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be restored.
+    pub fn restore_database<S, P>(&self, database_name: S, path: P) -> Result<()>
+    where
+        S: AsRef<str> + std::fmt::Debug,
+        P: AsRef<Path> + std::fmt::Debug,
+    {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.restore_database(database_name, path).await })
+    }
+
+    /// Provision a second instance from the installation of this server, replaying its roles and
+    /// extensions and copying the `databases`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the instance cannot be set up or started, or the roles, extensions or
+    /// databases cannot be copied.
+    pub fn clone_instance<S>(&self, settings: Settings, databases: &[S]) -> Result<Self>
+    where
+        S: AsRef<str> + std::fmt::Debug,
+    {
+        let inner = RUNTIME
+            .handle()
+            .block_on(async move { self.inner.clone_instance(settings, databases).await })?;
+        Ok(Self { inner })
+    }
+
+    /// Compare the schemas of the databases `database_a` and `database_b` of the server.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a schema cannot be dumped.
+    pub fn schema_diff<A, B>(&self, database_a: A, database_b: B) -> Result<SchemaDiff>
+    where
+        A: AsRef<str> + std::fmt::Debug,
+        B: AsRef<str> + std::fmt::Debug,
+    {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.schema_diff(database_a, database_b).await })
+    }
+
+    /// Compare the schema of the database `database_a` of the server with the schema of the
+    /// database `database_b` of the `other` server.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a schema cannot be dumped.
+    pub fn schema_diff_with<A, B>(
+        &self,
+        database_a: A,
+        other: &PostgreSQL,
+        database_b: B,
+    ) -> Result<SchemaDiff>
+    where
+        A: AsRef<str> + std::fmt::Debug,
+        B: AsRef<str> + std::fmt::Debug,
+    {
+        RUNTIME.handle().block_on(async move {
+            self.inner
+                .schema_diff_with(database_a, &other.inner, database_b)
+                .await
+        })
+    }
+
+    /// Compute a checksum of the rows of each of the `tables` of the database, or of all tables
+    /// if none are given.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a table does not exist or cannot be read.
+    pub fn table_checksums<S, T>(
+        &self,
+        database_name: S,
+        tables: &[T],
+    ) -> Result<BTreeMap<String, String>>
+    where
+        S: AsRef<str> + std::fmt::Debug,
+        T: AsRef<str> + std::fmt::Debug,
+    {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.table_checksums(database_name, tables).await })
+    }
+
+    /// Run the command of a [command builder](CommandBuilder) against the server and return its
+    /// [output](CommandOutput).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the command cannot be started or exits with a non-zero exit code.
+    pub fn run<B: CommandBuilder>(&self, command_builder: B) -> Result<CommandOutput> {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.run(command_builder).await })
+    }
+
+    /// Run the command of a [command builder](CommandBuilder) against the server, calling
+    /// `on_line` with each line of stdout as the command writes it.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the command fails.
+    pub fn run_streaming<B, F>(&self, command_builder: B, on_line: F) -> Result<()>
+    where
+        B: CommandBuilder,
+        F: FnMut(&str),
+    {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.run_streaming(command_builder, on_line).await })
+    }
+
+    /// Run SQL statements against a database with `psql`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a statement fails.
+    pub fn run_sql<S, Q>(&self, database_name: S, sql: Q) -> Result<SqlOutput>
+    where
+        S: AsRef<str> + std::fmt::Debug,
+        Q: AsRef<str>,
+    {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.run_sql(database_name, sql).await })
+    }
+
+    /// Run the SQL statements in the file at `path` against a database with `psql`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a statement fails.
+    pub fn run_sql_file<S, P>(&self, database_name: S, path: P) -> Result<SqlOutput>
+    where
+        S: AsRef<str> + std::fmt::Debug,
+        P: AsRef<Path> + std::fmt::Debug,
+    {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.run_sql_file(database_name, path).await })
+    }
+
+    /// Optimize a database after a restore or bulk load, optionally rebuilding all indexes.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the database cannot be optimized.
+    pub fn optimize_database<S>(&self, database_name: S, reindex: bool) -> Result<()>
+    where
+        S: AsRef<str> + std::fmt::Debug,
+    {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.optimize_database(database_name, reindex).await })
+    }
+
+    /// Get the configuration parameters set with `ALTER SYSTEM`, as persisted in the
+    /// `postgresql.auto.conf` file of the data directory.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration file cannot be read.
+    pub fn auto_conf(&self) -> Result<HashMap<String, String>> {
+        self.inner.auto_conf()
+    }
+
+    /// Get the last `max_lines` lines of the server log.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the server log cannot be read.
+    pub fn read_log(&self, max_lines: usize) -> Result<Vec<String>> {
+        self.inner.read_log(max_lines)
+    }
+
+    /// Repair a server that failed to start because a library in `shared_preload_libraries` is
+    /// missing; e.g. after an extension was uninstalled. The start log is checked for the failure,
+    /// the missing libraries are removed from `postgresql.auto.conf` and the server is started.
+    /// Returns the libraries that were removed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if no missing libraries are found, or the server cannot be started.
+    pub fn repair_preload_libraries(&mut self) -> Result<Vec<String>> {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.repair_preload_libraries().await })
+    }
+
+    /// Reset a configuration parameter set with `ALTER SYSTEM` and reload the configuration.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the configuration parameter cannot be reset.
+    pub fn reset_config<S>(&self, key: S) -> Result<()>
+    where
+        S: AsRef<str> + std::fmt::Debug,
+    {
+        RUNTIME
+            .handle()
+            .block_on(async move { self.inner.reset_config(key).await })
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,202 @@
+use std::io::ErrorKind;
+use std::path::Path;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Maximum number of attempts to remove a temporary path
+const MAX_ATTEMPTS: u32 = 5;
+/// Delay before the first retry; doubled after each failed attempt
+const INITIAL_DELAY: Duration = Duration::from_millis(50);
+
+/// Remove a temporary directory and its contents. See [`remove_path`].
+pub(crate) fn remove_temporary_dir(path: &Path) {
+    remove_path(path, |path| std::fs::remove_dir_all(path));
+}
+
+/// Remove a temporary file. See [`remove_path`].
+pub(crate) fn remove_temporary_file(path: &Path) {
+    remove_path(path, |path| std::fs::remove_file(path));
+}
+
+/// Remove the path without blocking the caller, e.g. the drop of a server; if the path cannot be
+/// removed, the removal is retried on a background thread while the server's file handles may
+/// still be closing after it stops, as on Windows. Returns the background thread, if any.
+fn remove_path(path: &Path, remove: fn(&Path) -> std::io::Result<()>) -> Option<JoinHandle<()>> {
+    let error = match remove(path) {
+        Ok(()) => return None,
+        Err(error) if error.kind() == ErrorKind::NotFound => return None,
+        Err(error) => error,
+    };
+    debug!(
+        "Failed to remove {}: {error}; retrying in the background",
+        path.to_string_lossy()
+    );
+    let background_path = path.to_path_buf();
+    let handle = std::thread::Builder::new()
+        .name("postgresql-cleanup".to_string())
+        .spawn(move || retry_removal(&background_path, remove));
+    match handle {
+        Ok(handle) => Some(handle),
+        Err(spawn_error) => {
+            warn!(
+                "Failed to remove {}: {error}; cannot retry: {spawn_error}",
+                path.to_string_lossy()
+            );
+            None
+        }
+    }
+}
+
+/// Retry the removal of the path with exponential backoff, after the first attempt failed. If
+/// the path cannot be removed, on Windows it is scheduled for removal on the next reboot; a
+/// warning is logged if the path is left behind.
+fn retry_removal(path: &Path, remove: fn(&Path) -> std::io::Result<()>) {
+    let mut delay = INITIAL_DELAY;
+    for attempt in 2..=MAX_ATTEMPTS {
+        std::thread::sleep(delay);
+        delay *= 2;
+        let error = match remove(path) {
+            Ok(()) => return,
+            Err(error) if error.kind() == ErrorKind::NotFound => return,
+            Err(error) => error,
+        };
+        if attempt < MAX_ATTEMPTS {
+            debug!(
+                "Failed to remove {} (attempt {attempt}/{MAX_ATTEMPTS}): {error}; retrying in {delay:?}",
+                path.to_string_lossy()
+            );
+            continue;
+        }
+
+        #[cfg(target_os = "windows")]
+        match schedule_removal_on_reboot(path) {
+            Ok(()) => {
+                warn!(
+                    "Failed to remove {}: {error}; scheduled for removal on next reboot",
+                    path.to_string_lossy()
+                );
+                return;
+            }
+            Err(schedule_error) => debug!(
+                "Failed to schedule removal of {} on reboot: {schedule_error}",
+                path.to_string_lossy()
+            ),
+        }
+        warn!(
+            "Failed to remove {} after {MAX_ATTEMPTS} attempts: {error}",
+            path.to_string_lossy()
+        );
+    }
+}
+
+/// Script that schedules the paths of the `POSTGRESQL_REMOVE_PATHS` environment variable, one per
+/// line, for removal on the next reboot with `MoveFileExW` and `MOVEFILE_DELAY_UNTIL_REBOOT`;
+/// the function is called from PowerShell, as this crate does not use unsafe code.
+#[cfg(target_os = "windows")]
+const SCHEDULE_REMOVAL_SCRIPT: &str = r#"
+$ErrorActionPreference = 'Stop'
+$signature = @'
+[DllImport("kernel32.dll", SetLastError = true, CharSet = CharSet.Unicode)]
+public static extern bool MoveFileExW(string existing, string replacement, int flags);
+'@
+Add-Type -Namespace PostgreSQL -Name Kernel32 -MemberDefinition $signature
+foreach ($path in $env:POSTGRESQL_REMOVE_PATHS -split "`n") {
+    if (-not [PostgreSQL.Kernel32]::MoveFileExW($path, $null, 4)) {
+        $code = [Runtime.InteropServices.Marshal]::GetLastWin32Error()
+        throw [ComponentModel.Win32Exception]$code
+    }
+}
+"#;
+
+/// Schedule the path, and its contents if it is a directory, for removal on the next reboot with
+/// `MoveFileExW` and `MOVEFILE_DELAY_UNTIL_REBOOT`; the contents of a directory are scheduled
+/// before the directory, so that it is empty when it is removed. Requires administrator
+/// privileges.
+#[cfg(target_os = "windows")]
+fn schedule_removal_on_reboot(path: &Path) -> std::io::Result<()> {
+    let mut paths = Vec::new();
+    collect_paths(path, &mut paths)?;
+    let paths = paths
+        .iter()
+        .map(|path| path.to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("\n");
+    let output = std::process::Command::new("powershell")
+        .args([
+            "-NoProfile",
+            "-NonInteractive",
+            "-Command",
+            SCHEDULE_REMOVAL_SCRIPT,
+        ])
+        .env("POSTGRESQL_REMOVE_PATHS", paths)
+        .output()?;
+    if output.status.success() {
+        Ok(())
+    } else {
+        Err(std::io::Error::other(
+            String::from_utf8_lossy(&output.stderr).trim().to_string(),
+        ))
+    }
+}
+
+/// Collect the path and its contents, ordering the contents of directories before the directory.
+#[cfg(target_os = "windows")]
+fn collect_paths(path: &Path, paths: &mut Vec<std::path::PathBuf>) -> std::io::Result<()> {
+    if path.is_dir() {
+        for entry in std::fs::read_dir(path)? {
+            collect_paths(&entry?.path(), paths)?;
+        }
+    }
+    paths.push(path.to_path_buf());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_remove_temporary_dir() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?.into_path();
+        std::fs::write(dir.join("file"), b"test")?;
+        remove_temporary_dir(&dir);
+        assert!(!dir.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_remove_temporary_file() -> std::io::Result<()> {
+        let dir = tempfile::tempdir()?;
+        let file = dir.path().join("file");
+        std::fs::write(&file, b"test")?;
+        remove_temporary_file(&file);
+        assert!(!file.exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_remove_missing_path() {
+        let path = Path::new("/missing/postgresql/data");
+        remove_temporary_dir(path);
+        remove_temporary_file(path);
+    }
+
+    #[test]
+    fn test_remove_path_retries() {
+        use std::sync::atomic::{AtomicU32, Ordering};
+        static ATTEMPTS: AtomicU32 = AtomicU32::new(0);
+
+        let handle = remove_path(Path::new("locked"), |_| {
+            if ATTEMPTS.fetch_add(1, Ordering::SeqCst) < 2 {
+                Err(std::io::Error::new(ErrorKind::PermissionDenied, "locked"))
+            } else {
+                Ok(())
+            }
+        });
+        // The first attempt is made by the caller, and the retries in the background
+        assert!(ATTEMPTS.load(Ordering::SeqCst) >= 1);
+        handle.expect("background removal").join().expect("join");
+        assert_eq!(3, ATTEMPTS.load(Ordering::SeqCst));
+    }
+}
@@ -0,0 +1,223 @@
+use crate::error::Error::ClusterError;
+use crate::error::Result;
+use crate::postgresql::{PostgreSQL, Status};
+use crate::settings::{Settings, BOOTSTRAP_DATABASE};
+use postgresql_commands::pg_basebackup::PgBaseBackupBuilder;
+use postgresql_commands::pg_ctl::Mode::Promote;
+use postgresql_commands::pg_ctl::PgCtlBuilder;
+use postgresql_commands::pg_rewind::PgRewindBuilder;
+use postgresql_commands::psql::PsqlBuilder;
+use postgresql_commands::CommandBuilder;
+use tracing::{debug, instrument, warn};
+
+/// Primary server and its streaming replication standbys, for testing high availability
+/// scenarios such as a [failover](Self::failover). Standbys are created from a base backup of
+/// the primary and use the installation of the primary.
+///
+/// The former primary is repointed after a failover with `pg_rewind`, which requires
+/// `wal_log_hints` to be enabled in the [configuration](Settings::configuration) of the primary
+/// (or the data directory to be initialized with data checksums); otherwise, its data directory
+/// is copied from the new primary again with `pg_basebackup`.
+#[derive(Debug)]
+pub struct Cluster {
+    primary: PostgreSQL,
+    standbys: Vec<PostgreSQL>,
+}
+
+impl Cluster {
+    /// Create a new [`Cluster`] with the `primary` server and no standbys
+    #[must_use]
+    pub fn new(primary: PostgreSQL) -> Self {
+        Self {
+            primary,
+            standbys: Vec::new(),
+        }
+    }
+
+    /// Get the primary server
+    #[must_use]
+    pub fn primary(&self) -> &PostgreSQL {
+        &self.primary
+    }
+
+    /// Get the standby servers, in the order they were added
+    #[must_use]
+    pub fn standbys(&self) -> &[PostgreSQL] {
+        &self.standbys
+    }
+
+    /// Add a standby that streams the write-ahead log of the primary. The data directory of the
+    /// `settings` is created with a base backup of the primary; the releases URL, version,
+    /// installation directory, users and password of the `settings` are replaced with those of
+    /// the primary, and the configuration options of the primary that are not set in the
+    /// `settings` are applied. The standby is set up and started.
+    ///
+    /// The primary must be started, and the data directory of the `settings` must not be
+    /// initialized.
+    #[instrument(skip(self, settings), fields(operation = "add_standby"))]
+    pub async fn add_standby(&mut self, mut settings: Settings) -> Result<&PostgreSQL> {
+        if self.primary.status() != Status::Started {
+            return Err(ClusterError(
+                "the primary must be started to add a standby".to_string(),
+            ));
+        }
+        if settings.data_dir.join("postgresql.conf").exists() {
+            return Err(ClusterError(format!(
+                "the data directory {} is already initialized",
+                settings.data_dir.to_string_lossy()
+            )));
+        }
+        let primary_settings = self.primary.settings();
+        settings
+            .releases_url
+            .clone_from(&primary_settings.releases_url);
+        settings.version.clone_from(&primary_settings.version);
+        settings
+            .installation_dir
+            .clone_from(&primary_settings.installation_dir);
+        settings.install_dir_layout = primary_settings.install_dir_layout;
+        settings.superuser.clone_from(&primary_settings.superuser);
+        settings.username.clone_from(&primary_settings.username);
+        settings.password.clone_from(&primary_settings.password);
+        settings.password_source = None;
+        for (key, value) in &primary_settings.configuration {
+            settings
+                .configuration
+                .entry(key.clone())
+                .or_insert_with(|| value.clone());
+        }
+
+        base_backup(&self.primary, &settings).await?;
+        let mut standby = PostgreSQL::new(settings);
+        standby.setup().await?;
+        standby.start().await?;
+        debug!(
+            "Added standby {host}:{port} of {primary_host}:{primary_port}",
+            host = standby.settings().host,
+            port = standby.settings().port,
+            primary_host = self.primary.settings().host,
+            primary_port = self.primary.settings().port
+        );
+        self.standbys.push(standby);
+        Ok(&self.standbys[self.standbys.len() - 1])
+    }
+
+    /// Fail over to the standby at index `standby` of the [standbys](Self::standbys):
+    ///
+    /// * the primary is stopped, if it is running;
+    /// * the standby is promoted and becomes the primary of the cluster;
+    /// * the former primary and the other standbys are stopped, repointed to the new primary with
+    ///   `pg_rewind`, or with a new base backup of the new primary if they cannot be rewound, and
+    ///   started as standbys of the new primary.
+    ///
+    /// The former primary is the last of the standbys of the cluster after the failover.
+    #[instrument(skip(self), fields(operation = "failover"))]
+    pub async fn failover(&mut self, standby: usize) -> Result<()> {
+        if standby >= self.standbys.len() {
+            return Err(ClusterError(format!("standby {standby} does not exist")));
+        }
+        if self.primary.status() == Status::Started {
+            self.primary.stop().await?;
+        }
+
+        let candidate = &self.standbys[standby];
+        debug!(
+            "Promoting standby {host}:{port}",
+            host = candidate.settings().host,
+            port = candidate.settings().port
+        );
+        let pg_ctl = PgCtlBuilder::new()
+            .mode(Promote)
+            .pgdata(&candidate.settings().data_dir)
+            .wait();
+        candidate
+            .run(pg_ctl)
+            .await
+            .map_err(|error| ClusterError(format!("failed to promote standby: {error}")))?;
+        // Checkpoint, so that the control file of the new primary records its new timeline,
+        // which pg_rewind compares with the timeline of the servers that are repointed
+        let checkpoint = PsqlBuilder::new()
+            .command("CHECKPOINT")
+            .dbname(BOOTSTRAP_DATABASE)
+            .username(&candidate.settings().superuser)
+            .no_psqlrc();
+        candidate.run(checkpoint).await?;
+
+        let promoted = self.standbys.remove(standby);
+        let former_primary = std::mem::replace(&mut self.primary, promoted);
+        self.standbys.push(former_primary);
+        for server in &mut self.standbys {
+            repoint(&self.primary, server).await?;
+        }
+        debug!(
+            "Failed over to {host}:{port}",
+            host = self.primary.settings().host,
+            port = self.primary.settings().port
+        );
+        Ok(())
+    }
+}
+
+/// Create the data directory of the `settings` with a base backup of the `primary`, configured to
+/// stream the write-ahead log of the `primary` as a standby.
+async fn base_backup(primary: &PostgreSQL, settings: &Settings) -> Result<()> {
+    let pg_basebackup = PgBaseBackupBuilder::new()
+        .pgdata(&settings.data_dir)
+        .username(&primary.settings().superuser)
+        .wal_method("stream")
+        .checkpoint("fast")
+        .write_recovery_conf();
+    primary
+        .run(pg_basebackup)
+        .await
+        .map_err(|error| ClusterError(format!("failed to create base backup: {error}")))?;
+    Ok(())
+}
+
+/// Stop the `server` and start it as a standby of the `primary`; the data directory of the
+/// `server` is rewound with `pg_rewind` to the point where its timeline diverged from the
+/// `primary`, or replaced with a new base backup of the `primary` if it cannot be rewound.
+async fn repoint(primary: &PostgreSQL, server: &mut PostgreSQL) -> Result<()> {
+    if server.status() == Status::Started {
+        server.stop().await?;
+    }
+    let primary_settings = primary.settings();
+    let source_server = format!(
+        "host={} port={} user={} dbname={}",
+        conninfo_value(&primary_settings.host),
+        primary_settings.port,
+        conninfo_value(&primary_settings.superuser),
+        conninfo_value(BOOTSTRAP_DATABASE)
+    );
+    let pg_rewind = PgRewindBuilder::new()
+        .target_pgdata(&server.settings().data_dir)
+        .source_server(source_server)
+        .write_recovery_conf()
+        .env("PGPASSWORD", primary_settings.password.as_str());
+    if let Err(error) = server.run(pg_rewind).await {
+        warn!(
+            "Failed to rewind {}; creating a new base backup: {error}",
+            server.settings().data_dir.to_string_lossy()
+        );
+        std::fs::remove_dir_all(&server.settings().data_dir)?;
+        base_backup(primary, server.settings()).await?;
+    }
+    server.start().await
+}
+
+/// Quote a value of a `libpq` connection string.
+fn conninfo_value(value: &str) -> String {
+    format!("'{}'", value.replace('\\', "\\\\").replace('\'', "\\'"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_conninfo_value() {
+        assert_eq!("'localhost'", conninfo_value("localhost"));
+        assert_eq!("'a b'", conninfo_value("a b"));
+        assert_eq!(r"'it\'s \\'", conninfo_value(r"it's \"));
+    }
+}
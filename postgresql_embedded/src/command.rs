@@ -0,0 +1,318 @@
+use crate::settings::Settings;
+use postgresql_commands::{CommandBuilder, Settings as _};
+use std::ffi::{OsStr, OsString};
+use std::path::PathBuf;
+#[cfg(feature = "tokio")]
+use std::time::Duration;
+use tracing::debug;
+
+/// A [command builder](CommandBuilder) that runs the command of another builder against an
+/// instance. The program directory defaults to the binary directory of the instance, and the
+/// `libpq` connection environment variables (`PGHOST`, `PGPORT`, `PGUSER` and `PGPASSWORD`) are
+/// set from the settings of the instance; options and environment variables set on the wrapped
+/// builder take precedence.
+#[derive(Debug)]
+pub(crate) struct InstanceCommand<B: CommandBuilder> {
+    builder: B,
+    program_dir: Option<PathBuf>,
+    envs: Vec<(OsString, OsString)>,
+}
+
+impl<B: CommandBuilder> InstanceCommand<B> {
+    /// Create a new instance command for the `builder` from the `settings` of the instance.
+    pub(crate) fn new(builder: B, settings: &Settings) -> Self {
+        let program_dir = builder
+            .get_program_dir()
+            .clone()
+            .or_else(|| Some(settings.get_binary_dir()));
+        let envs = vec![
+            ("PGHOST".into(), settings.get_host()),
+            ("PGPORT".into(), settings.get_port().to_string().into()),
+            ("PGUSER".into(), settings.get_username()),
+            ("PGPASSWORD".into(), settings.get_password()),
+        ];
+        Self {
+            builder,
+            program_dir,
+            envs,
+        }
+    }
+}
+
+impl<B: CommandBuilder> CommandBuilder for InstanceCommand<B> {
+    fn get_program(&self) -> &'static OsStr {
+        self.builder.get_program()
+    }
+
+    fn get_program_dir(&self) -> &Option<PathBuf> {
+        &self.program_dir
+    }
+
+    fn get_args(&self) -> Vec<OsString> {
+        self.builder.get_args()
+    }
+
+    fn get_envs(&self) -> Vec<(OsString, OsString)> {
+        let mut envs = self.envs.clone();
+        envs.extend(self.builder.get_envs());
+        envs
+    }
+
+    fn env<S: AsRef<OsStr>>(mut self, key: S, value: S) -> Self {
+        self.builder = self.builder.env(key, value);
+        self
+    }
+}
+
+#[cfg(not(feature = "tokio"))]
+/// Execute a command, calling `on_line` with each line of stdout as it is written. The stderr of
+/// the command is returned in the error if the command exits with a non-zero exit code.
+pub(crate) fn execute_streaming<B, F>(
+    command_builder: B,
+    mut on_line: F,
+) -> postgresql_commands::Result<()>
+where
+    B: CommandBuilder,
+    F: FnMut(&str),
+{
+    use std::io::{BufRead, BufReader, Read};
+    use std::process::Stdio;
+
+    let mut command = command_builder.build();
+    debug!("Executing command: {command:?}");
+    let program = command.get_program().to_os_string();
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|error| postgresql_commands::Error::spawn(&program, &error))?;
+    let mut stderr_pipe = child.stderr.take();
+    // Read stderr while stdout is streamed so that neither pipe fills up and blocks the command
+    let stderr_reader = std::thread::spawn(move || {
+        let mut stderr = String::new();
+        if let Some(pipe) = stderr_pipe.as_mut() {
+            let _ = pipe.read_to_string(&mut stderr);
+        }
+        stderr
+    });
+    if let Some(stdout) = child.stdout.take() {
+        for line in BufReader::new(stdout).lines() {
+            on_line(&line?);
+        }
+    }
+    let status = child.wait()?;
+    let stderr = stderr_reader.join().unwrap_or_default();
+    if status.success() {
+        Ok(())
+    } else {
+        Err(postgresql_commands::Error::CommandError {
+            code: status.code(),
+            stdout: String::new(),
+            stderr,
+        })
+    }
+}
+
+#[cfg(feature = "tokio")]
+/// Execute a command, calling `on_line` with each line of stdout as it is written. The stderr of
+/// the command is returned in the error if the command exits with a non-zero exit code. The
+/// command is killed if it does not complete within the `timeout`.
+pub(crate) async fn execute_streaming<B, F>(
+    command_builder: B,
+    timeout: Option<Duration>,
+    mut on_line: F,
+) -> postgresql_commands::Result<()>
+where
+    B: CommandBuilder,
+    F: FnMut(&str),
+{
+    use std::process::Stdio;
+    use tokio::io::{AsyncBufReadExt, AsyncReadExt, BufReader};
+
+    let mut command = command_builder.build_tokio();
+    debug!("Executing command: {command:?}");
+    let program = command.as_std().get_program().to_os_string();
+    let mut child = command
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|error| postgresql_commands::Error::spawn(&program, &error))?;
+    let stdout = child.stdout.take();
+    let mut stderr_pipe = child.stderr.take();
+    let run = async {
+        let read_stdout = async {
+            if let Some(stdout) = stdout {
+                let mut lines = BufReader::new(stdout).lines();
+                while let Some(line) = lines.next_line().await? {
+                    on_line(&line);
+                }
+            }
+            Ok::<(), std::io::Error>(())
+        };
+        let read_stderr = async {
+            let mut stderr = String::new();
+            if let Some(pipe) = stderr_pipe.as_mut() {
+                pipe.read_to_string(&mut stderr).await?;
+            }
+            Ok::<String, std::io::Error>(stderr)
+        };
+        let (stdout_result, stderr_result) = tokio::join!(read_stdout, read_stderr);
+        stdout_result?;
+        let stderr = stderr_result?;
+        let status = child.wait().await?;
+        Ok::<_, postgresql_commands::Error>((status, stderr))
+    };
+    let (status, stderr) = match timeout {
+        Some(duration) => tokio::time::timeout(duration, run).await??,
+        None => run.await?,
+    };
+    if status.success() {
+        Ok(())
+    } else {
+        Err(postgresql_commands::Error::CommandError {
+            code: status.code(),
+            stdout: String::new(),
+            stderr,
+        })
+    }
+}
+
+#[cfg(not(feature = "tokio"))]
+/// Execute the `command` attached to the current process until it exits, calling `on_line` with
+/// each line of stderr as it is written; stdout is inherited from the current process. Lines
+/// that are not valid UTF-8, e.g. messages in the encoding of the server locale, are decoded
+/// lossily. Returns the exit status of the command; the command is killed if stderr cannot be
+/// read.
+pub(crate) fn execute_foreground<F>(
+    mut command: std::process::Command,
+    mut on_line: F,
+) -> postgresql_commands::Result<std::process::ExitStatus>
+where
+    F: FnMut(&str),
+{
+    use std::io::{BufRead, BufReader};
+    use std::process::Stdio;
+
+    debug!("Executing command in the foreground: {command:?}");
+    let program = command.get_program().to_os_string();
+    let mut child = command
+        .stdin(Stdio::null())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(|error| postgresql_commands::Error::spawn(&program, &error))?;
+    if let Some(stderr) = child.stderr.take() {
+        let mut reader = BufReader::new(stderr);
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            match reader.read_until(b'\n', &mut line) {
+                Ok(0) => break,
+                Ok(_) => on_line(&decode_line(&line)),
+                Err(error) => {
+                    let _ = child.kill();
+                    let _ = child.wait();
+                    return Err(error.into());
+                }
+            }
+        }
+    }
+    Ok(child.wait()?)
+}
+
+#[cfg(feature = "tokio")]
+/// Execute the `command` attached to the current process until it exits, calling `on_line` with
+/// each line of stderr as it is written; stdout is inherited from the current process. Lines
+/// that are not valid UTF-8 are decoded lossily. Returns the exit status of the command. The
+/// command is killed if the future is dropped, or stderr cannot be read.
+pub(crate) async fn execute_foreground<F>(
+    command: std::process::Command,
+    mut on_line: F,
+) -> postgresql_commands::Result<std::process::ExitStatus>
+where
+    F: FnMut(&str),
+{
+    use std::process::Stdio;
+    use tokio::io::{AsyncBufReadExt, BufReader};
+
+    debug!("Executing command in the foreground: {command:?}");
+    let program = command.get_program().to_os_string();
+    let mut child = tokio::process::Command::from(command)
+        .stdin(Stdio::null())
+        .stdout(Stdio::inherit())
+        .stderr(Stdio::piped())
+        .kill_on_drop(true)
+        .spawn()
+        .map_err(|error| postgresql_commands::Error::spawn(&program, &error))?;
+    if let Some(stderr) = child.stderr.take() {
+        let mut reader = BufReader::new(stderr);
+        let mut line = Vec::new();
+        loop {
+            line.clear();
+            match reader.read_until(b'\n', &mut line).await {
+                Ok(0) => break,
+                Ok(_) => on_line(&decode_line(&line)),
+                Err(error) => {
+                    let _ = child.kill().await;
+                    return Err(error.into());
+                }
+            }
+        }
+    }
+    Ok(child.wait().await?)
+}
+
+/// Decode a `line` read from the output of a command, without the line terminator; bytes that
+/// are not valid UTF-8 are replaced.
+fn decode_line(line: &[u8]) -> String {
+    let line = line.strip_suffix(b"\n").unwrap_or(line);
+    let line = line.strip_suffix(b"\r").unwrap_or(line);
+    String::from_utf8_lossy(line).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use postgresql_commands::psql::PsqlBuilder;
+
+    #[cfg(all(feature = "tokio", not(target_os = "windows")))]
+    #[tokio::test]
+    async fn test_execute_foreground() -> postgresql_commands::Result<()> {
+        let mut command = std::process::Command::new("sh");
+        command.args(["-c", "echo one >&2; echo two >&2; exit 3"]);
+        let mut lines = Vec::new();
+        let status = execute_foreground(command, |line| lines.push(line.to_string())).await?;
+        assert_eq!(vec!["one", "two"], lines);
+        assert_eq!(Some(3), status.code());
+        Ok(())
+    }
+
+    #[test]
+    fn test_decode_line() {
+        assert_eq!("LOG:  ready", decode_line(b"LOG:  ready\n"));
+        assert_eq!("LOG:  ready", decode_line(b"LOG:  ready\r\n"));
+        assert_eq!("LOG:  \u{fffd}", decode_line(b"LOG:  \xe9"));
+    }
+
+    #[test]
+    fn test_instance_command() {
+        let settings = Settings::default();
+        let builder = PsqlBuilder::new()
+            .command("SELECT 1")
+            .env("PGHOST", "override");
+        let command = InstanceCommand::new(builder, &settings);
+
+        assert_eq!(OsStr::new("psql"), command.get_program());
+        assert_eq!(&Some(settings.binary_dir()), command.get_program_dir());
+        assert!(command.get_args().contains(&"SELECT 1".into()));
+        let envs = command.get_envs();
+        let host = envs
+            .iter()
+            .rev()
+            .find(|(key, _)| key == "PGHOST")
+            .map(|(_, value)| value.clone());
+        assert_eq!(Some(OsString::from("override")), host);
+        assert!(envs.contains(&("PGPORT".into(), settings.port.to_string().into())));
+    }
+}
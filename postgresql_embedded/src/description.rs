@@ -0,0 +1,116 @@
+use crate::error::Error::IoError;
+use crate::error::Result;
+use crate::postgresql::Status;
+use crate::settings::{Settings, BOOTSTRAP_DATABASE};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::path::PathBuf;
+
+/// Replacement for the password in a [description](Description) with masked secrets
+pub(crate) const MASKED_SECRET: &str = "********";
+
+/// Machine-readable coordinates of a [`PostgreSQL`](crate::PostgreSQL) instance, returned by
+/// [`describe`](crate::PostgreSQL::describe) so that external tooling, such as scripts, IDEs and
+/// test harnesses, can connect to the server without parsing logs
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub struct Description {
+    /// Status of the server
+    pub status: Status,
+    /// Version of the server
+    pub version: String,
+    /// Installation directory of the server
+    pub installation_dir: PathBuf,
+    /// Directory of the server binaries
+    pub binary_dir: PathBuf,
+    /// Data directory of the server
+    pub data_dir: PathBuf,
+    /// File containing the password of the user
+    pub password_file: PathBuf,
+    /// Host the server listens on
+    pub host: String,
+    /// Port the server listens on
+    pub port: u16,
+    /// Name of the user
+    pub username: String,
+    /// Password of the user, or `********` if secrets are masked
+    pub password: String,
+    /// Whether the data directory is removed when the instance is dropped
+    pub temporary: bool,
+    /// Whether the server is managed externally
+    pub external: bool,
+    /// Server configuration options
+    pub configuration: BTreeMap<String, String>,
+    /// Connection URL of the bootstrap database
+    pub url: String,
+}
+
+impl Description {
+    /// Create the description of a server with the `settings` and `status`; the password is
+    /// replaced in the description and the URL if `mask_secrets` is set.
+    pub(crate) fn new(
+        settings: &Settings,
+        status: Status,
+        external: bool,
+        mask_secrets: bool,
+    ) -> Self {
+        let mut settings = settings.clone();
+        if mask_secrets {
+            settings.password = MASKED_SECRET.to_string();
+        }
+        Self {
+            status,
+            version: settings.version.to_string(),
+            installation_dir: settings.installation_dir.clone(),
+            binary_dir: settings.binary_dir(),
+            data_dir: settings.data_dir.clone(),
+            password_file: settings.password_file.clone(),
+            host: settings.host.clone(),
+            port: settings.port,
+            username: settings.username.clone(),
+            password: settings.password.clone(),
+            temporary: settings.temporary,
+            external,
+            configuration: settings.server_configuration().into_iter().collect(),
+            url: settings.url(BOOTSTRAP_DATABASE),
+        }
+    }
+
+    /// Serialize the description to pretty printed JSON.
+    ///
+    /// # Errors
+    /// * If the description cannot be serialized.
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string_pretty(self).map_err(|error| IoError(error.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SettingsBuilder;
+
+    #[test]
+    fn test_description() -> Result<()> {
+        let settings = SettingsBuilder::new().password("secret").build();
+        let description = Description::new(&settings, Status::NotInstalled, false, false);
+        assert_eq!("secret", description.password);
+        assert_eq!(settings.url(BOOTSTRAP_DATABASE), description.url);
+        assert_eq!(settings.binary_dir(), description.binary_dir);
+
+        let json = description.to_json()?;
+        let parsed: Description =
+            serde_json::from_str(&json).map_err(|error| IoError(error.to_string()))?;
+        assert_eq!(description, parsed);
+        Ok(())
+    }
+
+    #[test]
+    fn test_description_mask_secrets() -> Result<()> {
+        let settings = SettingsBuilder::new().password("secret").build();
+        let description = Description::new(&settings, Status::Started, true, true);
+        assert_eq!(MASKED_SECRET, description.password);
+        assert!(description.external);
+        assert!(!description.to_json()?.contains("secret"));
+        Ok(())
+    }
+}
@@ -0,0 +1,134 @@
+use crate::error::Error::AttachError;
+use crate::error::Result;
+use crate::settings::{InstallLayout, Settings};
+use postgresql_archive::VersionReq;
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Name of the file in the data directory describing a detached server
+pub(crate) const STATE_FILE: &str = "postgresql_embedded.json";
+
+/// State of a detached server, written to the data directory so that another process can attach
+/// to the server with [`attach`](crate::PostgreSQL::attach)
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub(crate) struct DetachedState {
+    pub version: String,
+    pub installation_dir: PathBuf,
+    pub password_file: PathBuf,
+    pub host: String,
+    pub port: u16,
+    pub username: String,
+    pub password: String,
+}
+
+impl DetachedState {
+    /// Create the state of a server started with the `settings`.
+    pub fn new(settings: &Settings) -> Self {
+        Self {
+            version: settings.version.to_string(),
+            installation_dir: settings.installation_dir.clone(),
+            password_file: settings.password_file.clone(),
+            host: settings.host.clone(),
+            port: settings.port,
+            username: settings.username.clone(),
+            password: settings.password.clone(),
+        }
+    }
+
+    /// Read the state of the server from the `data_dir`.
+    ///
+    /// # Errors
+    /// * If the state file does not exist or cannot be read.
+    pub fn read(data_dir: &Path) -> Result<Self> {
+        let state_file = data_dir.join(STATE_FILE);
+        let contents = fs::read(&state_file).map_err(|error| {
+            AttachError(format!(
+                "cannot read {}: {error}",
+                state_file.to_string_lossy()
+            ))
+        })?;
+        serde_json::from_slice(&contents).map_err(|error| {
+            AttachError(format!(
+                "invalid state file {}: {error}",
+                state_file.to_string_lossy()
+            ))
+        })
+    }
+
+    /// Write the state of the server to the `data_dir`; the file is only readable by the owner
+    /// since it contains the password.
+    ///
+    /// # Errors
+    /// * If the state file cannot be written.
+    pub fn write(&self, data_dir: &Path) -> Result<()> {
+        let contents =
+            serde_json::to_vec_pretty(self).map_err(|error| AttachError(error.to_string()))?;
+        let state_file = data_dir.join(STATE_FILE);
+        fs::write(&state_file, contents)?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            fs::set_permissions(&state_file, fs::Permissions::from_mode(0o600))?;
+        }
+        Ok(())
+    }
+
+    /// Apply the state to the `settings` used to attach to the server. The installation directory
+    /// is used as is, since it was resolved when the server was set up.
+    ///
+    /// # Errors
+    /// * If the version in the state is not a valid version requirement.
+    pub fn apply(&self, settings: &mut Settings) -> Result<()> {
+        settings.version = VersionReq::parse(&self.version)?;
+        settings.installation_dir.clone_from(&self.installation_dir);
+        settings.install_dir_layout = InstallLayout::Flat;
+        settings.password_file.clone_from(&self.password_file);
+        settings.host.clone_from(&self.host);
+        settings.port = self.port;
+        settings.username.clone_from(&self.username);
+        settings.password.clone_from(&self.password);
+        settings.password_source = None;
+        settings.detached = true;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SettingsBuilder;
+
+    #[test]
+    fn test_state() -> Result<()> {
+        let data_dir = tempfile::tempdir()?;
+        let settings = SettingsBuilder::new()
+            .version(VersionReq::parse("=16.4.0")?)
+            .installation_dir(PathBuf::from("/tmp/postgresql/16.4.0"))
+            .port(5432)
+            .build();
+        DetachedState::new(&settings).write(data_dir.path())?;
+
+        let state = DetachedState::read(data_dir.path())?;
+        let mut attached = Settings::default();
+        state.apply(&mut attached)?;
+
+        assert_eq!(settings.version, attached.version);
+        assert_eq!(settings.installation_dir, attached.installation_dir);
+        assert_eq!(InstallLayout::Flat, attached.install_dir_layout);
+        assert_eq!(settings.password_file, attached.password_file);
+        assert_eq!(5432, attached.port);
+        assert_eq!(settings.username, attached.username);
+        assert_eq!(settings.password, attached.password);
+        assert!(attached.detached);
+        Ok(())
+    }
+
+    #[test]
+    fn test_read_missing_state() -> Result<()> {
+        let data_dir = tempfile::tempdir()?;
+        let error = DetachedState::read(data_dir.path()).expect_err("state file does not exist");
+        assert!(matches!(error, AttachError(_)));
+        Ok(())
+    }
+}
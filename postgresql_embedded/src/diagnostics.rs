@@ -0,0 +1,44 @@
+use semver::VersionReq;
+use std::path::PathBuf;
+use std::time::Duration;
+
+/// Durations of the most recent server operations; operations that have not been performed by the
+/// [`PostgreSQL`](crate::PostgreSQL) instance are `None`
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Timings {
+    /// Download and extraction of the installation archive
+    pub install: Option<Duration>,
+    /// Initialization of the data directory with `initdb`
+    pub initialize: Option<Duration>,
+    /// Server startup
+    pub start: Option<Duration>,
+    /// Server shutdown
+    pub stop: Option<Duration>,
+    /// Creation of a database
+    pub create_database: Option<Duration>,
+    /// Removal of a database
+    pub drop_database: Option<Duration>,
+}
+
+/// Outcome of [`PostgreSQL::setup`](crate::PostgreSQL::setup); phases that were skipped because
+/// the server was already installed or initialized have no duration
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SetupReport {
+    /// Whether the installation archive was downloaded; `false` if the server was already
+    /// installed or the bundled archive was used
+    pub downloaded: bool,
+    /// Size of the installation archive in bytes, or `0` if no archive was extracted
+    pub bytes: usize,
+    /// Resolving the version and downloading the installation archive
+    pub download: Option<Duration>,
+    /// Extraction of the installation archive
+    pub extract: Option<Duration>,
+    /// Initialization of the data directory with `initdb`
+    pub initialize: Option<Duration>,
+    /// Version of the server after setup
+    pub version: VersionReq,
+    /// Directory the server is installed in
+    pub installation_dir: PathBuf,
+    /// Data directory of the server
+    pub data_dir: PathBuf,
+}
@@ -0,0 +1,699 @@
+use crate::error::Error::{MissingSystemLibraries, TargetMismatch};
+use crate::error::Result;
+use crate::settings::{Settings, TARGET};
+use std::net::TcpListener;
+use std::path::Path;
+use std::process::Command;
+
+/// Minimum free disk space, in bytes, recommended for an installation and data directory
+#[cfg(unix)]
+const MINIMUM_FREE_SPACE: u64 = 1024 * 1024 * 1024;
+
+/// Severity of a [finding](Finding) reported by [`doctor`](crate::PostgreSQL::doctor)
+#[derive(Clone, Copy, Debug, Eq, Ord, PartialEq, PartialOrd)]
+pub enum Severity {
+    /// Informational; the server is expected to work
+    Info,
+    /// The server may not work as expected
+    Warning,
+    /// The server is not expected to install, initialize or start
+    Error,
+}
+
+/// Environment problem reported by [`doctor`](crate::PostgreSQL::doctor)
+#[derive(Clone, Debug, PartialEq)]
+pub struct Finding {
+    /// Name of the check that reported the finding; e.g. `port`
+    pub check: &'static str,
+    /// Severity of the finding
+    pub severity: Severity,
+    /// Description of the problem
+    pub message: String,
+    /// Suggested fix for the problem
+    pub suggestion: Option<String>,
+}
+
+impl Finding {
+    fn new(check: &'static str, severity: Severity, message: String, suggestion: &str) -> Self {
+        Self {
+            check,
+            severity,
+            message,
+            suggestion: Some(suggestion.to_string()),
+        }
+    }
+}
+
+/// Check the environment for common problems that prevent the server from being installed,
+/// initialized or started with the `settings`. The port is only checked if the server is not
+/// `running`.
+pub(crate) fn diagnose(settings: &Settings, running: bool) -> Vec<Finding> {
+    let mut findings = Vec::new();
+    check_locale(&mut findings);
+    check_disk_space(settings, &mut findings);
+    if !running {
+        check_port(settings, &mut findings);
+    }
+    check_permissions(settings, &mut findings);
+    check_file_locks(settings, &mut findings);
+    check_shared_libraries(settings, &mut findings);
+    check_emulation(settings, &mut findings);
+    findings
+}
+
+/// Check if the process runs under emulation, such as an `x86_64` build under Rosetta on Apple
+/// Silicon; emulated binaries run considerably slower than native binaries.
+fn check_emulation(settings: &Settings, findings: &mut Vec<Finding>) {
+    if !is_emulated() {
+        return;
+    }
+    let machine_target = machine_target();
+    let suggestion = if settings.prefer_native_binaries {
+        format!("build the application for {machine_target}")
+    } else {
+        "enable prefer_native_binaries to install native PostgreSQL binaries".to_string()
+    };
+    findings.push(Finding::new(
+        "emulation",
+        Severity::Warning,
+        format!(
+            "the application is built for {TARGET} and runs under emulation on {machine_target}"
+        ),
+        &suggestion,
+    ));
+}
+
+/// Check that the locale of the environment, which `initdb` uses for the new cluster, is
+/// installed.
+#[cfg(unix)]
+fn check_locale(findings: &mut Vec<Finding>) {
+    let locale = ["LC_ALL", "LC_CTYPE", "LANG"]
+        .iter()
+        .find_map(|name| std::env::var(name).ok().filter(|value| !value.is_empty()));
+    let Some(locale) = locale else {
+        findings.push(Finding::new(
+            "locale",
+            Severity::Info,
+            "no locale is set; the cluster will use the C locale".to_string(),
+            "set LANG (e.g. LANG=en_US.UTF-8) to use a different locale",
+        ));
+        return;
+    };
+    if matches!(locale.as_str(), "C" | "POSIX" | "C.UTF-8" | "C.utf8") {
+        return;
+    }
+    let Ok(output) = Command::new("locale").arg("-a").output() else {
+        return;
+    };
+    let available = String::from_utf8_lossy(&output.stdout);
+    if !locale_available(&locale, &available) {
+        findings.push(Finding::new(
+            "locale",
+            Severity::Warning,
+            format!("locale {locale} is not installed"),
+            "install the locale (e.g. with locale-gen) or set LANG to an installed locale",
+        ));
+    }
+}
+
+#[cfg(not(unix))]
+fn check_locale(_findings: &mut Vec<Finding>) {}
+
+/// Check if the `locale` is in the output of `locale -a`; the codeset is compared without case
+/// or dashes, so `en_US.UTF-8` matches `en_US.utf8`.
+#[cfg(any(unix, test))]
+fn locale_available(locale: &str, available: &str) -> bool {
+    let normalize = |locale: &str| locale.trim().to_lowercase().replace('-', "");
+    let locale = normalize(locale);
+    available.lines().any(|line| normalize(line) == locale)
+}
+
+/// Check that the file system of the installation and data directories has enough free space.
+#[cfg(unix)]
+fn check_disk_space(settings: &Settings, findings: &mut Vec<Finding>) {
+    for path in [&settings.installation_dir, &settings.data_dir] {
+        let Some(path) = existing_ancestor(path) else {
+            continue;
+        };
+        let Ok(output) = Command::new("df").arg("-Pk").arg(path).output() else {
+            continue;
+        };
+        let Some(available) = parse_df(&String::from_utf8_lossy(&output.stdout)) else {
+            continue;
+        };
+        if available < MINIMUM_FREE_SPACE {
+            findings.push(Finding::new(
+                "disk_space",
+                Severity::Warning,
+                format!(
+                    "only {} MiB of disk space is available for {}",
+                    available / 1024 / 1024,
+                    path.to_string_lossy()
+                ),
+                "free disk space or use a directory on a different file system",
+            ));
+        }
+    }
+}
+
+#[cfg(not(unix))]
+fn check_disk_space(_settings: &Settings, _findings: &mut Vec<Finding>) {}
+
+/// Parse the available space, in bytes, from the output of `df -Pk`.
+#[cfg(any(unix, test))]
+fn parse_df(output: &str) -> Option<u64> {
+    let line = output.lines().nth(1)?;
+    let available = line.split_whitespace().nth(3)?;
+    available
+        .parse::<u64>()
+        .ok()
+        .map(|kilobytes| kilobytes * 1024)
+}
+
+/// Check that the configured port is not in use by another process.
+fn check_port(settings: &Settings, findings: &mut Vec<Finding>) {
+    if settings.port == 0 {
+        return;
+    }
+    if let Err(error) = TcpListener::bind((settings.host.as_str(), settings.port)) {
+        findings.push(Finding::new(
+            "port",
+            Severity::Error,
+            format!(
+                "port {} on {} is not available: {error}",
+                settings.port, settings.host
+            ),
+            "stop the process using the port or set the port to 0 to use a random port",
+        ));
+    }
+}
+
+/// Check that the installation directory, unless the server is already installed, data
+/// directory and password file can be written.
+fn check_permissions(settings: &Settings, findings: &mut Vec<Finding>) {
+    let mut paths = vec![settings.data_dir.as_path()];
+    if !settings.binary_dir().exists() {
+        paths.push(settings.installation_dir.as_path());
+    }
+    if let Some(parent) = settings.password_file.parent() {
+        paths.push(parent);
+    }
+    for path in paths {
+        let Some(path) = existing_ancestor(path) else {
+            continue;
+        };
+        if let Err(error) = tempfile::tempfile_in(path) {
+            findings.push(Finding::new(
+                "permissions",
+                Severity::Error,
+                format!("{} is not writable: {error}", path.to_string_lossy()),
+                "change the ownership or permissions of the directory, or use a different directory",
+            ));
+        }
+    }
+}
+
+/// Check that the installed binaries are not locked by another process; e.g. antivirus software
+/// scanning newly extracted files.
+#[cfg(windows)]
+fn check_file_locks(settings: &Settings, findings: &mut Vec<Finding>) {
+    /// Windows `ERROR_SHARING_VIOLATION`
+    const SHARING_VIOLATION: i32 = 32;
+
+    let Ok(entries) = std::fs::read_dir(settings.binary_dir()) else {
+        return;
+    };
+    for entry in entries.flatten() {
+        let path = entry.path();
+        let Err(error) = std::fs::OpenOptions::new().append(true).open(&path) else {
+            continue;
+        };
+        if error.raw_os_error() == Some(SHARING_VIOLATION) {
+            findings.push(Finding::new(
+                "file_locks",
+                Severity::Warning,
+                format!("{} is locked by another process", path.to_string_lossy()),
+                "exclude the installation directory from antivirus scanning",
+            ));
+        }
+    }
+}
+
+#[cfg(not(windows))]
+#[expect(clippy::ptr_arg)] // The signature of the check on Windows
+fn check_file_locks(_settings: &Settings, _findings: &mut Vec<Finding>) {}
+
+/// Check that the shared libraries required by the `postgres` binary are installed.
+fn check_shared_libraries(settings: &Settings, findings: &mut Vec<Finding>) {
+    let postgres = settings.binary_dir().join("postgres");
+    for library in missing_libraries(&postgres) {
+        let suggestion = match install_hint(&library) {
+            Some(hint) => format!("install {hint}"),
+            None => "install the package that provides the library".to_string(),
+        };
+        findings.push(Finding {
+            check: "shared_libraries",
+            severity: Severity::Error,
+            message: format!(
+                "shared library {library} required by {} is not installed",
+                postgres.to_string_lossy()
+            ),
+            suggestion: Some(suggestion),
+        });
+    }
+}
+
+/// Check that the `postgres` binary in the `binary_dir` can be executed. If it cannot, the shared
+/// libraries it requires that are not installed are reported; e.g. `libicu` or `libxml2` on
+/// minimal distributions. A binary built for `archive_target` that is not executable on this
+/// machine (e.g. an `x86_64` binary on an `aarch64` machine) is reported as a target mismatch
+/// rather than an exec format error from `initdb`.
+///
+/// # Errors
+/// * If the binaries were built for a target that cannot be executed on this machine.
+/// * If shared libraries required by the `postgres` binary are not installed.
+pub(crate) fn check_system_libraries(binary_dir: &Path, archive_target: &str) -> Result<()> {
+    let postgres = binary_dir.join("postgres");
+    match Command::new(&postgres).arg("--version").output() {
+        Ok(output) if output.status.success() => return Ok(()),
+        Err(error) if error.raw_os_error().is_some_and(is_exec_format_error) => {
+            return Err(TargetMismatch {
+                archive_target: archive_target.to_string(),
+                target: machine_target(),
+            });
+        }
+        _ => {}
+    }
+    let libs = missing_libraries(&postgres);
+    if libs.is_empty() {
+        return Ok(());
+    }
+    Err(MissingSystemLibraries { libs })
+}
+
+/// Determine if the OS error code reports a binary that is not executable on this machine
+/// (`ENOEXEC` on Unix, `EBADARCH` on macOS, `ERROR_BAD_EXE_FORMAT` on Windows).
+fn is_exec_format_error(code: i32) -> bool {
+    if cfg!(target_os = "windows") {
+        code == 193
+    } else if cfg!(target_os = "macos") {
+        code == 8 || code == 86
+    } else {
+        code == 8
+    }
+}
+
+/// Get the target triple of this machine; the target the crate was built for, with the
+/// architecture of the hardware when the process runs under emulation (e.g. under Rosetta).
+pub(crate) fn machine_target() -> String {
+    if !is_emulated() {
+        return TARGET.to_string();
+    }
+    let (Some((_, rest)), Some(machine)) = (TARGET.split_once('-'), machine_arch()) else {
+        return TARGET.to_string();
+    };
+    format!("{machine}-{rest}")
+}
+
+/// Get the architecture of the hardware. Under Rosetta, `uname -m` reports the architecture of
+/// the translated process, so Apple Silicon is detected with `sysctl` instead.
+fn machine_arch() -> Option<String> {
+    #[cfg(target_os = "macos")]
+    if command_output("sysctl", &["-n", "hw.optional.arm64"]).as_deref() == Some("1") {
+        return Some("aarch64".to_string());
+    }
+    command_output("uname", &["-m"])
+        .filter(|machine| !machine.is_empty())
+        .map(|machine| normalize_arch(&machine).to_string())
+}
+
+/// Determine if the process runs under emulation; i.e. the hardware runs the binaries built for
+/// the target of the crate by translation, such as an `x86_64` build under Rosetta. 32-bit
+/// builds on 64-bit hardware of the same family (e.g. `i686` on `x86_64` or `armv7` on
+/// `aarch64`) run natively.
+pub(crate) fn is_emulated() -> bool {
+    #[cfg(target_os = "macos")]
+    {
+        command_output("sysctl", &["-n", "sysctl.proc_translated"]).as_deref() == Some("1")
+    }
+    #[cfg(not(target_os = "macos"))]
+    {
+        let Some((arch, _)) = TARGET.split_once('-') else {
+            return false;
+        };
+        machine_arch().is_some_and(|machine| arch_family(&machine) != arch_family(arch))
+    }
+}
+
+/// Get the family of an architecture; the 32-bit and 64-bit architectures of a family run the
+/// binaries of each other natively.
+#[cfg(not(target_os = "macos"))]
+fn arch_family(arch: &str) -> &str {
+    match arch {
+        "x86_64" | "x86" | "i386" | "i586" | "i686" => "x86",
+        "aarch64" | "arm64" => "arm",
+        arch if arch.starts_with("arm") || arch.starts_with("thumb") => "arm",
+        "powerpc64" | "powerpc64le" | "ppc64le" | "ppc64" | "powerpc" => "powerpc",
+        arch => arch,
+    }
+}
+
+/// Get the trimmed standard output of a command that exits successfully.
+fn command_output(program: &str, args: &[&str]) -> Option<String> {
+    Command::new(program)
+        .args(args)
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Directories searched for the dynamic loader of the C library
+#[cfg(target_os = "linux")]
+const LOADER_DIRS: &[&str] = &["/lib", "/lib64"];
+
+/// Get the `target` with the environment of the C library of the system on Linux; e.g.
+/// `x86_64-unknown-linux-musl` on Alpine Linux for `x86_64-unknown-linux-gnu`. Other targets, and
+/// targets on systems whose C library cannot be determined, are returned as is.
+pub(crate) fn with_system_libc(target: &str) -> String {
+    #[cfg(target_os = "linux")]
+    {
+        let loaders = LOADER_DIRS
+            .iter()
+            .filter_map(|dir| std::fs::read_dir(dir).ok())
+            .flatten()
+            .filter_map(|entry| Some(entry.ok()?.file_name().to_string_lossy().to_string()))
+            .collect::<Vec<_>>();
+        if let Some(libc) = libc_of_loaders(&loaders) {
+            return with_libc(target, libc);
+        }
+    }
+    target.to_string()
+}
+
+/// Determine the C library of the system from the names of the dynamic `loaders`; glibc takes
+/// precedence, since the musl loader may be installed alongside it for cross compilation.
+#[cfg_attr(not(target_os = "linux"), expect(dead_code))]
+fn libc_of_loaders(loaders: &[String]) -> Option<&'static str> {
+    if loaders.iter().any(|loader| loader.starts_with("ld-linux")) {
+        Some("gnu")
+    } else if loaders.iter().any(|loader| loader.starts_with("ld-musl-")) {
+        Some("musl")
+    } else {
+        None
+    }
+}
+
+/// Replace the C library of the environment of a Linux `target` with the `libc`, keeping the
+/// ABI; e.g. `armv7-unknown-linux-gnueabihf` becomes `armv7-unknown-linux-musleabihf`.
+#[cfg_attr(not(target_os = "linux"), expect(dead_code))]
+fn with_libc(target: &str, libc: &str) -> String {
+    let Some((prefix, environment)) = target.rsplit_once('-') else {
+        return target.to_string();
+    };
+    if !prefix.ends_with("-linux") {
+        return target.to_string();
+    }
+    match environment
+        .strip_prefix("gnu")
+        .or_else(|| environment.strip_prefix("musl"))
+    {
+        Some(abi) => format!("{prefix}-{libc}{abi}"),
+        None => target.to_string(),
+    }
+}
+
+/// Normalize the architecture names reported by `uname -m` to those used in target triples.
+fn normalize_arch(arch: &str) -> &str {
+    match arch {
+        "arm64" => "aarch64",
+        "amd64" => "x86_64",
+        arch => arch,
+    }
+}
+
+/// Get the shared libraries required by the `binary` that cannot be found.
+#[cfg(target_os = "linux")]
+pub(crate) fn missing_libraries(binary: &Path) -> Vec<String> {
+    if !binary.exists() {
+        return Vec::new();
+    }
+    let Ok(output) = Command::new("ldd").arg(binary).output() else {
+        return Vec::new();
+    };
+    parse_ldd(&String::from_utf8_lossy(&output.stdout))
+}
+
+/// Get the shared libraries required by the `binary` that cannot be found. System libraries are
+/// excluded as they are provided by the dyld shared cache rather than the file system.
+#[cfg(target_os = "macos")]
+pub(crate) fn missing_libraries(binary: &Path) -> Vec<String> {
+    if !binary.exists() {
+        return Vec::new();
+    }
+    let Ok(output) = Command::new("otool").arg("-L").arg(binary).output() else {
+        return Vec::new();
+    };
+    parse_otool(&String::from_utf8_lossy(&output.stdout))
+        .into_iter()
+        .filter(|library| {
+            !library.starts_with("/usr/lib/")
+                && !library.starts_with("/System/")
+                && !library.starts_with('@')
+                && !Path::new(library).exists()
+        })
+        .collect()
+}
+
+#[cfg(not(any(target_os = "linux", target_os = "macos")))]
+pub(crate) fn missing_libraries(_binary: &Path) -> Vec<String> {
+    Vec::new()
+}
+
+/// Parse the libraries that could not be found from the output of `ldd`.
+#[cfg(any(target_os = "linux", test))]
+fn parse_ldd(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter(|line| line.contains("not found"))
+        .filter_map(|line| line.split("=>").next())
+        .map(|library| library.trim().to_string())
+        .collect()
+}
+
+/// Parse the libraries referenced by a binary from the output of `otool -L`.
+#[cfg(any(target_os = "macos", test))]
+fn parse_otool(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .skip(1)
+        .filter_map(|line| line.trim().split(" (").next())
+        .filter(|library| !library.is_empty())
+        .map(ToString::to_string)
+        .collect()
+}
+
+/// Get the packages that commonly provide a shared library, by package manager.
+pub(crate) fn install_hint(library: &str) -> Option<&'static str> {
+    const HINTS: &[(&str, &str)] = &[
+        (
+            "libicu",
+            "ICU (apt: libicu-dev, apk: icu-libs, dnf: libicu)",
+        ),
+        (
+            "libxml2",
+            "libxml2 (apt: libxml2, apk: libxml2, dnf: libxml2)",
+        ),
+        (
+            "libxslt",
+            "libxslt (apt: libxslt1.1, apk: libxslt, dnf: libxslt)",
+        ),
+        (
+            "libssl",
+            "OpenSSL (apt: libssl3, apk: libssl3, dnf: openssl-libs)",
+        ),
+        (
+            "libcrypto",
+            "OpenSSL (apt: libssl3, apk: libcrypto3, dnf: openssl-libs)",
+        ),
+        ("libz.", "zlib (apt: zlib1g, apk: zlib, dnf: zlib)"),
+        (
+            "liblz4",
+            "LZ4 (apt: liblz4-1, apk: lz4-libs, dnf: lz4-libs)",
+        ),
+        (
+            "libzstd",
+            "Zstandard (apt: libzstd1, apk: zstd-libs, dnf: libzstd)",
+        ),
+        (
+            "libreadline",
+            "readline (apt: libreadline8, apk: readline, dnf: readline)",
+        ),
+        (
+            "libgssapi",
+            "Kerberos (apt: libgssapi-krb5-2, apk: krb5-libs, dnf: krb5-libs)",
+        ),
+        (
+            "libldap",
+            "OpenLDAP (apt: libldap-2.5-0, apk: libldap, dnf: openldap)",
+        ),
+        (
+            "libuuid",
+            "libuuid (apt: libuuid1, apk: libuuid, dnf: libuuid)",
+        ),
+    ];
+    HINTS
+        .iter()
+        .find(|(prefix, _)| library.starts_with(prefix))
+        .map(|(_, hint)| *hint)
+}
+
+/// Get the path, or the closest ancestor of the path, that exists.
+fn existing_ancestor(path: &Path) -> Option<&Path> {
+    path.ancestors().find(|path| path.exists())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::SettingsBuilder;
+
+    #[test]
+    fn test_locale_available() {
+        let available = "C\nC.utf8\nen_US.utf8\nPOSIX\n";
+        assert!(locale_available("en_US.UTF-8", available));
+        assert!(locale_available("en_US.utf8", available));
+        assert!(!locale_available("de_DE.UTF-8", available));
+    }
+
+    #[test]
+    fn test_parse_df() {
+        let output = "Filesystem 1024-blocks Used Available Capacity Mounted on\n\
+                      /dev/sda1 102400 51200 51200 50% /\n";
+        assert_eq!(Some(51200 * 1024), parse_df(output));
+        assert_eq!(None, parse_df(""));
+    }
+
+    #[test]
+    fn test_parse_ldd() {
+        let output = "\tlinux-vdso.so.1 (0x00007ffd)\n\
+                      \tlibicui18n.so.70 => not found\n\
+                      \tlibc.so.6 => /lib/x86_64-linux-gnu/libc.so.6 (0x00007f)\n";
+        assert_eq!(vec!["libicui18n.so.70".to_string()], parse_ldd(output));
+    }
+
+    #[test]
+    fn test_parse_otool() {
+        let output = "/opt/postgresql/bin/postgres:\n\
+                      \t/opt/homebrew/opt/icu4c/lib/libicui18n.74.dylib (compatibility version 74.0.0)\n\
+                      \t/usr/lib/libSystem.B.dylib (compatibility version 1.0.0)\n";
+        assert_eq!(
+            vec![
+                "/opt/homebrew/opt/icu4c/lib/libicui18n.74.dylib".to_string(),
+                "/usr/lib/libSystem.B.dylib".to_string()
+            ],
+            parse_otool(output)
+        );
+    }
+
+    #[test]
+    fn test_normalize_arch() {
+        assert_eq!("aarch64", normalize_arch("arm64"));
+        assert_eq!("x86_64", normalize_arch("amd64"));
+        assert_eq!("x86_64", normalize_arch("x86_64"));
+    }
+
+    #[test]
+    fn test_libc_of_loaders() {
+        let glibc = vec!["ld-linux-x86-64.so.2".to_string()];
+        let musl = vec!["ld-musl-x86_64.so.1".to_string()];
+        assert_eq!(Some("gnu"), libc_of_loaders(&glibc));
+        assert_eq!(Some("musl"), libc_of_loaders(&musl));
+        assert_eq!(Some("gnu"), libc_of_loaders(&[musl, glibc].concat()));
+        assert_eq!(None, libc_of_loaders(&["libc.so.6".to_string()]));
+    }
+
+    #[test]
+    fn test_with_libc() {
+        assert_eq!(
+            "x86_64-unknown-linux-musl",
+            with_libc("x86_64-unknown-linux-gnu", "musl")
+        );
+        assert_eq!(
+            "aarch64-unknown-linux-gnu",
+            with_libc("aarch64-unknown-linux-musl", "gnu")
+        );
+        assert_eq!(
+            "armv7-unknown-linux-musleabihf",
+            with_libc("armv7-unknown-linux-gnueabihf", "musl")
+        );
+        assert_eq!(
+            "aarch64-apple-darwin",
+            with_libc("aarch64-apple-darwin", "musl")
+        );
+        assert_eq!(
+            "x86_64-pc-windows-msvc",
+            with_libc("x86_64-pc-windows-msvc", "gnu")
+        );
+    }
+
+    #[cfg(not(target_os = "macos"))]
+    #[test]
+    fn test_arch_family() {
+        assert_eq!("x86", arch_family("x86_64"));
+        assert_eq!("x86", arch_family("i686"));
+        assert_eq!("arm", arch_family("aarch64"));
+        assert_eq!("arm", arch_family("armv7"));
+        assert_eq!("arm", arch_family("armv7l"));
+        assert_eq!("s390x", arch_family("s390x"));
+    }
+
+    #[test]
+    fn test_machine_target() {
+        let machine_target = machine_target();
+        let (_, rest) = TARGET.split_once('-').unwrap_or_default();
+        assert!(machine_target.ends_with(rest));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn test_check_system_libraries_not_executable() -> Result<()> {
+        use std::os::unix::fs::PermissionsExt;
+        let binary_dir = tempfile::tempdir()?;
+        let postgres = binary_dir.path().join("postgres");
+        std::fs::write(&postgres, [0u8; 64])?;
+        std::fs::set_permissions(&postgres, std::fs::Permissions::from_mode(0o755))?;
+        let error = check_system_libraries(binary_dir.path(), "foo-unknown-none")
+            .expect_err("binary is not executable");
+        assert!(matches!(error, TargetMismatch { .. }));
+        assert!(error.to_string().contains("foo-unknown-none"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_install_hint() {
+        assert!(install_hint("libicuuc.so.70").is_some_and(|hint| hint.starts_with("ICU")));
+        assert!(install_hint("libz.so.1").is_some());
+        assert_eq!(None, install_hint("libunknown.so.1"));
+    }
+
+    #[test]
+    fn test_check_port() {
+        let listener = TcpListener::bind(("localhost", 0)).unwrap();
+        let settings = SettingsBuilder::new()
+            .host("localhost")
+            .port(listener.local_addr().unwrap().port())
+            .build();
+        let mut findings = Vec::new();
+        check_port(&settings, &mut findings);
+        assert_eq!(1, findings.len());
+        assert_eq!("port", findings[0].check);
+        assert_eq!(Severity::Error, findings[0].severity);
+    }
+
+    #[test]
+    fn test_severity_order() {
+        assert!(Severity::Info < Severity::Warning);
+        assert!(Severity::Warning < Severity::Error);
+    }
+}
@@ -1,3 +1,5 @@
+use crate::settings::LocaleProvider;
+use std::path::PathBuf;
 use std::string::FromUtf8Error;
 
 /// `PostgreSQL` embedded result type
@@ -9,12 +11,52 @@ pub enum Error {
     /// Error when `PostgreSQL` archive operations fail
     #[error(transparent)]
     ArchiveError(postgresql_archive::Error),
-    /// Error when a command fails
-    #[error("Command error: stdout={stdout}; stderr={stderr}")]
-    CommandError { stdout: String, stderr: String },
+    /// Error when a detached server could not be attached to
+    #[error("{0}")]
+    AttachError(String),
+    /// Error when the checksums of tables could not be computed
+    #[error("{0}")]
+    ChecksumError(String),
+    /// Error when an instance could not be cloned
+    #[error("{0}")]
+    CloneError(String),
+    /// Error when a cluster operation, such as a failover, fails
+    #[error("{0}")]
+    ClusterError(String),
+    /// Error when a command exits with a non-zero exit code; the code is `None` if the command
+    /// was terminated by a signal
+    #[error(
+        "Command error: code={}; stdout={stdout}; stderr={stderr}",
+        .code.map_or_else(|| "none".to_string(), |code| code.to_string())
+    )]
+    CommandError {
+        code: Option<i32>,
+        stdout: String,
+        stderr: String,
+    },
+    /// Error when a command cannot be started; e.g. the binary is missing from the installation
+    /// or is not executable
+    #[error("failed to execute {program}: {message}")]
+    CommandSpawnError {
+        program: String,
+        kind: std::io::ErrorKind,
+        message: String,
+    },
+    /// Error when a command does not complete before the timeout is reached
+    #[error("{0}")]
+    CommandTimeoutError(String),
+    /// Error when a database could not be backed up
+    #[error("{0}")]
+    BackupError(String),
+    /// Error when the server configuration could not be changed
+    #[error("{0}")]
+    ConfigurationError(String),
     /// Error when the database could not be created
     #[error("{0}")]
     CreateDatabaseError(String),
+    /// Error when the data directory is in use by another `PostgreSQL` instance of the process
+    #[error("data directory {} is in use by another PostgreSQL instance", .0.to_string_lossy())]
+    DataDirInUse(PathBuf),
     /// Error when accessing the database
     #[error(transparent)]
     DatabaseError(#[from] sqlx::Error),
@@ -24,6 +66,9 @@ pub enum Error {
     /// Error when the database could not be initialized
     #[error("{0}")]
     DatabaseInitializationError(String),
+    /// Error when the database is performing crash recovery
+    #[error("{0}")]
+    DatabaseRecoveryError(String),
     /// Error when the database could not be started
     #[error("{0}")]
     DatabaseStartError(String),
@@ -33,21 +78,162 @@ pub enum Error {
     /// Error when the database could not be dropped
     #[error("{0}")]
     DropDatabaseError(String),
+    /// Error when application data could not be exported
+    #[error("{0}")]
+    ExportError(String),
+    /// Error when application data could not be imported
+    #[error("{0}")]
+    ImportError(String),
+    /// Error when a data directory could not be inspected
+    #[error("{0}")]
+    InspectError(String),
     /// Error when an invalid URL is provided
     #[error("Invalid URL: {url}; {message}")]
     InvalidUrl { url: String, message: String },
+    /// Error when the server does not become ready to accept connections
+    #[error("{0}")]
+    ReadinessError(String),
+    /// Error when an installation does not work from its current location
+    #[error("{0}")]
+    RelocationError(String),
+    /// Error when a replication slot operation fails
+    #[error("{0}")]
+    ReplicationSlotError(String),
+    /// Error when a database could not be restored
+    #[error("{0}")]
+    RestoreError(String),
+    /// Error when a restore point could not be created
+    #[error("{0}")]
+    RestorePointError(String),
+    /// Error when the schemas of databases could not be compared
+    #[error("{0}")]
+    SchemaDiffError(String),
+    /// Error when SQL statements could not be run
+    #[error("{0}")]
+    SqlError(String),
+    /// Error when a tenant operation fails
+    #[error("{0}")]
+    TenantError(String),
+    /// Error when the data directory could not be upgraded to a new major version
+    #[error("{0}")]
+    UpgradeError(String),
+    /// Error when a user operation fails
+    #[error("{0}")]
+    UserError(String),
+    /// Error when a Windows service operation fails
+    #[error("{0}")]
+    WindowsServiceError(String),
     /// Error when IO operations fail
     #[error("{0}")]
     IoError(String),
+    /// Error when the version reported by the installed `postgres` binary does not match the
+    /// version of the installation archive
+    #[error(
+        "installed PostgreSQL version {installed} does not match the expected version {expected}"
+    )]
+    InstalledVersionMismatch {
+        expected: semver::Version,
+        installed: semver::Version,
+    },
+    /// Error when shared libraries required by the installed binaries are not installed
+    #[error("missing system libraries: {}; {}", .libs.join(", "), install_hints(.libs))]
+    MissingSystemLibraries { libs: Vec<String> },
+    /// Error when the installed binaries were built for a target that cannot run on this machine
+    #[error("PostgreSQL binaries built for {archive_target} cannot be executed on {target}")]
+    TargetMismatch {
+        archive_target: String,
+        target: String,
+    },
+    /// Error when the locale provider is not supported by the installed binaries
+    #[error("locale provider {} is not supported: {message}", .provider.as_str())]
+    UnsupportedLocaleProvider {
+        provider: LocaleProvider,
+        message: String,
+    },
+    /// Error when no installed or cached version satisfies the version requirement in
+    /// [offline](crate::Settings::offline) mode
+    #[error(
+        "offline: no installed or cached version satisfies version requirement '{version_req}'; \
+        available versions: [{}]",
+        .cached_versions.iter().map(ToString::to_string).collect::<Vec<_>>().join(", ")
+    )]
+    OfflineCacheMiss {
+        version_req: String,
+        cached_versions: Vec<semver::Version>,
+    },
+    /// Error when a resource that is not installed or cached would be requested from the network
+    /// in [offline](crate::Settings::offline) mode
+    #[error("offline: '{0}' is not available locally and network access is disabled")]
+    OfflineResourceMissing(String),
+    /// Error when the database could not be optimized
+    #[error("{0}")]
+    OptimizeDatabaseError(String),
     /// Parse error
     #[error(transparent)]
     ParseError(#[from] semver::Error),
 }
 
+/// Describe the packages that provide the missing libraries
+fn install_hints(libs: &[String]) -> String {
+    let mut hints: Vec<&str> = libs
+        .iter()
+        .filter_map(|library| crate::doctor::install_hint(library))
+        .collect();
+    hints.sort_unstable();
+    hints.dedup();
+    if hints.is_empty() {
+        "install the packages that provide them".to_string()
+    } else {
+        format!("install {}", hints.join(", "))
+    }
+}
+
 /// Convert `PostgreSQL` [archive errors](postgresql_archive::Error) to an [embedded errors](Error::ArchiveError)
 impl From<postgresql_archive::Error> for Error {
     fn from(error: postgresql_archive::Error) -> Self {
-        Error::ArchiveError(error)
+        match error {
+            postgresql_archive::Error::OfflineCacheMiss {
+                version_req,
+                cached_versions,
+            } => Error::OfflineCacheMiss {
+                version_req,
+                cached_versions,
+            },
+            postgresql_archive::Error::OfflineResourceMissing(resource) => {
+                Error::OfflineResourceMissing(resource)
+            }
+            error => Error::ArchiveError(error),
+        }
+    }
+}
+
+/// Convert [command errors](postgresql_commands::Error) to [embedded errors](Error)
+impl From<postgresql_commands::Error> for Error {
+    fn from(error: postgresql_commands::Error) -> Self {
+        match error {
+            postgresql_commands::Error::CommandError {
+                code,
+                stdout,
+                stderr,
+            } => Error::CommandError {
+                code,
+                stdout,
+                stderr,
+            },
+            postgresql_commands::Error::SpawnError {
+                program,
+                kind,
+                message,
+            } => Error::CommandSpawnError {
+                program,
+                kind,
+                message,
+            },
+            postgresql_commands::Error::IoError(message) => Error::IoError(message),
+            postgresql_commands::Error::TimeoutError(message) => {
+                Error::CommandTimeoutError(message)
+            }
+        }
     }
 }
 
@@ -78,6 +264,69 @@ mod test {
         assert_eq!(error.to_string(), "version not found for 'test'");
     }
 
+    #[test]
+    fn test_from_archive_offline_cache_miss() {
+        let archive_error = postgresql_archive::Error::OfflineCacheMiss {
+            version_req: "=16".to_string(),
+            cached_versions: vec![semver::Version::new(15, 8, 0)],
+        };
+        let error = Error::from(archive_error);
+        assert_eq!(
+            error.to_string(),
+            "offline: no installed or cached version satisfies version requirement '=16'; \
+            available versions: [15.8.0]"
+        );
+    }
+
+    #[test]
+    fn test_from_archive_offline_resource_missing() {
+        let archive_error = postgresql_archive::Error::OfflineResourceMissing(
+            "https://github.com/theseus-rs/postgresql-binaries".to_string(),
+        );
+        let error = Error::from(archive_error);
+        assert!(matches!(error, Error::OfflineResourceMissing(_)));
+        assert_eq!(
+            error.to_string(),
+            "offline: 'https://github.com/theseus-rs/postgresql-binaries' is not available \
+            locally and network access is disabled"
+        );
+    }
+
+    #[test]
+    fn test_from_command_error() {
+        let command_error = postgresql_commands::Error::CommandError {
+            code: Some(1),
+            stdout: "out".to_string(),
+            stderr: "err".to_string(),
+        };
+        let error = Error::from(command_error);
+        assert!(matches!(error, Error::CommandError { code: Some(1), .. }));
+        assert_eq!(
+            error.to_string(),
+            "Command error: code=1; stdout=out; stderr=err"
+        );
+
+        let spawn_error = postgresql_commands::Error::SpawnError {
+            program: "initdb".to_string(),
+            kind: std::io::ErrorKind::NotFound,
+            message: "not found".to_string(),
+        };
+        let error = Error::from(spawn_error);
+        assert!(matches!(
+            error,
+            Error::CommandSpawnError {
+                kind: std::io::ErrorKind::NotFound,
+                ..
+            }
+        ));
+        assert_eq!(error.to_string(), "failed to execute initdb: not found");
+
+        let timeout_error = postgresql_commands::Error::TimeoutError("timeout".to_string());
+        let error = Error::from(timeout_error);
+        assert!(matches!(error, Error::CommandTimeoutError(_)));
+        assert_eq!(error.to_string(), "timeout");
+    }
+
     #[test]
     fn test_from_io_error() {
         let io_error = std::io::Error::new(std::io::ErrorKind::Other, "test");
@@ -85,6 +334,17 @@ mod test {
         assert_eq!(error.to_string(), "test");
     }
 
+    #[test]
+    fn test_missing_system_libraries() {
+        let error = Error::MissingSystemLibraries {
+            libs: vec!["libicuuc.so.70".to_string(), "libicui18n.so.70".to_string()],
+        };
+        assert_eq!(
+            error.to_string(),
+            "missing system libraries: libicuuc.so.70, libicui18n.so.70; install ICU (apt: libicu-dev, apk: icu-libs, dnf: libicu)"
+        );
+    }
+
     #[test]
     fn test_from_utf8_error() {
         let invalid_utf8: Vec<u8> = vec![0, 159, 146, 150];
@@ -0,0 +1,118 @@
+use postgresql_archive::{ExtractProgress, VersionReq};
+use std::fmt::{Debug, Formatter};
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Lifecycle event of a [`PostgreSQL`](crate::PostgreSQL) server, passed to the
+/// [event listeners](EventListener) of the server
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    /// Download of the installation archive for the version requirement started
+    DownloadStarted { version: VersionReq },
+    /// Download of the installation archive completed
+    DownloadCompleted { version: VersionReq, bytes: usize },
+    /// Extraction of the installation archive to the installation directory started
+    ExtractionStarted { path: PathBuf },
+    /// Progress of the extraction of the installation archive, reported as each file is extracted
+    ExtractionProgress {
+        path: PathBuf,
+        progress: ExtractProgress,
+    },
+    /// Extraction of the installation archive to the installation directory completed
+    ExtractionCompleted { path: PathBuf },
+    /// Initialization of the data directory with `initdb` started
+    InitializationStarted { path: PathBuf },
+    /// Initialization of the data directory with `initdb` completed
+    InitializationCompleted { path: PathBuf },
+    /// The server is starting
+    ServerStarting { port: u16 },
+    /// The server started and is accepting connections
+    ServerStarted { port: u16 },
+    /// The server is stopping
+    ServerStopping { port: u16 },
+    /// The server stopped
+    ServerStopped { port: u16 },
+    /// A database was created
+    DatabaseCreated { name: String },
+    /// A database was dropped
+    DatabaseDropped { name: String },
+}
+
+/// Listener for the lifecycle [events](Event) of a [`PostgreSQL`](crate::PostgreSQL) server; e.g.
+/// to record metrics or report progress during setup. Listeners are called synchronously on the
+/// task performing the operation and should return quickly.
+pub trait EventListener: Send + Sync {
+    /// Called when the `event` occurs
+    fn on_event(&self, event: &Event);
+}
+
+impl<F> EventListener for F
+where
+    F: Fn(&Event) + Send + Sync,
+{
+    fn on_event(&self, event: &Event) {
+        self(event);
+    }
+}
+
+/// Event listeners registered with a server; clones of the server share the listeners
+#[derive(Clone, Default)]
+pub(crate) struct EventListeners(Vec<Arc<dyn EventListener>>);
+
+impl EventListeners {
+    /// Register the `listener`
+    pub fn add(&mut self, listener: Arc<dyn EventListener>) {
+        self.0.push(listener);
+    }
+
+    /// Pass the `event` to each listener in the order they were registered
+    pub fn emit(&self, event: &Event) {
+        for listener in &self.0 {
+            listener.on_event(event);
+        }
+    }
+}
+
+impl Debug for EventListeners {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EventListeners")
+            .field("len", &self.0.len())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[test]
+    fn test_emit() {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut listeners = EventListeners::default();
+        for _ in 0..2 {
+            let events = events.clone();
+            listeners.add(Arc::new(move |event: &Event| {
+                events.lock().expect("events").push(event.clone());
+            }));
+        }
+
+        listeners.emit(&Event::ServerStarted { port: 5432 });
+
+        let events = events.lock().expect("events");
+        assert_eq!(
+            vec![
+                Event::ServerStarted { port: 5432 },
+                Event::ServerStarted { port: 5432 }
+            ],
+            *events
+        );
+    }
+
+    #[test]
+    fn test_debug() {
+        let mut listeners = EventListeners::default();
+        listeners.add(Arc::new(|_: &Event| {}));
+        assert_eq!("EventListeners { len: 1 }", format!("{listeners:?}"));
+    }
+}
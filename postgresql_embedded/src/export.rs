@@ -0,0 +1,178 @@
+use crate::error::Error::{ExportError, ImportError};
+use crate::error::Result;
+use postgresql_archive::Version;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::fs::File;
+use std::io::{copy, BufReader};
+use std::path::Path;
+use std::str::FromStr;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::debug;
+
+/// Version of the export archive format
+const FORMAT_VERSION: u32 = 1;
+/// Name of the manifest file within the export archive
+const MANIFEST_FILE: &str = "manifest.json";
+
+/// Manifest describing the contents of an application data export archive
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub(crate) struct ExportManifest {
+    pub format_version: u32,
+    pub postgresql_version: Option<String>,
+    pub created: u64,
+    pub databases: Vec<ExportedDatabase>,
+}
+
+/// A database dump contained in an application data export archive
+#[derive(Clone, Debug, Deserialize, PartialEq, Serialize)]
+pub(crate) struct ExportedDatabase {
+    pub name: String,
+    pub file: String,
+    pub sha256: String,
+}
+
+impl ExportManifest {
+    /// Creates a new manifest for an export created by the given `PostgreSQL` version.
+    pub fn new(postgresql_version: Option<&Version>) -> Self {
+        let created = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|duration| duration.as_secs())
+            .unwrap_or_default();
+        Self {
+            format_version: FORMAT_VERSION,
+            postgresql_version: postgresql_version.map(ToString::to_string),
+            created,
+            databases: Vec::new(),
+        }
+    }
+
+    /// Gets the `PostgreSQL` version that created the export, if known.
+    pub fn postgresql_version(&self) -> Option<Version> {
+        self.postgresql_version
+            .as_ref()
+            .and_then(|version| Version::from_str(version).ok())
+    }
+
+    /// Adds the database dump `file` in the `staging_dir` to the manifest.
+    ///
+    /// # Errors
+    /// * If the checksum of the file cannot be calculated.
+    pub fn add_database(&mut self, name: &str, staging_dir: &Path, file: &str) -> Result<()> {
+        let sha256 = checksum(&staging_dir.join(file))?;
+        self.databases.push(ExportedDatabase {
+            name: name.to_string(),
+            file: file.to_string(),
+            sha256,
+        });
+        Ok(())
+    }
+
+    /// Writes the manifest and the database dumps in the `staging_dir` to a single archive file.
+    ///
+    /// # Errors
+    /// * If the archive cannot be written.
+    pub fn write_archive(&self, staging_dir: &Path, path: &Path) -> Result<()> {
+        let manifest =
+            serde_json::to_vec_pretty(self).map_err(|error| ExportError(error.to_string()))?;
+        std::fs::write(staging_dir.join(MANIFEST_FILE), manifest)?;
+
+        let mut builder = tar::Builder::new(File::create(path)?);
+        builder.append_path_with_name(staging_dir.join(MANIFEST_FILE), MANIFEST_FILE)?;
+        for database in &self.databases {
+            builder.append_path_with_name(staging_dir.join(&database.file), &database.file)?;
+        }
+        builder.into_inner()?.sync_all()?;
+        debug!(
+            "Exported {} databases to {}",
+            self.databases.len(),
+            path.to_string_lossy()
+        );
+        Ok(())
+    }
+
+    /// Unpacks the archive file at `path` into the `staging_dir` and verifies the checksums of the
+    /// database dumps against the manifest.
+    ///
+    /// # Errors
+    /// * If the archive cannot be read.
+    /// * If the archive format is not supported.
+    /// * If a checksum does not match the manifest.
+    pub fn read_archive(path: &Path, staging_dir: &Path) -> Result<Self> {
+        let mut archive = tar::Archive::new(BufReader::new(File::open(path)?));
+        archive.unpack(staging_dir)?;
+
+        let manifest = std::fs::read(staging_dir.join(MANIFEST_FILE))?;
+        let manifest: Self =
+            serde_json::from_slice(&manifest).map_err(|error| ImportError(error.to_string()))?;
+        if manifest.format_version > FORMAT_VERSION {
+            return Err(ImportError(format!(
+                "unsupported export format version {}",
+                manifest.format_version
+            )));
+        }
+
+        for database in &manifest.databases {
+            let sha256 = checksum(&staging_dir.join(&database.file))?;
+            if sha256 != database.sha256 {
+                return Err(ImportError(format!(
+                    "checksum mismatch for database {}: expected {}, found {sha256}",
+                    database.name, database.sha256
+                )));
+            }
+        }
+        Ok(manifest)
+    }
+}
+
+/// Calculates the SHA2-256 checksum of the file at `path`.
+fn checksum(path: &Path) -> Result<String> {
+    let mut file = File::open(path)?;
+    let mut hasher = Sha256::new();
+    copy(&mut file, &mut hasher)?;
+    Ok(hex::encode(hasher.finalize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_archive_round_trip() -> Result<()> {
+        let staging_dir = tempfile::tempdir()?;
+        std::fs::write(staging_dir.path().join("0.dump"), b"test")?;
+        let version = Version::new(16, 4, 0);
+        let mut manifest = ExportManifest::new(Some(&version));
+        manifest.add_database("test", staging_dir.path(), "0.dump")?;
+        let archive_dir = tempfile::tempdir()?;
+        let archive = archive_dir.path().join("export.tar");
+        manifest.write_archive(staging_dir.path(), &archive)?;
+
+        let import_dir = tempfile::tempdir()?;
+        let imported = ExportManifest::read_archive(&archive, import_dir.path())?;
+        assert_eq!(manifest, imported);
+        assert_eq!(Some(version), imported.postgresql_version());
+        assert_eq!(
+            "9f86d081884c7d659a2feaa0c55ad015a3bf4f1b2b0b822cd15d6c15b0f00a08",
+            imported.databases[0].sha256
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_archive_checksum_mismatch() -> Result<()> {
+        let staging_dir = tempfile::tempdir()?;
+        std::fs::write(staging_dir.path().join("0.dump"), b"test")?;
+        let mut manifest = ExportManifest::new(None);
+        manifest.add_database("test", staging_dir.path(), "0.dump")?;
+        manifest.databases[0].sha256 = "invalid".to_string();
+        let archive_dir = tempfile::tempdir()?;
+        let archive = archive_dir.path().join("export.tar");
+        manifest.write_archive(staging_dir.path(), &archive)?;
+
+        let import_dir = tempfile::tempdir()?;
+        let error = ExportManifest::read_archive(&archive, import_dir.path()).unwrap_err();
+        assert!(error.to_string().contains("checksum mismatch"));
+        Ok(())
+    }
+}
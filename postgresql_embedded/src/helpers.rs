@@ -0,0 +1,238 @@
+//! Helpers for the tasks that most applications perform after starting a server, such as creating
+//! a connection pool or enabling an extension that must be loaded when the server starts:
+//!
+//! ```no_run
+//! use postgresql_embedded::helpers::{connect_pool, enable_extension, preload_libraries};
+//! use postgresql_embedded::PostgreSQL;
+//!
+//! # async fn example() -> postgresql_embedded::Result<()> {
+//! let mut postgresql = PostgreSQL::default();
+//! postgresql.setup().await?;
+//! postgresql.start().await?;
+//! postgresql.create_database("example").await?;
+//!
+//! preload_libraries(&mut postgresql, &["pg_stat_statements"]).await?;
+//! enable_extension(&postgresql, "example", "pg_stat_statements").await?;
+//! let pool = connect_pool(&postgresql, "example").await?;
+//! # Ok(())
+//! # }
+//! ```
+
+use crate::postgresql::{quote_identifier, quote_literal};
+use crate::retry::retry;
+use crate::trace_context::annotate;
+use crate::Error::ConfigurationError;
+use crate::{PostgreSQL, Result, BOOTSTRAP_DATABASE};
+use sqlx::postgres::{PgConnectOptions, PgPoolOptions};
+use sqlx::PgPool;
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::{debug, instrument};
+
+/// Maximum number of connections of the pools created with [`pool_options`]
+pub const DEFAULT_MAX_CONNECTIONS: u32 = 5;
+/// Time to wait for a connection of the pools created with [`pool_options`]
+pub const DEFAULT_ACQUIRE_TIMEOUT: Duration = Duration::from_secs(3);
+/// Configuration option of the libraries that are loaded when the server starts
+const SHARED_PRELOAD_LIBRARIES: &str = "shared_preload_libraries";
+
+/// Get the default options of the pools created with [`connect_pool`]; at most
+/// [`DEFAULT_MAX_CONNECTIONS`] connections, acquired within [`DEFAULT_ACQUIRE_TIMEOUT`].
+#[must_use]
+pub fn pool_options() -> PgPoolOptions {
+    PgPoolOptions::new()
+        .max_connections(DEFAULT_MAX_CONNECTIONS)
+        .acquire_timeout(DEFAULT_ACQUIRE_TIMEOUT)
+}
+
+/// Create a connection pool to the database as the user of the settings, with the default
+/// [pool options](pool_options).
+///
+/// # Errors
+/// * If the pool cannot connect to the database.
+pub async fn connect_pool(postgresql: &PostgreSQL, database_name: &str) -> Result<PgPool> {
+    connect_pool_with(postgresql, database_name, pool_options()).await
+}
+
+/// Create a connection pool to the database as the user of the settings, with the given pool
+/// `options`. Connecting is retried according to the
+/// [retry policy](crate::Settings::retry_policy) of the settings, as a server that was just
+/// started may not accept connections yet.
+///
+/// # Errors
+/// * If the pool cannot connect to the database.
+#[instrument(skip(postgresql, options))]
+pub async fn connect_pool_with(
+    postgresql: &PostgreSQL,
+    database_name: &str,
+    options: PgPoolOptions,
+) -> Result<PgPool> {
+    let settings = postgresql.settings();
+    let database_url = settings.url(database_name);
+    let connect_options = PgConnectOptions::from_str(database_url.as_str())?;
+    let pool = retry(&settings.retry_policy, || {
+        options.clone().connect_with(connect_options.clone())
+    })
+    .await?;
+    Ok(pool)
+}
+
+/// Restart the server; e.g. for configuration parameters that only take effect when the server
+/// is started.
+///
+/// # Errors
+/// * If the server cannot be stopped or started.
+#[instrument(skip(postgresql))]
+pub async fn restart(postgresql: &mut PostgreSQL) -> Result<()> {
+    postgresql.stop().await?;
+    postgresql.start().await
+}
+
+/// Add the `libraries` to the `shared_preload_libraries` with `ALTER SYSTEM` and restart the
+/// server so that they are loaded, as required by extensions such as `pg_stat_statements` or
+/// `vectors`. If the [configuration](crate::Settings::configuration) of the settings sets
+/// `shared_preload_libraries`, which takes precedence over `ALTER SYSTEM`, the libraries are
+/// added to that option instead. Libraries that are already configured are kept, and the server
+/// is only restarted if a library was added. Returns `true` if the server was restarted.
+///
+/// # Errors
+/// * If the configuration cannot be read or changed, or the server cannot be restarted.
+#[instrument(skip(postgresql))]
+pub async fn preload_libraries(postgresql: &mut PostgreSQL, libraries: &[&str]) -> Result<bool> {
+    if let Some(configured) = postgresql
+        .settings()
+        .configuration
+        .get(SHARED_PRELOAD_LIBRARIES)
+    {
+        let mut preload_libraries = parse_libraries(configured);
+        if !add_libraries(&mut preload_libraries, libraries) {
+            debug!("Shared preload libraries {configured} are already configured");
+            return Ok(false);
+        }
+
+        let value = preload_libraries.join(",");
+        debug!("Setting shared preload libraries configuration to {value}");
+        postgresql
+            .settings_mut()
+            .configuration
+            .insert(SHARED_PRELOAD_LIBRARIES.to_string(), value);
+        restart(postgresql).await?;
+        return Ok(true);
+    }
+
+    let pool = connect_pool(postgresql, BOOTSTRAP_DATABASE).await?;
+    let configured: String = sqlx::query_scalar("SHOW shared_preload_libraries")
+        .fetch_one(&pool)
+        .await
+        .map_err(|error| ConfigurationError(error.to_string()))?;
+    let mut preload_libraries = parse_libraries(&configured);
+    if !add_libraries(&mut preload_libraries, libraries) {
+        pool.close().await;
+        debug!("Shared preload libraries {configured} are already configured");
+        return Ok(false);
+    }
+
+    let value = preload_libraries
+        .iter()
+        .map(|library| quote_literal(library))
+        .collect::<Vec<_>>()
+        .join(", ");
+    debug!("Setting shared preload libraries to {value}");
+    sqlx::query(
+        annotate(&format!(
+            "ALTER SYSTEM SET shared_preload_libraries = {value}"
+        ))
+        .as_str(),
+    )
+    .execute(&pool)
+    .await
+    .map_err(|error| ConfigurationError(error.to_string()))?;
+    pool.close().await;
+
+    restart(postgresql).await?;
+    Ok(true)
+}
+
+/// Create the `extension` in the database if it does not exist. Extensions that must be loaded
+/// when the server starts must be [preloaded](preload_libraries) before they are enabled.
+///
+/// # Errors
+/// * If the extension cannot be created.
+#[instrument(skip(postgresql))]
+pub async fn enable_extension(
+    postgresql: &PostgreSQL,
+    database_name: &str,
+    extension: &str,
+) -> Result<()> {
+    let pool = connect_pool(postgresql, database_name).await?;
+    let sql = format!(
+        "CREATE EXTENSION IF NOT EXISTS {}",
+        quote_identifier(extension)
+    );
+    let result = sqlx::query(annotate(&sql).as_str()).execute(&pool).await;
+    pool.close().await;
+    result?;
+    debug!("Enabled extension {extension} in database {database_name}");
+    Ok(())
+}
+
+/// Add the `libraries` that are not already in the `preload_libraries`; returns `true` if a
+/// library was added.
+fn add_libraries(preload_libraries: &mut Vec<String>, libraries: &[&str]) -> bool {
+    let mut added = false;
+    for library in libraries {
+        if !preload_libraries.iter().any(|name| name == library) {
+            preload_libraries.push((*library).to_string());
+            added = true;
+        }
+    }
+    added
+}
+
+/// Parse the libraries of the `shared_preload_libraries` as shown by the server; e.g.
+/// `"vectors.so", pg_stat_statements`.
+fn parse_libraries(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|library| library.trim().trim_matches('"').to_string())
+        .filter(|library| !library.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pool_options() {
+        let options = pool_options();
+        assert_eq!(DEFAULT_MAX_CONNECTIONS, options.get_max_connections());
+        assert_eq!(DEFAULT_ACQUIRE_TIMEOUT, options.get_acquire_timeout());
+    }
+
+    #[test]
+    fn test_add_libraries() {
+        let mut preload_libraries = vec!["pg_stat_statements".to_string()];
+        assert!(!add_libraries(
+            &mut preload_libraries,
+            &["pg_stat_statements"]
+        ));
+        assert!(add_libraries(
+            &mut preload_libraries,
+            &["vectors.so", "pg_stat_statements"]
+        ));
+        assert_eq!(
+            vec!["pg_stat_statements".to_string(), "vectors.so".to_string()],
+            preload_libraries
+        );
+    }
+
+    #[test]
+    fn test_parse_libraries() {
+        assert!(parse_libraries("").is_empty());
+        assert_eq!(
+            vec!["vectors.so".to_string(), "pg_stat_statements".to_string()],
+            parse_libraries("\"vectors.so\", pg_stat_statements")
+        );
+    }
+}
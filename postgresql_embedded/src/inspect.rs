@@ -0,0 +1,135 @@
+use crate::error::Error::InspectError;
+use crate::error::Result;
+use crate::installation::{copy_dir, EXCLUDED_DATA_FILES};
+use crate::postgresql::{quote_identifier, quote_literal};
+use crate::upgrade::data_dir_major_version;
+use postgresql_commands::postgres::PostgresBuilder;
+use postgresql_commands::CommandBuilder;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::process::Stdio;
+use tracing::debug;
+
+/// Client authentication of an inspection copy; local connections authenticate with the password
+/// that is [set](set_password) for the user of the inspecting instance, as the passwords of the
+/// copied roles are not known
+const INSPECT_HBA_CONF: &str = "# Inspection copy created by postgresql_embedded
+local all all scram-sha-256
+host all all 127.0.0.1/32 scram-sha-256
+host all all ::1/128 scram-sha-256
+";
+
+/// Copy the data directory `source` to `target` for inspection, requiring password
+/// authentication for local connections to the copy. Returns the major version of `PostgreSQL`
+/// that initialized the data directory.
+///
+/// # Errors
+/// * If `source` is not an initialized data directory or cannot be copied.
+pub(crate) fn copy_data_dir(source: &Path, target: &Path) -> Result<u64> {
+    let Some(major) = data_dir_major_version(source)? else {
+        return Err(InspectError(format!(
+            "{} is not a PostgreSQL data directory",
+            source.to_string_lossy()
+        )));
+    };
+    debug!(
+        "Copying data directory {} to {}",
+        source.to_string_lossy(),
+        target.to_string_lossy()
+    );
+    copy_dir(source, target, EXCLUDED_DATA_FILES)?;
+    fs::write(target.join("pg_hba.conf"), INSPECT_HBA_CONF)?;
+    Ok(major)
+}
+
+/// Set the `password` of the superuser `username` in the inspection copy at `data_dir`, creating
+/// the role if the copy has no such role, with `postgres` in single-user mode, which does not
+/// authenticate. The server of the copy must not be running.
+///
+/// # Errors
+/// * If `postgres` cannot be run, or the password cannot be set.
+pub(crate) fn set_password(
+    binary_dir: &Path,
+    data_dir: &Path,
+    username: &str,
+    password: &str,
+) -> Result<()> {
+    let role = quote_identifier(username);
+    let password = quote_literal(password);
+    // Each line is a statement in single-user mode
+    let statements = format!(
+        "SET password_encryption = 'scram-sha-256'\n\
+         DO $$ BEGIN \
+         IF EXISTS (SELECT FROM pg_roles WHERE rolname = {name}) THEN \
+         ALTER ROLE {role} SUPERUSER LOGIN PASSWORD {password}; \
+         ELSE CREATE ROLE {role} SUPERUSER LOGIN PASSWORD {password}; \
+         END IF; END $$\n",
+        name = quote_literal(username),
+    );
+    // The database of the bootstrap superuser may not exist in the copy, unlike template1
+    let mut command = PostgresBuilder::new()
+        .program_dir(binary_dir)
+        .single_user_mode()
+        .dbname("template1")
+        .build();
+    let mut child = command
+        .env("PGDATA", data_dir)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()?;
+    if let Some(mut stdin) = child.stdin.take() {
+        stdin.write_all(statements.as_bytes())?;
+    }
+    let output = child.wait_with_output()?;
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    if !output.status.success() || stderr.contains("ERROR:") {
+        return Err(InspectError(format!(
+            "failed to set the password of {username}: {}",
+            stderr.trim()
+        )));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_copy_data_dir() -> Result<()> {
+        let source = tempfile::tempdir()?;
+        fs::write(source.path().join("PG_VERSION"), "16\n")?;
+        fs::write(
+            source.path().join("pg_hba.conf"),
+            "host all all all scram-sha-256\n",
+        )?;
+        fs::write(source.path().join("postmaster.pid"), "42\n")?;
+        let target = tempfile::tempdir()?;
+
+        let major = copy_data_dir(source.path(), target.path())?;
+
+        assert_eq!(16, major);
+        assert!(target.path().join("PG_VERSION").exists());
+        assert!(!target.path().join("postmaster.pid").exists());
+        assert_eq!(
+            INSPECT_HBA_CONF,
+            fs::read_to_string(target.path().join("pg_hba.conf"))?
+        );
+        assert_eq!(
+            "host all all all scram-sha-256\n",
+            fs::read_to_string(source.path().join("pg_hba.conf"))?
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_copy_data_dir_not_initialized() -> Result<()> {
+        let source = tempfile::tempdir()?;
+        let target = tempfile::tempdir()?;
+        let error = copy_data_dir(source.path(), target.path()).expect_err("not a data directory");
+        assert!(matches!(error, InspectError(_)));
+        Ok(())
+    }
+}
@@ -0,0 +1,196 @@
+use crate::detached::STATE_FILE;
+use crate::doctor::missing_libraries;
+use crate::error::Error::RelocationError;
+use crate::error::Result;
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+use tracing::debug;
+
+/// Files in the data directory that are specific to a running server and are not exported
+pub(crate) const EXCLUDED_DATA_FILES: &[&str] =
+    &["postmaster.pid", "postmaster.opts", "start.log", STATE_FILE];
+
+/// Name of the launch script of an exported installation
+#[cfg(not(target_os = "windows"))]
+const LAUNCH_SCRIPT: &str = "start.sh";
+/// Name of the launch script of an exported installation
+#[cfg(target_os = "windows")]
+const LAUNCH_SCRIPT: &str = "start.cmd";
+
+/// Launch script of an exported installation; initializes the data directory if it does not exist
+/// and runs the server in the foreground, passing any arguments to `postgres`.
+#[cfg(not(target_os = "windows"))]
+const LAUNCH_SCRIPT_CONTENTS: &str = r#"#!/bin/sh
+# Start the PostgreSQL server in the foreground. The data directory defaults to the data
+# directory next to this script; set PGDATA to use a different directory. If the data directory
+# is not initialized, it is initialized with the password in POSTGRES_PASSWORD_FILE.
+set -e
+DIR="$(cd "$(dirname "$0")" && pwd)"
+PGDATA="${PGDATA:-$DIR/data}"
+if [ ! -f "$PGDATA/PG_VERSION" ]; then
+    "$DIR/bin/initdb" -D "$PGDATA" -U postgres -A password --pwfile="${POSTGRES_PASSWORD_FILE:?POSTGRES_PASSWORD_FILE is required to initialize $PGDATA}" -E UTF8
+fi
+exec "$DIR/bin/postgres" -D "$PGDATA" "$@"
+"#;
+
+/// Launch script of an exported installation; initializes the data directory if it does not exist
+/// and runs the server in the foreground, passing any arguments to `postgres`.
+#[cfg(target_os = "windows")]
+const LAUNCH_SCRIPT_CONTENTS: &str = r#"@echo off
+rem Start the PostgreSQL server in the foreground. The data directory defaults to the data
+rem directory next to this script; set PGDATA to use a different directory. If the data directory
+rem is not initialized, it is initialized with the password in POSTGRES_PASSWORD_FILE.
+setlocal
+set "DIR=%~dp0"
+if "%PGDATA%"=="" set "PGDATA=%DIR%data"
+if not exist "%PGDATA%\PG_VERSION" (
+    "%DIR%bin\initdb.exe" -D "%PGDATA%" -U postgres -A password --pwfile="%POSTGRES_PASSWORD_FILE%" -E UTF8 || exit /b 1
+)
+"%DIR%bin\postgres.exe" -D "%PGDATA%" %*
+"#;
+
+/// Copy the installation in `installation_dir` to `target_dir` along with a launch script, and
+/// the data directory as a pre-initialized template if `data_dir` is provided.
+///
+/// # Errors
+/// * If the installation or data directory cannot be copied.
+pub(crate) fn export_installation(
+    installation_dir: &Path,
+    data_dir: Option<&Path>,
+    target_dir: &Path,
+) -> Result<()> {
+    debug!(
+        "Exporting installation {} to {}",
+        installation_dir.to_string_lossy(),
+        target_dir.to_string_lossy()
+    );
+    copy_dir(installation_dir, target_dir, &[])?;
+    if let Some(data_dir) = data_dir {
+        copy_dir(data_dir, &target_dir.join("data"), EXCLUDED_DATA_FILES)?;
+    }
+
+    let launch_script = target_dir.join(LAUNCH_SCRIPT);
+    fs::write(&launch_script, LAUNCH_SCRIPT_CONTENTS)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&launch_script, fs::Permissions::from_mode(0o755))?;
+    }
+    validate_relocation(target_dir)
+}
+
+/// Validate that the installation at `path` works from its current location. The binaries locate
+/// their shared libraries relative to their own location (`$ORIGIN` on Linux, `@loader_path` on
+/// macOS and the executable directory on Windows); a binary that references a library by the
+/// absolute path of the directory it was built or extracted in fails once the installation is
+/// moved.
+///
+/// # Errors
+/// * If a binary references libraries that cannot be found, or `postgres` cannot be executed.
+pub(crate) fn validate_relocation(path: &Path) -> Result<()> {
+    let binary_dir = path.join("bin");
+    let mut problems = Vec::new();
+    for entry in fs::read_dir(&binary_dir)? {
+        let binary = entry?.path();
+        for library in missing_libraries(&binary) {
+            problems.push(format!(
+                "{} references missing library {library}",
+                binary.to_string_lossy()
+            ));
+        }
+    }
+
+    let postgres = binary_dir.join("postgres");
+    match Command::new(&postgres).arg("--version").output() {
+        Ok(output) if output.status.success() => {}
+        Ok(output) => problems.push(format!(
+            "{} cannot be executed: {}",
+            postgres.to_string_lossy(),
+            String::from_utf8_lossy(&output.stderr).trim()
+        )),
+        Err(error) => problems.push(format!(
+            "{} cannot be executed: {error}",
+            postgres.to_string_lossy()
+        )),
+    }
+
+    if problems.is_empty() {
+        debug!("Installation {} is relocatable", path.to_string_lossy());
+        Ok(())
+    } else {
+        Err(RelocationError(format!(
+            "installation {} is not relocatable: {}",
+            path.to_string_lossy(),
+            problems.join("; ")
+        )))
+    }
+}
+
+/// Recursively copy the `source` directory to the `target` directory, preserving file permissions
+/// and symbolic links, and skipping top level files named in `excluded`.
+pub(crate) fn copy_dir(source: &Path, target: &Path, excluded: &[&str]) -> Result<()> {
+    fs::create_dir_all(target)?;
+    fs::set_permissions(target, fs::metadata(source)?.permissions())?;
+    for entry in fs::read_dir(source)? {
+        let entry = entry?;
+        if excluded
+            .iter()
+            .any(|excluded| entry.file_name() == *excluded)
+        {
+            continue;
+        }
+        let source = entry.path();
+        let target = target.join(entry.file_name());
+        let file_type = entry.file_type()?;
+        if file_type.is_dir() {
+            copy_dir(&source, &target, &[])?;
+        } else if file_type.is_symlink() {
+            #[cfg(unix)]
+            std::os::unix::fs::symlink(fs::read_link(&source)?, &target)?;
+            #[cfg(not(unix))]
+            fs::copy(&source, &target)?;
+        } else {
+            fs::copy(&source, &target)?;
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_validate_relocation_missing_postgres() -> Result<()> {
+        let installation_dir = tempfile::tempdir()?;
+        fs::create_dir_all(installation_dir.path().join("bin"))?;
+        let error = validate_relocation(installation_dir.path()).expect_err("not relocatable");
+        assert!(error.to_string().contains("is not relocatable"));
+        Ok(())
+    }
+
+    #[test]
+    fn test_copy_dir() -> Result<()> {
+        let installation_dir = tempfile::tempdir()?;
+        fs::create_dir_all(installation_dir.path().join("bin"))?;
+        fs::write(installation_dir.path().join("bin").join("postgres"), "")?;
+        let data_dir = tempfile::tempdir()?;
+        fs::write(data_dir.path().join("PG_VERSION"), "17")?;
+        fs::write(data_dir.path().join("postmaster.pid"), "")?;
+        let target_dir = tempfile::tempdir()?;
+
+        copy_dir(installation_dir.path(), target_dir.path(), &[])?;
+        copy_dir(
+            data_dir.path(),
+            &target_dir.path().join("data"),
+            EXCLUDED_DATA_FILES,
+        )?;
+
+        let target_dir = target_dir.path();
+        assert!(target_dir.join("bin").join("postgres").exists());
+        assert!(target_dir.join("data").join("PG_VERSION").exists());
+        assert!(!target_dir.join("data").join("postmaster.pid").exists());
+        Ok(())
+    }
+}
@@ -12,6 +12,7 @@
 //!
 //! - [Examples](#examples)
 //! - [Information](#information)
+//! - [Tracing](#tracing)
 //! - [Feature flags](#feature-flags)
 //! - [Safety](#safety)
 //! - [License](#license)
@@ -75,10 +76,59 @@
 //! - Unix: `$HOME/.theseus/postgresql`
 //! - Windows: `%USERPROFILE%\.theseus\postgresql`
 //!
+//! By default, the PostgreSQL binaries for the architecture the application is built for are
+//! installed. When an application built for `x86_64` runs under Rosetta on Apple Silicon, or under
+//! emulation on other platforms, set `prefer_native_binaries` to `true` in the settings to install
+//! the binaries for the architecture of the hardware, as they are considerably faster.
+//! On Linux, binaries linked with the C library of the system (glibc or musl) are installed. Set
+//! `target` in the settings to install binaries for another target triple; e.g.
+//! `x86_64-unknown-linux-musl`.
+//!
 //! Performance can be improved by using a specific version of the PostgreSQL binaries (e.g. `=16.4.0`).
 //! After the first download, the PostgreSQL binaries will be cached and reused for subsequent runs.
 //! Further, the repository will no longer be queried to calculate the version match.
 //!
+//! ## Tracing
+//!
+//! Long-running operations are instrumented with [tracing](https://docs.rs/tracing) spans that
+//! use the following stable fields, so that they can be consumed by `tracing-indicatif`,
+//! OpenTelemetry or other subscribers. Fields that are not known when the span is entered are
+//! recorded once they are determined.
+//!
+//! | Field         | Description                                                              |
+//! |---------------|--------------------------------------------------------------------------|
+//! | `operation`   | Name of the operation (see below)                                        |
+//! | `version`     | PostgreSQL or extension version (requirement until resolved)             |
+//! | `bytes_total` | Size of the downloaded or extracted archive in bytes                     |
+//! | `path`        | Directory operated on (installation, data or extension library directory) |
+//!
+//! | `operation`           | Crate                   | Fields                                   |
+//! |-----------------------|-------------------------|------------------------------------------|
+//! | `get_version`         | `postgresql_archive`    | `version`                                |
+//! | `download`            | `postgresql_archive`    | `version`, `bytes_total`                 |
+//! | `extract`             | `postgresql_archive`    | `path`, `bytes_total`                    |
+//! | `install_extension`   | `postgresql_extensions` | `version`, `bytes_total`, `path`         |
+//! | `uninstall_extension` | `postgresql_extensions` |                                          |
+//! | `setup`               | `postgresql_embedded`   | `version`, `path`                        |
+//! | `install`             | `postgresql_embedded`   | `version`, `bytes_total`, `path`         |
+//! | `initialize`          | `postgresql_embedded`   | `version`, `path`                        |
+//! | `start`               | `postgresql_embedded`   | `version`, `path`                        |
+//! | `stop`                | `postgresql_embedded`   | `version`, `path`                        |
+//! | `reload`              | `postgresql_embedded`   | `version`, `path`                        |
+//! | `upgrade`             | `postgresql_embedded`   | `version`, `path`                        |
+//! | `inspect`             | `postgresql_embedded`   | `path`                                   |
+//! | `create_database`     | `postgresql_embedded`   |                                          |
+//! | `drop_database`       | `postgresql_embedded`   |                                          |
+//! | `backup`              | `postgresql_embedded`   |                                          |
+//! | `restore`             | `postgresql_embedded`   |                                          |
+//! | `export`              | `postgresql_embedded`   |                                          |
+//! | `import`              | `postgresql_embedded`   |                                          |
+//! | `export_installation` | `postgresql_embedded`   | `version`, `path`                        |
+//!
+//! Applications that do not use a tracing subscriber, such as GUI applications, can receive the
+//! progress of downloads and extractions with a [`ProgressReporter`] set with
+//! [`PostgreSQL::with_progress_reporter`].
+//!
 //! ## Feature flags
 //!
 //! postgresql_embedded uses feature flags to address compile time and binary size
@@ -87,15 +137,23 @@
 //! The following features are available:
 //!
 //!
-//! | Name         | Description                                              | Default? |
-//! |--------------|----------------------------------------------------------|----------|
-//! | `bundled`    | Bundles the PostgreSQL archive into the resulting binary | No       |
-//! | `blocking`   | Enables the blocking API; requires `tokio`               | No       |
-//! | `native-tls` | Enables native-tls support                               | Yes      |
-//! | `rustls-tls` | Enables rustls-tls support                               | No       |
-//! | `theseus`    | Enables theseus PostgreSQL binaries                      | Yes      |
-//! | `tokio`      | Enables using tokio for async                            | No       |
-//! | `zonky`      | Enables zonky PostgreSQL binaries                        | No       |
+//! | Name            | Description                                              | Default? |
+//! |-----------------|----------------------------------------------------------|----------|
+//! | `bundled`       | Bundles the PostgreSQL archive into the resulting binary | No       |
+//! | `blocking`      | Enables the blocking API; requires `tokio`               | No       |
+//! | `extensions`    | Re-exports the extension installer in the prelude        | No       |
+//! | `file`          | Enables installing archives from a local `file://` URL   | No       |
+//! | `gitlab`        | Enables installing archives from GitLab releases         | No       |
+//! | `helpers`       | Enables helpers for connection pools and extensions      | No       |
+//! | `http`          | Enables installing archives from an `index+` HTTP mirror | No       |
+//! | `minisign`      | Verifies archive signatures with minisign                | No       |
+//! | `native-tls`    | Enables native-tls support                               | Yes      |
+//! | `opentelemetry` | Propagates the OpenTelemetry trace context to the server | No       |
+//! | `rustls-tls`    | Enables rustls-tls support                               | No       |
+//! | `tar-zst`       | Enables Zstandard (`.tar.zst`) archives                  | No       |
+//! | `theseus`       | Enables theseus PostgreSQL binaries                      | Yes      |
+//! | `tokio`         | Enables using tokio for async                            | No       |
+//! | `zonky`         | Enables zonky PostgreSQL binaries                        | No       |
 //!
 //! ## Safety
 //!
@@ -119,17 +177,70 @@
 #![allow(clippy::doc_markdown)]
 #![allow(deprecated)]
 
+mod backup;
 #[cfg(feature = "blocking")]
 pub mod blocking;
+mod cleanup;
+mod cluster;
+mod command;
+mod description;
+mod detached;
+mod diagnostics;
+mod doctor;
 mod error;
+mod event;
+mod export;
+#[cfg(feature = "helpers")]
+pub mod helpers;
+mod inspect;
+mod installation;
 mod postgresql;
+mod postmaster;
+pub mod prelude;
+mod registry;
+mod replication_slot;
+mod retry;
+mod schema_diff;
+mod server_log;
+mod service;
 mod settings;
+mod settings_builder;
+mod system;
+mod tenant;
+mod trace_context;
+mod upgrade;
 
+pub use backup::BackupFormat;
+pub use cluster::Cluster;
+pub use description::Description;
+pub use diagnostics::{SetupReport, Timings};
+pub use doctor::{Finding, Severity};
 pub use error::{Error, Result};
-pub use postgresql::{PostgreSQL, Status};
-pub use postgresql_archive::{Version, VersionReq};
-pub use settings::Settings;
+pub use event::{Event, EventListener};
+pub use postgresql::{PostgreSQL, SqlOutput, Status};
+pub use postgresql_archive::cache::{
+    ArchiveCache, CacheEntry, CachedArchive, FileSystemCache, PrunePolicy,
+};
+pub use postgresql_archive::{
+    clear_credentials_providers, latest_of, list_versions, register_credentials_provider,
+    set_default_extract_options, Channel, CredentialsProvider, ExtractFilter, ExtractOptions,
+    ExtractProgress, ProgressReporter, ProxyOptions, ReleaseInfo, RetryPolicy, RetryableFn,
+    StaticCredentials, Version, VersionReq, DEFAULT_RETRY_STATUS_CODES,
+};
+pub use postgresql_commands::CommandOutput;
+pub use replication_slot::{ReplicationSlot, ReplicationSlotType};
+pub use schema_diff::{SchemaChange, SchemaDiff, SchemaObject};
+pub use service::{
+    ServiceKind, ServiceStartType, ServiceUnitOptions, WindowsServiceOptions, DEFAULT_SERVICE_LABEL,
+};
+pub use settings::{
+    DataDirStrategy, DatabaseOptions, InstallLayout, LocaleProvider, LogCapture, PasswordCallback,
+    PasswordSource, ProcessMode, Settings, ShutdownMode, ShutdownOptions, Timezone,
+    TransactionIsolation, WaitStrategy,
+};
+pub use settings_builder::SettingsBuilder;
 use std::sync::LazyLock;
+pub use tenant::{Tenant, TenantManager, TenantQuota, DEFAULT_TENANT_PREFIX};
 
 /// The latest PostgreSQL version requirement
 pub static LATEST: VersionReq = VersionReq::STAR;
@@ -0,0 +1,255 @@
+use crate::error::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::warn;
+
+/// File in the data directory written by the postmaster while the server is running
+pub(crate) const PID_FILE: &str = "postmaster.pid";
+
+/// Contents of the postmaster PID file of a running server
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct PostmasterPid {
+    /// Process ID of the postmaster
+    pub pid: u32,
+    /// Data directory of the server
+    pub data_dir: PathBuf,
+    /// Port the server listens on
+    pub port: u16,
+    /// Status of the server (e.g. `starting`, `ready` or `stopping`), if reported
+    pub status: Option<String>,
+}
+
+impl PostmasterPid {
+    /// Read the PID file in the `data_dir`; returns `None` if the file does not exist or is not
+    /// complete, as when the postmaster is still writing it.
+    pub fn read(data_dir: &Path) -> Option<Self> {
+        let contents = fs::read_to_string(data_dir.join(PID_FILE)).ok()?;
+        Self::parse(&contents)
+    }
+
+    /// Parse the contents of a PID file; the lines are the process ID, data directory, start
+    /// time, port, socket directory, listen address, shared memory key and status.
+    fn parse(contents: &str) -> Option<Self> {
+        let lines: Vec<&str> = contents.lines().collect();
+        let pid = lines.first()?.trim().parse().ok()?;
+        let data_dir = PathBuf::from(lines.get(1)?.trim());
+        let port = lines.get(3)?.trim().parse().ok()?;
+        let status = lines.get(7).map(|status| status.trim().to_string());
+        Some(Self {
+            pid,
+            data_dir,
+            port,
+            status,
+        })
+    }
+
+    /// Returns `true` if the server reported that it is ready to accept connections.
+    pub fn is_ready(&self) -> bool {
+        self.status.as_deref() == Some("ready")
+    }
+
+    /// Returns `true` if the process ID belongs to a running `postgres` process; after an unclean
+    /// shutdown, the process may no longer exist or the ID may have been reused by an unrelated
+    /// process. If the processes cannot be inspected, the server is assumed to be running.
+    #[cfg(target_os = "linux")]
+    pub fn is_alive(&self) -> bool {
+        let process_dir = Path::new("/proc").join(self.pid.to_string());
+        let Ok(name) = fs::read_to_string(process_dir.join("comm")) else {
+            return process_dir.exists();
+        };
+        if name.trim() != "postgres" {
+            return false;
+        }
+        // The name of the process can be changed, so the program of the command line must be
+        // postgres as well
+        if let Ok(command_line) = fs::read(process_dir.join("cmdline")) {
+            let command_line = String::from_utf8_lossy(&command_line);
+            let program = command_line.split('\0').next().unwrap_or_default();
+            if !is_postgres_program(program) {
+                return false;
+            }
+        }
+        // The postmaster changes its working directory to the data directory
+        match (
+            fs::read_link(process_dir.join("cwd")),
+            fs::canonicalize(&self.data_dir),
+        ) {
+            (Ok(working_dir), Ok(data_dir)) => working_dir == data_dir,
+            _ => true,
+        }
+    }
+
+    /// Returns `true` if the process ID belongs to a running `postgres` process; after an unclean
+    /// shutdown, the process may no longer exist or the ID may have been reused by an unrelated
+    /// process. If the processes cannot be inspected, the server is assumed to be running.
+    #[cfg(all(unix, not(target_os = "linux")))]
+    pub fn is_alive(&self) -> bool {
+        let pid = self.pid.to_string();
+        match Command::new("ps")
+            .args(["-p", &pid, "-o", "comm="])
+            .output()
+        {
+            Ok(output) if output.status.success() => {
+                let command = String::from_utf8_lossy(&output.stdout);
+                is_postgres_program(command.trim())
+            }
+            Ok(_) => false,
+            Err(_) => true,
+        }
+    }
+
+    /// Returns `true` if the process ID belongs to a running `postgres` process; after an unclean
+    /// shutdown, the process may no longer exist or the ID may have been reused by an unrelated
+    /// process. If the processes cannot be inspected, the server is assumed to be running.
+    #[cfg(target_os = "windows")]
+    pub fn is_alive(&self) -> bool {
+        let filter = format!("PID eq {}", self.pid);
+        match Command::new("tasklist")
+            .args(["/FI", &filter, "/NH", "/FO", "CSV"])
+            .output()
+        {
+            Ok(output) if output.status.success() => {
+                // The first field is the image name; if there is no such process, an
+                // informational message is written instead
+                let stdout = String::from_utf8_lossy(&output.stdout);
+                stdout
+                    .split(',')
+                    .next()
+                    .is_some_and(|name| is_postgres_program(name.trim().trim_matches('"')))
+            }
+            _ => true,
+        }
+    }
+}
+
+/// Returns `true` if the file name of the `program` is the `postgres` executable.
+fn is_postgres_program(program: &str) -> bool {
+    Path::new(program)
+        .file_name()
+        .and_then(|name| name.to_str())
+        .is_some_and(|name| name == "postgres" || name.eq_ignore_ascii_case("postgres.exe"))
+}
+
+/// Send the `signal` (e.g. `INT` or `KILL`) to the process with the process ID `pid`. Returns
+/// `true` if the signal was sent.
+#[cfg(unix)]
+pub(crate) fn signal_process(pid: u32, signal: &str) -> bool {
+    send_signal(&pid.to_string(), signal)
+}
+
+/// Send the `signal` (e.g. `INT` or `KILL`) to all processes of the process group `pgid`; the
+/// postmaster of a supervised child leads the process group of the server processes. Returns
+/// `true` if the signal was sent.
+#[cfg(unix)]
+pub(crate) fn signal_process_group(pgid: u32, signal: &str) -> bool {
+    send_signal(&format!("-{pgid}"), signal)
+}
+
+/// Send the `signal` to the `target` process, or process group if negative, with `kill`.
+#[cfg(unix)]
+fn send_signal(target: &str, signal: &str) -> bool {
+    Command::new("kill")
+        .args(["-s", signal, "--", target])
+        .stdout(std::process::Stdio::null())
+        .stderr(std::process::Stdio::null())
+        .status()
+        .is_ok_and(|status| status.success())
+}
+
+/// Returns `true` if the PID file in the `data_dir` was left behind by a server that is no longer
+/// running, such as after a power loss or the server being killed.
+pub(crate) fn is_stale(data_dir: &Path) -> bool {
+    PostmasterPid::read(data_dir).is_some_and(|postmaster_pid| !postmaster_pid.is_alive())
+}
+
+/// Remove the PID file in the `data_dir` if it is stale. Returns `true` if the file was removed.
+///
+/// # Errors
+/// * If the stale PID file cannot be removed.
+pub(crate) fn remove_stale_pid_file(data_dir: &Path) -> Result<bool> {
+    if !is_stale(data_dir) {
+        return Ok(false);
+    }
+    warn!(
+        "Removing stale PID file from {}; the server was not shut down cleanly",
+        data_dir.to_string_lossy()
+    );
+    match fs::remove_file(data_dir.join(PID_FILE)) {
+        Err(error) if error.kind() != std::io::ErrorKind::NotFound => Err(error.into()),
+        _ => Ok(true),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PID_FILE_CONTENTS: &str = "4242
+/tmp/data
+1700000000
+5432
+/tmp
+localhost
+  5432001    163840
+ready
+";
+
+    #[test]
+    fn test_parse() {
+        let postmaster_pid = PostmasterPid::parse(PID_FILE_CONTENTS).expect("pid file");
+        assert_eq!(4242, postmaster_pid.pid);
+        assert_eq!(PathBuf::from("/tmp/data"), postmaster_pid.data_dir);
+        assert_eq!(5432, postmaster_pid.port);
+        assert!(postmaster_pid.is_ready());
+    }
+
+    #[test]
+    fn test_parse_incomplete() {
+        assert_eq!(None, PostmasterPid::parse("4242\n/tmp/data\n"));
+        assert_eq!(None, PostmasterPid::parse(""));
+        let postmaster_pid =
+            PostmasterPid::parse("4242\n/tmp/data\n1700000000\n5432\n").expect("pid file");
+        assert!(!postmaster_pid.is_ready());
+    }
+
+    #[test]
+    fn test_is_postgres_program() {
+        assert!(is_postgres_program("/usr/lib/postgresql/16/bin/postgres"));
+        assert!(is_postgres_program("postgres"));
+        assert!(is_postgres_program("POSTGRES.EXE"));
+        assert!(!is_postgres_program("/usr/bin/postgres-exporter"));
+        assert!(!is_postgres_program(""));
+    }
+
+    #[test]
+    fn test_stale_pid_file() -> Result<()> {
+        let data_dir = tempfile::tempdir()?;
+        assert!(!is_stale(data_dir.path()));
+        assert!(!remove_stale_pid_file(data_dir.path())?);
+
+        // The test process is running, but is not a postgres process
+        let contents = format!(
+            "{}\n{}\n1700000000\n5432\n",
+            std::process::id(),
+            data_dir.path().to_string_lossy()
+        );
+        fs::write(data_dir.path().join(PID_FILE), contents)?;
+        assert!(is_stale(data_dir.path()));
+        assert!(remove_stale_pid_file(data_dir.path())?);
+        assert!(!data_dir.path().join(PID_FILE).exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_read() -> std::io::Result<()> {
+        let data_dir = tempfile::tempdir()?;
+        assert_eq!(None, PostmasterPid::read(data_dir.path()));
+        fs::write(data_dir.path().join(PID_FILE), PID_FILE_CONTENTS)?;
+        assert_eq!(
+            Some(5432),
+            PostmasterPid::read(data_dir.path()).map(|postmaster_pid| postmaster_pid.port)
+        );
+        Ok(())
+    }
+}
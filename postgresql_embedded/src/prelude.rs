@@ -0,0 +1,47 @@
+//! The prelude re-exports the most used items of `postgresql_embedded`, and of the
+//! `postgresql_commands` and `postgresql_extensions` crates, so that they can be imported with a
+//! single line:
+//!
+//! ```no_run
+//! use postgresql_embedded::prelude::*;
+//!
+//! # async fn example() -> Result<()> {
+//! let mut postgresql = PostgreSQL::new(Settings::default());
+//! postgresql.setup().await?;
+//! postgresql.start().await?;
+//! # Ok(())
+//! # }
+//! ```
+
+pub use crate::{
+    BackupFormat, Channel, DatabaseOptions, Error, PostgreSQL, Result, Settings, SettingsBuilder,
+    Status, Version, VersionReq, LATEST, V14, V15, V16, V17,
+};
+pub use postgresql_commands::createdb::CreateDbBuilder;
+pub use postgresql_commands::dropdb::DropDbBuilder;
+pub use postgresql_commands::initdb::InitDbBuilder;
+pub use postgresql_commands::pg_ctl::PgCtlBuilder;
+pub use postgresql_commands::pg_dump::PgDumpBuilder;
+pub use postgresql_commands::pg_dumpall::PgDumpAllBuilder;
+pub use postgresql_commands::pg_restore::PgRestoreBuilder;
+pub use postgresql_commands::psql::PsqlBuilder;
+pub use postgresql_commands::Settings as _;
+pub use postgresql_commands::{AsyncCommandExecutor, CommandBuilder, CommandExecutor};
+#[cfg(feature = "extensions")]
+pub use postgresql_extensions::{
+    get_available_extensions, get_installed_extensions, install, uninstall, AvailableExtension,
+    InstalledExtension,
+};
+
+#[cfg(test)]
+mod tests {
+    use crate::prelude::*;
+
+    #[test]
+    fn test_prelude() {
+        let settings = Settings::default();
+        let builder = PsqlBuilder::from(&settings).command("SELECT 1");
+        assert_eq!("psql", builder.get_program());
+        assert!(builder.get_args().contains(&"SELECT 1".into()));
+    }
+}
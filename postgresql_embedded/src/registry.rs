@@ -0,0 +1,137 @@
+use crate::error::Error::DataDirInUse;
+use crate::error::Result;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{LazyLock, Mutex};
+
+/// Source of the identifiers of the [`PostgreSQL`](crate::PostgreSQL) instances of the process
+static NEXT_INSTANCE_ID: AtomicU64 = AtomicU64::new(1);
+
+/// Data directories of the servers started by the process, keyed by canonical path, and the
+/// claim of the instance that started each of them
+static DATA_DIRS: LazyLock<Mutex<HashMap<PathBuf, Claim>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Claim of an instance on a data directory
+#[derive(Clone, Debug)]
+struct Claim {
+    /// Identifier of the instance
+    id: u64,
+    /// Canonical installation directory of the binaries that the server runs
+    installation_dir: PathBuf,
+}
+
+/// Get a new identifier for a [`PostgreSQL`](crate::PostgreSQL) instance; clones of an instance
+/// share its identifier.
+pub(crate) fn next_instance_id() -> u64 {
+    NEXT_INSTANCE_ID.fetch_add(1, Ordering::Relaxed)
+}
+
+/// Claim the `data_dir` for the instance `id`, so that another instance of the process does not
+/// start or stop a server for the same data directory, and the binaries in the
+/// `installation_dir` are not pruned while the server runs. Claiming a data directory that is
+/// already claimed by the instance succeeds.
+///
+/// # Errors
+/// * If the data directory is claimed by another instance.
+pub(crate) fn claim(data_dir: &Path, installation_dir: &Path, id: u64) -> Result<()> {
+    let installation_dir = key(installation_dir);
+    let key = key(data_dir);
+    let mut data_dirs = match DATA_DIRS.lock() {
+        Ok(data_dirs) => data_dirs,
+        Err(error) => error.into_inner(),
+    };
+    match data_dirs.get(&key) {
+        Some(owner) if owner.id != id => Err(DataDirInUse(key)),
+        _ => {
+            data_dirs.insert(
+                key,
+                Claim {
+                    id,
+                    installation_dir,
+                },
+            );
+            Ok(())
+        }
+    }
+}
+
+/// Claim the `data_dir` for the instance `id`, taking over any claim of another instance; e.g.
+/// when [attaching](crate::PostgreSQL::attach) to a running server.
+pub(crate) fn take_over(data_dir: &Path, installation_dir: &Path, id: u64) {
+    let mut data_dirs = match DATA_DIRS.lock() {
+        Ok(data_dirs) => data_dirs,
+        Err(error) => error.into_inner(),
+    };
+    let installation_dir = key(installation_dir);
+    data_dirs.insert(
+        key(data_dir),
+        Claim {
+            id,
+            installation_dir,
+        },
+    );
+}
+
+/// Whether the binaries in the `installation_dir` are used by a server of the process.
+pub(crate) fn installation_in_use(installation_dir: &Path) -> bool {
+    let installation_dir = key(installation_dir);
+    let data_dirs = match DATA_DIRS.lock() {
+        Ok(data_dirs) => data_dirs,
+        Err(error) => error.into_inner(),
+    };
+    data_dirs
+        .values()
+        .any(|claim| claim.installation_dir == installation_dir)
+}
+
+/// Release the claim of the instance `id` on the `data_dir`, if any.
+pub(crate) fn release(data_dir: &Path, id: u64) {
+    let key = key(data_dir);
+    let mut data_dirs = match DATA_DIRS.lock() {
+        Ok(data_dirs) => data_dirs,
+        Err(error) => error.into_inner(),
+    };
+    if data_dirs.get(&key).is_some_and(|claim| claim.id == id) {
+        data_dirs.remove(&key);
+    }
+}
+
+/// Get the registry key of the `data_dir`; the canonical path if the directory exists, so that
+/// different paths to the same directory are detected.
+fn key(data_dir: &Path) -> PathBuf {
+    std::fs::canonicalize(data_dir).unwrap_or_else(|_| data_dir.to_path_buf())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_claim_and_release() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let data_dir = temp_dir.path();
+        let installation_dir = &data_dir.join("installation");
+        let first = next_instance_id();
+        let second = next_instance_id();
+        assert_ne!(first, second);
+
+        claim(data_dir, installation_dir, first)?;
+        claim(data_dir, installation_dir, first)?;
+        assert!(installation_in_use(installation_dir));
+        let error = claim(&data_dir.join("."), installation_dir, second)
+            .expect_err("data directory in use");
+        assert!(matches!(error, DataDirInUse(_)));
+
+        release(data_dir, second);
+        assert!(claim(data_dir, installation_dir, second).is_err());
+        release(data_dir, first);
+        assert!(!installation_in_use(installation_dir));
+        claim(data_dir, installation_dir, second)?;
+        take_over(data_dir, installation_dir, first);
+        assert!(claim(data_dir, installation_dir, second).is_err());
+        release(data_dir, first);
+        Ok(())
+    }
+}
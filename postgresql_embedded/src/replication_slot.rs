@@ -0,0 +1,23 @@
+/// Type of a replication slot
+#[derive(Clone, Debug, PartialEq)]
+pub enum ReplicationSlotType {
+    /// Physical replication slot used by streaming replication and `pg_basebackup`
+    Physical,
+    /// Logical replication slot using the given output plugin (e.g. `pgoutput`)
+    Logical { plugin: String },
+}
+
+/// Replication slot as reported by the `pg_replication_slots` catalog view
+#[derive(Clone, Debug, PartialEq)]
+pub struct ReplicationSlot {
+    /// Slot name
+    pub name: String,
+    /// Slot type
+    pub slot_type: ReplicationSlotType,
+    /// Database the slot is associated with; only set for logical slots
+    pub database: Option<String>,
+    /// Oldest WAL location still required by the consumer of the slot
+    pub restart_lsn: Option<String>,
+    /// Whether a consumer is currently connected to the slot
+    pub active: bool,
+}
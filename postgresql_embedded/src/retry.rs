@@ -0,0 +1,134 @@
+use postgresql_archive::RetryPolicy;
+use std::future::Future;
+use tracing::debug;
+
+/// SQLSTATE codes of database errors that are transient; the server is starting up, shutting down
+/// or in recovery and cannot accept connections yet, or too many clients are connected
+const TRANSIENT_SQLSTATES: &[&str] = &["57P03", "53300"];
+
+/// Gets the default [retry policy](RetryPolicy) of the [settings](crate::Settings), which retries
+/// [transient](is_transient) errors.
+pub(crate) fn default_retry_policy() -> RetryPolicy {
+    RetryPolicy {
+        retryable: is_transient,
+        ..RetryPolicy::default()
+    }
+}
+
+/// Returns `true` if the `error` is a [transient](postgresql_archive::is_transient) I/O or HTTP
+/// error, or a database error that is transient while the server starts; e.g. a pool that timed
+/// out acquiring a connection, or a server that cannot accept connections yet.
+pub(crate) fn is_transient(error: &(dyn std::error::Error + 'static)) -> bool {
+    if postgresql_archive::is_transient(error) {
+        return true;
+    }
+    match error.downcast_ref::<sqlx::Error>() {
+        Some(sqlx::Error::PoolTimedOut) => true,
+        Some(sqlx::Error::Database(error)) => error
+            .code()
+            .is_some_and(|code| TRANSIENT_SQLSTATES.contains(&code.as_ref())),
+        _ => false,
+    }
+}
+
+/// Runs the asynchronous `operation`, retrying it according to the [retry policy](RetryPolicy).
+///
+/// # Errors
+/// * The error of the last attempt if the operation does not succeed.
+pub(crate) async fn retry<T, E, F, Fut>(
+    policy: &RetryPolicy,
+    mut operation: F,
+) -> std::result::Result<T, E>
+where
+    E: std::error::Error + 'static,
+    F: FnMut() -> Fut,
+    Fut: Future<Output = std::result::Result<T, E>>,
+{
+    let mut attempt = 1;
+    loop {
+        match operation().await {
+            Ok(value) => return Ok(value),
+            Err(error) if policy.should_retry(attempt, &error) => {
+                let backoff = policy.backoff(attempt - 1);
+                debug!(
+                    "Attempt {attempt}/{} failed: {error}; retrying in {backoff:?}",
+                    policy.max_attempts
+                );
+                #[cfg(feature = "tokio")]
+                tokio::time::sleep(backoff).await;
+                #[cfg(not(feature = "tokio"))]
+                std::thread::sleep(backoff);
+                attempt += 1;
+            }
+            Err(error) => return Err(error),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::ErrorKind;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn test_retry() {
+        let policy = RetryPolicy {
+            initial_backoff: Duration::ZERO,
+            ..default_retry_policy()
+        };
+        let mut attempts = 0;
+        let result = retry(&policy, || {
+            attempts += 1;
+            let attempt = attempts;
+            async move {
+                if attempt < 3 {
+                    Err(std::io::Error::new(ErrorKind::ConnectionRefused, "test"))
+                } else {
+                    Ok(attempt)
+                }
+            }
+        })
+        .await;
+        assert_eq!(3, result.expect("result"));
+    }
+
+    #[tokio::test]
+    async fn test_retry_not_retryable() {
+        let policy = RetryPolicy {
+            retryable: |_| false,
+            ..default_retry_policy()
+        };
+        let mut attempts = 0;
+        let result: std::result::Result<(), _> = retry(&policy, || {
+            attempts += 1;
+            async { Err(std::io::Error::new(ErrorKind::Other, "test")) }
+        })
+        .await;
+        assert!(result.is_err());
+        assert_eq!(1, attempts);
+    }
+
+    #[test]
+    fn test_is_transient() {
+        assert!(is_transient(&std::io::Error::new(
+            ErrorKind::TimedOut,
+            "test"
+        )));
+        assert!(!is_transient(&std::io::Error::new(
+            ErrorKind::NotFound,
+            "test"
+        )));
+        assert!(is_transient(&sqlx::Error::PoolTimedOut));
+        assert!(is_transient(&sqlx::Error::Io(std::io::Error::new(
+            ErrorKind::ConnectionRefused,
+            "test"
+        ))));
+        assert!(!is_transient(&sqlx::Error::RowNotFound));
+        assert!(!is_transient(&crate::Error::CommandError {
+            code: Some(1),
+            stdout: String::new(),
+            stderr: "test".to_string(),
+        }));
+    }
+}
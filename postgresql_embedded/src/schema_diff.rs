@@ -0,0 +1,242 @@
+use std::collections::BTreeMap;
+
+/// Prefix of the comment that precedes each object in a plain `pg_dump`
+const OBJECT_HEADER: &str = "-- Name: ";
+
+/// Type, schema and name identifying an object of a schema
+type ObjectKey = (String, Option<String>, String);
+
+/// Object of a database schema, as dumped by `pg_dump --schema-only`
+#[derive(Clone, Debug, PartialEq)]
+pub struct SchemaObject {
+    /// Type of the object; e.g. `TABLE`, `INDEX` or `FUNCTION`
+    pub object_type: String,
+    /// Schema of the object, if it belongs to a schema
+    pub schema: Option<String>,
+    /// Name of the object; e.g. `users` or `add(integer, integer)`
+    pub name: String,
+    /// SQL statements that create the object
+    pub definition: String,
+}
+
+/// Object whose definition differs between two schemas
+#[derive(Clone, Debug, PartialEq)]
+pub struct SchemaChange {
+    /// Object in the first schema
+    pub old: SchemaObject,
+    /// Object in the second schema
+    pub new: SchemaObject,
+}
+
+/// Differences between the schemas of two databases, as returned by
+/// [`schema_diff`](crate::PostgreSQL::schema_diff); objects are ordered by type, schema and name
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SchemaDiff {
+    /// Objects that only exist in the second database
+    pub added: Vec<SchemaObject>,
+    /// Objects that only exist in the first database
+    pub removed: Vec<SchemaObject>,
+    /// Objects that exist in both databases with different definitions
+    pub changed: Vec<SchemaChange>,
+}
+
+impl SchemaDiff {
+    /// Compare the plain schema dumps `old` and `new`.
+    pub(crate) fn new(old: &str, new: &str) -> Self {
+        let mut old = parse_objects(old);
+        let new = parse_objects(new);
+        let mut diff = SchemaDiff::default();
+        for (key, new_object) in new {
+            match old.remove(&key) {
+                None => diff.added.push(new_object),
+                Some(old_object) if old_object.definition != new_object.definition => {
+                    diff.changed.push(SchemaChange {
+                        old: old_object,
+                        new: new_object,
+                    });
+                }
+                Some(_) => {}
+            }
+        }
+        diff.removed = old.into_values().collect();
+        diff
+    }
+
+    /// Returns `true` if the schemas are the same.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+/// Parse the objects of a plain schema dump, keyed by type, schema and name. Each object starts
+/// with a `-- Name: <name>; Type: <type>; Schema: <schema>; Owner: <owner>` comment; settings
+/// before the first object, comments and `psql` meta-commands are ignored.
+fn parse_objects(dump: &str) -> BTreeMap<ObjectKey, SchemaObject> {
+    let mut objects = BTreeMap::new();
+    let mut current: Option<SchemaObject> = None;
+    for line in dump.lines() {
+        if let Some(header) = line.strip_prefix(OBJECT_HEADER) {
+            insert_object(&mut objects, current.take());
+            current = parse_header(header);
+            continue;
+        }
+        if line.starts_with("--") || line.starts_with('\\') || line.trim().is_empty() {
+            continue;
+        }
+        if let Some(object) = current.as_mut() {
+            if !object.definition.is_empty() {
+                object.definition.push('\n');
+            }
+            object.definition.push_str(line.trim_end());
+        }
+    }
+    insert_object(&mut objects, current);
+    objects
+}
+
+/// Insert the `object`, if any, into the `objects`; the definitions of objects with the same
+/// key, such as the access privileges of a table, are combined.
+fn insert_object(objects: &mut BTreeMap<ObjectKey, SchemaObject>, object: Option<SchemaObject>) {
+    let Some(object) = object else {
+        return;
+    };
+    let key = (
+        object.object_type.clone(),
+        object.schema.clone(),
+        object.name.clone(),
+    );
+    match objects.get_mut(&key) {
+        Some(existing) => {
+            existing.definition.push('\n');
+            existing.definition.push_str(&object.definition);
+        }
+        None => {
+            objects.insert(key, object);
+        }
+    }
+}
+
+/// Parse the `<name>; Type: <type>; Schema: <schema>; Owner: <owner>` object header; the owner is
+/// ignored, and a schema of `-` indicates that the object does not belong to a schema.
+fn parse_header(header: &str) -> Option<SchemaObject> {
+    let (name, fields) = header.split_once("; Type: ")?;
+    let (object_type, fields) = fields.split_once("; Schema: ")?;
+    let schema = fields
+        .split_once("; Owner: ")
+        .map_or(fields, |(schema, _owner)| schema)
+        .trim();
+    Some(SchemaObject {
+        object_type: object_type.to_string(),
+        schema: (schema != "-").then(|| schema.to_string()),
+        name: name.to_string(),
+        definition: String::new(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const OLD: &str = r"--
+-- PostgreSQL database dump
+--
+
+SET statement_timeout = 0;
+
+--
+-- Name: users; Type: TABLE; Schema: public; Owner: postgres
+--
+
+CREATE TABLE public.users (
+    id integer NOT NULL
+);
+
+--
+-- Name: orders; Type: TABLE; Schema: public; Owner: postgres
+--
+
+CREATE TABLE public.orders (
+    id integer NOT NULL
+);
+
+--
+-- Name: users_pkey; Type: CONSTRAINT; Schema: public; Owner: postgres
+--
+
+ALTER TABLE ONLY public.users
+    ADD CONSTRAINT users_pkey PRIMARY KEY (id);
+
+--
+-- PostgreSQL database dump complete
+--
+";
+
+    const NEW: &str = r"SET statement_timeout = 0;
+
+--
+-- Name: users; Type: TABLE; Schema: public; Owner: app
+--
+
+CREATE TABLE public.users (
+    id integer NOT NULL,
+    name text
+);
+
+--
+-- Name: users_pkey; Type: CONSTRAINT; Schema: public; Owner: app
+--
+
+ALTER TABLE ONLY public.users
+    ADD CONSTRAINT users_pkey PRIMARY KEY (id);
+
+--
+-- Name: vector; Type: EXTENSION; Schema: -; Owner: -
+--
+
+CREATE EXTENSION IF NOT EXISTS vector WITH SCHEMA public;
+";
+
+    #[test]
+    fn test_parse_header() {
+        let object = parse_header("vector; Type: EXTENSION; Schema: -; Owner: -").expect("object");
+        assert_eq!("vector", object.name);
+        assert_eq!("EXTENSION", object.object_type);
+        assert_eq!(None, object.schema);
+        assert!(parse_header("invalid").is_none());
+    }
+
+    #[test]
+    fn test_parse_objects() {
+        let objects = parse_objects(OLD);
+        assert_eq!(3, objects.len());
+        let key = (
+            "TABLE".to_string(),
+            Some("public".to_string()),
+            "users".to_string(),
+        );
+        assert_eq!(
+            "CREATE TABLE public.users (\n    id integer NOT NULL\n);",
+            objects[&key].definition
+        );
+    }
+
+    #[test]
+    fn test_diff() {
+        let diff = SchemaDiff::new(OLD, NEW);
+        assert!(!diff.is_empty());
+        let names = |objects: &[SchemaObject]| -> Vec<String> {
+            objects.iter().map(|object| object.name.clone()).collect()
+        };
+        assert_eq!(vec!["vector"], names(&diff.added));
+        assert_eq!(vec!["orders"], names(&diff.removed));
+        assert_eq!(1, diff.changed.len());
+        assert_eq!("users", diff.changed[0].new.name);
+        assert!(diff.changed[0].new.definition.contains("name text"));
+    }
+
+    #[test]
+    fn test_diff_same() {
+        assert!(SchemaDiff::new(OLD, OLD).is_empty());
+    }
+}
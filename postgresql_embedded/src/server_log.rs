@@ -0,0 +1,247 @@
+use std::collections::VecDeque;
+use std::fs::File;
+use std::io::{BufRead, BufReader, Seek, SeekFrom};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread::JoinHandle;
+use std::time::Duration;
+use tracing::{debug, error, info, warn, Level};
+
+/// Interval at which the server log is checked for new lines
+const POLL_INTERVAL: Duration = Duration::from_millis(100);
+/// Target of the `tracing` events for server log lines
+const TARGET: &str = "postgresql";
+/// Severities of server log lines and the level they are forwarded at; the severity is followed
+/// by a colon and two spaces (e.g. `LOG:  database system is ready to accept connections`)
+const SEVERITIES: &[(&str, Level)] = &[
+    ("PANIC", Level::ERROR),
+    ("FATAL", Level::ERROR),
+    ("ERROR", Level::ERROR),
+    ("WARNING", Level::WARN),
+    ("NOTICE", Level::INFO),
+    ("INFO", Level::INFO),
+    ("LOG", Level::INFO),
+    ("DETAIL", Level::INFO),
+    ("HINT", Level::INFO),
+    ("CONTEXT", Level::INFO),
+    ("STATEMENT", Level::INFO),
+    ("DEBUG1", Level::DEBUG),
+    ("DEBUG2", Level::DEBUG),
+    ("DEBUG3", Level::DEBUG),
+    ("DEBUG4", Level::DEBUG),
+    ("DEBUG5", Level::DEBUG),
+];
+
+/// Tails the server log in a background thread, forwarding each line as a `tracing` event
+#[derive(Debug)]
+pub(crate) struct LogTailer {
+    stop: Arc<AtomicBool>,
+    handle: Option<JoinHandle<()>>,
+}
+
+impl LogTailer {
+    /// Start tailing the log file at `path` from the byte `offset`; lines written before the
+    /// offset, such as those of previous runs of the server, are not forwarded.
+    pub fn start(path: PathBuf, offset: u64) -> Self {
+        let stop = Arc::new(AtomicBool::new(false));
+        let stopping = stop.clone();
+        let handle = std::thread::Builder::new()
+            .name("postgresql-log".to_string())
+            .spawn(move || tail(&path, offset, &stopping, &forward))
+            .inspect_err(|error| debug!("Unable to tail the server log: {error}"))
+            .ok();
+        Self { stop, handle }
+    }
+
+    /// Stop tailing the log once the lines written so far have been forwarded.
+    pub fn stop(mut self) {
+        self.stop.store(true, Ordering::Release);
+        if let Some(handle) = self.handle.take() {
+            let _ = handle.join();
+        }
+    }
+}
+
+impl Drop for LogTailer {
+    fn drop(&mut self) {
+        self.stop.store(true, Ordering::Release);
+    }
+}
+
+/// Pass the lines appended to the log file at `path` after `offset` to `forward` until `stop` is
+/// set.
+fn tail(path: &Path, mut offset: u64, stop: &AtomicBool, forward: &dyn Fn(&str)) {
+    loop {
+        let stopping = stop.load(Ordering::Acquire);
+        if let Ok(mut file) = File::open(path) {
+            if file
+                .metadata()
+                .is_ok_and(|metadata| metadata.len() < offset)
+            {
+                // The log was truncated or replaced
+                offset = 0;
+            }
+            if file.seek(SeekFrom::Start(offset)).is_ok() {
+                let mut reader = BufReader::new(file);
+                let mut line = Vec::new();
+                while let Ok(read) = reader.read_until(b'\n', &mut line) {
+                    // Partial lines are forwarded once they are complete, or when stopping
+                    if read == 0 || (!line.ends_with(b"\n") && !stopping) {
+                        break;
+                    }
+                    offset += read as u64;
+                    forward(String::from_utf8_lossy(&line).trim_end());
+                    line.clear();
+                }
+            }
+        }
+        if stopping {
+            return;
+        }
+        std::thread::sleep(POLL_INTERVAL);
+    }
+}
+
+/// Forward a server log line as a `tracing` event at the level of its severity.
+pub(crate) fn forward(line: &str) {
+    if line.is_empty() {
+        return;
+    }
+    let level = severity(line);
+    if level == Level::ERROR {
+        error!(target: TARGET, "{line}");
+    } else if level == Level::WARN {
+        warn!(target: TARGET, "{line}");
+    } else if level == Level::DEBUG {
+        debug!(target: TARGET, "{line}");
+    } else {
+        info!(target: TARGET, "{line}");
+    }
+}
+
+/// Get the level of a server log line from the first severity it contains; lines without a
+/// severity, such as the output of `pg_ctl`, are informational.
+pub(crate) fn severity(line: &str) -> Level {
+    SEVERITIES
+        .iter()
+        .filter_map(|(severity, level)| {
+            line.find(&format!("{severity}:  "))
+                .filter(|&index| index == 0 || line[..index].ends_with(' '))
+                .map(|index| (index, *level))
+        })
+        .min_by_key(|(index, _)| *index)
+        .map_or(Level::INFO, |(_, level)| level)
+}
+
+/// Get the last `max_lines` lines of the log `contents`.
+pub(crate) fn last_lines(contents: &str, max_lines: usize) -> Vec<String> {
+    let mut lines = VecDeque::with_capacity(max_lines);
+    for line in contents.lines() {
+        if lines.len() == max_lines {
+            lines.pop_front();
+        }
+        if max_lines > 0 {
+            lines.push_back(line.to_string());
+        }
+    }
+    lines.into()
+}
+
+/// Get the lines of the log at `path` written after `offset`; e.g. the lines written since the
+/// server was started. Returns no lines if the log cannot be read.
+pub(crate) fn log_lines_since(path: &Path, offset: u64) -> Vec<String> {
+    let Ok(mut file) = File::open(path) else {
+        return Vec::new();
+    };
+    if file.seek(SeekFrom::Start(offset)).is_err() {
+        return Vec::new();
+    }
+    BufReader::new(file)
+        .lines()
+        .map_while(std::result::Result::ok)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_severity() {
+        let prefix = "2024-01-01 00:00:00.000 UTC [42]";
+        for (line, level) in [
+            (
+                format!("{prefix} LOG:  database system is ready"),
+                Level::INFO,
+            ),
+            (format!("{prefix} WARNING:  could not flush"), Level::WARN),
+            (
+                format!("{prefix} ERROR:  relation does not exist"),
+                Level::ERROR,
+            ),
+            (
+                format!("{prefix} FATAL:  could not access file"),
+                Level::ERROR,
+            ),
+            (format!("{prefix} PANIC:  could not write"), Level::ERROR),
+            (format!("{prefix} DEBUG2:  checkpointer"), Level::DEBUG),
+            (
+                format!("{prefix} LOG:  statement: SELECT 'ERROR:  '"),
+                Level::INFO,
+            ),
+            ("FATAL:  lock file exists".to_string(), Level::ERROR),
+            ("waiting for server to start....".to_string(), Level::INFO),
+        ] {
+            assert_eq!(level, severity(&line), "{line}");
+        }
+    }
+
+    #[test]
+    fn test_last_lines() {
+        let contents = "one\ntwo\nthree\n";
+        assert_eq!(vec!["two", "three"], last_lines(contents, 2));
+        assert_eq!(vec!["one", "two", "three"], last_lines(contents, 10));
+        assert!(last_lines(contents, 0).is_empty());
+    }
+
+    #[test]
+    fn test_log_lines_since() -> std::io::Result<()> {
+        let log_dir = tempfile::tempdir()?;
+        let path = log_dir.path().join("start.log");
+        assert!(log_lines_since(&path, 0).is_empty());
+        std::fs::write(&path, "previous\n")?;
+        let offset = std::fs::metadata(&path)?.len();
+        std::fs::write(&path, "previous\nLOG:  started\n")?;
+        assert_eq!(vec!["LOG:  started"], log_lines_since(&path, offset));
+        assert_eq!(vec!["previous", "LOG:  started"], log_lines_since(&path, 0));
+        Ok(())
+    }
+
+    #[test]
+    fn test_tail() -> std::io::Result<()> {
+        let log_dir = tempfile::tempdir()?;
+        let path = log_dir.path().join("start.log");
+        std::fs::write(&path, "previous\n")?;
+        let offset = std::fs::metadata(&path)?.len();
+        std::fs::write(&path, "previous\nLOG:  started\npartial")?;
+
+        let lines = std::cell::RefCell::new(Vec::new());
+        let stop = AtomicBool::new(true);
+        tail(&path, offset, &stop, &|line| {
+            lines.borrow_mut().push(line.to_string());
+        });
+        assert_eq!(vec!["LOG:  started", "partial"], lines.into_inner());
+        Ok(())
+    }
+
+    #[test]
+    fn test_log_tailer() -> std::io::Result<()> {
+        let log_dir = tempfile::tempdir()?;
+        let path = log_dir.path().join("start.log");
+        let tailer = LogTailer::start(path.clone(), 0);
+        std::fs::write(&path, "LOG:  started\n")?;
+        tailer.stop();
+        Ok(())
+    }
+}
@@ -0,0 +1,268 @@
+use crate::settings::Settings;
+use std::fmt::Write;
+
+/// Default label of generated service units
+pub const DEFAULT_SERVICE_LABEL: &str = "org.postgresql.embedded";
+
+/// Start type of a Windows service
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub enum ServiceStartType {
+    /// Start the service automatically when the system boots
+    #[default]
+    Auto,
+    /// Start the service on demand
+    Demand,
+}
+
+/// Options for registering the server as a Windows service with
+/// [`register_windows_service`](crate::PostgreSQL::register_windows_service)
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct WindowsServiceOptions {
+    /// Account the service runs as (e.g. `NT AUTHORITY\NetworkService`); defaults to the
+    /// `LocalSystem` account
+    pub username: Option<String>,
+    /// Password of the service account
+    pub password: Option<String>,
+    /// Start type of the service
+    pub start_type: ServiceStartType,
+}
+
+/// Kind of service unit generated by [`generate_service_unit`](crate::PostgreSQL::generate_service_unit)
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ServiceKind {
+    /// systemd unit file (e.g. `~/.config/systemd/user/postgresql.service`)
+    Systemd,
+    /// launchd property list (e.g. `~/Library/LaunchAgents/org.postgresql.embedded.plist`)
+    Launchd,
+}
+
+/// Options for generating a service unit with
+/// [`generate_service_unit`](crate::PostgreSQL::generate_service_unit)
+#[derive(Clone, Debug, PartialEq)]
+pub struct ServiceUnitOptions {
+    /// Label of the service; used as the launchd label
+    pub label: String,
+    /// Description of the service; used as the systemd unit description
+    pub description: String,
+    /// User the server runs as; defaults to the user that loads the unit
+    pub username: Option<String>,
+    /// Restart the server if it exits unexpectedly
+    pub restart: bool,
+}
+
+impl Default for ServiceUnitOptions {
+    fn default() -> Self {
+        Self {
+            label: DEFAULT_SERVICE_LABEL.to_string(),
+            description: "PostgreSQL database server".to_string(),
+            username: None,
+            restart: true,
+        }
+    }
+}
+
+/// Get the `postgres` command line for the server with the given settings.
+fn server_arguments(settings: &Settings) -> Vec<String> {
+    let postgres = settings.binary_dir().join("postgres");
+    let mut arguments = vec![
+        postgres.to_string_lossy().to_string(),
+        "-D".to_string(),
+        settings.data_dir.to_string_lossy().to_string(),
+        "-p".to_string(),
+        settings.port.to_string(),
+    ];
+    let mut configuration: Vec<_> = settings.configuration.iter().collect();
+    configuration.sort();
+    for (key, value) in configuration {
+        arguments.push("-c".to_string());
+        arguments.push(format!("{key}={value}"));
+    }
+    arguments
+}
+
+/// Generate a service unit of the given kind that runs the server with the given settings.
+pub(crate) fn generate_service_unit(
+    kind: ServiceKind,
+    settings: &Settings,
+    options: &ServiceUnitOptions,
+) -> String {
+    let arguments = server_arguments(settings);
+    match kind {
+        ServiceKind::Systemd => systemd_unit(&arguments, options),
+        ServiceKind::Launchd => launchd_plist(&arguments, options),
+    }
+}
+
+/// Generate a systemd unit that runs `postgres` in the foreground.
+fn systemd_unit(arguments: &[String], options: &ServiceUnitOptions) -> String {
+    let exec_start = arguments
+        .iter()
+        .map(|argument| format!("\"{}\"", escape_systemd_argument(argument)))
+        .collect::<Vec<_>>()
+        .join(" ");
+    let mut unit = String::new();
+    let _ = writeln!(unit, "[Unit]");
+    let _ = writeln!(
+        unit,
+        "Description={}",
+        escape_systemd_specifiers(&options.description)
+    );
+    let _ = writeln!(unit, "After=network.target");
+    let _ = writeln!(unit);
+    let _ = writeln!(unit, "[Service]");
+    let _ = writeln!(unit, "Type=simple");
+    if let Some(username) = &options.username {
+        let _ = writeln!(unit, "User={}", escape_systemd_specifiers(username));
+    }
+    let _ = writeln!(unit, "ExecStart={exec_start}");
+    let _ = writeln!(unit, "ExecReload=/bin/kill -HUP $MAINPID");
+    let _ = writeln!(unit, "KillMode=mixed");
+    let _ = writeln!(unit, "KillSignal=SIGINT");
+    let _ = writeln!(unit, "TimeoutSec=infinity");
+    if options.restart {
+        let _ = writeln!(unit, "Restart=on-failure");
+    }
+    let _ = writeln!(unit);
+    let _ = writeln!(unit, "[Install]");
+    let wanted_by = if options.username.is_some() {
+        "multi-user.target"
+    } else {
+        "default.target"
+    };
+    let _ = writeln!(unit, "WantedBy={wanted_by}");
+    unit
+}
+
+/// Escape the systemd specifiers in the value; i.e. `%` as `%%`.
+fn escape_systemd_specifiers(value: &str) -> String {
+    value.replace('%', "%%")
+}
+
+/// Escape a quoted `ExecStart` argument; backslashes and quotes are escaped with a backslash,
+/// and specifiers and variables are escaped as `%%` and `$$` so they are passed literally.
+fn escape_systemd_argument(argument: &str) -> String {
+    escape_systemd_specifiers(argument)
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('$', "$$")
+}
+
+/// Generate a launchd property list that runs `postgres` in the foreground.
+fn launchd_plist(arguments: &[String], options: &ServiceUnitOptions) -> String {
+    let mut plist = String::new();
+    let _ = writeln!(plist, r#"<?xml version="1.0" encoding="UTF-8"?>"#);
+    let _ = writeln!(
+        plist,
+        r#"<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">"#
+    );
+    let _ = writeln!(plist, r#"<plist version="1.0">"#);
+    let _ = writeln!(plist, "<dict>");
+    let _ = writeln!(plist, "    <key>Label</key>");
+    let _ = writeln!(plist, "    <string>{}</string>", escape_xml(&options.label));
+    if let Some(username) = &options.username {
+        let _ = writeln!(plist, "    <key>UserName</key>");
+        let _ = writeln!(plist, "    <string>{}</string>", escape_xml(username));
+    }
+    let _ = writeln!(plist, "    <key>ProgramArguments</key>");
+    let _ = writeln!(plist, "    <array>");
+    for argument in arguments {
+        let _ = writeln!(plist, "        <string>{}</string>", escape_xml(argument));
+    }
+    let _ = writeln!(plist, "    </array>");
+    let _ = writeln!(plist, "    <key>RunAtLoad</key>");
+    let _ = writeln!(plist, "    <true/>");
+    let _ = writeln!(plist, "    <key>KeepAlive</key>");
+    if options.restart {
+        let _ = writeln!(plist, "    <dict>");
+        let _ = writeln!(plist, "        <key>SuccessfulExit</key>");
+        let _ = writeln!(plist, "        <false/>");
+        let _ = writeln!(plist, "    </dict>");
+    } else {
+        let _ = writeln!(plist, "    <false/>");
+    }
+    let _ = writeln!(plist, "</dict>");
+    let _ = writeln!(plist, "</plist>");
+    plist
+}
+
+/// Escape the XML special characters in the value.
+fn escape_xml(value: &str) -> String {
+    value
+        .replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::path::PathBuf;
+
+    fn settings() -> Settings {
+        let mut settings = Settings {
+            installation_dir: PathBuf::from("/opt/postgresql"),
+            data_dir: PathBuf::from("/var/lib/postgresql"),
+            port: 5432,
+            ..Settings::default()
+        };
+        settings
+            .configuration
+            .insert("max_connections".to_string(), "42".to_string());
+        settings
+    }
+
+    #[test]
+    fn test_systemd_unit() {
+        let options = ServiceUnitOptions {
+            username: Some("postgres".to_string()),
+            ..ServiceUnitOptions::default()
+        };
+        let unit = generate_service_unit(ServiceKind::Systemd, &settings(), &options);
+        assert!(unit.contains("Description=PostgreSQL database server\n"));
+        assert!(unit.contains("User=postgres\n"));
+        assert!(unit.contains(
+            r#"ExecStart="/opt/postgresql/bin/postgres" "-D" "/var/lib/postgresql" "-p" "5432" "-c" "max_connections=42""#
+        ));
+        assert!(unit.contains("Restart=on-failure\n"));
+        assert!(unit.contains("WantedBy=multi-user.target\n"));
+    }
+
+    #[test]
+    fn test_systemd_unit_escaping() {
+        let mut settings = settings();
+        settings.data_dir = PathBuf::from("/var/lib/100%/$HOME");
+        let options = ServiceUnitOptions {
+            description: "PostgreSQL 100%".to_string(),
+            ..ServiceUnitOptions::default()
+        };
+        let unit = generate_service_unit(ServiceKind::Systemd, &settings, &options);
+        assert!(unit.contains("Description=PostgreSQL 100%%\n"));
+        assert!(unit.contains(r#""-D" "/var/lib/100%%/$$HOME""#));
+        assert!(unit.contains("ExecReload=/bin/kill -HUP $MAINPID\n"));
+    }
+
+    #[test]
+    fn test_launchd_plist() {
+        let options = ServiceUnitOptions {
+            restart: false,
+            ..ServiceUnitOptions::default()
+        };
+        let plist = generate_service_unit(ServiceKind::Launchd, &settings(), &options);
+        assert!(plist.contains("<string>org.postgresql.embedded</string>"));
+        assert!(plist.contains("<string>/opt/postgresql/bin/postgres</string>"));
+        assert!(plist.contains("<string>/var/lib/postgresql</string>"));
+        assert!(plist.contains("<string>max_connections=42</string>"));
+        assert!(!plist.contains("UserName"));
+        assert!(plist.contains("<key>KeepAlive</key>\n    <false/>"));
+    }
+
+    #[test]
+    fn test_escape_xml() {
+        assert_eq!(
+            "&lt;a href=&quot;x&quot;&gt;&amp;&apos;",
+            escape_xml(r#"<a href="x">&'"#)
+        );
+    }
+}
@@ -0,0 +1,580 @@
+use crate::error::{Error, Result};
+use crate::settings::{
+    DataDirStrategy, DatabaseOptions, InstallLayout, LocaleProvider, LogCapture, PasswordSource,
+    ProcessMode, Settings, ShutdownOptions, Timezone, TransactionIsolation, WaitStrategy,
+};
+use postgresql_archive::{
+    Channel, ExactVersion, ExtractFilter, ProxyOptions, RetryPolicy, VersionReq,
+};
+use semver::Op;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+#[cfg(unix)]
+use tracing::warn;
+
+/// Ports below this port are privileged on Unix, unless the system allows unprivileged processes
+/// to bind them (e.g. `net.ipv4.ip_unprivileged_port_start` on Linux)
+#[cfg(unix)]
+const PRIVILEGED_PORT_END: u16 = 1024;
+/// Server configuration option of the directories the Unix domain sockets are created in
+const UNIX_SOCKET_DIRECTORIES: &str = "unix_socket_directories";
+
+/// Builder for [`Settings`]. The builder starts from the [default settings](Settings::default) and
+/// covers every setting; [`try_build`](Self::try_build) validates the settings before they are
+/// used to set up a server:
+///
+/// ```no_run
+/// use postgresql_embedded::{SettingsBuilder, VersionReq};
+/// use std::time::Duration;
+///
+/// # fn example() -> postgresql_embedded::Result<()> {
+/// let settings = SettingsBuilder::new()
+///     .version(VersionReq::parse("=16.4.0")?)
+///     .installation_dir("/opt/example/postgresql")
+///     .data_dir("/var/lib/example/data")
+///     .temporary(false)
+///     .port(5432)
+///     .timeout(Some(Duration::from_secs(10)))
+///     .config("max_connections", "42")
+///     .try_build()?;
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct SettingsBuilder {
+    settings: Settings,
+}
+
+impl SettingsBuilder {
+    /// Create a new [`SettingsBuilder`] from the [default settings](Settings::default)
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a new [`SettingsBuilder`] from existing settings
+    #[must_use]
+    pub fn from_settings(settings: Settings) -> Self {
+        Self { settings }
+    }
+
+    /// Set the URL of the releases location of the installation archives
+    #[must_use]
+    pub fn releases_url<S: AsRef<str>>(mut self, releases_url: S) -> Self {
+        self.settings.releases_url = releases_url.as_ref().to_string();
+        self
+    }
+
+    /// Set the asset name pattern used to select the archive from the releases location
+    #[must_use]
+    pub fn asset_pattern<S: AsRef<str>>(mut self, asset_pattern: S) -> Self {
+        self.settings.asset_pattern = Some(asset_pattern.as_ref().to_string());
+        self
+    }
+
+    /// Set the minisign public key the archive signatures are verified with
+    #[must_use]
+    pub fn signature_public_key<S: AsRef<str>>(mut self, public_key: S) -> Self {
+        self.settings.signature_public_key = Some(public_key.as_ref().to_string());
+        self
+    }
+
+    /// Set the version requirement
+    #[must_use]
+    pub fn version(mut self, version: VersionReq) -> Self {
+        self.settings.version = version;
+        self
+    }
+
+    /// Set the release channel the version is resolved in
+    #[must_use]
+    pub fn channel(mut self, channel: Channel) -> Self {
+        self.settings.channel = channel;
+        self
+    }
+
+    /// Set whether native binaries are preferred when running under emulation
+    #[must_use]
+    pub fn prefer_native_binaries(mut self, prefer_native_binaries: bool) -> Self {
+        self.settings.prefer_native_binaries = prefer_native_binaries;
+        self
+    }
+
+    /// Set the target triple of the binaries to install
+    #[must_use]
+    pub fn target<S: AsRef<str>>(mut self, target: S) -> Self {
+        self.settings.target = Some(target.as_ref().to_string());
+        self
+    }
+
+    /// Set whether an installation on the system is used instead of downloading one
+    #[must_use]
+    pub fn use_system_binaries(mut self, use_system_binaries: bool) -> Self {
+        self.settings.use_system_binaries = use_system_binaries;
+        self
+    }
+
+    /// Set whether the network is never accessed
+    #[must_use]
+    pub fn offline(mut self, offline: bool) -> Self {
+        self.settings.offline = offline;
+        self
+    }
+
+    /// Set the installation directory
+    #[must_use]
+    pub fn installation_dir<P: AsRef<Path>>(mut self, installation_dir: P) -> Self {
+        self.settings.installation_dir = installation_dir.as_ref().to_path_buf();
+        self
+    }
+
+    /// Set the layout of the installation directory
+    #[must_use]
+    pub fn install_dir_layout(mut self, install_dir_layout: InstallLayout) -> Self {
+        self.settings.install_dir_layout = install_dir_layout;
+        self
+    }
+
+    /// Set how the data directory is chosen for the installed version
+    #[must_use]
+    pub fn data_dir_strategy(mut self, data_dir_strategy: DataDirStrategy) -> Self {
+        self.settings.data_dir_strategy = data_dir_strategy;
+        self
+    }
+
+    /// Set the password file
+    #[must_use]
+    pub fn password_file<P: AsRef<Path>>(mut self, password_file: P) -> Self {
+        self.settings.password_file = password_file.as_ref().to_path_buf();
+        self
+    }
+
+    /// Set the data directory
+    #[must_use]
+    pub fn data_dir<P: AsRef<Path>>(mut self, data_dir: P) -> Self {
+        self.settings.data_dir = data_dir.as_ref().to_path_buf();
+        self
+    }
+
+    /// Set the directory the Unix domain socket of the server is created in; i.e. the
+    /// `unix_socket_directories` server configuration option
+    #[must_use]
+    pub fn socket_dir<P: AsRef<Path>>(self, socket_dir: P) -> Self {
+        let socket_dir = socket_dir.as_ref().to_string_lossy().into_owned();
+        self.config(UNIX_SOCKET_DIRECTORIES, socket_dir)
+    }
+
+    /// Set the host
+    #[must_use]
+    pub fn host<S: AsRef<str>>(mut self, host: S) -> Self {
+        self.settings.host = host.as_ref().to_string();
+        self
+    }
+
+    /// Set the port; a random available port is used if the port is `0`
+    #[must_use]
+    pub fn port(mut self, port: u16) -> Self {
+        self.settings.port = port;
+        self
+    }
+
+    /// Set the user name of the connection [URLs](Settings::url)
+    #[must_use]
+    pub fn username<S: AsRef<str>>(mut self, username: S) -> Self {
+        self.settings.username = username.as_ref().to_string();
+        self
+    }
+
+    /// Set the name of the bootstrap superuser that the data directory is initialized with and
+    /// that the server is administered as; [`BOOTSTRAP_SUPERUSER`](crate::BOOTSTRAP_SUPERUSER) by
+    /// default
+    #[must_use]
+    pub fn superuser<S: AsRef<str>>(mut self, superuser: S) -> Self {
+        self.settings.superuser = superuser.as_ref().to_string();
+        self
+    }
+
+    /// Set the password
+    #[must_use]
+    pub fn password<S: AsRef<str>>(mut self, password: S) -> Self {
+        self.settings.password = password.as_ref().to_string();
+        self
+    }
+
+    /// Set the source the password is resolved from when the instance is set up
+    #[must_use]
+    pub fn password_source(mut self, password_source: PasswordSource) -> Self {
+        self.settings.password_source = Some(password_source);
+        self
+    }
+
+    /// Set whether the data directory is removed when the instance is dropped
+    #[must_use]
+    pub fn temporary(mut self, temporary: bool) -> Self {
+        self.settings.temporary = temporary;
+        self
+    }
+
+    /// Set whether members of the owner's group can read the data directory
+    #[must_use]
+    pub fn allow_group_access(mut self, allow_group_access: bool) -> Self {
+        self.settings.allow_group_access = allow_group_access;
+        self
+    }
+
+    /// Set whether the data directory is initialized with `initdb --no-sync`, instead of only
+    /// temporary data directories
+    #[must_use]
+    pub fn initdb_no_sync(mut self, initdb_no_sync: bool) -> Self {
+        self.settings.initdb_no_sync = Some(initdb_no_sync);
+        self
+    }
+
+    /// Set whether the server keeps running when the instance is dropped
+    #[must_use]
+    pub fn detached(mut self, detached: bool) -> Self {
+        self.settings.detached = detached;
+        self
+    }
+
+    /// Set whether the server is started read only
+    #[must_use]
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.settings.read_only = read_only;
+        self
+    }
+
+    /// Set the command execution timeout
+    #[must_use]
+    pub fn timeout(mut self, timeout: Option<Duration>) -> Self {
+        self.settings.timeout = timeout;
+        self
+    }
+
+    /// Set the policy for retrying downloads, extraction, connections and server startup
+    #[must_use]
+    pub fn retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.settings.retry_policy = retry_policy;
+        self
+    }
+
+    /// Set the proxy the installation archives are downloaded through
+    #[must_use]
+    pub fn proxy(mut self, proxy: ProxyOptions) -> Self {
+        self.settings.proxy = proxy;
+        self
+    }
+
+    /// Set the filter of the files extracted from the installation archive
+    #[must_use]
+    pub fn extract_filter(mut self, extract_filter: ExtractFilter) -> Self {
+        self.settings.extract_filter = extract_filter;
+        self
+    }
+
+    /// Set a server configuration option
+    #[must_use]
+    pub fn config<K: AsRef<str>, V: AsRef<str>>(mut self, key: K, value: V) -> Self {
+        self.settings
+            .configuration
+            .insert(key.as_ref().to_string(), value.as_ref().to_string());
+        self
+    }
+
+    /// Set the server configuration options, replacing any options set previously
+    #[must_use]
+    pub fn configuration(mut self, configuration: HashMap<String, String>) -> Self {
+        self.settings.configuration = configuration;
+        self
+    }
+
+    /// Set the number of CPUs the parallelism settings are derived from
+    #[must_use]
+    pub fn parallelism(mut self, parallelism: usize) -> Self {
+        self.settings.parallelism = Some(parallelism);
+        self
+    }
+
+    /// Set how the server process is started and stopped
+    #[must_use]
+    pub fn process_mode(mut self, process_mode: ProcessMode) -> Self {
+        self.settings.process_mode = process_mode;
+        self
+    }
+
+    /// Set whether `SIGTERM` and `SIGINT` are forwarded to a server started as a direct child
+    #[must_use]
+    pub fn forward_signals(mut self, forward_signals: bool) -> Self {
+        self.settings.forward_signals = forward_signals;
+        self
+    }
+
+    /// Set how the server is determined to be ready when it is started
+    #[must_use]
+    pub fn wait_strategy(mut self, wait_strategy: WaitStrategy) -> Self {
+        self.settings.wait_strategy = wait_strategy;
+        self
+    }
+
+    /// Set whether the server log is captured as `tracing` events
+    #[must_use]
+    pub fn log_capture(mut self, log_capture: LogCapture) -> Self {
+        self.settings.log_capture = log_capture;
+        self
+    }
+
+    /// Set how the server is stopped
+    #[must_use]
+    pub fn shutdown(mut self, shutdown: ShutdownOptions) -> Self {
+        self.settings.shutdown = shutdown;
+        self
+    }
+
+    /// Set the locale provider of the databases created when the data directory is initialized
+    #[must_use]
+    pub fn locale_provider(mut self, locale_provider: LocaleProvider) -> Self {
+        self.settings.locale_provider = Some(locale_provider);
+        self
+    }
+
+    /// Set the options applied to created databases
+    #[must_use]
+    pub fn database_options(mut self, database_options: DatabaseOptions) -> Self {
+        self.settings.database_options = database_options;
+        self
+    }
+
+    /// Set the default isolation level of transactions
+    #[must_use]
+    pub fn default_transaction_isolation(mut self, isolation: TransactionIsolation) -> Self {
+        self.settings.default_transaction_isolation = Some(isolation);
+        self
+    }
+
+    /// Set the time zone of sessions
+    #[must_use]
+    pub fn timezone(mut self, timezone: Timezone) -> Self {
+        self.settings.timezone = Some(timezone);
+        self
+    }
+
+    /// Build the settings without validating them
+    #[must_use]
+    pub fn build(self) -> Settings {
+        self.settings
+    }
+
+    /// Build the settings, validating that:
+    /// * the host, user name and superuser are set, and the parallelism is at least `1` if set,
+    /// * the data, installation and password file directories can be written to, or already
+    ///   contain the installation, and
+    /// * the locale provider is supported by the major version of the version requirement.
+    ///
+    /// # Errors
+    /// * If a setting is invalid.
+    pub fn try_build(self) -> Result<Settings> {
+        let settings = self.settings;
+        #[cfg(unix)]
+        if settings.port != 0 && settings.port < PRIVILEGED_PORT_END {
+            warn!(
+                "Port {} may be privileged and require the server to have permission to bind it",
+                settings.port
+            );
+        }
+        if settings.host.is_empty() {
+            return Err(Error::ConfigurationError("Host is empty".to_string()));
+        }
+        if settings.username.is_empty() {
+            return Err(Error::ConfigurationError("User name is empty".to_string()));
+        }
+        if settings.superuser.is_empty() {
+            return Err(Error::ConfigurationError("Superuser is empty".to_string()));
+        }
+        if settings.parallelism == Some(0) {
+            return Err(Error::ConfigurationError(
+                "Parallelism must be at least 1".to_string(),
+            ));
+        }
+
+        check_writable(&settings.data_dir)?;
+        if let Some(parent) = settings.password_file.parent() {
+            check_writable(parent)?;
+        }
+        if !settings.use_system_binaries && !is_installed(&settings) {
+            check_writable(&settings.installation_root())?;
+        }
+
+        if let (Some(locale_provider), Some(major)) =
+            (settings.locale_provider, required_major(&settings.version))
+        {
+            if major < locale_provider.minimum_version() {
+                return Err(Error::ConfigurationError(format!(
+                    "Locale provider {} requires PostgreSQL {} or later; version {} was requested",
+                    locale_provider.as_str(),
+                    locale_provider.minimum_version(),
+                    settings.version
+                )));
+            }
+        }
+        Ok(settings)
+    }
+}
+
+/// Returns `true` if the exact version of the settings is already installed.
+fn is_installed(settings: &Settings) -> bool {
+    settings.version.exact_version().is_some_and(|version| {
+        settings
+            .install_dir_layout
+            .installation_dir(&settings.installation_dir, &version)
+            .join("bin")
+            .is_dir()
+    })
+}
+
+/// Returns the major version required by the version requirement, if the requirement only
+/// matches versions of a single major version; e.g. `=16.4.0`, `16` or `~16.4`.
+pub(crate) fn required_major(version: &VersionReq) -> Option<u64> {
+    match version.comparators.as_slice() {
+        [comparator] if matches!(comparator.op, Op::Exact | Op::Tilde | Op::Caret) => {
+            Some(comparator.major)
+        }
+        _ => None,
+    }
+}
+
+/// Checks that files can be created in the `dir`, or in the nearest existing parent directory if
+/// the directory does not exist yet.
+///
+/// # Errors
+/// * If the directory is not a directory or cannot be written to.
+fn check_writable(dir: &Path) -> Result<()> {
+    let mut existing_dir = PathBuf::from(dir);
+    while !existing_dir.exists() {
+        if !existing_dir.pop() {
+            return Ok(());
+        }
+    }
+    if !existing_dir.is_dir() {
+        return Err(Error::ConfigurationError(format!(
+            "{} is not a directory",
+            existing_dir.to_string_lossy()
+        )));
+    }
+    tempfile::tempfile_in(&existing_dir).map_err(|error| {
+        Error::ConfigurationError(format!(
+            "{} is not writable: {error}",
+            existing_dir.to_string_lossy()
+        ))
+    })?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_settings_builder() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let settings = SettingsBuilder::new()
+            .releases_url("https://github.com")
+            .version(VersionReq::parse("=16.4.0")?)
+            .installation_dir(temp_dir.path().join("postgresql"))
+            .data_dir(temp_dir.path().join("data"))
+            .password_file(temp_dir.path().join(".pgpass"))
+            .socket_dir(temp_dir.path())
+            .port(5432)
+            .username("example")
+            .superuser("admin")
+            .temporary(false)
+            .timeout(Some(Duration::from_secs(10)))
+            .config("max_connections", "42")
+            .locale_provider(LocaleProvider::Icu)
+            .try_build()?;
+
+        assert_eq!("https://github.com", settings.releases_url);
+        assert_eq!(VersionReq::parse("=16.4.0")?, settings.version);
+        assert_eq!(temp_dir.path().join("data"), settings.data_dir);
+        assert_eq!(5432, settings.port);
+        assert_eq!("example", settings.username);
+        assert_eq!("admin", settings.superuser);
+        assert!(!settings.temporary);
+        assert_eq!(Some(Duration::from_secs(10)), settings.timeout);
+        assert_eq!(
+            Some(&"42".to_string()),
+            settings.configuration.get("max_connections")
+        );
+        assert_eq!(
+            Some(&temp_dir.path().to_string_lossy().to_string()),
+            settings.configuration.get(UNIX_SOCKET_DIRECTORIES)
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_settings_builder_defaults() {
+        assert_eq!(
+            Settings::default().port,
+            SettingsBuilder::new().build().port
+        );
+        let settings = SettingsBuilder::new().port(5432).build();
+        assert_eq!(
+            settings,
+            SettingsBuilder::from_settings(settings.clone()).build()
+        );
+    }
+
+    #[test]
+    fn test_try_build_privileged_port() {
+        assert!(SettingsBuilder::new().port(80).try_build().is_ok());
+        assert!(SettingsBuilder::new().port(0).try_build().is_ok());
+    }
+
+    #[test]
+    fn test_try_build_empty_values() {
+        assert!(SettingsBuilder::new().host("").try_build().is_err());
+        assert!(SettingsBuilder::new().username("").try_build().is_err());
+        assert!(SettingsBuilder::new().superuser("").try_build().is_err());
+        assert!(SettingsBuilder::new().parallelism(0).try_build().is_err());
+    }
+
+    #[test]
+    fn test_try_build_data_dir_not_directory() -> Result<()> {
+        let file = tempfile::NamedTempFile::new()?;
+        let result = SettingsBuilder::new()
+            .data_dir(file.path().join("data"))
+            .try_build();
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_build_unsupported_locale_provider() -> Result<()> {
+        let result = SettingsBuilder::new()
+            .version(VersionReq::parse("=16.4.0")?)
+            .locale_provider(LocaleProvider::Builtin)
+            .try_build();
+        assert!(result.is_err());
+        let result = SettingsBuilder::new()
+            .version(VersionReq::parse(">=16")?)
+            .locale_provider(LocaleProvider::Builtin)
+            .try_build();
+        assert!(result.is_ok());
+        let result = SettingsBuilder::new()
+            .version(VersionReq::parse("=14.13.0")?)
+            .locale_provider(LocaleProvider::Libc)
+            .try_build();
+        assert!(result.is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_required_major() -> Result<()> {
+        assert_eq!(Some(16), required_major(&VersionReq::parse("=16.4.0")?));
+        assert_eq!(Some(16), required_major(&VersionReq::parse("16")?));
+        assert_eq!(None, required_major(&VersionReq::parse(">=16")?));
+        assert_eq!(None, required_major(&VersionReq::STAR));
+        Ok(())
+    }
+}
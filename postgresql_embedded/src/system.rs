@@ -0,0 +1,161 @@
+use crate::error::Error::ConfigurationError;
+use crate::error::Result;
+use semver::Version;
+use std::env;
+use std::env::consts::EXE_SUFFIX;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use tracing::debug;
+
+/// Environment variable with the installation directory of a system `PostgreSQL` installation;
+/// searched before the directories of the `PATH`
+pub(crate) const PG_HOME: &str = "PG_HOME";
+
+/// Binaries that an installation must contain to be managed
+const REQUIRED_BINARIES: &[&str] = &["initdb", "pg_ctl", "postgres"];
+
+/// A `PostgreSQL` installation on the system, such as one installed by a package manager
+#[derive(Clone, Debug, PartialEq)]
+pub(crate) struct SystemInstallation {
+    /// Installation directory; the binaries are in its `bin` directory
+    pub installation_dir: PathBuf,
+    /// Version of the `postgres` binary
+    pub version: Version,
+}
+
+/// Find the system installations of `PostgreSQL`, in the order they are searched: the
+/// installation directory in the `PG_HOME` environment variable, then the directories of the
+/// `PATH` that contain the required binaries. Installations whose version cannot be determined
+/// are skipped.
+pub(crate) fn find_system_installations() -> Vec<SystemInstallation> {
+    let mut binary_dirs = Vec::new();
+    if let Some(pg_home) = env::var_os(PG_HOME) {
+        binary_dirs.push(PathBuf::from(pg_home).join("bin"));
+    }
+    if let Some(path) = env::var_os("PATH") {
+        binary_dirs.extend(env::split_paths(&path));
+    }
+
+    let mut installations: Vec<SystemInstallation> = Vec::new();
+    for binary_dir in binary_dirs {
+        let Some(installation_dir) = installation_dir(&binary_dir) else {
+            continue;
+        };
+        if installations
+            .iter()
+            .any(|installation| installation.installation_dir == installation_dir)
+        {
+            continue;
+        }
+        match binary_version(&binary_dir) {
+            Ok(version) => {
+                debug!(
+                    "Found PostgreSQL {version} installation in {}",
+                    installation_dir.to_string_lossy()
+                );
+                installations.push(SystemInstallation {
+                    installation_dir,
+                    version,
+                });
+            }
+            Err(error) => debug!(
+                "Skipping PostgreSQL installation in {}: {error}",
+                installation_dir.to_string_lossy()
+            ),
+        }
+    }
+    installations
+}
+
+/// Get the installation directory of the `binary_dir` if it contains the required binaries and
+/// is named `bin`, as the binary directory of an installation is expected to be.
+fn installation_dir(binary_dir: &Path) -> Option<PathBuf> {
+    if !binary_dir.ends_with("bin") {
+        return None;
+    }
+    let complete = REQUIRED_BINARIES
+        .iter()
+        .all(|binary| binary_dir.join(format!("{binary}{EXE_SUFFIX}")).is_file());
+    if complete {
+        binary_dir.parent().map(Path::to_path_buf)
+    } else {
+        None
+    }
+}
+
+/// Get the version of the `postgres` binary in the `binary_dir`.
+///
+/// # Errors
+/// * If the binary cannot be executed, or its version cannot be parsed.
+pub(crate) fn binary_version(binary_dir: &Path) -> Result<Version> {
+    let postgres = binary_dir.join("postgres");
+    let output = Command::new(&postgres).arg("--version").output()?;
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    parse_version_output(&stdout).ok_or_else(|| {
+        ConfigurationError(format!(
+            "cannot determine the version of {}: {}",
+            postgres.to_string_lossy(),
+            stdout.trim()
+        ))
+    })
+}
+
+/// Parse the version from the output of `postgres --version`; e.g.
+/// `postgres (PostgreSQL) 16.4 (Debian 16.4-1.pgdg120+1)`. Versions from 10 have two parts, which
+/// are the major and minor version.
+fn parse_version_output(output: &str) -> Option<Version> {
+    let version = output
+        .split_whitespace()
+        .find(|token| token.starts_with(|c: char| c.is_ascii_digit()))?;
+    let mut parts = version
+        .split(|c: char| !c.is_ascii_digit())
+        .take_while(|part| !part.is_empty())
+        .map(str::parse::<u64>);
+    let major = parts.next()?.ok()?;
+    let minor = parts.next().and_then(Result::ok).unwrap_or_default();
+    let patch = parts.next().and_then(Result::ok).unwrap_or_default();
+    Some(Version::new(major, minor, patch))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_version_output() {
+        assert_eq!(
+            Some(Version::new(16, 4, 0)),
+            parse_version_output("postgres (PostgreSQL) 16.4")
+        );
+        assert_eq!(
+            Some(Version::new(16, 4, 0)),
+            parse_version_output("postgres (PostgreSQL) 16.4 (Debian 16.4-1.pgdg120+1)")
+        );
+        assert_eq!(
+            Some(Version::new(9, 6, 24)),
+            parse_version_output("postgres (PostgreSQL) 9.6.24")
+        );
+        assert_eq!(
+            Some(Version::new(17, 0, 0)),
+            parse_version_output("postgres (PostgreSQL) 17beta1")
+        );
+        assert_eq!(None, parse_version_output("postgres (PostgreSQL)"));
+    }
+
+    #[test]
+    fn test_installation_dir() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let binary_dir = temp_dir.path().join("bin");
+        std::fs::create_dir_all(&binary_dir)?;
+        assert_eq!(None, installation_dir(&binary_dir));
+        for binary in REQUIRED_BINARIES {
+            std::fs::write(binary_dir.join(format!("{binary}{EXE_SUFFIX}")), "")?;
+        }
+        assert_eq!(
+            Some(temp_dir.path().to_path_buf()),
+            installation_dir(&binary_dir)
+        );
+        assert_eq!(None, installation_dir(temp_dir.path()));
+        Ok(())
+    }
+}
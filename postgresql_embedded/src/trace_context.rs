@@ -0,0 +1,56 @@
+/// Get the W3C `traceparent` of the current span. Only available with the `opentelemetry` feature
+/// when the span is part of a valid OpenTelemetry trace.
+pub(crate) fn traceparent() -> Option<String> {
+    #[cfg(feature = "opentelemetry")]
+    {
+        use opentelemetry::trace::TraceContextExt;
+        use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+        let context = tracing::Span::current().context();
+        let span = context.span();
+        let span_context = span.span_context();
+        if span_context.is_valid() {
+            return Some(format!(
+                "00-{}-{}-{:02x}",
+                span_context.trace_id(),
+                span_context.span_id(),
+                span_context.trace_flags().to_u8()
+            ));
+        }
+    }
+    None
+}
+
+/// Append the trace context of the current span to the SQL statement as a
+/// [sqlcommenter](https://google.github.io/sqlcommenter/) comment, so that it appears in the
+/// server log and `pg_stat_activity`.
+pub(crate) fn annotate(sql: &str) -> String {
+    match traceparent() {
+        Some(traceparent) => comment(sql, &traceparent),
+        None => sql.to_string(),
+    }
+}
+
+/// Append the `traceparent` to the SQL statement as a sqlcommenter comment.
+fn comment(sql: &str, traceparent: &str) -> String {
+    format!("{sql} /*traceparent='{traceparent}'*/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_annotate_without_trace() {
+        assert_eq!("SELECT 1", annotate("SELECT 1"));
+    }
+
+    #[test]
+    fn test_comment() {
+        let traceparent = "00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01";
+        assert_eq!(
+            "CREATE DATABASE \"test\" /*traceparent='00-4bf92f3577b34da6a3ce929d0e0e4736-00f067aa0ba902b7-01'*/",
+            comment("CREATE DATABASE \"test\"", traceparent)
+        );
+    }
+}
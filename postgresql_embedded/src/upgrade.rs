@@ -0,0 +1,131 @@
+use crate::error::Error::UpgradeError;
+use crate::error::Result;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// File in the data directory containing the major version that initialized it
+const PG_VERSION_FILE: &str = "PG_VERSION";
+
+/// Get the major version of `PostgreSQL` that initialized the data directory, or `None` if the
+/// data directory is not initialized.
+///
+/// # Errors
+/// * If the version file cannot be read or parsed.
+pub(crate) fn data_dir_major_version(data_dir: &Path) -> Result<Option<u64>> {
+    let version_file = data_dir.join(PG_VERSION_FILE);
+    if !version_file.exists() {
+        return Ok(None);
+    }
+    let contents = fs::read_to_string(&version_file)?;
+    // Versions before 10 have a two part major version (e.g. 9.6)
+    let major = contents.trim().split('.').next().unwrap_or_default();
+    match major.parse() {
+        Ok(major) => Ok(Some(major)),
+        Err(error) => Err(UpgradeError(format!(
+            "invalid version '{}' in {}: {error}",
+            contents.trim(),
+            version_file.to_string_lossy()
+        ))),
+    }
+}
+
+/// Get the path of a directory next to `data_dir`, named after it with the given `suffix`.
+pub(crate) fn sibling_dir(data_dir: &Path, suffix: &str) -> PathBuf {
+    let name = data_dir
+        .file_name()
+        .map(|name| name.to_string_lossy().to_string())
+        .unwrap_or_default();
+    data_dir.with_file_name(format!("{name}.{suffix}"))
+}
+
+/// Replace `data_dir` with the upgraded data directory `upgrade_dir`. The existing data directory
+/// is renamed while the upgraded directory is moved into place, and restored if the move fails;
+/// it is only removed once the upgraded directory is in place.
+///
+/// # Errors
+/// * If the data directory cannot be replaced.
+pub(crate) fn replace_data_dir(data_dir: &Path, upgrade_dir: &Path) -> Result<()> {
+    let backup_dir = sibling_dir(data_dir, "old");
+    if backup_dir.exists() {
+        fs::remove_dir_all(&backup_dir)?;
+    }
+    fs::rename(data_dir, &backup_dir)?;
+    if let Err(error) = fs::rename(upgrade_dir, data_dir) {
+        fs::rename(&backup_dir, data_dir)?;
+        return Err(UpgradeError(format!(
+            "unable to move {} to {}: {error}",
+            upgrade_dir.to_string_lossy(),
+            data_dir.to_string_lossy()
+        )));
+    }
+    debug!(
+        "Replaced data directory {}; removing {}",
+        data_dir.to_string_lossy(),
+        backup_dir.to_string_lossy()
+    );
+    fs::remove_dir_all(&backup_dir)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_data_dir_major_version() -> Result<()> {
+        let data_dir = tempfile::tempdir()?;
+        assert_eq!(None, data_dir_major_version(data_dir.path())?);
+
+        fs::write(data_dir.path().join(PG_VERSION_FILE), "16\n")?;
+        assert_eq!(Some(16), data_dir_major_version(data_dir.path())?);
+
+        fs::write(data_dir.path().join(PG_VERSION_FILE), "9.6\n")?;
+        assert_eq!(Some(9), data_dir_major_version(data_dir.path())?);
+
+        fs::write(data_dir.path().join(PG_VERSION_FILE), "foo\n")?;
+        assert!(data_dir_major_version(data_dir.path()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_sibling_dir() {
+        assert_eq!(
+            PathBuf::from("/tmp/data.upgrade"),
+            sibling_dir(Path::new("/tmp/data"), "upgrade")
+        );
+    }
+
+    #[test]
+    fn test_replace_data_dir() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let data_dir = temp_dir.path().join("data");
+        let upgrade_dir = sibling_dir(&data_dir, "upgrade");
+        fs::create_dir_all(&data_dir)?;
+        fs::write(data_dir.join(PG_VERSION_FILE), "15")?;
+        fs::create_dir_all(&upgrade_dir)?;
+        fs::write(upgrade_dir.join(PG_VERSION_FILE), "16")?;
+
+        replace_data_dir(&data_dir, &upgrade_dir)?;
+
+        assert_eq!(Some(16), data_dir_major_version(&data_dir)?);
+        assert!(!upgrade_dir.exists());
+        assert!(!sibling_dir(&data_dir, "old").exists());
+        Ok(())
+    }
+
+    #[test]
+    fn test_replace_data_dir_rollback() -> Result<()> {
+        let temp_dir = tempfile::tempdir()?;
+        let data_dir = temp_dir.path().join("data");
+        fs::create_dir_all(&data_dir)?;
+        fs::write(data_dir.join(PG_VERSION_FILE), "15")?;
+
+        let error = replace_data_dir(&data_dir, &sibling_dir(&data_dir, "missing"))
+            .expect_err("upgrade directory does not exist");
+
+        assert!(matches!(error, UpgradeError(_)));
+        assert_eq!(Some(15), data_dir_major_version(&data_dir)?);
+        Ok(())
+    }
+}
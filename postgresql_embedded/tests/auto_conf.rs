@@ -0,0 +1,66 @@
+use postgresql_embedded::{PostgreSQL, BOOTSTRAP_DATABASE};
+use sqlx::PgPool;
+use test_log::test;
+
+#[test(tokio::test)]
+async fn auto_conf() -> anyhow::Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+    assert!(!postgresql.auto_conf()?.contains_key("work_mem"));
+
+    let database_url = postgresql.settings().url(BOOTSTRAP_DATABASE);
+    let pool = PgPool::connect(database_url.as_str()).await?;
+    sqlx::query("ALTER SYSTEM SET work_mem = '8MB'")
+        .execute(&pool)
+        .await?;
+    sqlx::query("ALTER SYSTEM SET search_path = \"$user\", public, vectors")
+        .execute(&pool)
+        .await?;
+    pool.close().await;
+
+    let auto_conf = postgresql.auto_conf()?;
+    assert_eq!(Some(&"8MB".to_string()), auto_conf.get("work_mem"));
+    assert_eq!(
+        Some(&"\"$user\", public, vectors".to_string()),
+        auto_conf.get("search_path")
+    );
+
+    postgresql.reset_config("work_mem").await?;
+    postgresql.reset_config("search_path").await?;
+    let auto_conf = postgresql.auto_conf()?;
+    assert!(!auto_conf.contains_key("work_mem"));
+    assert!(!auto_conf.contains_key("search_path"));
+
+    postgresql.stop().await?;
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn reload() -> anyhow::Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+
+    let database_url = postgresql.settings().url(BOOTSTRAP_DATABASE);
+    let pool = PgPool::connect(database_url.as_str()).await?;
+    sqlx::query("ALTER SYSTEM SET work_mem = '8MB'")
+        .execute(&pool)
+        .await?;
+    postgresql.reload().await?;
+
+    // The reload is processed asynchronously by the server
+    let mut work_mem = String::new();
+    for _ in 0..50 {
+        work_mem = sqlx::query_scalar("SHOW work_mem").fetch_one(&pool).await?;
+        if work_mem == "8MB" {
+            break;
+        }
+        tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+    }
+    assert_eq!("8MB", work_mem);
+    pool.close().await;
+
+    postgresql.stop().await?;
+    Ok(())
+}
@@ -0,0 +1,56 @@
+use postgresql_embedded::{BackupFormat, Error, PostgreSQL};
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_backup_and_restore() -> anyhow::Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+
+    let database_name = "backup";
+    postgresql.create_database(database_name).await?;
+    postgresql
+        .run_sql(
+            database_name,
+            "CREATE TABLE test (id INT); INSERT INTO test VALUES (1), (2);",
+        )
+        .await?;
+
+    let backup_dir = tempfile::tempdir()?;
+    for (index, format) in [
+        BackupFormat::Plain,
+        BackupFormat::Custom,
+        BackupFormat::Directory,
+        BackupFormat::Tar,
+    ]
+    .into_iter()
+    .enumerate()
+    {
+        let path = backup_dir.path().join(format.as_str());
+        postgresql
+            .backup_database(database_name, &path, format)
+            .await?;
+
+        let restored_name = format!("restored_{index}");
+        postgresql.restore_database(&restored_name, &path).await?;
+        let output = postgresql
+            .run_sql(&restored_name, "SELECT count(*) FROM test")
+            .await?;
+        assert!(output.stdout.contains('2'), "{format:?}: {}", output.stdout);
+    }
+
+    let missing = backup_dir.path().join("missing");
+    let error = postgresql
+        .backup_database("missing", &missing, BackupFormat::Custom)
+        .await
+        .expect_err("database does not exist");
+    assert!(matches!(error, Error::BackupError(_)));
+    let error = postgresql
+        .restore_database("missing", &missing)
+        .await
+        .expect_err("backup does not exist");
+    assert!(matches!(error, Error::RestoreError(_)));
+
+    postgresql.stop().await?;
+    Ok(())
+}
@@ -0,0 +1,54 @@
+use postgresql_embedded::{Error, PostgreSQL, Settings, Status};
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_clone_instance() -> anyhow::Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+
+    postgresql.create_user("app", "secret").await?;
+    postgresql.create_database("app").await?;
+    postgresql
+        .run_sql(
+            "app",
+            "CREATE TABLE users (id INT, name TEXT); \
+            INSERT INTO users VALUES (1, 'a'), (2, 'b');",
+        )
+        .await?;
+    postgresql.create_database("other").await?;
+
+    let clone = postgresql
+        .clone_instance(Settings::default(), &["app"])
+        .await?;
+    assert_eq!(Status::Started, clone.status());
+    assert_eq!(
+        postgresql.settings().installation_dir,
+        clone.settings().installation_dir
+    );
+    assert_ne!(postgresql.settings().data_dir, clone.settings().data_dir);
+    assert!(clone.user_exists("app").await?);
+    assert!(clone.database_exists("app").await?);
+    assert!(!clone.database_exists("other").await?);
+    assert_eq!(
+        postgresql.table_checksums("app", &["users"]).await?,
+        clone.table_checksums("app", &["users"]).await?
+    );
+
+    clone.stop().await?;
+    postgresql.stop().await?;
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_clone_instance_not_started() -> anyhow::Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+
+    let error = postgresql
+        .clone_instance(Settings::default(), &[] as &[&str])
+        .await
+        .expect_err("server not started");
+    assert!(matches!(error, Error::CloneError(_)));
+    Ok(())
+}
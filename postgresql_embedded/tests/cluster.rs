@@ -0,0 +1,72 @@
+use postgresql_embedded::{Cluster, Error, PostgreSQL, Settings, SettingsBuilder, Status};
+use std::time::{Duration, Instant};
+use test_log::test;
+
+/// Wait until the `users` table of the `standby` has the same rows as the `primary`.
+async fn wait_for_replication(primary: &PostgreSQL, standby: &PostgreSQL) -> anyhow::Result<()> {
+    let checksums = primary.table_checksums("app", &["users"]).await?;
+    let started = Instant::now();
+    loop {
+        if standby.table_checksums("app", &["users"]).await.ok() == Some(checksums.clone()) {
+            return Ok(());
+        }
+        if started.elapsed() > Duration::from_secs(30) {
+            anyhow::bail!("standby did not catch up with the primary");
+        }
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+}
+
+#[test(tokio::test)]
+async fn test_cluster_failover() -> anyhow::Result<()> {
+    let settings = SettingsBuilder::new().config("wal_log_hints", "on").build();
+    let mut primary = PostgreSQL::new(settings);
+    primary.setup().await?;
+    primary.start().await?;
+    primary.create_database("app").await?;
+    primary
+        .run_sql(
+            "app",
+            "CREATE TABLE users (id INT, name TEXT); INSERT INTO users VALUES (1, 'a');",
+        )
+        .await?;
+    let primary_data_dir = primary.settings().data_dir.clone();
+
+    let mut cluster = Cluster::new(primary);
+    cluster.add_standby(Settings::default()).await?;
+    cluster.add_standby(Settings::default()).await?;
+    assert_eq!(2, cluster.standbys().len());
+    for standby in cluster.standbys() {
+        assert_eq!(Status::Started, standby.status());
+        wait_for_replication(cluster.primary(), standby).await?;
+    }
+
+    let promoted_data_dir = cluster.standbys()[0].settings().data_dir.clone();
+    cluster.failover(0).await?;
+    assert_eq!(promoted_data_dir, cluster.primary().settings().data_dir);
+    assert_eq!(2, cluster.standbys().len());
+    assert_eq!(primary_data_dir, cluster.standbys()[1].settings().data_dir);
+
+    cluster
+        .primary()
+        .run_sql("app", "INSERT INTO users VALUES (2, 'b')")
+        .await?;
+    for standby in cluster.standbys() {
+        assert_eq!(Status::Started, standby.status());
+        wait_for_replication(cluster.primary(), standby).await?;
+    }
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_cluster_failover_missing_standby() -> anyhow::Result<()> {
+    let mut primary = PostgreSQL::default();
+    primary.setup().await?;
+    primary.start().await?;
+
+    let mut cluster = Cluster::new(primary);
+    let error = cluster.failover(0).await.expect_err("no standby");
+    assert!(matches!(error, Error::ClusterError(_)));
+    assert_eq!(Status::Started, cluster.primary().status());
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use postgresql_embedded::{DatabaseOptions, PostgreSQL, SettingsBuilder};
+use sqlx::{PgPool, Row};
+use std::time::Duration;
+use test_log::test;
+
+#[test(tokio::test)]
+async fn database_options() -> anyhow::Result<()> {
+    let database_options = DatabaseOptions {
+        connection_limit: Some(5),
+        idle_in_transaction_session_timeout: Some(Duration::from_secs(30)),
+        statement_timeout: Some(Duration::from_millis(1500)),
+    };
+    let settings = SettingsBuilder::new()
+        .database_options(database_options)
+        .build();
+    let mut postgresql = PostgreSQL::new(settings);
+    postgresql.setup().await?;
+    postgresql.start().await?;
+
+    let database_name = "test";
+    postgresql.create_database(database_name).await?;
+
+    let database_url = postgresql.settings().url(database_name);
+    let pool = PgPool::connect(database_url.as_str()).await?;
+    let row = sqlx::query("SELECT datconnlimit FROM pg_database WHERE datname = $1")
+        .bind(database_name)
+        .fetch_one(&pool)
+        .await?;
+    let connection_limit: i32 = row.get(0);
+    assert_eq!(5, connection_limit);
+
+    let row = sqlx::query("SELECT current_setting('idle_in_transaction_session_timeout'), current_setting('statement_timeout')")
+        .fetch_one(&pool)
+        .await?;
+    let idle_in_transaction_session_timeout: String = row.get(0);
+    let statement_timeout: String = row.get(1);
+    pool.close().await;
+    assert_eq!("30s", idle_in_transaction_session_timeout);
+    assert_eq!("1500ms", statement_timeout);
+
+    Ok(())
+}
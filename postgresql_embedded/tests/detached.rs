@@ -0,0 +1,65 @@
+use postgresql_embedded::{PostgreSQL, SettingsBuilder, Status};
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_detached() -> anyhow::Result<()> {
+    let data_dir = tempfile::tempdir()?;
+    let settings = SettingsBuilder::new()
+        .data_dir(data_dir.path())
+        .detached(true)
+        .build();
+    let mut postgresql = PostgreSQL::new(settings);
+    postgresql.setup().await?;
+    postgresql.start().await?;
+    let port = postgresql.settings().port;
+    drop(postgresql);
+
+    let settings = SettingsBuilder::new().data_dir(data_dir.path()).build();
+    let postgresql = PostgreSQL::attach(settings).await?;
+    assert_eq!(Status::Started, postgresql.status());
+    assert_eq!(port, postgresql.settings().port);
+    assert!(postgresql.settings().detached);
+    postgresql.create_database("test").await?;
+    assert!(postgresql.database_exists("test").await?);
+
+    postgresql.stop().await?;
+    assert_eq!(Status::Stopped, postgresql.status());
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_attach_running() -> anyhow::Result<()> {
+    let data_dir = tempfile::tempdir()?;
+    let settings = SettingsBuilder::new()
+        .data_dir(data_dir.path())
+        .temporary(false)
+        .build();
+    let mut postgresql = PostgreSQL::new(settings.clone());
+    postgresql.setup().await?;
+    postgresql.start().await?;
+    let port = postgresql.settings().port;
+    // Leave the server running, as after a crash of the application
+    std::mem::forget(postgresql);
+
+    let wrong_port = SettingsBuilder::from_settings(settings.clone())
+        .port(port.wrapping_add(1))
+        .build();
+    assert!(PostgreSQL::attach(wrong_port).await.is_err());
+
+    let postgresql = PostgreSQL::attach(settings).await?;
+    assert_eq!(Status::Started, postgresql.status());
+    assert_eq!(port, postgresql.settings().port);
+    assert!(!postgresql.settings().detached);
+    postgresql.create_database("test").await?;
+    postgresql.stop().await?;
+    assert_eq!(Status::Stopped, postgresql.status());
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_attach_not_running() -> anyhow::Result<()> {
+    let data_dir = tempfile::tempdir()?;
+    let settings = SettingsBuilder::new().data_dir(data_dir.path()).build();
+    assert!(PostgreSQL::attach(settings).await.is_err());
+    Ok(())
+}
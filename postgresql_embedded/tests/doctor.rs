@@ -0,0 +1,31 @@
+use postgresql_embedded::{PostgreSQL, SettingsBuilder, Severity};
+use std::net::TcpListener;
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_doctor() -> anyhow::Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    let findings = postgresql.doctor();
+    assert!(!findings
+        .iter()
+        .any(|finding| finding.severity == Severity::Error));
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_doctor_port_unavailable() -> anyhow::Result<()> {
+    let listener = TcpListener::bind(("localhost", 0))?;
+    let settings = SettingsBuilder::new()
+        .port(listener.local_addr()?.port())
+        .build();
+    let postgresql = PostgreSQL::new(settings);
+    let findings = postgresql.doctor();
+    let finding = findings
+        .iter()
+        .find(|finding| finding.check == "port")
+        .expect("port finding");
+    assert_eq!(Severity::Error, finding.severity);
+    assert!(finding.suggestion.is_some());
+    Ok(())
+}
@@ -24,7 +24,7 @@ async fn dump_command() -> anyhow::Result<()> {
         .no_align()
         .tuples_only()
         .build();
-    let (_stdout, _stderr) = psql.execute()?;
+    psql.execute()?;
 
     let temp_file = NamedTempFile::new()?;
     let file = temp_file.as_ref();
@@ -33,7 +33,7 @@ async fn dump_command() -> anyhow::Result<()> {
         .schema_only()
         .file(file.to_string_lossy().to_string())
         .build();
-    let (_stdout, _stderr) = pgdump.execute()?;
+    pgdump.execute()?;
 
     let contents = fs::read_to_string(file)?;
     assert!(contents.contains("person42"));
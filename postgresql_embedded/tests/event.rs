@@ -0,0 +1,68 @@
+use postgresql_embedded::{Event, PostgreSQL, Status};
+use std::sync::{Arc, Mutex};
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_event_listener() -> anyhow::Result<()> {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let listener_events = events.clone();
+    let mut postgresql = PostgreSQL::default().with_event_listener(move |event: &Event| {
+        if let Ok(mut events) = listener_events.lock() {
+            events.push(event.clone());
+        }
+    });
+    postgresql.setup().await?;
+    postgresql.start().await?;
+    let port = postgresql.settings().port;
+    postgresql.create_database("test").await?;
+    postgresql.drop_database("test").await?;
+    postgresql.stop().await?;
+
+    let events = events.lock().expect("events").clone();
+    let data_dir = postgresql.settings().data_dir.clone();
+    let expected = vec![
+        Event::InitializationStarted {
+            path: data_dir.clone(),
+        },
+        Event::InitializationCompleted { path: data_dir },
+        Event::ServerStarting { port },
+        Event::ServerStarted { port },
+        Event::DatabaseCreated {
+            name: "test".to_string(),
+        },
+        Event::DatabaseDropped {
+            name: "test".to_string(),
+        },
+        Event::ServerStopping { port },
+        Event::ServerStopped { port },
+    ];
+    // Download and extraction events are only emitted if the version is not already installed
+    assert_eq!(expected, events[events.len() - expected.len()..]);
+    Ok(())
+}
+
+#[cfg(feature = "tokio")]
+#[test(tokio::test)]
+async fn test_state_stream() -> anyhow::Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    let mut state_stream = postgresql.state_stream();
+    assert_eq!(postgresql.status(), *state_stream.borrow_and_update());
+
+    postgresql.setup().await?;
+    assert_eq!(Status::Stopped, *state_stream.borrow_and_update());
+
+    postgresql.start().await?;
+    assert!(state_stream.has_changed()?);
+    assert_eq!(Status::Started, *state_stream.borrow_and_update());
+
+    postgresql.stop().await?;
+    assert!(state_stream.has_changed()?);
+    assert_eq!(Status::Stopped, *state_stream.borrow_and_update());
+    Ok(())
+}
+
+#[test]
+fn test_status_serialize() -> anyhow::Result<()> {
+    assert_eq!("\"Started\"", serde_json::to_string(&Status::Started)?);
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use postgresql_embedded::{PostgreSQL, BOOTSTRAP_DATABASE};
+use sqlx::{PgPool, Row};
+use test_log::test;
+
+#[test(tokio::test)]
+async fn export_import_app_data() -> anyhow::Result<()> {
+    let database_name = "app";
+    let archive_dir = tempfile::tempdir()?;
+    let archive = archive_dir.path().join("app.export");
+
+    let mut source = PostgreSQL::default();
+    source.setup().await?;
+    source.start().await?;
+    source.create_database(database_name).await?;
+    let pool = PgPool::connect(source.settings().url(database_name).as_str()).await?;
+    sqlx::query("CREATE TABLE person (id INTEGER, name VARCHAR(20))")
+        .execute(&pool)
+        .await?;
+    sqlx::query("INSERT INTO person VALUES (1, 'Alice'), (2, 'Bob')")
+        .execute(&pool)
+        .await?;
+    pool.close().await;
+    source.export_app_data(&archive).await?;
+    assert!(archive.exists());
+
+    let mut target = PostgreSQL::default();
+    target.setup().await?;
+    target.start().await?;
+    target.import_app_data(&archive, true).await?;
+    assert!(target.database_exists(database_name).await?);
+    assert!(target.database_exists(BOOTSTRAP_DATABASE).await?);
+
+    let pool = PgPool::connect(target.settings().url(database_name).as_str()).await?;
+    let row = sqlx::query("SELECT COUNT(*) FROM person")
+        .fetch_one(&pool)
+        .await?;
+    let count: i64 = row.get(0);
+    pool.close().await;
+    assert_eq!(2, count);
+
+    Ok(())
+}
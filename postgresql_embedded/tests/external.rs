@@ -0,0 +1,35 @@
+use postgresql_embedded::{PostgreSQL, SettingsBuilder, Status};
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_external() -> anyhow::Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+    let settings = postgresql.settings();
+    let url = format!(
+        "postgresql://{}:{}@{}:{}",
+        settings.username, settings.password, settings.host, settings.port
+    );
+
+    let mut external = PostgreSQL::external_url(url).await?;
+    assert!(external.is_external());
+    assert_eq!(Status::Started, external.status());
+    external.setup().await?;
+    external.start().await?;
+    external.create_database("external").await?;
+    assert!(external.database_exists("external").await?);
+    external.drop_database("external").await?;
+    external.stop().await?;
+    drop(external);
+
+    assert_eq!(Status::Started, postgresql.status());
+    assert!(postgresql.is_ready().await);
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_external_not_running() {
+    let settings = SettingsBuilder::new().port(1).build();
+    assert!(PostgreSQL::external(settings).await.is_err());
+}
@@ -0,0 +1,61 @@
+#![cfg(feature = "helpers")]
+
+use postgresql_embedded::helpers::{connect_pool, enable_extension, preload_libraries, restart};
+use postgresql_embedded::{PostgreSQL, Status};
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_connect_pool() -> anyhow::Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+
+    let database_name = "test";
+    postgresql.create_database(database_name).await?;
+    let pool = connect_pool(&postgresql, database_name).await?;
+    let value: i32 = sqlx::query_scalar("SELECT 1").fetch_one(&pool).await?;
+    assert_eq!(1, value);
+    pool.close().await;
+
+    postgresql.stop().await?;
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_restart() -> anyhow::Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+
+    restart(&mut postgresql).await?;
+    assert_eq!(Status::Started, postgresql.status());
+
+    postgresql.stop().await?;
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_preload_libraries_and_enable_extension() -> anyhow::Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+
+    let database_name = "test";
+    postgresql.create_database(database_name).await?;
+    assert!(preload_libraries(&mut postgresql, &["pg_stat_statements"]).await?);
+    assert!(!preload_libraries(&mut postgresql, &["pg_stat_statements"]).await?);
+    assert_eq!(Status::Started, postgresql.status());
+
+    enable_extension(&postgresql, database_name, "pg_stat_statements").await?;
+    // Enabling an extension that already exists succeeds
+    enable_extension(&postgresql, database_name, "pg_stat_statements").await?;
+    let pool = connect_pool(&postgresql, database_name).await?;
+    let calls: i64 = sqlx::query_scalar("SELECT count(*) FROM pg_stat_statements")
+        .fetch_one(&pool)
+        .await?;
+    assert!(calls >= 0);
+    pool.close().await;
+
+    postgresql.stop().await?;
+    Ok(())
+}
@@ -0,0 +1,49 @@
+use postgresql_embedded::{PostgreSQL, SettingsBuilder, BOOTSTRAP_DATABASE};
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_inspect() -> anyhow::Result<()> {
+    let data_dir = tempfile::tempdir()?;
+    let settings = SettingsBuilder::new()
+        .data_dir(data_dir.path())
+        .temporary(false)
+        .build();
+    let mut postgresql = PostgreSQL::new(settings);
+    postgresql.setup().await?;
+    postgresql.start().await?;
+    postgresql
+        .run_sql(
+            BOOTSTRAP_DATABASE,
+            "CREATE TABLE test (id INT); INSERT INTO test VALUES (1);",
+        )
+        .await?;
+    postgresql.stop().await?;
+
+    let inspection = PostgreSQL::inspect(data_dir.path()).await?;
+    let inspect_dir = inspection.settings().data_dir.clone();
+    assert_ne!(data_dir.path(), inspect_dir);
+    inspection
+        .run_sql(BOOTSTRAP_DATABASE, "INSERT INTO test VALUES (2);")
+        .await?;
+    let output = inspection
+        .run_sql(BOOTSTRAP_DATABASE, "SELECT count(*) FROM test;")
+        .await?;
+    assert!(output.stdout.contains('2'));
+    drop(inspection);
+    assert!(!inspect_dir.exists());
+
+    // Changes made to the copy do not affect the source
+    let inspection = PostgreSQL::inspect(data_dir.path()).await?;
+    let output = inspection
+        .run_sql(BOOTSTRAP_DATABASE, "SELECT count(*) FROM test;")
+        .await?;
+    assert!(output.stdout.contains('1'));
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_inspect_not_a_data_dir() -> anyhow::Result<()> {
+    let data_dir = tempfile::tempdir()?;
+    assert!(PostgreSQL::inspect(data_dir.path()).await.is_err());
+    Ok(())
+}
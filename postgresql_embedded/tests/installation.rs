@@ -0,0 +1,38 @@
+use postgresql_embedded::{PostgreSQL, Result};
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_export_installation() -> Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    let target_dir = tempfile::tempdir()?;
+
+    postgresql.export_installation(target_dir.path(), true)?;
+
+    let target_dir = target_dir.path();
+    assert!(target_dir.join("bin").exists());
+    assert!(target_dir.join("data").join("PG_VERSION").exists());
+    assert!(target_dir
+        .join(if cfg!(target_os = "windows") {
+            "start.cmd"
+        } else {
+            "start.sh"
+        })
+        .exists());
+    PostgreSQL::validate_relocation(target_dir)?;
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_export_installation_with_data_started() -> Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+    let target_dir = tempfile::tempdir()?;
+
+    assert!(postgresql
+        .export_installation(target_dir.path(), true)
+        .is_err());
+
+    postgresql.stop().await
+}
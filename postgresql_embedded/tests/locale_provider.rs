@@ -0,0 +1,35 @@
+use postgresql_embedded::{Error, LocaleProvider, PostgreSQL, SettingsBuilder, VersionReq};
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_locale_provider_libc() -> anyhow::Result<()> {
+    let settings = SettingsBuilder::new()
+        .locale_provider(LocaleProvider::Libc)
+        .build();
+    let mut postgresql = PostgreSQL::new(settings);
+    postgresql.setup().await?;
+    postgresql.start().await?;
+    postgresql.stop().await?;
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_locale_provider_unsupported_version() -> anyhow::Result<()> {
+    let settings = SettingsBuilder::new()
+        .version(VersionReq::parse("=16.4.0")?)
+        .locale_provider(LocaleProvider::Builtin)
+        .build();
+    let mut postgresql = PostgreSQL::new(settings);
+    let error = postgresql
+        .setup()
+        .await
+        .expect_err("unsupported locale provider");
+    assert!(matches!(
+        error,
+        Error::UnsupportedLocaleProvider {
+            provider: LocaleProvider::Builtin,
+            ..
+        }
+    ));
+    Ok(())
+}
@@ -0,0 +1,65 @@
+#![cfg(unix)]
+
+use postgresql_embedded::{PostgreSQL, Result, Settings, SettingsBuilder};
+use std::os::unix::fs::PermissionsExt;
+use std::path::Path;
+use test_log::test;
+
+fn mode(path: &Path) -> Result<u32> {
+    Ok(std::fs::metadata(path)?.permissions().mode() & 0o777)
+}
+
+#[test(tokio::test)]
+async fn test_data_dir_permissions() -> Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    let settings = postgresql.settings();
+    assert_eq!(0o700, mode(&settings.data_dir)?);
+    assert_eq!(0o600, mode(&settings.password_file)?);
+    assert_eq!(0o600, mode(&settings.data_dir.join("postgresql.conf"))?);
+
+    postgresql.start().await?;
+    postgresql.stop().await?;
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_data_dir_allow_group_access() -> Result<()> {
+    let settings = SettingsBuilder::new().allow_group_access(true).build();
+    let mut postgresql = PostgreSQL::new(settings);
+    postgresql.setup().await?;
+    let settings = postgresql.settings();
+    assert_eq!(0o750, mode(&settings.data_dir)?);
+    assert_eq!(0o640, mode(&settings.data_dir.join("postgresql.conf"))?);
+
+    postgresql.start().await?;
+    postgresql.stop().await?;
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_pre_created_data_dir_permissions() -> Result<()> {
+    let settings = Settings::default();
+    std::fs::set_permissions(&settings.data_dir, std::fs::Permissions::from_mode(0o755))?;
+    let mut postgresql = PostgreSQL::new(settings);
+    postgresql.setup().await?;
+    assert_eq!(0o700, mode(&postgresql.settings().data_dir)?);
+
+    postgresql.start().await?;
+    postgresql.stop().await?;
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_data_dir_group_permissions_kept() -> Result<()> {
+    let settings = SettingsBuilder::new().allow_group_access(true).build();
+    let mut postgresql = PostgreSQL::new(settings);
+    postgresql.setup().await?;
+    let data_dir = postgresql.settings().data_dir.clone();
+    std::fs::set_permissions(&data_dir, std::fs::Permissions::from_mode(0o710))?;
+
+    postgresql.start().await?;
+    assert_eq!(0o710, mode(&data_dir)?);
+    postgresql.stop().await?;
+    Ok(())
+}
@@ -1,6 +1,10 @@
+use postgresql_archive::ExactVersion;
 use postgresql_commands::psql::PsqlBuilder;
 use postgresql_commands::CommandBuilder;
-use postgresql_embedded::{PostgreSQL, Result, Settings, Status};
+use postgresql_embedded::{
+    Error, PostgreSQL, ProcessMode, Result, Settings, SettingsBuilder, Status, Timings, VersionReq,
+    WaitStrategy,
+};
 use std::fs::{remove_dir_all, remove_file};
 use test_log::test;
 
@@ -109,13 +113,12 @@ async fn test_persistent_database_reuse() -> Result<()> {
     assert!(data_dir.exists());
     assert!(password_file.exists());
 
-    let settings = Settings {
-        data_dir: data_dir.clone(),
-        password: password.clone(),
-        password_file: password_file.clone(),
-        temporary: false,
-        ..Default::default()
-    };
+    let settings = SettingsBuilder::new()
+        .data_dir(data_dir.clone())
+        .password(password.clone())
+        .password_file(password_file.clone())
+        .temporary(false)
+        .build();
 
     {
         let mut postgresql = PostgreSQL::new(settings);
@@ -145,6 +148,53 @@ async fn postgres_concurrency() -> Result<()> {
     Ok(())
 }
 
+#[cfg(feature = "tokio")]
+#[test(tokio::test(flavor = "multi_thread"))]
+async fn test_concurrent_setup() -> anyhow::Result<()> {
+    let data_dir = tempfile::tempdir()?;
+    let settings = SettingsBuilder::new()
+        .data_dir(data_dir.path().join("data"))
+        .temporary(false)
+        .build();
+    let mut postgresql1 = PostgreSQL::new(settings.clone());
+    let mut postgresql2 = PostgreSQL::new(settings);
+    let (report1, report2) = tokio::try_join!(postgresql1.setup(), postgresql2.setup())?;
+
+    // The data directory is initialized once; the other setup waits and reuses it
+    assert!(report1.initialize.is_some() ^ report2.initialize.is_some());
+    assert!(!data_dir.path().join("data.lock").exists());
+    postgresql1.start().await?;
+    postgresql1.stop().await?;
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_data_dir_in_use() -> anyhow::Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+
+    let settings = SettingsBuilder::from_settings(postgresql.settings().clone())
+        .port(0)
+        .build();
+    let mut duplicate = PostgreSQL::new(settings);
+    let error = duplicate.start().await.expect_err("data directory in use");
+    assert!(matches!(error, Error::DataDirInUse(_)));
+    assert!(matches!(
+        duplicate.stop().await,
+        Err(Error::DataDirInUse(_))
+    ));
+    // Dropping the duplicate leaves the server and data directory in place
+    drop(duplicate);
+    assert_eq!(Status::Started, postgresql.status());
+
+    // Clones share the data directory
+    let clone = postgresql.clone();
+    clone.stop().await?;
+    assert_eq!(Status::Stopped, postgresql.status());
+    Ok(())
+}
+
 #[test(tokio::test)]
 async fn test_authentication_success() -> Result<()> {
     let mut postgresql = PostgreSQL::default();
@@ -200,10 +250,7 @@ async fn test_authentication_invalid_password() -> Result<()> {
 
 #[test(tokio::test)]
 async fn test_username_setting() -> Result<()> {
-    let settings = Settings {
-        username: "admin".to_string(),
-        ..Default::default()
-    };
+    let settings = SettingsBuilder::new().username("admin").build();
     let mut postgresql = PostgreSQL::new(settings);
     postgresql.setup().await?;
     postgresql.start().await?;
@@ -217,3 +264,282 @@ async fn test_username_setting() -> Result<()> {
     assert!(!database_exists);
     Ok(())
 }
+
+#[test(tokio::test)]
+async fn test_create_databases() -> Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+
+    let database_names: Vec<String> = (0..10).map(|index| format!("test_{index}")).collect();
+    postgresql.create_databases(&database_names).await?;
+    for database_name in &database_names {
+        assert!(postgresql.database_exists(database_name).await?);
+    }
+
+    let result = postgresql.create_databases(&["test_0"]).await;
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_wait_for_recovery() -> Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+    postgresql
+        .wait_for_recovery(std::time::Duration::from_secs(10))
+        .await?;
+    postgresql.stop().await?;
+
+    let result = postgresql
+        .wait_for_recovery(std::time::Duration::from_millis(100))
+        .await;
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_wait_until_ready() -> Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+    postgresql
+        .wait_until_ready(std::time::Duration::from_secs(10))
+        .await?;
+    assert!(postgresql.is_ready().await);
+    postgresql.stop().await?;
+
+    assert!(!postgresql.is_ready().await);
+    let result = postgresql
+        .wait_until_ready(std::time::Duration::from_millis(100))
+        .await;
+    assert!(result.is_err());
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_stale_pid_file() -> Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+    postgresql.stop().await?;
+
+    // Simulate the PID file of a server that was not shut down cleanly, whose process ID has been
+    // reused by another process
+    let data_dir = postgresql.settings().data_dir.clone();
+    let contents = format!(
+        "{}\n{}\n1700000000\n{}\n",
+        std::process::id(),
+        data_dir.to_string_lossy(),
+        postgresql.settings().port
+    );
+    std::fs::write(data_dir.join("postmaster.pid"), contents)?;
+    assert_eq!(Status::Stopped, postgresql.status());
+
+    postgresql.start().await?;
+    assert_eq!(Status::Started, postgresql.status());
+    postgresql.stop().await?;
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_create_restore_point() -> Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+
+    let first = postgresql.create_restore_point("first").await?;
+    let second = postgresql.create_restore_point("second").await?;
+    assert!(first.contains('/'));
+    assert_ne!(first, second);
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_optimize_database() -> Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+
+    let database_name = "test";
+    postgresql.create_database(database_name).await?;
+    postgresql.optimize_database(database_name, false).await?;
+    postgresql.optimize_database(database_name, true).await?;
+    assert!(postgresql
+        .optimize_database("missing", false)
+        .await
+        .is_err());
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_direct_child_process_mode() -> Result<()> {
+    let settings = SettingsBuilder::new()
+        .process_mode(ProcessMode::DirectChild)
+        .build();
+    let mut postgresql = PostgreSQL::new(settings);
+    postgresql.setup().await?;
+    postgresql.start().await?;
+    assert_eq!(Status::Started, postgresql.status());
+
+    let database_name = "test";
+    postgresql.create_database(database_name).await?;
+    assert!(postgresql.database_exists(database_name).await?);
+
+    postgresql.stop().await?;
+    assert_eq!(Status::Stopped, postgresql.status());
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_wait_strategy() -> Result<()> {
+    for process_mode in [ProcessMode::PgCtl, ProcessMode::DirectChild] {
+        let settings = SettingsBuilder::new()
+            .process_mode(process_mode)
+            .wait_strategy(WaitStrategy::Composite(vec![
+                WaitStrategy::PortOpen,
+                WaitStrategy::PgIsReady,
+                WaitStrategy::QuerySucceeds("SELECT 1".to_string()),
+                WaitStrategy::LogLinePattern("ready to accept connections".to_string()),
+            ]))
+            .build();
+        let mut postgresql = PostgreSQL::new(settings);
+        postgresql.setup().await?;
+        postgresql.start().await?;
+        assert_eq!(Status::Started, postgresql.status());
+        postgresql.stop().await?;
+    }
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_wait_strategy_invalid_log_line_pattern() -> Result<()> {
+    let settings = SettingsBuilder::new()
+        .wait_strategy(WaitStrategy::LogLinePattern("(".to_string()))
+        .build();
+    let mut postgresql = PostgreSQL::new(settings);
+    postgresql.setup().await?;
+    assert!(postgresql.start().await.is_err());
+    assert_eq!(Status::Stopped, postgresql.status());
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_timings() -> Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    assert_eq!(Timings::default(), postgresql.timings());
+
+    postgresql.setup().await?;
+    assert!(postgresql.timings().initialize.is_some());
+    postgresql.start().await?;
+    let database_name = "test";
+    postgresql.create_database(database_name).await?;
+    postgresql.drop_database(database_name).await?;
+    postgresql.stop().await?;
+
+    let timings = postgresql.timings();
+    assert!(timings.start.is_some());
+    assert!(timings.stop.is_some());
+    assert!(timings.create_database.is_some());
+    assert!(timings.drop_database.is_some());
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_setup_report() -> Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    let report = postgresql.setup().await?;
+    let settings = postgresql.settings();
+    assert!(report.initialize.is_some());
+    assert_eq!(settings.version, report.version);
+    assert!(settings.version.exact_version().is_some());
+    assert_eq!(settings.installation_dir, report.installation_dir);
+    assert_eq!(settings.data_dir, report.data_dir);
+    if report.downloaded {
+        assert!(report.bytes > 0);
+        assert!(report.download.is_some());
+        assert!(report.extract.is_some());
+    }
+
+    // A second setup neither installs nor initializes the server
+    let report = postgresql.setup().await?;
+    assert!(!report.downloaded);
+    assert_eq!(0, report.bytes);
+    assert_eq!(None, report.extract);
+    assert_eq!(None, report.initialize);
+    postgresql.ensure_setup().await?;
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_run_sql() -> Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+
+    let database_name = "test";
+    postgresql.create_database(database_name).await?;
+    postgresql
+        .run_sql(
+            database_name,
+            "CREATE TABLE person (name TEXT); INSERT INTO person VALUES ('Alice');",
+        )
+        .await?;
+    let output = postgresql
+        .run_sql(database_name, "SELECT name FROM person")
+        .await?;
+    assert!(output.stdout.contains("Alice"));
+
+    // Execution stops at the first error
+    assert!(postgresql
+        .run_sql(
+            database_name,
+            "SELECT * FROM missing; INSERT INTO person VALUES ('Bob');"
+        )
+        .await
+        .is_err());
+    let output = postgresql
+        .run_sql(database_name, "SELECT count(*) FROM person")
+        .await?;
+    assert!(output.stdout.contains('1'));
+
+    let sql_file = tempfile::NamedTempFile::new()?;
+    std::fs::write(sql_file.path(), "INSERT INTO person VALUES ('Carol');")?;
+    postgresql
+        .run_sql_file(database_name, sql_file.path())
+        .await?;
+    let output = postgresql
+        .run_sql(database_name, "SELECT name FROM person")
+        .await?;
+    assert!(output.stdout.contains("Carol"));
+
+    postgresql.stop().await
+}
+
+#[test(tokio::test)]
+async fn test_check_for_updates() -> Result<()> {
+    let settings = SettingsBuilder::new()
+        .version(VersionReq::parse("=16")?)
+        .build();
+    let mut postgresql = PostgreSQL::new(settings);
+    assert!(postgresql.check_for_updates().await.is_err());
+
+    postgresql.setup().await?;
+    let version = postgresql.settings().version.clone();
+    if let Some(release_info) = postgresql.check_for_updates().await? {
+        assert!(!version.matches(&release_info.version));
+        assert!(release_info.published_at.is_some());
+    }
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_available_versions() -> Result<()> {
+    let postgresql = PostgreSQL::default();
+    let versions = postgresql.available_versions().await?;
+    assert!(versions
+        .iter()
+        .any(|version| version.major == 16 && version.minor >= 4));
+    Ok(())
+}
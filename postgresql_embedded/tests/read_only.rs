@@ -0,0 +1,40 @@
+use postgresql_embedded::{PostgreSQL, SettingsBuilder, BOOTSTRAP_DATABASE};
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_read_only() -> anyhow::Result<()> {
+    let data_dir = tempfile::tempdir()?;
+    let settings = SettingsBuilder::new()
+        .data_dir(data_dir.path())
+        .temporary(false)
+        .build();
+    let mut postgresql = PostgreSQL::new(settings.clone());
+    postgresql.setup().await?;
+    postgresql.start().await?;
+    postgresql
+        .run_sql(
+            BOOTSTRAP_DATABASE,
+            "CREATE TABLE test (id INT); INSERT INTO test VALUES (1);",
+        )
+        .await?;
+    postgresql.stop().await?;
+
+    let settings = SettingsBuilder::from_settings(settings)
+        .read_only(true)
+        .build();
+    let mut postgresql = PostgreSQL::new(settings);
+    postgresql.setup().await?;
+    postgresql.start().await?;
+
+    let output = postgresql
+        .run_sql(BOOTSTRAP_DATABASE, "SELECT count(*) FROM test;")
+        .await?;
+    assert!(output.stdout.contains('1'));
+    let result = postgresql
+        .run_sql(BOOTSTRAP_DATABASE, "INSERT INTO test VALUES (2);")
+        .await;
+    assert!(result.is_err());
+
+    postgresql.stop().await?;
+    Ok(())
+}
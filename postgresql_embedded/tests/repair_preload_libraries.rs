@@ -0,0 +1,37 @@
+use postgresql_embedded::{PostgreSQL, Status, BOOTSTRAP_DATABASE};
+use sqlx::PgPool;
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_repair_preload_libraries() -> anyhow::Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+
+    let database_url = postgresql.settings().url(BOOTSTRAP_DATABASE);
+    let pool = PgPool::connect(database_url.as_str()).await?;
+    sqlx::query("ALTER SYSTEM SET shared_preload_libraries = 'missing_library'")
+        .execute(&pool)
+        .await?;
+    pool.close().await;
+    postgresql.stop().await?;
+
+    assert!(postgresql.start().await.is_err());
+    let removed = postgresql.repair_preload_libraries().await?;
+    assert_eq!(vec!["missing_library".to_string()], removed);
+    assert_eq!(Status::Started, postgresql.status());
+    assert!(!postgresql
+        .auto_conf()?
+        .contains_key("shared_preload_libraries"));
+
+    postgresql.stop().await?;
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_repair_preload_libraries_not_found() -> anyhow::Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    assert!(postgresql.repair_preload_libraries().await.is_err());
+    Ok(())
+}
@@ -0,0 +1,63 @@
+use postgresql_embedded::{PostgreSQL, ReplicationSlotType, Result, SettingsBuilder};
+use std::collections::HashMap;
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_physical_replication_slot() -> Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+
+    let slot_name = "physical_slot";
+    postgresql
+        .create_replication_slot(slot_name, &ReplicationSlotType::Physical)
+        .await?;
+    let slots = postgresql.replication_slots().await?;
+    assert_eq!(1, slots.len());
+    let slot = &slots[0];
+    assert_eq!(slot_name, slot.name);
+    assert_eq!(ReplicationSlotType::Physical, slot.slot_type);
+    assert_eq!(None, slot.database);
+    assert!(!slot.active);
+
+    postgresql.drop_replication_slot(slot_name).await?;
+    assert!(postgresql.replication_slots().await?.is_empty());
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_logical_replication_slot() -> Result<()> {
+    let configuration = HashMap::from([("wal_level".to_string(), "logical".to_string())]);
+    let settings = SettingsBuilder::new().configuration(configuration).build();
+    let mut postgresql = PostgreSQL::new(settings);
+    postgresql.setup().await?;
+    postgresql.start().await?;
+
+    let slot_name = "logical_slot";
+    let slot_type = ReplicationSlotType::Logical {
+        plugin: "pgoutput".to_string(),
+    };
+    postgresql
+        .create_replication_slot(slot_name, &slot_type)
+        .await?;
+    let slots = postgresql.replication_slots().await?;
+    assert_eq!(1, slots.len());
+    let slot = &slots[0];
+    assert_eq!(slot_name, slot.name);
+    assert_eq!(slot_type, slot.slot_type);
+    assert_eq!(Some("postgres".to_string()), slot.database);
+    assert!(slot.restart_lsn.is_some());
+
+    postgresql.drop_replication_slot(slot_name).await?;
+    assert!(postgresql.replication_slots().await?.is_empty());
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_drop_replication_slot_not_found() -> Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+    assert!(postgresql.drop_replication_slot("missing").await.is_err());
+    Ok(())
+}
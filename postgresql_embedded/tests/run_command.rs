@@ -0,0 +1,69 @@
+use postgresql_commands::psql::PsqlBuilder;
+use postgresql_embedded::{Error, PostgreSQL};
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_run() -> anyhow::Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+
+    let psql = PsqlBuilder::new()
+        .command("SELECT 40 + 2")
+        .tuples_only()
+        .no_align();
+    let output = postgresql.run(psql).await?;
+    assert_eq!("42", output.stdout.trim());
+    assert_eq!(Some(0), output.code());
+
+    let psql = PsqlBuilder::new()
+        .command("SELECT * FROM missing")
+        .variable(("ON_ERROR_STOP", "1"));
+    let error = postgresql.run(psql).await.expect_err("command error");
+    assert!(matches!(error, Error::CommandError { code: Some(_), .. }));
+
+    postgresql.stop().await?;
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_run_streaming() -> anyhow::Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+
+    let psql = PsqlBuilder::new()
+        .command("SELECT generate_series(1, 3)")
+        .tuples_only()
+        .no_align();
+    let mut lines = Vec::new();
+    postgresql
+        .run_streaming(psql, |line| lines.push(line.to_string()))
+        .await?;
+    assert_eq!(vec!["1", "2", "3"], lines);
+
+    postgresql.stop().await?;
+    Ok(())
+}
+
+#[cfg(feature = "tokio")]
+#[test(tokio::test)]
+async fn test_run_foreground() -> anyhow::Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+
+    let mut lines = Vec::new();
+    let mut ready = false;
+    // The server runs until it is stopped; dropping the future when the timeout elapses kills it
+    let result = tokio::time::timeout(
+        std::time::Duration::from_secs(10),
+        postgresql.run_foreground(|line| {
+            ready |= line.contains("ready to accept connections");
+            lines.push(line.to_string());
+        }),
+    )
+    .await;
+    assert!(result.is_err(), "postgres exited: {lines:?}");
+    assert!(ready, "postgres did not become ready: {lines:?}");
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use postgresql_embedded::PostgreSQL;
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_schema_diff() -> anyhow::Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+
+    postgresql.create_database("a").await?;
+    postgresql.create_database("b").await?;
+    postgresql
+        .run_sql(
+            "a",
+            "CREATE TABLE users (id INT); CREATE TABLE orders (id INT);",
+        )
+        .await?;
+    postgresql
+        .run_sql(
+            "b",
+            "CREATE TABLE users (id INT, name TEXT); CREATE VIEW names AS SELECT 1;",
+        )
+        .await?;
+
+    assert!(postgresql.schema_diff("a", "a").await?.is_empty());
+    let diff = postgresql.schema_diff("a", "b").await?;
+    let added: Vec<_> = diff
+        .added
+        .iter()
+        .map(|object| object.name.as_str())
+        .collect();
+    let removed: Vec<_> = diff
+        .removed
+        .iter()
+        .map(|object| object.name.as_str())
+        .collect();
+    assert_eq!(vec!["names"], added);
+    assert_eq!(vec!["orders"], removed);
+    assert_eq!(1, diff.changed.len());
+    assert_eq!("TABLE", diff.changed[0].new.object_type);
+    assert_eq!(Some("public".to_string()), diff.changed[0].new.schema);
+    assert_eq!("users", diff.changed[0].new.name);
+
+    let mut other = PostgreSQL::default();
+    other.setup().await?;
+    other.start().await?;
+    other.create_database("b").await?;
+    other
+        .run_sql(
+            "b",
+            "CREATE TABLE users (id INT); CREATE TABLE orders (id INT);",
+        )
+        .await?;
+    assert!(postgresql
+        .schema_diff_with("a", &other, "b")
+        .await?
+        .is_empty());
+
+    other.stop().await?;
+    postgresql.stop().await?;
+    Ok(())
+}
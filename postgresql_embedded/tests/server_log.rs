@@ -0,0 +1,23 @@
+use postgresql_embedded::{LogCapture, PostgreSQL, SettingsBuilder};
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_read_log() -> anyhow::Result<()> {
+    let settings = SettingsBuilder::new()
+        .log_capture(LogCapture::Tracing)
+        .build();
+    let mut postgresql = PostgreSQL::new(settings);
+    postgresql.setup().await?;
+    assert!(postgresql.read_log(10)?.is_empty());
+
+    postgresql.start().await?;
+    postgresql.stop().await?;
+
+    let lines = postgresql.read_log(100)?;
+    assert!(!lines.is_empty());
+    assert!(lines.len() <= 100);
+    assert!(lines
+        .iter()
+        .any(|line| line.contains("database system is ready to accept connections")));
+    Ok(())
+}
@@ -0,0 +1,61 @@
+use postgresql_embedded::{
+    PostgreSQL, SettingsBuilder, ShutdownMode, ShutdownOptions, Status, BOOTSTRAP_DATABASE,
+};
+use sqlx::PgPool;
+use std::time::Duration;
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_stop_with() -> anyhow::Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+
+    postgresql
+        .stop_with(ShutdownMode::Smart, Duration::from_secs(10))
+        .await?;
+    assert_eq!(Status::Stopped, postgresql.status());
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_stop_smart_timeout() -> anyhow::Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+    // A smart shutdown waits for connected clients to disconnect
+    let pool = PgPool::connect(&postgresql.settings().url(BOOTSTRAP_DATABASE)).await?;
+    sqlx::query("SELECT 1").execute(&pool).await?;
+
+    let result = postgresql
+        .stop_with(ShutdownMode::Smart, Duration::from_secs(1))
+        .await;
+    assert!(result.is_err());
+    assert_eq!(Status::Started, postgresql.status());
+
+    pool.close().await;
+    postgresql.stop().await?;
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_stop_kill_after_timeout() -> anyhow::Result<()> {
+    let settings = SettingsBuilder::new()
+        .shutdown(ShutdownOptions {
+            mode: ShutdownMode::Smart,
+            timeout: Some(Duration::from_secs(1)),
+            kill_after_timeout: true,
+        })
+        .build();
+    let mut postgresql = PostgreSQL::new(settings);
+    postgresql.setup().await?;
+    postgresql.start().await?;
+    let pool = PgPool::connect(&postgresql.settings().url(BOOTSTRAP_DATABASE)).await?;
+    sqlx::query("SELECT 1").execute(&pool).await?;
+
+    postgresql.stop().await?;
+    assert_eq!(Status::Stopped, postgresql.status());
+
+    pool.close().await;
+    Ok(())
+}
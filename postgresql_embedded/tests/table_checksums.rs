@@ -0,0 +1,53 @@
+use postgresql_embedded::{Error, PostgreSQL};
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_table_checksums() -> anyhow::Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+
+    postgresql.create_database("a").await?;
+    postgresql.create_database("b").await?;
+    postgresql
+        .run_sql(
+            "a",
+            "CREATE TABLE users (id INT, name TEXT); \
+            INSERT INTO users VALUES (1, 'a'), (2, 'b'); \
+            CREATE TABLE empty (id INT);",
+        )
+        .await?;
+    // Same rows, inserted in a different order
+    postgresql
+        .run_sql(
+            "b",
+            "CREATE TABLE users (id INT, name TEXT); \
+            INSERT INTO users VALUES (2, 'b'), (1, 'a'); \
+            CREATE TABLE empty (id INT);",
+        )
+        .await?;
+
+    let checksums_a = postgresql.table_checksums("a", &[] as &[&str]).await?;
+    let checksums_b = postgresql.table_checksums("b", &[] as &[&str]).await?;
+    assert_eq!(
+        vec!["public.empty", "public.users"],
+        checksums_a.keys().collect::<Vec<_>>()
+    );
+    assert_eq!(checksums_a, checksums_b);
+
+    postgresql
+        .run_sql("b", "UPDATE users SET name = 'c' WHERE id = 2")
+        .await?;
+    let checksums_a = postgresql.table_checksums("a", &["users"]).await?;
+    let checksums_b = postgresql.table_checksums("b", &["users"]).await?;
+    assert_ne!(checksums_a["users"], checksums_b["users"]);
+
+    let error = postgresql
+        .table_checksums("a", &["missing"])
+        .await
+        .expect_err("missing table");
+    assert!(matches!(error, Error::ChecksumError(_)));
+
+    postgresql.stop().await?;
+    Ok(())
+}
@@ -0,0 +1,42 @@
+use postgresql_embedded::{PostgreSQL, TenantManager, TenantQuota};
+use sqlx::{PgPool, Row};
+use test_log::test;
+
+#[test(tokio::test)]
+async fn tenant_lifecycle() -> anyhow::Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+
+    let manager = TenantManager::new(postgresql.settings());
+    let quota = TenantQuota {
+        connection_limit: Some(2),
+        temp_file_limit: Some("10MB".to_string()),
+    };
+    let acme = manager.provision("acme", &quota).await?;
+    let globex = manager.provision("globex", &TenantQuota::default()).await?;
+    assert_eq!("tenant_acme", acme.database_name);
+    assert_eq!(
+        vec!["acme".to_string(), "globex".to_string()],
+        manager.tenants().await?
+    );
+
+    let pool = PgPool::connect(manager.url(&acme).as_str()).await?;
+    let row = sqlx::query("SELECT current_setting('temp_file_limit')")
+        .fetch_one(&pool)
+        .await?;
+    let temp_file_limit: String = row.get(0);
+    pool.close().await;
+    assert_eq!("10MB", temp_file_limit);
+
+    // Tenants cannot connect to the databases of other tenants
+    let mut url = manager.url(&globex);
+    url = url.replace("/tenant_globex", "/tenant_acme");
+    assert!(PgPool::connect(url.as_str()).await.is_err());
+
+    let dropped = manager.deprovision_all().await?;
+    assert_eq!(2, dropped.len());
+    assert!(manager.tenants().await?.is_empty());
+    assert!(!postgresql.database_exists("tenant_acme").await?);
+    Ok(())
+}
@@ -0,0 +1,77 @@
+use postgresql_embedded::{
+    Error, PostgreSQL, SettingsBuilder, Status, Version, VersionReq, V15, V16,
+};
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_upgrade() -> anyhow::Result<()> {
+    let settings = SettingsBuilder::new()
+        .version(V15.clone())
+        .temporary(false)
+        .build();
+    let mut postgresql = PostgreSQL::new(settings.clone());
+    postgresql.setup().await?;
+    postgresql.start().await?;
+    postgresql.create_database("upgrade").await?;
+    postgresql
+        .run_sql(
+            "upgrade",
+            "CREATE TABLE test (id INT); INSERT INTO test VALUES (42);",
+        )
+        .await?;
+    postgresql.stop().await?;
+    drop(postgresql);
+
+    let settings = SettingsBuilder::from_settings(settings)
+        .version(V16.clone())
+        .temporary(true)
+        .build();
+    let mut postgresql = PostgreSQL::new(settings);
+    let error = postgresql
+        .setup()
+        .await
+        .expect_err("data directory requires upgrade");
+    assert!(matches!(error, Error::UpgradeError(_)));
+
+    assert_eq!(Some(15), postgresql.upgrade().await?);
+    assert_eq!(None, postgresql.upgrade().await?);
+    postgresql.setup().await?;
+    postgresql.start().await?;
+    let output = postgresql.run_sql("upgrade", "SELECT id FROM test").await?;
+    assert!(output.stdout.contains("42"));
+    postgresql.stop().await?;
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_upgrade_not_initialized() -> anyhow::Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    assert_eq!(None, postgresql.upgrade().await?);
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_minor_upgrade() -> anyhow::Result<()> {
+    let settings = SettingsBuilder::new()
+        .version(VersionReq::parse("=16.3.0")?)
+        .build();
+    let mut postgresql = PostgreSQL::new(settings);
+    postgresql.setup().await?;
+    postgresql.start().await?;
+    postgresql.create_database("minor_upgrade").await?;
+
+    let version = postgresql
+        .minor_upgrade()
+        .await?
+        .expect("newer minor version");
+    assert!(version > Version::new(16, 3, 0));
+    assert_eq!(Status::Started, postgresql.status());
+    assert!(postgresql
+        .settings()
+        .installation_dir
+        .ends_with(version.to_string()));
+    assert!(postgresql.database_exists("minor_upgrade").await?);
+    assert_eq!(None, postgresql.minor_upgrade().await?);
+    postgresql.stop().await?;
+    Ok(())
+}
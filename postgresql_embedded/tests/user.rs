@@ -0,0 +1,46 @@
+use postgresql_embedded::{PostgreSQL, Result};
+use sqlx::PgPool;
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_user_lifecycle() -> anyhow::Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+
+    let username = "app";
+    assert!(!postgresql.user_exists(username).await?);
+    postgresql.create_user(username, "pass'word").await?;
+    assert!(postgresql.user_exists(username).await?);
+
+    let settings = postgresql.settings();
+    let database_url = |password: &str| {
+        format!(
+            "postgresql://{username}:{password}@{host}:{port}/postgres",
+            host = settings.host,
+            port = settings.port
+        )
+    };
+    let pool = PgPool::connect(&database_url("pass%27word")).await?;
+    pool.close().await;
+
+    postgresql.alter_user_password(username, "changed").await?;
+    assert!(PgPool::connect(&database_url("pass%27word")).await.is_err());
+    let pool = PgPool::connect(&database_url("changed")).await?;
+    pool.close().await;
+
+    postgresql.drop_user(username).await?;
+    assert!(!postgresql.user_exists(username).await?);
+
+    postgresql.stop().await?;
+    Ok(())
+}
+
+#[test(tokio::test)]
+async fn test_drop_user_not_found() -> Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+    postgresql.start().await?;
+    postgresql.drop_user("missing").await?;
+    postgresql.stop().await
+}
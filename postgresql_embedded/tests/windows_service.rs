@@ -0,0 +1,21 @@
+#![cfg(not(target_os = "windows"))]
+
+use postgresql_embedded::{PostgreSQL, Result, WindowsServiceOptions};
+use test_log::test;
+
+#[test(tokio::test)]
+async fn test_windows_service_not_supported() -> Result<()> {
+    let mut postgresql = PostgreSQL::default();
+    postgresql.setup().await?;
+
+    let options = WindowsServiceOptions::default();
+    assert!(postgresql
+        .register_windows_service("postgresql", &options)
+        .await
+        .is_err());
+    assert!(postgresql
+        .unregister_windows_service("postgresql")
+        .await
+        .is_err());
+    Ok(())
+}
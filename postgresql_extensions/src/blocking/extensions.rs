@@ -1,9 +1,10 @@
 #![allow(dead_code)]
 use crate::model::AvailableExtension;
 use crate::{InstalledExtension, Result};
+use postgresql_archive::cache::ArchiveCache;
 use postgresql_commands::Settings;
 use semver::VersionReq;
-use std::sync::LazyLock;
+use std::sync::{Arc, LazyLock};
 use tokio::runtime::Runtime;
 
 static RUNTIME: LazyLock<Runtime> = LazyLock::new(|| Runtime::new().unwrap());
@@ -43,6 +44,25 @@ pub fn install(
         .block_on(async move { crate::install(settings, namespace, name, version).await })
 }
 
+/// Installs the extension with the specified `namespace`, `name`, and `version`, caching the
+/// archive of the extension in the archive `cache`; the archive is retrieved from the `cache`
+/// without accessing the network when `offline`.
+///
+/// # Errors
+/// * If an error occurs while installing the extension.
+pub fn install_with_cache(
+    settings: &impl Settings,
+    namespace: &str,
+    name: &str,
+    version: &VersionReq,
+    cache: Arc<dyn ArchiveCache>,
+    offline: bool,
+) -> Result<()> {
+    RUNTIME.handle().block_on(async move {
+        crate::install_with_cache(settings, namespace, name, version, cache, offline).await
+    })
+}
+
 /// Uninstalls the extension with the specified `namespace` and `name`.
 ///
 /// # Errors
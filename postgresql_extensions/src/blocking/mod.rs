@@ -1,3 +1,5 @@
 mod extensions;
 
-pub use extensions::{get_available_extensions, get_installed_extensions, install, uninstall};
+pub use extensions::{
+    get_available_extensions, get_installed_extensions, install, install_with_cache, uninstall,
+};
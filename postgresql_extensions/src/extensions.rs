@@ -3,6 +3,10 @@ use crate::repository::registry;
 use crate::repository::registry::get_repositories;
 use crate::Error::IoError;
 use crate::{InstalledConfiguration, InstalledExtension, Result};
+use postgresql_archive::cache::ArchiveCache;
+use postgresql_archive::{
+    default_download_options, with_scoped_options, DownloadOptions, ScopedOptions,
+};
 use postgresql_commands::pg_config::PgConfigBuilder;
 use postgresql_commands::postgres::PostgresBuilder;
 #[cfg(feature = "tokio")]
@@ -10,11 +14,14 @@ use postgresql_commands::AsyncCommandExecutor;
 use postgresql_commands::CommandBuilder;
 #[cfg(not(feature = "tokio"))]
 use postgresql_commands::CommandExecutor;
+use postgresql_commands::CommandOutput;
 use postgresql_commands::Settings;
 use regex_lite::Regex;
 use semver::VersionReq;
 use std::path::PathBuf;
-use tracing::{debug, instrument};
+use std::sync::Arc;
+use tracing::field::Empty;
+use tracing::{debug, instrument, Span};
 
 const CONFIGURATION_FILE: &str = "postgresql_extensions.json";
 
@@ -50,11 +57,18 @@ pub async fn get_installed_extensions(settings: &impl Settings) -> Result<Vec<In
     Ok(extensions.clone())
 }
 
-/// Installs the extension with the specified `namespace`, `name`, and `version`.
+/// Installs the extension with the specified `namespace`, `name`, and `version`. When the
+/// [default download options](postgresql_archive::default_download_options) are
+/// [offline](postgresql_archive::DownloadOptions::offline), the archive of the extension is
+/// retrieved from the cached archives without accessing the network.
 ///
 /// # Errors
 /// * If an error occurs while installing the extension.
-#[instrument(level = "debug", skip(settings))]
+#[instrument(
+    level = "debug",
+    skip(settings, version),
+    fields(operation = "install_extension", version = Empty, bytes_total = Empty, path = Empty)
+)]
 pub async fn install(
     settings: &impl Settings,
     namespace: &str,
@@ -77,6 +91,10 @@ pub async fn install(
         .await?;
     let library_dir = get_library_path(settings).await?;
     let extension_dir = get_extension_path(settings).await?;
+    let span = Span::current();
+    span.record("version", version.to_string());
+    span.record("bytes_total", archive.len());
+    span.record("path", library_dir.to_string_lossy().as_ref());
     let files = repository
         .install(name, library_dir, extension_dir, &archive)
         .await?;
@@ -94,11 +112,44 @@ pub async fn install(
     Ok(())
 }
 
+/// Installs the extension with the specified `namespace`, `name`, and `version`, caching the
+/// archive of the extension in the archive `cache`; e.g. the
+/// [archive cache](postgresql_archive::cache::FileSystemCache) of the server the extension is
+/// installed in, so that the extension can be installed again when `offline`. When `offline`,
+/// the archive is retrieved from the `cache` without accessing the network; e.g. when the
+/// server is set up [offline](postgresql_archive::DownloadOptions::offline).
+///
+/// # Errors
+/// * If an error occurs while installing the extension.
+pub async fn install_with_cache(
+    settings: &impl Settings,
+    namespace: &str,
+    name: &str,
+    version: &VersionReq,
+    cache: Arc<dyn ArchiveCache>,
+    offline: bool,
+) -> Result<()> {
+    let download_options = DownloadOptions {
+        offline,
+        ..default_download_options()
+    };
+    let options = ScopedOptions {
+        download_options: Some(download_options),
+        cache: Some(cache),
+        ..ScopedOptions::default()
+    };
+    with_scoped_options(options, install(settings, namespace, name, version)).await
+}
+
 /// Uninstalls the extension with the specified `namespace` and `name`.
 ///
 /// # Errors
 /// * If an error occurs while uninstalling the extension.
-#[instrument(level = "debug", skip(settings))]
+#[instrument(
+    level = "debug",
+    skip(settings),
+    fields(operation = "uninstall_extension")
+)]
 pub async fn uninstall(settings: &impl Settings, namespace: &str, name: &str) -> Result<()> {
     let configuration_file = get_configuration_file(settings).await?;
     if !configuration_file.exists() {
@@ -150,7 +201,7 @@ async fn get_configuration_file(settings: &dyn Settings) -> Result<PathBuf> {
 async fn get_library_path(settings: &dyn Settings) -> Result<PathBuf> {
     let command = PgConfigBuilder::from(settings).libdir();
     match execute_command(command).await {
-        Ok((stdout, _stderr)) => Ok(PathBuf::from(stdout.trim())),
+        Ok(output) => Ok(PathBuf::from(output.stdout.trim())),
         Err(error) => {
             debug!("Failed to get library path using pg_config: {error:?}");
             let binary_dir = settings.get_binary_dir();
@@ -174,7 +225,7 @@ async fn get_library_path(settings: &dyn Settings) -> Result<PathBuf> {
 async fn get_shared_path(settings: &dyn Settings) -> Result<PathBuf> {
     let command = PgConfigBuilder::from(settings).sharedir();
     match execute_command(command).await {
-        Ok((stdout, _stderr)) => Ok(PathBuf::from(stdout.trim())),
+        Ok(output) => Ok(PathBuf::from(output.stdout.trim())),
         Err(error) => {
             debug!("Failed to get shared path using pg_config: {error:?}");
             let binary_dir = settings.get_binary_dir();
@@ -209,7 +260,7 @@ async fn get_postgresql_version(settings: &dyn Settings) -> Result<String> {
     let command = PostgresBuilder::new()
         .program_dir(settings.get_binary_dir())
         .version();
-    let (stdout, _stderr) = execute_command(command).await?;
+    let stdout = execute_command(command).await?.stdout;
     let re = Regex::new(r"PostgreSQL\)\s(\d+\.\d+)")?;
     let Some(captures) = re.captures(&stdout) else {
         return Err(IoError(format!(
@@ -227,21 +278,21 @@ async fn get_postgresql_version(settings: &dyn Settings) -> Result<String> {
 }
 
 #[cfg(not(feature = "tokio"))]
-/// Execute a command and return the stdout and stderr as strings.
+/// Execute a command and return its output.
 #[instrument(level = "debug", skip(command_builder), fields(program = ?command_builder.get_program()))]
 async fn execute_command<B: CommandBuilder>(
     command_builder: B,
-) -> postgresql_commands::Result<(String, String)> {
+) -> postgresql_commands::Result<CommandOutput> {
     let mut command = command_builder.build();
     command.execute()
 }
 
 #[cfg(feature = "tokio")]
-/// Execute a command and return the stdout and stderr as strings.
+/// Execute a command and return its output.
 #[instrument(level = "debug", skip(command_builder), fields(program = ?command_builder.get_program()))]
 async fn execute_command<B: CommandBuilder>(
     command_builder: B,
-) -> postgresql_commands::Result<(String, String)> {
+) -> postgresql_commands::Result<CommandOutput> {
     let mut command = command_builder.build_tokio();
     command.execute(None).await
 }
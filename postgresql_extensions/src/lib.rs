@@ -97,7 +97,9 @@ mod model;
 pub mod repository;
 
 pub use error::{Error, Result};
-pub use extensions::{get_available_extensions, get_installed_extensions, install, uninstall};
+pub use extensions::{
+    get_available_extensions, get_installed_extensions, install, install_with_cache, uninstall,
+};
 pub use matcher::{matcher, tar_gz_matcher, zip_matcher};
 #[cfg(test)]
 pub use model::TestSettings;
@@ -10,8 +10,8 @@ use url::Url;
 ///
 /// # Errors
 /// * If the asset matcher fails.
-pub fn tar_gz_matcher(url: &str, name: &str, version: &Version) -> Result<bool> {
-    if !matcher(url, name, version)? {
+pub fn tar_gz_matcher(url: &str, name: &str, version: &Version, target: &str) -> Result<bool> {
+    if !matcher(url, name, version, target)? {
         return Ok(false);
     }
 
@@ -24,8 +24,8 @@ pub fn tar_gz_matcher(url: &str, name: &str, version: &Version) -> Result<bool>
 /// # Errors
 /// * If the asset matcher fails.
 #[expect(clippy::case_sensitive_file_extension_comparisons)]
-pub fn zip_matcher(url: &str, name: &str, version: &Version) -> Result<bool> {
-    if !matcher(url, name, version)? {
+pub fn zip_matcher(url: &str, name: &str, version: &Version, target: &str) -> Result<bool> {
+    if !matcher(url, name, version, target)? {
         return Ok(false);
     }
 
@@ -33,11 +33,12 @@ pub fn zip_matcher(url: &str, name: &str, version: &Version) -> Result<bool> {
 }
 
 /// Default asset matcher that matches the asset name to the postgresql major version, target triple
-/// or OS/CPU architecture.
+/// or OS/CPU architecture. The OS/CPU architecture is only matched for the target this crate was
+/// built for.
 ///
 /// # Errors
 /// * If the asset matcher fails.
-pub fn matcher(url: &str, name: &str, _version: &Version) -> Result<bool> {
+pub fn matcher(url: &str, name: &str, _version: &Version, target: &str) -> Result<bool> {
     let Ok(url) = Url::parse(url) else {
         return Ok(false);
     };
@@ -56,10 +57,13 @@ pub fn matcher(url: &str, name: &str, _version: &Version) -> Result<bool> {
         return Ok(false);
     }
 
-    let target_re = regex(target_triple::TARGET)?;
+    let target_re = regex(target)?;
     if target_re.is_match(name) {
         return Ok(true);
     }
+    if target != target_triple::TARGET {
+        return Ok(false);
+    }
 
     let os = consts::OS;
     let os_re = regex(os)?;
@@ -114,13 +118,23 @@ mod tests {
     #[test]
     fn test_invalid_url() -> Result<()> {
         let url = "^";
-        assert!(!matcher(url, "", &Version::new(0, 0, 0))?);
+        assert!(!matcher(
+            url,
+            "",
+            &Version::new(0, 0, 0),
+            target_triple::TARGET
+        )?);
         Ok(())
     }
 
     #[test]
     fn test_no_version() -> Result<()> {
-        assert!(!matcher("https://foo", "", &Version::new(0, 0, 0))?);
+        assert!(!matcher(
+            "https://foo",
+            "",
+            &Version::new(0, 0, 0),
+            target_triple::TARGET
+        )?);
         Ok(())
     }
 
@@ -129,7 +143,8 @@ mod tests {
         assert!(!matcher(
             "https://foo?postgresql_version=16",
             "",
-            &Version::new(0, 0, 0)
+            &Version::new(0, 0, 0),
+            target_triple::TARGET
         )?);
         Ok(())
     }
@@ -144,12 +159,12 @@ mod tests {
         let valid_name = format!("postgresql-pg{postgresql_major_version}-{target}.tar.gz");
         let invalid_name = format!("postgresql-pg{postgresql_major_version}-{target}.zip");
         assert!(
-            tar_gz_matcher(url.as_str(), valid_name.as_str(), &version)?,
+            tar_gz_matcher(url.as_str(), valid_name.as_str(), &version, target)?,
             "{}",
             valid_name
         );
         assert!(
-            !tar_gz_matcher(url.as_str(), invalid_name.as_str(), &version)?,
+            !tar_gz_matcher(url.as_str(), invalid_name.as_str(), &version, target)?,
             "{}",
             invalid_name
         );
@@ -166,12 +181,12 @@ mod tests {
         let valid_name = format!("postgresql-pg{postgresql_major_version}-{target}.zip");
         let invalid_name = format!("postgresql-pg{postgresql_major_version}-{target}.tar.gz");
         assert!(
-            zip_matcher(url.as_str(), valid_name.as_str(), &version)?,
+            zip_matcher(url.as_str(), valid_name.as_str(), &version, target)?,
             "{}",
             valid_name
         );
         assert!(
-            !zip_matcher(url.as_str(), invalid_name.as_str(), &version)?,
+            !zip_matcher(url.as_str(), invalid_name.as_str(), &version, target)?,
             "{}",
             invalid_name
         );
@@ -198,7 +213,25 @@ mod tests {
         ];
 
         for name in names {
-            assert!(matcher(url.as_str(), name.as_str(), &version)?, "{}", name);
+            assert!(
+                matcher(url.as_str(), name.as_str(), &version, target)?,
+                "{}",
+                name
+            );
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn test_matcher_target() -> Result<()> {
+        let url = "https://foo?postgresql_version=16.3";
+        let version = Version::parse("1.2.3")?;
+        let target = "aarch64-apple-darwin";
+        let name = format!("postgresql-pg16-{target}.tar.gz");
+        assert!(matcher(url, name.as_str(), &version, target)?, "{}", name);
+        if target != target_triple::TARGET {
+            let name = format!("postgresql-pg16-{}.tar.gz", target_triple::TARGET);
+            assert!(!matcher(url, name.as_str(), &version, target)?, "{}", name);
         }
         Ok(())
     }
@@ -221,7 +254,11 @@ mod tests {
         ];
 
         for name in names {
-            assert!(!matcher(url.as_str(), name.as_str(), &version)?, "{}", name);
+            assert!(
+                !matcher(url.as_str(), name.as_str(), &version, target)?,
+                "{}",
+                name
+            );
         }
         Ok(())
     }